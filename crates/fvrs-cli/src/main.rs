@@ -1,4 +1,9 @@
-use fvrs_core::core::{FileSystem, MonitoringSettings, MonitoringFilter};
+use fvrs_core::core::{EntryKind, FileSystem, FsError, FsResult, MonitoringSettings, MonitoringFilter, SearchOptions};
+use fvrs_core::duplicates::{self, DuplicateOptions};
+use fvrs_core::bookmarks::{self, Bookmarks};
+use fvrs_core::index::{FileIndex, IndexSettings, IndexWatcher, QuerySyntax};
+use fvrs_core::smart_folders::SmartFolders;
+use std::sync::atomic::AtomicBool;
 use std::path::PathBuf;
 use std::env;
 use tokio;
@@ -13,6 +18,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("  {} list [path]        - List files in directory", args[0]);
         println!("  {} monitor [path]     - Monitor directory for changes", args[0]);
         println!("  {} search <pattern>   - Search for files matching pattern", args[0]);
+        println!("  {} dupes [options] [path...] - Find duplicate files (JSON output)", args[0]);
+        println!("      --min-size <bytes>  --include <glob>  --exclude <glob>  --hardlinks  --follow-links");
+        println!("  {} bookmarks          - List bookmarks", args[0]);
+        println!("  {} locate [options] <pattern> - Find names in the file index", args[0]);
+        println!("      --glob  --regex  --case  --files  --dirs  --in <path>  --limit <n>");
+        println!("  {} index [status|add <path>|remove <path>|rebuild|watch] - Manage the file index", args[0]);
+        println!("  {} smart [name]       - List smart folders, or the files in one", args[0]);
+        println!("Paths may start with @name or @name/sub/dir to use a bookmark.");
         return Ok(());
     }
     
@@ -22,7 +35,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     match command.as_str() {
         "list" => {
             let path = if args.len() > 2 {
-                PathBuf::from(&args[2])
+                match resolve_path(&args[2]) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        return Ok(());
+                    }
+                }
             } else {
                 std::env::current_dir()?
             };
@@ -58,7 +77,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         
         "monitor" => {
             let path = if args.len() > 2 {
-                PathBuf::from(&args[2])
+                match resolve_path(&args[2]) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        return Ok(());
+                    }
+                }
             } else {
                 std::env::current_dir()?
             };
@@ -127,15 +152,333 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         
+        "dupes" => {
+            let mut options = DuplicateOptions::default();
+            let mut roots = Vec::new();
+            let mut rest = args[2..].iter();
+            
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--min-size" => {
+                        match rest.next().map(|v| v.parse::<u64>()) {
+                            Some(Ok(size)) => options.min_size = size,
+                            _ => {
+                                eprintln!("Error: --min-size requires a number of bytes");
+                                return Ok(());
+                            }
+                        }
+                    }
+                    "--include" | "--exclude" => {
+                        let Some(pattern) = rest.next() else {
+                            eprintln!("Error: {} requires a glob pattern", arg);
+                            return Ok(());
+                        };
+                        if arg == "--include" {
+                            options.include.push(pattern.clone());
+                        } else {
+                            options.exclude.push(pattern.clone());
+                        }
+                    }
+                    "--hardlinks" => options.hardlinks_are_duplicates = true,
+                    "--follow-links" => options.follow_links = true,
+                    _ => match resolve_path(arg) {
+                        Ok(path) => roots.push(path),
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            return Ok(());
+                        }
+                    },
+                }
+            }
+            
+            if roots.is_empty() {
+                roots.push(std::env::current_dir()?);
+            }
+            
+            let cancel = AtomicBool::new(false);
+            match duplicates::find_duplicates(&roots, &options, &cancel, |_| {}) {
+                Ok(report) => {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    eprintln!("{} groups, {} reclaimable", 
+                        report.groups.len(),
+                        format_size(report.wasted_space)
+                    );
+                }
+                Err(e) => {
+                    eprintln!("Error finding duplicates: {}", e);
+                }
+            }
+        }
+        
+        "bookmarks" => {
+            let bookmarks = match load_bookmarks() {
+                Ok(bookmarks) => bookmarks,
+                Err(e) => {
+                    eprintln!("Error reading bookmarks: {}", e);
+                    return Ok(());
+                }
+            };
+            if bookmarks.is_empty() {
+                println!("No bookmarks. Add them from the bookmark section of the GUI sidebar.");
+            }
+            for bookmark in bookmarks.bookmarks() {
+                let shortcut = bookmark.shortcut.map(|n| format!("Ctrl+{}", n)).unwrap_or_default();
+                println!("{}{:<20} {:<10} {:<15} {}",
+                    bookmarks::NAME_PREFIX,
+                    bookmark.name,
+                    shortcut,
+                    bookmark.group,
+                    bookmark.path.display()
+                );
+            }
+        }
+        
+        "locate" => {
+            let mut syntax = QuerySyntax::Substring;
+            let mut options = SearchOptions { case_sensitive: false, ..SearchOptions::default() };
+            let mut within = None;
+            let mut pattern = None;
+            let mut rest = args[2..].iter();
+            
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--glob" => syntax = QuerySyntax::Glob,
+                    "--regex" => syntax = QuerySyntax::Regex,
+                    "--case" => options.case_sensitive = true,
+                    "--files" => options.kind = EntryKind::Files,
+                    "--dirs" => options.kind = EntryKind::Directories,
+                    "--in" => match rest.next().map(|path| resolve_path(path)) {
+                        Some(Ok(path)) => within = Some(path),
+                        Some(Err(e)) => {
+                            eprintln!("Error: {}", e);
+                            return Ok(());
+                        }
+                        None => {
+                            eprintln!("Error: --in requires a path");
+                            return Ok(());
+                        }
+                    },
+                    "--limit" => match rest.next().map(|v| v.parse::<usize>()) {
+                        Some(Ok(limit)) => options.max_results = Some(limit),
+                        _ => {
+                            eprintln!("Error: --limit requires a number");
+                            return Ok(());
+                        }
+                    },
+                    _ => pattern = Some(arg.clone()),
+                }
+            }
+            let Some(pattern) = pattern else {
+                eprintln!("Error: Pattern required for locate command");
+                return Ok(());
+            };
+            options.pattern = syntax.to_regex(&pattern);
+            
+            let index = match load_index() {
+                Ok(index) => index,
+                Err(e) => {
+                    eprintln!("Error reading the file index: {}", e);
+                    return Ok(());
+                }
+            };
+            if index.is_empty() {
+                eprintln!("The file index is empty. Add a directory with 'index add <path>'.");
+                return Ok(());
+            }
+            
+            let started = std::time::Instant::now();
+            let within = within.map(|path| std::path::absolute(&path).unwrap_or(path));
+            match index.search(within.as_deref(), &options) {
+                Ok(entries) => {
+                    for entry in &entries {
+                        let size_str = if entry.is_dir {
+                            "<DIR>".to_string()
+                        } else {
+                            format_size(entry.size)
+                        };
+                        println!("{} ({})", entry.path.display(), size_str);
+                    }
+                    eprintln!("{} found in {:.1} ms", entries.len(), started.elapsed().as_secs_f64() * 1000.0);
+                }
+                Err(e) => {
+                    eprintln!("Error searching the file index: {}", e);
+                }
+            }
+        }
+        
+        "index" => {
+            if let Err(e) = index_command(&args[2..]) {
+                eprintln!("Error: {}", e);
+            }
+        }
+        
+        "smart" => {
+            let folders = match SmartFolders::default_path() {
+                Some(path) => SmartFolders::load(&path),
+                None => Ok(SmartFolders::default()),
+            };
+            let folders = match folders {
+                Ok(folders) => folders,
+                Err(e) => {
+                    eprintln!("Error reading smart folders: {}", e);
+                    return Ok(());
+                }
+            };
+            let Some(name) = args.get(2) else {
+                if folders.is_empty() {
+                    println!("No smart folders. Save a search from the search dialog of the GUI.");
+                }
+                for folder in folders.folders() {
+                    println!("{:<20} {}", folder.name, folder.root.display());
+                }
+                return Ok(());
+            };
+            let Some(folder) = folders.get(name) else {
+                eprintln!("Error: no smart folder named {}", name);
+                return Ok(());
+            };
+            
+            let cancel = AtomicBool::new(false);
+            let result = folder.run(&cancel, |hit| {
+                let size_str = if hit.entry.is_dir {
+                    "<DIR>".to_string()
+                } else {
+                    format_size(hit.entry.size)
+                };
+                println!("{} ({})", hit.entry.path.display(), size_str);
+            });
+            match result {
+                Ok(summary) => eprintln!("{} found, {} entries scanned", summary.hits, summary.scanned),
+                Err(e) => eprintln!("Error searching {}: {}", folder.root.display(), e),
+            }
+        }
+        
         _ => {
             eprintln!("Unknown command: {}", command);
-            eprintln!("Use 'list', 'monitor', or 'search'");
+            eprintln!("Use 'list', 'monitor', 'search', 'dupes', 'bookmarks', 'locate', 'index', or 'smart'");
         }
     }
     
     Ok(())
 }
 
+/// Path argument, with a leading @name replaced by the bookmarked directory
+fn resolve_path(argument: &str) -> FsResult<PathBuf> {
+    if !argument.starts_with(bookmarks::NAME_PREFIX) {
+        return Ok(PathBuf::from(argument));
+    }
+    load_bookmarks()?.resolve(argument)
+}
+
+fn load_bookmarks() -> FsResult<Bookmarks> {
+    match Bookmarks::default_path() {
+        Some(path) => Bookmarks::load(&path),
+        None => Ok(Bookmarks::default()),
+    }
+}
+
+/// The `index` subcommands
+fn index_command(args: &[String]) -> FsResult<()> {
+    let settings_path = IndexSettings::default_path()
+        .ok_or_else(|| FsError::NotSupported("no configuration directory for the file index".to_string()))?;
+    let mut settings = IndexSettings::load(&settings_path)?;
+    
+    match args.first().map(String::as_str).unwrap_or("status") {
+        "status" => {
+            let index = load_index()?;
+            if settings.roots.is_empty() {
+                println!("No directories are indexed. Add one with 'index add <path>'.");
+            }
+            for root in &settings.roots {
+                println!("Root: {}", root.display());
+            }
+            println!("Excluded: {}", settings.exclude.join(" "));
+            println!("Entries: {}", index.len());
+            match index.built() {
+                Some(built) => println!("Built: {}", built.format("%Y-%m-%d %H:%M:%S")),
+                None => println!("Built: never"),
+            }
+            if index.settings() != &settings {
+                println!("The settings changed since the last build; run 'index rebuild'.");
+            }
+        }
+        "add" | "remove" => {
+            let Some(path) = args.get(1) else {
+                return Err(FsError::InvalidPath(format!("{} requires a path", args[0])));
+            };
+            let path = resolve_path(path)?;
+            let path = std::path::absolute(&path).unwrap_or(path);
+            if args[0] == "add" {
+                settings.add_root(&path)?;
+            } else if !settings.remove_root(&path) {
+                return Err(FsError::InvalidPath(format!("{} is not an indexed root", path.display())));
+            }
+            settings.save(&settings_path)?;
+            rebuild_index(&settings)?;
+        }
+        "rebuild" => {
+            rebuild_index(&settings)?;
+        }
+        "watch" => {
+            let mut index = load_index()?;
+            if index.settings() != &settings || index.built().is_none() {
+                index = rebuild_index(&settings)?;
+            }
+            let watcher = IndexWatcher::new(&settings.roots, || {})?;
+            println!("Keeping the file index up to date. Press Ctrl+C to stop...");
+            
+            loop {
+                std::thread::sleep(std::time::Duration::from_secs(2));
+                let mut changed = 0;
+                for event in watcher.pending_events() {
+                    if index.apply(&event) {
+                        changed += 1;
+                    }
+                }
+                if index.is_stale() {
+                    eprintln!("Changes were lost; rebuilding the index");
+                    index = rebuild_index(&settings)?;
+                } else if changed > 0 {
+                    save_index(&index)?;
+                    println!("{} changes, {} entries", changed, index.len());
+                }
+            }
+        }
+        other => {
+            return Err(FsError::NotSupported(format!("unknown index command '{}'", other)));
+        }
+    }
+    Ok(())
+}
+
+/// Build the index from scratch and save it
+fn rebuild_index(settings: &IndexSettings) -> FsResult<FileIndex> {
+    let started = std::time::Instant::now();
+    let index = FileIndex::build(settings, &AtomicBool::new(false), |count| {
+        eprint!("\rIndexing... {} entries", count);
+    })?;
+    save_index(&index)?;
+    eprintln!("\rIndexed {} entries in {:.1} s ({} unreadable)",
+        index.len(),
+        started.elapsed().as_secs_f64(),
+        index.errors()
+    );
+    Ok(index)
+}
+
+fn load_index() -> FsResult<FileIndex> {
+    match FileIndex::default_path() {
+        Some(path) => FileIndex::load(&path),
+        None => Ok(FileIndex::default()),
+    }
+}
+
+fn save_index(index: &FileIndex) -> FsResult<()> {
+    let path = FileIndex::default_path()
+        .ok_or_else(|| FsError::NotSupported("no data directory for the file index".to_string()))?;
+    index.save(&path)
+}
+
 fn format_size(size: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = size as f64;
@@ -0,0 +1,278 @@
+//! Reading and changing file attributes
+//!
+//! An `AttributeChanges` only lists what should change: a flag left as
+//! `None`, a mode bit in neither mask or a missing owner is kept as it is.
+//! That way one edit can be applied to many files, or down a whole tree,
+//! without flattening whatever differs between them. The flags are the
+//! read-only, hidden, system and archive attributes on Windows; the mode
+//! bits, owner and group are Unix only. Timestamps work on both.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use filetime::FileTime;
+use serde::{Serialize, Deserialize};
+use walkdir::WalkDir;
+use crate::core::{FsError, FsResult};
+
+/// Setuid, setgid, sticky and the nine permission bits
+pub const MODE_MASK: u32 = 0o7777;
+
+/// Attributes of one file or directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileAttributes {
+    pub is_dir: bool,
+    pub readonly: bool,
+    /// Hidden attribute (Windows) or a leading dot (Unix)
+    pub hidden: bool,
+    /// System attribute (Windows)
+    pub system: bool,
+    /// Archive attribute (Windows)
+    pub archive: bool,
+    /// Permission bits within `MODE_MASK` (Unix)
+    pub mode: Option<u32>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    pub modified: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+}
+
+/// Changes to make to one kind of entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeChanges {
+    pub readonly: Option<bool>,
+    /// Windows only
+    pub hidden: Option<bool>,
+    /// Windows only
+    pub system: Option<bool>,
+    /// Windows only
+    pub archive: Option<bool>,
+    /// Mode bits to turn on (Unix)
+    pub mode_set: u32,
+    /// Mode bits to turn off (Unix)
+    pub mode_clear: u32,
+    /// New owner (Unix)
+    pub uid: Option<u32>,
+    /// New group (Unix)
+    pub gid: Option<u32>,
+    pub modified: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+}
+
+impl AttributeChanges {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What to change in a selection of files and directories
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeRules {
+    pub files: AttributeChanges,
+    pub directories: AttributeChanges,
+    /// Also change everything inside the selected directories
+    pub recursive: bool,
+}
+
+/// Outcome of `apply`
+#[derive(Debug, Clone, Default)]
+pub struct AttributeReport {
+    /// Entries that were changed
+    pub changed: usize,
+    /// Entries that were already as requested
+    pub unchanged: usize,
+    /// Entries that could not be changed, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Read the attributes of `path`, following symlinks
+pub fn read(path: &Path) -> FsResult<FileAttributes> {
+    let metadata = std::fs::metadata(path)?;
+    let mut attributes = FileAttributes {
+        is_dir: metadata.is_dir(),
+        readonly: metadata.permissions().readonly(),
+        modified: metadata.modified().ok(),
+        accessed: metadata.accessed().ok(),
+        ..Default::default()
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        attributes.hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        attributes.mode = Some(metadata.mode() & MODE_MASK);
+        attributes.uid = Some(metadata.uid());
+        attributes.gid = Some(metadata.gid());
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        let flags = metadata.file_attributes();
+        attributes.hidden = flags & windows_flags::HIDDEN != 0;
+        attributes.system = flags & windows_flags::SYSTEM != 0;
+        attributes.archive = flags & windows_flags::ARCHIVE != 0;
+    }
+    Ok(attributes)
+}
+
+/// Apply `changes` to one entry, returning whether anything changed
+///
+/// Ownership is changed before the mode, since changing the owner clears
+/// the setuid and setgid bits on most systems.
+pub fn apply_changes(path: &Path, changes: &AttributeChanges) -> FsResult<bool> {
+    let mut changed = false;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+        let metadata = std::fs::metadata(path)?;
+        let uid = changes.uid.filter(|&uid| uid != metadata.uid());
+        let gid = changes.gid.filter(|&gid| gid != metadata.gid());
+        if uid.is_some() || gid.is_some() {
+            std::os::unix::fs::chown(path, uid, gid)
+                .map_err(|e| FsError::Permission(format!("changing the owner: {}", e)))?;
+            changed = true;
+        }
+
+        let mode = std::fs::metadata(path)?.mode() & MODE_MASK;
+        let mut new_mode = (mode | changes.mode_set) & !changes.mode_clear;
+        match changes.readonly {
+            Some(true) => new_mode &= !0o222,
+            Some(false) => new_mode |= 0o200,
+            None => {}
+        }
+        if new_mode != mode {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(new_mode))?;
+            changed = true;
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        changed |= windows_flags::apply(path, changes)?;
+    }
+
+    let metadata = std::fs::metadata(path)?;
+    if let Some(modified) = changes.modified.filter(|&time| metadata.modified().ok() != Some(time)) {
+        filetime::set_file_mtime(path, FileTime::from_system_time(modified))?;
+        changed = true;
+    }
+    if let Some(accessed) = changes.accessed.filter(|&time| metadata.accessed().ok() != Some(time)) {
+        filetime::set_file_atime(path, FileTime::from_system_time(accessed))?;
+        changed = true;
+    }
+    Ok(changed)
+}
+
+/// Apply `rules` to every path, and to their contents if recursive
+///
+/// Errors on single entries are collected in the report rather than
+/// stopping the run. Symlinks met while recursing are skipped, so a change
+/// never leaves the selected trees. Contents are changed before their
+/// directory, so taking away access to a directory happens last. Returns
+/// `FsError::Cancelled` once `cancel` is set.
+pub fn apply(
+    paths: &[PathBuf],
+    rules: &AttributeRules,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(&Path),
+) -> FsResult<AttributeReport> {
+    let mut report = AttributeReport::default();
+    let mut apply_one = |path: &Path, is_dir: bool, report: &mut AttributeReport| {
+        progress(path);
+        let changes = if is_dir { &rules.directories } else { &rules.files };
+        if changes.is_empty() {
+            return;
+        }
+        match apply_changes(path, changes) {
+            Ok(true) => report.changed += 1,
+            Ok(false) => report.unchanged += 1,
+            Err(e) => report.failed.push((path.to_path_buf(), e.to_string())),
+        }
+    };
+
+    for path in paths {
+        let is_dir = path.is_dir();
+        if !(rules.recursive && is_dir) {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+            apply_one(path, is_dir, &mut report);
+            continue;
+        }
+
+        for entry in WalkDir::new(path).contents_first(true) {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+            match entry {
+                Ok(entry) if entry.depth() > 0 && entry.path_is_symlink() => {}
+                Ok(entry) => apply_one(entry.path(), entry.file_type().is_dir(), &mut report),
+                Err(e) => {
+                    let failed = e.path().unwrap_or(path).to_path_buf();
+                    report.failed.push((failed, e.to_string()));
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Format mode bits like `ls -l`, e.g. `rwxr-sr-x`
+pub fn format_mode(mode: u32) -> String {
+    let mut text: Vec<char> = "rwxrwxrwx"
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if mode & (0o400 >> i) != 0 { c } else { '-' })
+        .collect();
+    for (bit, index, letter) in [(0o4000, 2, 's'), (0o2000, 5, 's'), (0o1000, 8, 't')] {
+        if mode & bit != 0 {
+            text[index] = if text[index] == 'x' { letter } else { letter.to_ascii_uppercase() };
+        }
+    }
+    text.into_iter().collect()
+}
+
+#[cfg(windows)]
+pub(crate) mod windows_flags {
+    use super::*;
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::{
+        GetFileAttributesW, SetFileAttributesW, FILE_FLAGS_AND_ATTRIBUTES,
+    };
+
+    pub const READONLY: u32 = 0x1;
+    pub const HIDDEN: u32 = 0x2;
+    pub const SYSTEM: u32 = 0x4;
+    pub const ARCHIVE: u32 = 0x20;
+
+    /// Set or clear the flags named in `changes`, returning whether any changed
+    pub fn apply(path: &Path, changes: &AttributeChanges) -> FsResult<bool> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let flags = unsafe { GetFileAttributesW(PCWSTR(wide.as_ptr())) };
+        if flags == u32::MAX {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let mut new_flags = flags;
+        for (wanted, flag) in [
+            (changes.readonly, READONLY),
+            (changes.hidden, HIDDEN),
+            (changes.system, SYSTEM),
+            (changes.archive, ARCHIVE),
+        ] {
+            match wanted {
+                Some(true) => new_flags |= flag,
+                Some(false) => new_flags &= !flag,
+                None => {}
+            }
+        }
+        if new_flags == flags {
+            return Ok(false);
+        }
+        unsafe { SetFileAttributesW(PCWSTR(wide.as_ptr()), FILE_FLAGS_AND_ATTRIBUTES(new_flags)) }
+            .map_err(|e| FsError::Permission(e.message().to_string()))?;
+        Ok(true)
+    }
+}
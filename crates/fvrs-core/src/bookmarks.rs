@@ -0,0 +1,236 @@
+//! User-defined bookmarks for directories
+//!
+//! Bookmarks are kept in `bookmarks.json` in the configuration directory so
+//! the GUI and the CLI share them. On the command line a path written as
+//! `@name` or `@name/rest` starts at the bookmark called `name`.
+
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult};
+
+/// File the bookmarks are stored in, inside `config::config_dir`
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+/// Icon used when a bookmark has none
+pub const DEFAULT_ICON: &str = "📁";
+/// Prefix that marks a bookmark name in a path argument
+pub const NAME_PREFIX: char = '@';
+/// Highest shortcut number (shortcuts are 1 to 9)
+pub const MAX_SHORTCUT: u8 = 9;
+
+/// A named directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub path: PathBuf,
+    #[serde(default = "default_icon")]
+    pub icon: String,
+    /// Group shown as a heading; empty for ungrouped bookmarks
+    #[serde(default)]
+    pub group: String,
+    /// Number of the Ctrl+number shortcut, 1 to `MAX_SHORTCUT`
+    #[serde(default)]
+    pub shortcut: Option<u8>,
+}
+
+fn default_icon() -> String {
+    DEFAULT_ICON.to_string()
+}
+
+impl Bookmark {
+    /// Bookmark named after the last component of `path`
+    pub fn for_path(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+        Self { name, path: path.to_path_buf(), icon: default_icon(), group: String::new(), shortcut: None }
+    }
+}
+
+/// All bookmarks, in the order they are shown
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bookmarks {
+    bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    /// Where the bookmarks are stored for the current user
+    pub fn default_path() -> Option<PathBuf> {
+        crate::config::config_dir().map(|dir| dir.join(BOOKMARKS_FILE))
+    }
+
+    /// Read bookmarks saved by `save`; a missing file means no bookmarks
+    pub fn load(path: &Path) -> FsResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| FsError::Serialization(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the bookmarks, replacing the file only once it is complete
+    pub fn save(&self, path: &Path) -> FsResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| FsError::Serialization(e.to_string()))?;
+        let temp = crate::copy::temp_path(path);
+        std::fs::write(&temp, text)
+            .and_then(|()| std::fs::rename(&temp, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&temp);
+                e.into()
+            })
+    }
+
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.name == name)
+    }
+
+    /// Bookmark with the Ctrl+`number` shortcut
+    pub fn by_shortcut(&self, number: u8) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.shortcut == Some(number))
+    }
+
+    /// Whether `path` is bookmarked under any name
+    pub fn contains_path(&self, path: &Path) -> bool {
+        self.bookmarks.iter().any(|bookmark| bookmark.path == path)
+    }
+
+    /// Group names in the order they first appear, ungrouped ("") included
+    pub fn groups(&self) -> Vec<&str> {
+        let mut groups: Vec<&str> = Vec::new();
+        for bookmark in &self.bookmarks {
+            if !groups.contains(&bookmark.group.as_str()) {
+                groups.push(&bookmark.group);
+            }
+        }
+        groups
+    }
+
+    /// `name`, or `name (2)`, `name (3)`... if it is already taken
+    pub fn unique_name(&self, name: &str) -> String {
+        (1..)
+            .map(|n| if n == 1 { name.to_string() } else { format!("{} ({})", name, n) })
+            .find(|candidate| self.get(candidate).is_none())
+            .unwrap_or_default()
+    }
+
+    /// Add a bookmark at the end
+    ///
+    /// Fails if the name is invalid or taken. A shortcut already used by
+    /// another bookmark moves to the new one.
+    pub fn add(&mut self, bookmark: Bookmark) -> FsResult<()> {
+        self.check(&bookmark, None)?;
+        self.release_shortcut(bookmark.shortcut);
+        self.bookmarks.push(bookmark);
+        Ok(())
+    }
+
+    /// Replace the bookmark called `name`, keeping its position
+    pub fn update(&mut self, name: &str, bookmark: Bookmark) -> FsResult<()> {
+        let index = self.index_of(name)?;
+        self.check(&bookmark, Some(index))?;
+        self.release_shortcut(bookmark.shortcut);
+        self.bookmarks[index] = bookmark;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Bookmark> {
+        let index = self.bookmarks.iter().position(|bookmark| bookmark.name == name)?;
+        Some(self.bookmarks.remove(index))
+    }
+
+    /// Move the bookmark called `name` one place up or down within its group
+    pub fn move_within_group(&mut self, name: &str, up: bool) -> FsResult<()> {
+        let index = self.index_of(name)?;
+        let group = &self.bookmarks[index].group;
+        let neighbour = if up {
+            self.bookmarks[..index].iter().rposition(|bookmark| bookmark.group == *group)
+        } else {
+            self.bookmarks[index + 1..]
+                .iter()
+                .position(|bookmark| bookmark.group == *group)
+                .map(|offset| index + 1 + offset)
+        };
+        if let Some(neighbour) = neighbour {
+            self.bookmarks.swap(index, neighbour);
+        }
+        Ok(())
+    }
+
+    /// Expand a leading `@name` in a path argument
+    ///
+    /// `@name` becomes the bookmarked directory and `@name/rest` a path
+    /// inside it; anything else is returned unchanged.
+    pub fn resolve(&self, argument: &str) -> FsResult<PathBuf> {
+        let Some(rest) = argument.strip_prefix(NAME_PREFIX) else {
+            return Ok(PathBuf::from(argument));
+        };
+        let (name, tail) = match rest.find(['/', '\\']) {
+            Some(index) => (&rest[..index], rest[index + 1..].trim_start_matches(['/', '\\'])),
+            None => (rest, ""),
+        };
+        let bookmark = self
+            .get(name)
+            .ok_or_else(|| FsError::InvalidPath(format!("no bookmark named {}{}", NAME_PREFIX, name)))?;
+        Ok(if tail.is_empty() { bookmark.path.clone() } else { bookmark.path.join(tail) })
+    }
+
+    fn index_of(&self, name: &str) -> FsResult<usize> {
+        self.bookmarks
+            .iter()
+            .position(|bookmark| bookmark.name == name)
+            .ok_or_else(|| FsError::InvalidPath(format!("no bookmark named {}{}", NAME_PREFIX, name)))
+    }
+
+    /// Validate `bookmark` as the entry at `index` (None when adding)
+    fn check(&self, bookmark: &Bookmark, index: Option<usize>) -> FsResult<()> {
+        let name = &bookmark.name;
+        if name.trim().is_empty() || name.trim() != name {
+            return Err(FsError::InvalidPath(format!("bookmark name {:?} is empty or padded with spaces", name)));
+        }
+        if name.starts_with(NAME_PREFIX) || name.chars().any(|c| c == '/' || c == '\\' || c.is_control()) {
+            return Err(FsError::InvalidPath(format!(
+                "bookmark name {:?} must not start with {} or contain slashes",
+                name, NAME_PREFIX
+            )));
+        }
+        if let Some(number) = bookmark.shortcut {
+            if !(1..=MAX_SHORTCUT).contains(&number) {
+                return Err(FsError::NotSupported(format!("shortcut {} is not between 1 and {}", number, MAX_SHORTCUT)));
+            }
+        }
+        let taken = self
+            .bookmarks
+            .iter()
+            .enumerate()
+            .any(|(i, other)| Some(i) != index && other.name == *name);
+        if taken {
+            return Err(FsError::Conflict(format!("a bookmark named {} already exists", name)));
+        }
+        Ok(())
+    }
+
+    /// Take `shortcut` away from whichever bookmark has it
+    fn release_shortcut(&mut self, shortcut: Option<u8>) {
+        if shortcut.is_none() {
+            return;
+        }
+        for bookmark in &mut self.bookmarks {
+            if bookmark.shortcut == shortcut {
+                bookmark.shortcut = None;
+            }
+        }
+    }
+}
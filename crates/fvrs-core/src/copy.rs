@@ -0,0 +1,558 @@
+//! Copy engine used by copy and move operations
+//!
+//! Regular files are copied with the cheapest mechanism the platform
+//! offers: a reflink clone where the filesystem supports it, a
+//! hole-preserving copy for sparse files, `copy_file_range` on Linux and a
+//! buffered loop everywhere else. Symbolic links follow a configurable
+//! policy, and timestamps, permissions and extended attributes are carried
+//! over. Moves fall back to copy-then-delete across filesystems.
+
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::{Serialize, Deserialize};
+use crate::core::{hash_file, FsError, FsResult, HashAlgorithm};
+
+/// Size of one read/write or `copy_file_range` step between progress reports
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Keeps the names of in-progress copies and saves unique within this process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// How symbolic links are copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SymlinkPolicy {
+    /// Recreate the link itself
+    #[default]
+    Preserve,
+    /// Copy whatever the link points to
+    Follow,
+    /// Leave links out of the copy
+    Skip,
+}
+
+/// Options for the copy engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CopyOptions {
+    pub symlinks: SymlinkPolicy,
+    /// Keep access and modification times
+    pub preserve_times: bool,
+    /// Keep permission bits (read-only flag on Windows)
+    pub preserve_permissions: bool,
+    /// Keep extended attributes (unix only)
+    pub preserve_xattrs: bool,
+    /// Keep holes in sparse files instead of writing zeros
+    pub sparse: bool,
+    /// Share data blocks with the source when the filesystem supports it
+    pub reflink: bool,
+    /// Hash source and copy afterwards and fail if they differ
+    pub verify: Option<HashAlgorithm>,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            symlinks: SymlinkPolicy::Preserve,
+            preserve_times: true,
+            preserve_permissions: true,
+            preserve_xattrs: true,
+            sparse: true,
+            reflink: true,
+            verify: None,
+        }
+    }
+}
+
+/// How a single entry was copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMethod {
+    Reflink,
+    CopyFileRange,
+    Sparse,
+    Buffered,
+    Symlink,
+    /// Left out because of [`SymlinkPolicy::Skip`]
+    Skipped,
+}
+
+/// Totals for a tree copy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CopyStats {
+    pub files: u64,
+    pub dirs: u64,
+    pub symlinks: u64,
+    pub skipped: u64,
+    pub bytes: u64,
+}
+
+/// Called with the number of bytes copied since the previous call;
+/// returning `false` cancels the operation with [`FsError::Cancelled`]
+pub type Progress<'a> = dyn FnMut(u64) -> bool + 'a;
+
+/// Copy a file or symbolic link to `target`, replacing an existing file
+///
+/// The copy is written next to `target` under a temporary name and renamed
+/// over it only once complete, so a failed or cancelled copy leaves an
+/// existing target untouched.
+pub fn copy_file(source: &Path, target: &Path, options: &CopyOptions, progress: &mut Progress) -> FsResult<CopyMethod> {
+    let mut meta = std::fs::symlink_metadata(source)?;
+    if meta.file_type().is_symlink() {
+        match options.symlinks {
+            SymlinkPolicy::Skip => return Ok(CopyMethod::Skipped),
+            SymlinkPolicy::Preserve => {
+                return replace_with(target, |temp| {
+                    copy_symlink(source, temp)?;
+                    copy_metadata(source, temp, options)?;
+                    Ok(CopyMethod::Symlink)
+                });
+            }
+            SymlinkPolicy::Follow => meta = std::fs::metadata(source)?,
+        }
+    }
+    if meta.is_dir() {
+        return Err(FsError::InvalidPath(format!("{} is a directory", source.display())));
+    }
+
+    replace_with(target, |temp| {
+        let method = copy_contents(source, temp, &meta, options, progress)?;
+        copy_metadata(source, temp, options)?;
+        if let Some(algorithm) = options.verify {
+            verify_copy(source, temp, algorithm)?;
+        }
+        Ok(method)
+    })
+}
+
+/// Build an entry at a temporary path beside `target` and rename it over
+/// `target` on success; on failure only the temporary entry is removed
+fn replace_with<T>(target: &Path, build: impl FnOnce(&Path) -> FsResult<T>) -> FsResult<T> {
+    let temp = temp_path(target);
+    let result = build(&temp).and_then(|value| {
+        std::fs::rename(&temp, target)?;
+        Ok(value)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Hidden sibling of `target` that an in-progress copy or save is written to
+pub(crate) fn temp_path(target: &Path) -> PathBuf {
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    target.with_file_name(format!(
+        ".{}.{}-{}.fvrs-part",
+        name,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Copy a file or directory tree to `target`, merging into existing directories
+///
+/// Directories created by a copy that fails are removed again; existing
+/// files are only ever replaced by complete copies.
+pub fn copy_tree(source: &Path, target: &Path, options: &CopyOptions, progress: &mut Progress) -> FsResult<CopyStats> {
+    let mut stats = CopyStats::default();
+    copy_tree_into(source, target, options, progress, &mut stats)?;
+    Ok(stats)
+}
+
+fn copy_tree_into(source: &Path, target: &Path, options: &CopyOptions, progress: &mut Progress, stats: &mut CopyStats) -> FsResult<()> {
+    let meta = std::fs::symlink_metadata(source)?;
+    let is_dir = meta.is_dir()
+        || (meta.file_type().is_symlink()
+            && options.symlinks == SymlinkPolicy::Follow
+            && source.is_dir());
+    if !is_dir {
+        let size = meta.len();
+        match copy_file(source, target, options, progress)? {
+            CopyMethod::Skipped => stats.skipped += 1,
+            CopyMethod::Symlink => stats.symlinks += 1,
+            _ => {
+                stats.files += 1;
+                stats.bytes += size;
+            }
+        }
+        return Ok(());
+    }
+
+    if target.starts_with(source) {
+        return Err(FsError::InvalidPath(format!("cannot copy {} into itself", source.display())));
+    }
+    let created = match std::fs::create_dir(target) {
+        Ok(()) => true,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists && target.is_dir() => false,
+        Err(e) => return Err(e.into()),
+    };
+    stats.dirs += 1;
+    let result = copy_dir_contents(source, target, options, progress, stats);
+    if result.is_err() && created {
+        // Everything below a directory this call created is a partial copy
+        let _ = std::fs::remove_dir_all(target);
+    }
+    result
+}
+
+fn copy_dir_contents(source: &Path, target: &Path, options: &CopyOptions, progress: &mut Progress, stats: &mut CopyStats) -> FsResult<()> {
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        copy_tree_into(&entry.path(), &target.join(entry.file_name()), options, progress, stats)?;
+    }
+    // Directory times change while children are written, so apply them last
+    copy_metadata(source, target, options)
+}
+
+/// Move a file or directory, copying and deleting when `rename` cannot
+/// cross filesystems
+///
+/// If the copy step fails the source is kept and no partial target is left
+/// behind.
+///
+/// Returns `true` if the entry was renamed in place.
+pub fn move_path(source: &Path, target: &Path, options: &CopyOptions, progress: &mut Progress) -> FsResult<bool> {
+    match std::fs::rename(source, target) {
+        Ok(()) => return Ok(true),
+        Err(e) if is_cross_device(&e) => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    // Links must move as links, whatever the copy policy says
+    let options = CopyOptions {
+        symlinks: SymlinkPolicy::Preserve,
+        ..options.clone()
+    };
+    copy_tree(source, target, &options, progress)?;
+    if std::fs::symlink_metadata(source)?.is_dir() {
+        std::fs::remove_dir_all(source)?;
+    } else {
+        std::fs::remove_file(source)?;
+    }
+    Ok(false)
+}
+
+/// Whether a `rename` error means source and target are on different filesystems
+pub fn is_cross_device(error: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    if error.raw_os_error() == Some(libc::EXDEV) {
+        return true;
+    }
+    error.kind() == ErrorKind::CrossesDevices
+}
+
+/// Copy times, permissions and extended attributes from `source` to `target`
+///
+/// Extended attributes are copied on a best-effort basis: attributes the
+/// target filesystem rejects (such as `security.*` without privileges) are
+/// left out rather than failing the copy.
+pub fn copy_metadata(source: &Path, target: &Path, options: &CopyOptions) -> FsResult<()> {
+    let meta = std::fs::symlink_metadata(source)?;
+    let is_symlink = meta.file_type().is_symlink()
+        && std::fs::symlink_metadata(target).map(|m| m.file_type().is_symlink()).unwrap_or(false);
+    let meta = if meta.file_type().is_symlink() && !is_symlink {
+        std::fs::metadata(source)?
+    } else {
+        meta
+    };
+
+    #[cfg(unix)]
+    if options.preserve_xattrs {
+        if let Ok(names) = xattr::list(source) {
+            for name in names {
+                if let Ok(Some(value)) = xattr::get(source, &name) {
+                    let _ = xattr::set(target, &name, &value);
+                }
+            }
+        }
+    }
+
+    if options.preserve_permissions && !is_symlink {
+        std::fs::set_permissions(target, meta.permissions())?;
+    }
+
+    if options.preserve_times {
+        let accessed = filetime::FileTime::from_last_access_time(&meta);
+        let modified = filetime::FileTime::from_last_modification_time(&meta);
+        if is_symlink {
+            filetime::set_symlink_file_times(target, accessed, modified)?;
+        } else {
+            filetime::set_file_times(target, accessed, modified)?;
+        }
+    }
+    Ok(())
+}
+
+/// Hash both files and fail with [`FsError::Hash`] if the contents differ
+pub fn verify_copy(source: &Path, target: &Path, algorithm: HashAlgorithm) -> FsResult<()> {
+    let expected = hash_file(source, algorithm)?;
+    let actual = hash_file(target, algorithm)?;
+    if expected.hash != actual.hash || expected.size != actual.size {
+        return Err(FsError::Hash(format!(
+            "verification failed: {} differs from {}",
+            target.display(),
+            source.display()
+        )));
+    }
+    Ok(())
+}
+
+fn copy_contents(source: &Path, target: &Path, meta: &std::fs::Metadata, options: &CopyOptions, progress: &mut Progress) -> FsResult<CopyMethod> {
+    let len = meta.len();
+
+    #[cfg(target_os = "macos")]
+    if options.reflink && platform::clone_file(source, target) {
+        report(progress, len)?;
+        return Ok(CopyMethod::Reflink);
+    }
+
+    let mut reader = File::open(source)?;
+    let mut writer = OpenOptions::new().write(true).create(true).truncate(true).open(target)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        if options.reflink && platform::reflink(&reader, &writer) {
+            report(progress, len)?;
+            return Ok(CopyMethod::Reflink);
+        }
+        if options.sparse && platform::is_sparse(meta) {
+            platform::copy_sparse(&reader, &writer, len, progress)?;
+            return Ok(CopyMethod::Sparse);
+        }
+        if platform::copy_file_range(&reader, &writer, len, progress)? {
+            return Ok(CopyMethod::CopyFileRange);
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let _ = options;
+
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read])?;
+        report(progress, read as u64)?;
+    }
+    writer.flush()?;
+    Ok(CopyMethod::Buffered)
+}
+
+fn report(progress: &mut Progress, bytes: u64) -> FsResult<()> {
+    if progress(bytes) {
+        Ok(())
+    } else {
+        Err(FsError::Cancelled)
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> FsResult<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(source)?, target)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, target: &Path) -> FsResult<()> {
+    let link = std::fs::read_link(source)?;
+    if std::fs::metadata(source).map(|m| m.is_dir()).unwrap_or(false) {
+        std::os::windows::fs::symlink_dir(link, target)?;
+    } else {
+        std::os::windows::fs::symlink_file(link, target)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn copy_symlink(source: &Path, _target: &Path) -> FsResult<()> {
+    Err(FsError::NotSupported(format!("cannot copy symbolic link {}", source.display())))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::os::unix::fs::{FileExt, MetadataExt};
+    use std::os::unix::io::AsRawFd;
+
+    /// Clone the source's data blocks into the target (btrfs, XFS, ...)
+    pub fn reflink(source: &File, target: &File) -> bool {
+        // SAFETY: both descriptors are open for the duration of the call
+        unsafe { libc::ioctl(target.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) == 0 }
+    }
+
+    /// Whether fewer blocks are allocated than the length needs
+    pub fn is_sparse(meta: &std::fs::Metadata) -> bool {
+        meta.blocks() * 512 < meta.len()
+    }
+
+    /// Copy only the data regions found with `SEEK_DATA`/`SEEK_HOLE`
+    pub fn copy_sparse(source: &File, target: &File, len: u64, progress: &mut Progress) -> FsResult<()> {
+        let fd = source.as_raw_fd();
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut offset: u64 = 0;
+        let mut copied: u64 = 0;
+        while offset < len {
+            // SAFETY: lseek on an open descriptor has no memory effects
+            let data = unsafe { libc::lseek(fd, offset as libc::off_t, libc::SEEK_DATA) };
+            if data < 0 {
+                // ENXIO: no more data after `offset`
+                break;
+            }
+            // SAFETY: as above
+            let hole = unsafe { libc::lseek(fd, data, libc::SEEK_HOLE) };
+            let (start, end) = (data as u64, if hole < 0 { len } else { (hole as u64).min(len) });
+            let mut position = start;
+            while position < end {
+                let want = ((end - position) as usize).min(buffer.len());
+                let read = source.read_at(&mut buffer[..want], position)?;
+                if read == 0 {
+                    break;
+                }
+                target.write_all_at(&buffer[..read], position)?;
+                position += read as u64;
+                report(progress, read as u64)?;
+            }
+            copied += position - start;
+            offset = end;
+        }
+        target.set_len(len)?;
+        // Holes count as done so progress adds up to the file size
+        report(progress, len.saturating_sub(copied))
+    }
+
+    /// Copy with `copy_file_range`; returns `false` if the kernel or
+    /// filesystem does not support it and nothing has been copied yet
+    pub fn copy_file_range(source: &File, target: &File, len: u64, progress: &mut Progress) -> FsResult<bool> {
+        let mut copied: u64 = 0;
+        while copied < len {
+            let want = ((len - copied) as usize).min(CHUNK_SIZE * 8);
+            // SAFETY: null offsets use and advance the descriptors' own positions
+            let result = unsafe {
+                libc::copy_file_range(source.as_raw_fd(), std::ptr::null_mut(), target.as_raw_fd(), std::ptr::null_mut(), want, 0)
+            };
+            if result < 0 {
+                let error = std::io::Error::last_os_error();
+                let unsupported = matches!(
+                    error.raw_os_error(),
+                    Some(libc::ENOSYS) | Some(libc::EXDEV) | Some(libc::EOPNOTSUPP) | Some(libc::EINVAL) | Some(libc::EPERM)
+                );
+                if unsupported && copied == 0 {
+                    return Ok(false);
+                }
+                return Err(error.into());
+            }
+            if result == 0 {
+                // The source shrank while copying
+                break;
+            }
+            copied += result as u64;
+            report(progress, result as u64)?;
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    /// Create `target` as an APFS clone of `source`
+    pub fn clone_file(source: &Path, target: &Path) -> bool {
+        let (Ok(source), Ok(target)) = (
+            CString::new(source.as_os_str().as_bytes()),
+            CString::new(target.as_os_str().as_bytes()),
+        ) else {
+            return false;
+        };
+        // clonefile refuses to replace an existing file; fall back to a copy then
+        // SAFETY: both pointers are valid NUL-terminated strings
+        unsafe { libc::clonefile(source.as_ptr(), target.as_ptr(), 0) == 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn copy_file_replaces_existing_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let (source, target) = (dir.path().join("a"), dir.path().join("b"));
+        std::fs::write(&source, b"new contents").unwrap();
+        std::fs::write(&target, b"old").unwrap();
+
+        copy_file(&source, &target, &CopyOptions::default(), &mut |_| true).unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"new contents");
+        assert_eq!(names(dir.path()), ["a", "b"]);
+    }
+
+    #[test]
+    fn cancelled_copy_keeps_existing_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let (source, target) = (dir.path().join("a"), dir.path().join("b"));
+        std::fs::write(&source, vec![7u8; 4096]).unwrap();
+        std::fs::write(&target, b"keep me").unwrap();
+
+        let options = CopyOptions { reflink: false, sparse: false, ..CopyOptions::default() };
+        let result = copy_file(&source, &target, &options, &mut |_| false);
+        assert!(matches!(result, Err(FsError::Cancelled)));
+        assert_eq!(std::fs::read(&target).unwrap(), b"keep me");
+        assert_eq!(names(dir.path()), ["a", "b"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_symlink_replaces_existing_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let (source, target) = (dir.path().join("link"), dir.path().join("copy"));
+        std::os::unix::fs::symlink("somewhere", &source).unwrap();
+        std::fs::write(&target, b"old").unwrap();
+
+        let method = copy_file(&source, &target, &CopyOptions::default(), &mut |_| true).unwrap();
+        assert_eq!(method, CopyMethod::Symlink);
+        assert_eq!(std::fs::read_link(&target).unwrap(), Path::new("somewhere"));
+    }
+
+    #[test]
+    fn failed_tree_copy_removes_created_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        std::fs::create_dir_all(source.join("nested")).unwrap();
+        std::fs::write(source.join("nested/file"), vec![1u8; 4096]).unwrap();
+
+        let target = dir.path().join("dst");
+        let options = CopyOptions { reflink: false, sparse: false, ..CopyOptions::default() };
+        let result = copy_tree(&source, &target, &options, &mut |_| false);
+        assert!(matches!(result, Err(FsError::Cancelled)));
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn failed_tree_copy_keeps_existing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        std::fs::create_dir(&source).unwrap();
+        std::fs::write(source.join("file"), vec![1u8; 4096]).unwrap();
+        let target = dir.path().join("dst");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("file"), b"old").unwrap();
+
+        let options = CopyOptions { reflink: false, sparse: false, ..CopyOptions::default() };
+        assert!(copy_tree(&source, &target, &options, &mut |_| false).is_err());
+        assert_eq!(std::fs::read(target.join("file")).unwrap(), b"old");
+        assert_eq!(names(&target), ["file"]);
+    }
+}
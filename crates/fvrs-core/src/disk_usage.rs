@@ -0,0 +1,399 @@
+//! Disk usage analysis
+//!
+//! Recursively sizes a directory tree with `WalkDir` and keeps a per-directory
+//! cache keyed on the directory modification time. On a re-scan, directories
+//! whose mtime is unchanged reuse their cached file sizes instead of stat'ing
+//! every file again. Subdirectories are still visited, since changes below a
+//! directory do not update its mtime.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use serde::{Serialize, Deserialize};
+use walkdir::WalkDir;
+use crate::core::{FsError, FsResult};
+
+/// A file or directory in a disk usage tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageNode {
+    /// File or directory name
+    pub name: String,
+    /// Full path
+    pub path: PathBuf,
+    /// Allocated size in bytes, including all descendants
+    pub size: u64,
+    /// Number of files at or below this node
+    pub file_count: u64,
+    /// Number of directories below this node
+    pub dir_count: u64,
+    /// Is directory
+    pub is_dir: bool,
+    /// Children, largest first
+    pub children: Vec<DiskUsageNode>,
+}
+
+impl DiskUsageNode {
+    /// Find the node for `path` within this subtree
+    pub fn find(&self, path: &Path) -> Option<&DiskUsageNode> {
+        if self.path == path {
+            return Some(self);
+        }
+        if !self.is_dir || !path.starts_with(&self.path) {
+            return None;
+        }
+        self.children.iter().find_map(|child| child.find(path))
+    }
+
+    /// Remove the node for `path`, subtracting its size and counts from every ancestor
+    pub fn remove(&mut self, path: &Path) -> Option<DiskUsageNode> {
+        if !self.is_dir || self.path == path || !path.starts_with(&self.path) {
+            return None;
+        }
+        let removed = match self.children.iter().position(|child| child.path == path) {
+            Some(index) => self.children.remove(index),
+            None => self.children.iter_mut().find_map(|child| child.remove(path))?,
+        };
+        self.size = self.size.saturating_sub(removed.size);
+        self.file_count = self.file_count.saturating_sub(removed.file_count + u64::from(!removed.is_dir));
+        self.dir_count = self.dir_count.saturating_sub(removed.dir_count + u64::from(removed.is_dir));
+        Some(removed)
+    }
+
+    /// Sort children recursively
+    pub fn sort_by(&mut self, key: DiskUsageSort, ascending: bool) {
+        self.children.sort_by(|a, b| {
+            let ordering = match key {
+                DiskUsageSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                DiskUsageSort::Size => a.size.cmp(&b.size),
+                DiskUsageSort::Files => a.file_count.cmp(&b.file_count),
+            };
+            if ascending { ordering } else { ordering.reverse() }
+        });
+        for child in &mut self.children {
+            child.sort_by(key, ascending);
+        }
+    }
+
+    /// Share of `total` taken by this node, in the range `0.0..=1.0`
+    pub fn ratio_of(&self, total: u64) -> f32 {
+        if total == 0 {
+            0.0
+        } else {
+            (self.size as f64 / total as f64).clamp(0.0, 1.0) as f32
+        }
+    }
+}
+
+/// Sort keys for disk usage trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiskUsageSort {
+    Name,
+    Size,
+    Files,
+}
+
+/// Options for a disk usage scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Do not cross filesystem boundaries
+    pub one_file_system: bool,
+    /// Ignore the cache and stat every file
+    pub full_rescan: bool,
+}
+
+/// Progress of a running scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanProgress {
+    /// Files seen so far
+    pub files: u64,
+    /// Directories seen so far
+    pub dirs: u64,
+    /// Directories whose file sizes came from the cache
+    pub cached_dirs: u64,
+    /// Bytes counted so far
+    pub bytes: u64,
+    /// Entries that could not be read
+    pub errors: u64,
+    /// Directory currently being read
+    pub current: PathBuf,
+}
+
+/// Cached contents of a single directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedDir {
+    modified: SystemTime,
+    own_size: u64,
+    files: Vec<CachedFile>,
+    subdirs: Vec<String>,
+}
+
+/// Cached size of a single file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    name: String,
+    size: u64,
+    /// `(device, inode)` for files with more than one hard link
+    link_id: Option<(u64, u64)>,
+}
+
+/// Per-directory scan cache, reused across scans for incremental updates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskUsageCache {
+    dirs: HashMap<PathBuf, CachedDir>,
+}
+
+impl DiskUsageCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget a directory so the next scan re-reads it
+    pub fn invalidate(&mut self, path: &Path) {
+        self.dirs.remove(path);
+    }
+
+    /// Forget everything
+    pub fn clear(&mut self) {
+        self.dirs.clear();
+    }
+
+    /// Number of cached directories
+    pub fn len(&self) -> usize {
+        self.dirs.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+}
+
+/// How often the progress callback is invoked, in entries
+const PROGRESS_INTERVAL: u64 = 512;
+
+/// `(device, inode)` of a file with several hard links, so it is only counted once
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn hard_link_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Allocated size of a file on disk
+pub fn allocated_size(metadata: &Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.blocks() * 512
+    }
+
+    #[cfg(not(unix))]
+    {
+        metadata.len()
+    }
+}
+
+/// Recursively size `root`, reusing and refreshing `cache`
+///
+/// Files with several hard links are counted once, like `du`. Returns an
+/// error if `root` is not a directory or the scan was cancelled through
+/// `cancel`. Unreadable entries are counted in [`ScanProgress::errors`] and
+/// otherwise skipped.
+pub fn scan(
+    root: &Path,
+    cache: &mut DiskUsageCache,
+    options: &ScanOptions,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(&ScanProgress),
+) -> FsResult<DiskUsageNode> {
+    if !root.is_dir() {
+        return Err(FsError::InvalidPath(format!("Not a directory: {}", root.display())));
+    }
+    if options.full_rescan {
+        cache.dirs.retain(|path, _| !path.starts_with(root));
+    }
+
+    let mut records: HashMap<PathBuf, CachedDir> = HashMap::new();
+    let mut seen_links: HashSet<(u64, u64)> = HashSet::new();
+    let mut state = ScanProgress::default();
+    let mut seen: u64 = 0;
+
+    {
+        // Directories whose cached file list is still valid; their files are
+        // filtered out of the walk so they are never stat'ed.
+        let fresh: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+        let cached = &cache.dirs;
+
+        let walker = WalkDir::new(root)
+            .follow_links(false)
+            .same_file_system(options.one_file_system)
+            .into_iter()
+            .filter_entry(|entry| {
+                if entry.file_type().is_dir() {
+                    if let (Some(cached), Ok(metadata)) = (cached.get(entry.path()), entry.metadata()) {
+                        if metadata.modified().ok() == Some(cached.modified) {
+                            fresh.borrow_mut().insert(entry.path().to_path_buf());
+                        }
+                    }
+                    true
+                } else {
+                    entry.path().parent().is_none_or(|parent| !fresh.borrow().contains(parent))
+                }
+            });
+
+        for entry in walker {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => {
+                    state.errors += 1;
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let parent = path.parent().filter(|_| entry.depth() > 0);
+
+            if entry.file_type().is_dir() {
+                state.dirs += 1;
+                state.current = path.to_path_buf();
+
+                let record = if fresh.borrow().contains(path) {
+                    let record = cached[path].clone();
+                    state.cached_dirs += 1;
+                    state.files += record.files.len() as u64;
+                    state.bytes += record.own_size;
+                    for file in &record.files {
+                        if file.link_id.is_none_or(|id| seen_links.insert(id)) {
+                            state.bytes += file.size;
+                        }
+                    }
+                    record
+                } else {
+                    let metadata = entry.metadata().ok();
+                    let own_size = metadata.as_ref().map(allocated_size).unwrap_or(0);
+                    state.bytes += own_size;
+                    CachedDir {
+                        modified: metadata
+                            .and_then(|m| m.modified().ok())
+                            .unwrap_or(SystemTime::UNIX_EPOCH),
+                        own_size,
+                        files: Vec::new(),
+                        subdirs: Vec::new(),
+                    }
+                };
+                records.insert(path.to_path_buf(), record);
+
+                // Cached parents already know their subdirectories
+                if let Some(parent) = parent {
+                    if !fresh.borrow().contains(parent) {
+                        if let Some(parent_record) = records.get_mut(parent) {
+                            parent_record.subdirs.push(entry.file_name().to_string_lossy().into_owned());
+                        }
+                    }
+                }
+            } else {
+                let (size, link_id) = match entry.metadata() {
+                    Ok(metadata) => (allocated_size(&metadata), hard_link_id(&metadata)),
+                    Err(_) => {
+                        state.errors += 1;
+                        (0, None)
+                    }
+                };
+                state.files += 1;
+                if link_id.is_none_or(|id| seen_links.insert(id)) {
+                    state.bytes += size;
+                }
+                if let Some(parent_record) = parent.and_then(|parent| records.get_mut(parent)) {
+                    parent_record.files.push(CachedFile {
+                        name: entry.file_name().to_string_lossy().into_owned(),
+                        size,
+                        link_id,
+                    });
+                }
+            }
+
+            seen += 1;
+            if seen.is_multiple_of(PROGRESS_INTERVAL) {
+                progress(&state);
+            }
+        }
+    }
+    progress(&state);
+
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| root.display().to_string());
+    let mut tree = build_node(root, name, &records, &mut HashSet::new());
+    tree.sort_by(DiskUsageSort::Size, false);
+
+    cache.dirs.retain(|path, _| !path.starts_with(root));
+    cache.dirs.extend(records);
+
+    Ok(tree)
+}
+
+/// Assemble a directory node from scan records
+fn build_node(
+    path: &Path,
+    name: String,
+    records: &HashMap<PathBuf, CachedDir>,
+    seen_links: &mut HashSet<(u64, u64)>,
+) -> DiskUsageNode {
+    let mut node = DiskUsageNode {
+        name,
+        path: path.to_path_buf(),
+        size: 0,
+        file_count: 0,
+        dir_count: 0,
+        is_dir: true,
+        children: Vec::new(),
+    };
+    let Some(record) = records.get(path) else {
+        return node;
+    };
+
+    node.size = record.own_size;
+    for file in &record.files {
+        // Only the first occurrence of a hard-linked file carries its size
+        let size = if file.link_id.is_none_or(|id| seen_links.insert(id)) { file.size } else { 0 };
+        node.size += size;
+        node.file_count += 1;
+        node.children.push(DiskUsageNode {
+            name: file.name.clone(),
+            path: path.join(&file.name),
+            size,
+            file_count: 0,
+            dir_count: 0,
+            is_dir: false,
+            children: Vec::new(),
+        });
+    }
+    for dir_name in &record.subdirs {
+        let child_path = path.join(dir_name);
+        // Subdirectories excluded by the walk (e.g. other filesystems) are skipped
+        if !records.contains_key(&child_path) {
+            continue;
+        }
+        let child = build_node(&child_path, dir_name.clone(), records, seen_links);
+        node.size += child.size;
+        node.file_count += child.file_count;
+        node.dir_count += child.dir_count + 1;
+        node.children.push(child);
+    }
+    node
+}
@@ -0,0 +1,399 @@
+//! Duplicate file detection
+//!
+//! Files are grouped in three passes so that most files are never read in
+//! full: first by size, then by a BLAKE3 hash of their first block, and
+//! finally by a BLAKE3 hash of the whole content. Hard links to the same
+//! inode are recognised and reported as one file.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use walkdir::WalkDir;
+use crate::core::{FsError, FsResult};
+
+/// Number of bytes hashed in the partial pass
+const PARTIAL_HASH_SIZE: usize = 64 * 1024;
+
+/// Options for a duplicate search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DuplicateOptions {
+    /// Ignore files smaller than this many bytes
+    pub min_size: u64,
+    /// Only consider files matching one of these globs (empty = all files)
+    pub include: Vec<String>,
+    /// Skip files and directories matching any of these globs
+    pub exclude: Vec<String>,
+    /// Follow symbolic links while walking
+    pub follow_links: bool,
+    /// Report hard links to the same inode as duplicates of each other
+    pub hardlinks_are_duplicates: bool,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            min_size: 1,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            follow_links: false,
+            hardlinks_are_duplicates: false,
+        }
+    }
+}
+
+/// A file that belongs to a duplicate group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateFile {
+    /// Path of the file
+    pub path: PathBuf,
+    /// Last modification time
+    pub modified: Option<DateTime<Local>>,
+    /// Other paths that are hard links to the same data
+    pub hard_links: Vec<PathBuf>,
+}
+
+/// A set of files with identical content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// Size of each file in bytes
+    pub size: u64,
+    /// BLAKE3 hash of the content in hexadecimal
+    pub hash: String,
+    /// Files with this content, oldest first
+    pub files: Vec<DuplicateFile>,
+}
+
+impl DuplicateGroup {
+    /// Bytes that would be freed by keeping only one copy
+    pub fn wasted_space(&self) -> u64 {
+        self.size * self.files.len().saturating_sub(1) as u64
+    }
+}
+
+/// Result of a duplicate search
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    /// Roots that were searched
+    pub roots: Vec<PathBuf>,
+    /// Duplicate groups, largest wasted space first
+    pub groups: Vec<DuplicateGroup>,
+    /// Number of files considered
+    pub files_scanned: u64,
+    /// Number of bytes read for hashing
+    pub bytes_hashed: u64,
+    /// Total bytes that could be reclaimed
+    pub wasted_space: u64,
+    /// Entries that could not be read
+    pub errors: u64,
+}
+
+/// Stage of a running duplicate search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DuplicateStage {
+    /// Walking the roots and grouping by size
+    #[default]
+    Scanning,
+    /// Hashing the first block of same-sized files
+    PartialHash,
+    /// Hashing the full content of remaining candidates
+    FullHash,
+}
+
+/// Progress of a running duplicate search
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateProgress {
+    /// Current stage
+    pub stage: DuplicateStage,
+    /// Items processed in the current stage
+    pub done: u64,
+    /// Items in the current stage (0 while scanning)
+    pub total: u64,
+    /// Bytes read for hashing so far
+    pub bytes_hashed: u64,
+    /// File currently being processed
+    pub current: PathBuf,
+}
+
+/// A file found while walking, with all paths that share its inode
+struct Candidate {
+    paths: Vec<PathBuf>,
+    size: u64,
+    modified: Option<std::time::SystemTime>,
+}
+
+fn compile_globs(patterns: &[String]) -> FsResult<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| {
+            glob::Pattern::new(p.trim())
+                .map_err(|e| FsError::Search(format!("Invalid glob pattern '{}': {}", p, e)))
+        })
+        .collect()
+}
+
+/// Globs without a separator match the file name, others the full path
+fn glob_matches(pattern: &glob::Pattern, path: &Path) -> bool {
+    if pattern.as_str().contains('/') || pattern.as_str().contains('\\') {
+        pattern.matches_path(path)
+    } else {
+        path.file_name()
+            .map(|name| pattern.matches(&name.to_string_lossy()))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(unix)]
+fn inode_id(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn inode_id(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Hash up to `limit` bytes of a file (the whole file if `None`)
+fn hash_file(path: &Path, limit: Option<usize>, cancel: &AtomicBool) -> FsResult<(blake3::Hash, u64)> {
+    let mut file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; 256 * 1024];
+    let mut read_total: u64 = 0;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(FsError::Cancelled);
+        }
+        let want = match limit {
+            Some(limit) => (limit as u64 - read_total).min(buffer.len() as u64) as usize,
+            None => buffer.len(),
+        };
+        if want == 0 {
+            break;
+        }
+        let n = file.read(&mut buffer[..want])?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        read_total += n as u64;
+    }
+    Ok((hasher.finalize(), read_total))
+}
+
+/// Find files with identical content below `roots`
+pub fn find_duplicates(
+    roots: &[PathBuf],
+    options: &DuplicateOptions,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(&DuplicateProgress),
+) -> FsResult<DuplicateReport> {
+    let include = compile_globs(&options.include)?;
+    let exclude = compile_globs(&options.exclude)?;
+
+    // Drop roots nested inside other roots so no file is visited twice
+    let mut canonical_roots: Vec<PathBuf> = roots
+        .iter()
+        .map(|root| {
+            root.canonicalize()
+                .map_err(|e| FsError::InvalidPath(format!("{}: {}", root.display(), e)))
+        })
+        .collect::<FsResult<_>>()?;
+    canonical_roots.sort();
+    canonical_roots.dedup();
+    let nested: Vec<bool> = canonical_roots
+        .iter()
+        .map(|root| canonical_roots.iter().any(|other| other != root && root.starts_with(other)))
+        .collect();
+    let walk_roots: Vec<&PathBuf> = canonical_roots
+        .iter()
+        .zip(nested)
+        .filter_map(|(root, nested)| (!nested).then_some(root))
+        .collect();
+
+    let mut report = DuplicateReport {
+        roots: canonical_roots.clone(),
+        ..Default::default()
+    };
+    let mut state = DuplicateProgress::default();
+
+    // Pass 1: walk and group by size, merging hard links
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut by_inode: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut seen_paths: HashSet<PathBuf> = HashSet::new();
+    for root in walk_roots {
+        let walker = WalkDir::new(root)
+            .follow_links(options.follow_links)
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !exclude.iter().any(|p| glob_matches(p, entry.path())));
+        for entry in walker {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => {
+                    report.errors += 1;
+                    continue;
+                }
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            if !include.is_empty() && !include.iter().any(|p| glob_matches(p, entry.path())) {
+                continue;
+            }
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => {
+                    report.errors += 1;
+                    continue;
+                }
+            };
+            if metadata.len() < options.min_size || !seen_paths.insert(entry.path().to_path_buf()) {
+                continue;
+            }
+
+            report.files_scanned += 1;
+            state.done = report.files_scanned;
+            if report.files_scanned.is_multiple_of(1024) {
+                state.current = entry.path().to_path_buf();
+                progress(&state);
+            }
+
+            let inode = inode_id(&metadata).filter(|_| !options.hardlinks_are_duplicates);
+            if let Some(&index) = inode.as_ref().and_then(|id| by_inode.get(id)) {
+                candidates[index].paths.push(entry.path().to_path_buf());
+                continue;
+            }
+            if let Some(id) = inode {
+                by_inode.insert(id, candidates.len());
+            }
+            candidates.push(Candidate {
+                paths: vec![entry.path().to_path_buf()],
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+            });
+        }
+    }
+
+    let mut by_size: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        by_size.entry(candidate.size).or_default().push(index);
+    }
+    let size_groups: Vec<Vec<usize>> = by_size.into_values().filter(|g| g.len() > 1).collect();
+
+    // Hash every file of each group and split it by hash
+    let mut hash_pass = |groups: Vec<Vec<usize>>, stage: DuplicateStage, state: &mut DuplicateProgress, report: &mut DuplicateReport| -> FsResult<Vec<(Vec<usize>, blake3::Hash)>> {
+        state.stage = stage;
+        state.done = 0;
+        state.total = groups.iter().map(|g| g.len() as u64).sum();
+        progress(state);
+
+        let mut result = Vec::new();
+        for group in groups {
+            let mut by_hash: HashMap<blake3::Hash, Vec<usize>> = HashMap::new();
+            for index in group {
+                let candidate = &candidates[index];
+                let limit = match stage {
+                    DuplicateStage::PartialHash => Some(PARTIAL_HASH_SIZE),
+                    _ => None,
+                };
+                state.current = candidate.paths[0].clone();
+                match hash_file(&candidate.paths[0], limit, cancel) {
+                    Ok((hash, bytes)) => {
+                        report.bytes_hashed += bytes;
+                        state.bytes_hashed = report.bytes_hashed;
+                        by_hash.entry(hash).or_default().push(index);
+                    }
+                    Err(FsError::Cancelled) => {
+                        return Err(FsError::Cancelled);
+                    }
+                    Err(_) => report.errors += 1,
+                }
+                state.done += 1;
+                progress(state);
+            }
+            result.extend(by_hash.into_iter().filter(|(_, g)| g.len() > 1).map(|(h, g)| (g, h)));
+        }
+        Ok(result)
+    };
+
+    // Pass 2: partial hash of same-sized files
+    let partial = hash_pass(size_groups, DuplicateStage::PartialHash, &mut state, &mut report)?;
+
+    // Files no larger than the partial block are already fully hashed
+    let mut confirmed: Vec<(Vec<usize>, blake3::Hash)> = Vec::new();
+    let mut needs_full: Vec<Vec<usize>> = Vec::new();
+    for (group, hash) in partial {
+        if candidates[group[0]].size <= PARTIAL_HASH_SIZE as u64 {
+            confirmed.push((group, hash));
+        } else {
+            needs_full.push(group);
+        }
+    }
+
+    // Pass 3: full hash of the remaining candidates
+    confirmed.extend(hash_pass(needs_full, DuplicateStage::FullHash, &mut state, &mut report)?);
+
+    report.groups = confirmed
+        .into_iter()
+        .map(|(group, hash)| {
+            let size = candidates[group[0]].size;
+            let mut files: Vec<(Option<std::time::SystemTime>, DuplicateFile)> = group
+                .into_iter()
+                .map(|index| {
+                    let candidate = &candidates[index];
+                    let mut paths = candidate.paths.clone();
+                    paths.sort();
+                    let path = paths.remove(0);
+                    (
+                        candidate.modified,
+                        DuplicateFile {
+                            path,
+                            modified: candidate.modified.map(DateTime::<Local>::from),
+                            hard_links: paths,
+                        },
+                    )
+                })
+                .collect();
+            files.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.path.cmp(&b.1.path)));
+            DuplicateGroup {
+                size,
+                hash: hash.to_hex().to_string(),
+                files: files.into_iter().map(|(_, file)| file).collect(),
+            }
+        })
+        .collect();
+    report.groups.sort_by(|a, b| {
+        b.wasted_space().cmp(&a.wasted_space()).then_with(|| a.files[0].path.cmp(&b.files[0].path))
+    });
+    report.wasted_space = report.groups.iter().map(DuplicateGroup::wasted_space).sum();
+
+    Ok(report)
+}
+
+/// Replace `duplicate` with a hard link to `original`
+///
+/// The link is created under a temporary name next to `duplicate` and then
+/// renamed over it, so `duplicate` is never missing if linking fails.
+pub fn replace_with_hard_link(original: &Path, duplicate: &Path) -> FsResult<()> {
+    let parent = duplicate
+        .parent()
+        .ok_or_else(|| FsError::InvalidPath(duplicate.display().to_string()))?;
+    let file_name = duplicate
+        .file_name()
+        .ok_or_else(|| FsError::InvalidPath(duplicate.display().to_string()))?;
+    let temp = parent.join(format!(".{}.fvrs-link", file_name.to_string_lossy()));
+    std::fs::hard_link(original, &temp)?;
+    if let Err(e) = std::fs::rename(&temp, duplicate) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,627 @@
+//! Editing files byte by byte
+//!
+//! A `ByteDocument` never loads the file it edits. Its contents are a
+//! piece table: a list of pieces that each point either into the original
+//! file, read on demand, or into a buffer holding every byte typed or
+//! pasted. An edit only rewrites the piece list, so undoing it means
+//! putting back the pieces it replaced. Saving streams the pieces into a
+//! temporary file next to the original and renames it over the original.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use chrono::{DateTime, NaiveDate, Utc};
+use memchr::memmem;
+use crate::core::{FsError, FsResult};
+use crate::paged::read_at;
+
+/// Bytes read at a time while searching or saving
+const CHUNK_SIZE: usize = 1 << 20;
+/// Most edits kept for undo
+const MAX_UNDO: usize = 10_000;
+/// Seconds from 1601-01-01 (the FILETIME epoch) to 1970-01-01
+const FILETIME_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// The file as it was opened
+    Original,
+    /// The buffer of typed and pasted bytes
+    Added,
+}
+
+/// A run of bytes from one source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    source: Source,
+    start: u64,
+    len: u64,
+}
+
+/// One edit: `removed` was replaced by `inserted` at `offset`
+#[derive(Debug, Clone)]
+struct Edit {
+    offset: u64,
+    removed: Vec<Piece>,
+    inserted: Vec<Piece>,
+}
+
+/// The bytes of a document at one point in time
+///
+/// Cloning is cheap, so a search can run on a copy while editing goes on.
+#[derive(Debug, Clone)]
+pub struct ByteContent {
+    file: Arc<File>,
+    added: Arc<Vec<u8>>,
+    pieces: Vec<Piece>,
+    len: u64,
+}
+
+impl ByteContent {
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Read up to `len` bytes at `offset` (fewer at the end)
+    pub fn read(&self, offset: u64, len: usize) -> FsResult<Vec<u8>> {
+        let end = offset.saturating_add(len as u64).min(self.len);
+        let mut out = Vec::with_capacity(end.saturating_sub(offset) as usize);
+        let mut position = 0;
+        for piece in &self.pieces {
+            if position >= end {
+                break;
+            }
+            let piece_end = position + piece.len;
+            if piece_end > offset {
+                let from = piece.start + offset.max(position) - position;
+                let to = piece.start + end.min(piece_end) - position;
+                match piece.source {
+                    Source::Added => out.extend_from_slice(&self.added[from as usize..to as usize]),
+                    Source::Original => {
+                        let bytes = read_at(&self.file, from, (to - from) as usize)?;
+                        if bytes.len() as u64 != to - from {
+                            return Err(FsError::Io(std::io::Error::new(
+                                std::io::ErrorKind::UnexpectedEof,
+                                "the file was truncated while being edited",
+                            )));
+                        }
+                        out.extend_from_slice(&bytes);
+                    }
+                }
+            }
+            position = piece_end;
+        }
+        Ok(out)
+    }
+
+    /// Find `pattern`, wrapping around the end of the document
+    ///
+    /// Searching forward returns the first match starting at or after
+    /// `from`; searching backward returns the last match starting before
+    /// it. Returns `FsError::Cancelled` once `cancel` is set.
+    pub fn find(&self, pattern: &[u8], from: u64, backward: bool, cancel: &AtomicBool) -> FsResult<Option<u64>> {
+        if pattern.is_empty() || pattern.len() as u64 > self.len {
+            return Ok(None);
+        }
+        let from = from.min(self.len);
+        // Matches starting before `from` end no later than this
+        let before = (from + pattern.len() as u64 - 1).min(self.len);
+        if backward {
+            match self.rfind_in(pattern, 0, before, cancel)? {
+                Some(found) => Ok(Some(found)),
+                None => self.rfind_in(pattern, from, self.len, cancel),
+            }
+        } else {
+            match self.find_in(pattern, from, self.len, cancel)? {
+                Some(found) => Ok(Some(found)),
+                None => self.find_in(pattern, 0, before, cancel),
+            }
+        }
+    }
+
+    /// Write every byte to `out`
+    pub fn write_to(&self, out: &mut impl Write, cancel: &AtomicBool) -> FsResult<()> {
+        let mut offset = 0;
+        while offset < self.len {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+            let chunk = self.read(offset, CHUNK_SIZE)?;
+            out.write_all(&chunk)?;
+            offset += chunk.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// First match lying wholly within `start..end`
+    fn find_in(&self, pattern: &[u8], start: u64, end: u64, cancel: &AtomicBool) -> FsResult<Option<u64>> {
+        let finder = memmem::Finder::new(pattern);
+        let chunk_size = CHUNK_SIZE.max(pattern.len() * 2) as u64;
+        let overlap = pattern.len() as u64 - 1;
+        let mut offset = start;
+        while offset + pattern.len() as u64 <= end {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+            let len = (end - offset).min(chunk_size);
+            let chunk = self.read(offset, len as usize)?;
+            if let Some(position) = finder.find(&chunk) {
+                return Ok(Some(offset + position as u64));
+            }
+            if offset + len >= end {
+                break;
+            }
+            offset += len - overlap;
+        }
+        Ok(None)
+    }
+
+    /// Last match lying wholly within `start..end`
+    fn rfind_in(&self, pattern: &[u8], start: u64, end: u64, cancel: &AtomicBool) -> FsResult<Option<u64>> {
+        let finder = memmem::FinderRev::new(pattern);
+        let chunk_size = CHUNK_SIZE.max(pattern.len() * 2) as u64;
+        let overlap = pattern.len() as u64 - 1;
+        let mut chunk_end = end;
+        while chunk_end >= start + pattern.len() as u64 {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+            let chunk_start = chunk_end - (chunk_end - start).min(chunk_size);
+            let chunk = self.read(chunk_start, (chunk_end - chunk_start) as usize)?;
+            if let Some(position) = finder.rfind(&chunk) {
+                return Ok(Some(chunk_start + position as u64));
+            }
+            if chunk_start == start {
+                break;
+            }
+            chunk_end = chunk_start + overlap;
+        }
+        Ok(None)
+    }
+
+    /// Make `at` a piece boundary and return the index of the piece starting there
+    fn split(&mut self, at: u64) -> usize {
+        let mut position = 0;
+        for i in 0..self.pieces.len() {
+            let piece = self.pieces[i];
+            if position == at {
+                return i;
+            }
+            if at < position + piece.len {
+                let head = at - position;
+                self.pieces[i].len = head;
+                self.pieces.insert(i + 1, Piece {
+                    start: piece.start + head,
+                    len: piece.len - head,
+                    ..piece
+                });
+                return i + 1;
+            }
+            position += piece.len;
+        }
+        self.pieces.len()
+    }
+
+    /// Replace `remove` bytes at `offset` with `inserted`, returning the pieces removed
+    fn splice(&mut self, offset: u64, remove: u64, inserted: &[Piece]) -> Vec<Piece> {
+        let first = self.split(offset);
+        let last = self.split(offset + remove);
+        let removed: Vec<Piece> = self.pieces.splice(first..last, inserted.iter().copied()).collect();
+        self.len = self.len - remove + total_len(inserted);
+
+        // Join pieces that continue one another, as typing produces
+        let mut merged: Vec<Piece> = Vec::with_capacity(self.pieces.len());
+        for piece in self.pieces.drain(..) {
+            match merged.last_mut() {
+                Some(last) if last.source == piece.source && last.start + last.len == piece.start => {
+                    last.len += piece.len;
+                }
+                _ => merged.push(piece),
+            }
+        }
+        self.pieces = merged;
+        removed
+    }
+}
+
+/// A file being edited byte by byte, with undo and redo
+#[derive(Debug)]
+pub struct ByteDocument {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    content: ByteContent,
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    /// Undo depth matching the file on disk, None once that state is gone
+    saved_depth: Option<usize>,
+}
+
+impl ByteDocument {
+    /// Open a file for editing
+    pub fn open(path: &Path) -> FsResult<Self> {
+        // Edit the target of a symlink, not the link itself
+        let path = path.canonicalize()?;
+        let file = open_shared(&path)?;
+        let metadata = file.metadata()?;
+        if !metadata.is_file() {
+            return Err(FsError::NotSupported(format!("editing {} as bytes", path.display())));
+        }
+        let len = metadata.len();
+        let pieces = if len > 0 {
+            vec![Piece { source: Source::Original, start: 0, len }]
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            path,
+            modified: metadata.modified().ok(),
+            content: ByteContent {
+                file: Arc::new(file),
+                added: Arc::new(Vec::new()),
+                pieces,
+                len,
+            },
+            undo: Vec::new(),
+            redo: Vec::new(),
+            saved_depth: Some(0),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn content(&self) -> &ByteContent {
+        &self.content
+    }
+
+    pub fn len(&self) -> u64 {
+        self.content.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.content.len == 0
+    }
+
+    /// Whether the document differs from the file on disk
+    pub fn is_modified(&self) -> bool {
+        self.saved_depth != Some(self.undo.len())
+    }
+
+    /// Replace the bytes at `offset`, growing the document if they run past its end
+    pub fn overwrite(&mut self, offset: u64, bytes: &[u8]) {
+        let offset = offset.min(self.len());
+        let remove = (self.len() - offset).min(bytes.len() as u64);
+        self.edit(offset, remove, bytes);
+    }
+
+    /// Insert bytes before `offset`
+    pub fn insert(&mut self, offset: u64, bytes: &[u8]) {
+        self.edit(offset.min(self.len()), 0, bytes);
+    }
+
+    /// Delete the bytes in `range`
+    pub fn delete(&mut self, range: Range<u64>) {
+        let start = range.start.min(self.len());
+        let end = range.end.clamp(start, self.len());
+        self.edit(start, end - start, &[]);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Undo the last edit and return the range it restored
+    pub fn undo(&mut self) -> Option<Range<u64>> {
+        let edit = self.undo.pop()?;
+        self.content.splice(edit.offset, total_len(&edit.inserted), &edit.removed);
+        let range = edit.offset..edit.offset + total_len(&edit.removed);
+        self.redo.push(edit);
+        Some(range)
+    }
+
+    /// Redo the last undone edit and return the range it wrote
+    pub fn redo(&mut self) -> Option<Range<u64>> {
+        let edit = self.redo.pop()?;
+        self.content.splice(edit.offset, total_len(&edit.removed), &edit.inserted);
+        let range = edit.offset..edit.offset + total_len(&edit.inserted);
+        self.undo.push(edit);
+        Some(range)
+    }
+
+    /// Write the document over its file through a temporary file
+    ///
+    /// Fails if the file was changed on disk since it was opened. Once
+    /// saved the document is reopened from the new file and the undo
+    /// history is cleared, since it refers to the old contents.
+    pub fn save(&mut self, cancel: &AtomicBool) -> FsResult<()> {
+        let metadata = std::fs::metadata(&self.path)?;
+        if metadata.modified().ok() != self.modified {
+            return Err(FsError::Conflict(format!("{} was changed by another program", self.path.display())));
+        }
+
+        let temp = crate::copy::temp_path(&self.path);
+        let result = (|| -> FsResult<()> {
+            let mut out = BufWriter::new(File::create(&temp)?);
+            self.content.write_to(&mut out, cancel)?;
+            let file = out.into_inner().map_err(|e| e.into_error())?;
+            file.sync_all()?;
+            drop(file);
+            std::fs::set_permissions(&temp, metadata.permissions())?;
+            std::fs::rename(&temp, &self.path)?;
+            Ok(())
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp);
+        }
+        result?;
+
+        let path = self.path.clone();
+        *self = Self::open(&path)?;
+        Ok(())
+    }
+
+    fn edit(&mut self, offset: u64, remove: u64, bytes: &[u8]) {
+        if remove == 0 && bytes.is_empty() {
+            return;
+        }
+        let inserted = if bytes.is_empty() {
+            Vec::new()
+        } else {
+            let added = Arc::make_mut(&mut self.content.added);
+            let start = added.len() as u64;
+            added.extend_from_slice(bytes);
+            vec![Piece { source: Source::Added, start, len: bytes.len() as u64 }]
+        };
+        let removed = self.content.splice(offset, remove, &inserted);
+
+        // The saved state can no longer be reached by redoing
+        if self.saved_depth.is_some_and(|depth| depth > self.undo.len()) {
+            self.saved_depth = None;
+        }
+        self.redo.clear();
+        self.undo.push(Edit { offset, removed, inserted });
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+            self.saved_depth = self.saved_depth.and_then(|depth| depth.checked_sub(1));
+        }
+    }
+}
+
+/// One row of the data inspector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectedValue {
+    pub label: &'static str,
+    /// Value read as little-endian, None if there are too few bytes
+    pub little_endian: Option<String>,
+    /// Value read as big-endian, None if there are too few bytes
+    pub big_endian: Option<String>,
+}
+
+/// Decode the bytes at the cursor as integers, floats and timestamps
+///
+/// Rows that do not depend on byte order show the same value in both
+/// columns. Timestamps outside chrono's range are shown as None.
+pub fn inspect(bytes: &[u8]) -> Vec<InspectedValue> {
+    let single = |label, value: Option<String>| InspectedValue {
+        label,
+        little_endian: value.clone(),
+        big_endian: value,
+    };
+    vec![
+        single("binary", bytes.first().map(|b| format!("{:08b}", b))),
+        single("int8", bytes.first().map(|&b| (b as i8).to_string())),
+        single("uint8", bytes.first().map(|b| b.to_string())),
+        both("int16", bytes, |a: [u8; 2]| Some(i16::from_le_bytes(a).to_string())),
+        both("uint16", bytes, |a: [u8; 2]| Some(u16::from_le_bytes(a).to_string())),
+        both("int32", bytes, |a: [u8; 4]| Some(i32::from_le_bytes(a).to_string())),
+        both("uint32", bytes, |a: [u8; 4]| Some(u32::from_le_bytes(a).to_string())),
+        both("int64", bytes, |a: [u8; 8]| Some(i64::from_le_bytes(a).to_string())),
+        both("uint64", bytes, |a: [u8; 8]| Some(u64::from_le_bytes(a).to_string())),
+        both("float32", bytes, |a: [u8; 4]| Some(format!("{:?}", f32::from_le_bytes(a)))),
+        both("float64", bytes, |a: [u8; 8]| Some(format!("{:?}", f64::from_le_bytes(a)))),
+        both("Unix time (32-bit)", bytes, |a: [u8; 4]| format_time(i32::from_le_bytes(a) as i64, 0)),
+        both("Unix time (64-bit)", bytes, |a: [u8; 8]| format_time(i64::from_le_bytes(a), 0)),
+        both("FILETIME", bytes, |a: [u8; 8]| {
+            let ticks = u64::from_le_bytes(a);
+            let seconds = (ticks / 10_000_000) as i64 - FILETIME_EPOCH_OFFSET;
+            format_time(seconds, (ticks % 10_000_000) as u32 * 100)
+        }),
+        both("DOS date/time", bytes, |a: [u8; 4]| {
+            let value = u32::from_le_bytes(a);
+            let (time, date) = (value & 0xFFFF, value >> 16);
+            NaiveDate::from_ymd_opt(1980 + (date >> 9) as i32, (date >> 5) & 0xF, date & 0x1F)?
+                .and_hms_opt(time >> 11, (time >> 5) & 0x3F, (time & 0x1F) * 2)
+                .map(|datetime| datetime.format("%Y-%m-%d %H:%M:%S").to_string())
+        }),
+        single("UTF-8", (1..=bytes.len().min(4)).find_map(|len| {
+            let c = std::str::from_utf8(&bytes[..len]).ok()?.chars().next()?;
+            Some(format!("{:?} U+{:04X}", c, c as u32))
+        })),
+    ]
+}
+
+/// Parse bytes written in hex, such as `DE AD be ef` or `0xdeadbeef`
+pub fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: String = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|token| token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")).unwrap_or(token))
+        .collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// A row whose value depends on byte order; `decode` reads a little-endian array
+fn both<const N: usize>(label: &'static str, bytes: &[u8], decode: impl Fn([u8; N]) -> Option<String>) -> InspectedValue {
+    let little: Option<[u8; N]> = bytes.get(..N).and_then(|slice| slice.try_into().ok());
+    InspectedValue {
+        label,
+        little_endian: little.and_then(&decode),
+        big_endian: little.and_then(|mut array| {
+            array.reverse();
+            decode(array)
+        }),
+    }
+}
+
+fn format_time(seconds: i64, nanos: u32) -> Option<String> {
+    DateTime::<Utc>::from_timestamp(seconds, nanos)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+}
+
+fn total_len(pieces: &[Piece]) -> u64 {
+    pieces.iter().map(|piece| piece.len).sum()
+}
+
+/// Open for reading while still letting the file be replaced by a rename
+fn open_shared(path: &Path) -> std::io::Result<File> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE
+        std::fs::OpenOptions::new().read(true).share_mode(0x7).open(path)
+    }
+    #[cfg(not(windows))]
+    {
+        File::open(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(contents: &[u8]) -> (tempfile::TempDir, ByteDocument) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.bin");
+        std::fs::write(&path, contents).unwrap();
+        let document = ByteDocument::open(&path).unwrap();
+        (dir, document)
+    }
+
+    fn bytes(document: &ByteDocument) -> Vec<u8> {
+        document.content().read(0, document.len() as usize).unwrap()
+    }
+
+    #[test]
+    fn edits_undo_and_redo() {
+        let (_dir, mut document) = open(b"0123456789");
+        document.overwrite(2, b"ab");
+        assert_eq!(bytes(&document), b"01ab456789");
+        document.insert(5, b"XYZ");
+        assert_eq!(bytes(&document), b"01ab4XYZ56789");
+        document.delete(0..3);
+        assert_eq!(bytes(&document), b"b4XYZ56789");
+        // Overwriting past the end grows the document
+        document.overwrite(8, b"!!!");
+        assert_eq!(bytes(&document), b"b4XYZ567!!!");
+        assert!(document.is_modified());
+
+        assert_eq!(document.undo(), Some(8..10));
+        assert_eq!(document.undo(), Some(0..3));
+        assert_eq!(bytes(&document), b"01ab4XYZ56789");
+        assert_eq!(document.undo(), Some(5..5));
+        assert_eq!(document.undo(), Some(2..4));
+        assert_eq!(bytes(&document), b"0123456789");
+        assert!(!document.can_undo());
+        assert!(!document.is_modified());
+
+        assert_eq!(document.redo(), Some(2..4));
+        assert_eq!(document.redo(), Some(5..8));
+        assert_eq!(bytes(&document), b"01ab4XYZ56789");
+
+        // A new edit drops what could still be redone
+        document.insert(0, b"-");
+        assert!(!document.can_redo());
+        assert_eq!(bytes(&document), b"-01ab4XYZ56789");
+    }
+
+    #[test]
+    fn save_writes_edits() {
+        let (_dir, mut document) = open(b"hello world");
+        document.overwrite(0, b"J");
+        document.delete(5..11);
+        document.insert(5, b"!");
+        document.save(&AtomicBool::new(false)).unwrap();
+        assert_eq!(std::fs::read(document.path()).unwrap(), b"Jello!");
+        assert!(!document.is_modified());
+        assert!(!document.can_undo());
+    }
+
+    #[test]
+    fn find_wraps_around_across_chunks() {
+        let mut contents = vec![0u8; CHUNK_SIZE * 2 + 100];
+        let pattern = b"needle";
+        // One match straddling the first chunk boundary, one near the end
+        let first = CHUNK_SIZE as u64 - 3;
+        let second = CHUNK_SIZE as u64 * 2 + 50;
+        for at in [first, second] {
+            contents[at as usize..at as usize + pattern.len()].copy_from_slice(pattern);
+        }
+        let (_dir, document) = open(&contents);
+        let content = document.content();
+        let cancel = AtomicBool::new(false);
+
+        assert_eq!(content.find(pattern, 0, false, &cancel).unwrap(), Some(first));
+        assert_eq!(content.find(pattern, first + 1, false, &cancel).unwrap(), Some(second));
+        assert_eq!(content.find(pattern, second + 1, false, &cancel).unwrap(), Some(first));
+        assert_eq!(content.find(pattern, second, true, &cancel).unwrap(), Some(first));
+        assert_eq!(content.find(pattern, first, true, &cancel).unwrap(), Some(second));
+        assert_eq!(content.find(b"missing", 0, false, &cancel).unwrap(), None);
+    }
+
+    #[test]
+    fn find_sees_edits() {
+        let (_dir, mut document) = open(b"aaaaaaaaaa");
+        document.insert(4, b"xy");
+        document.overwrite(6, b"z");
+        let cancel = AtomicBool::new(false);
+        assert_eq!(document.content().find(b"xyz", 0, false, &cancel).unwrap(), Some(4));
+    }
+
+    #[test]
+    fn parse_hex_accepts_common_forms() {
+        assert_eq!(parse_hex("DE AD be ef"), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(parse_hex("0xdeadbeef"), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(parse_hex("01,0x02, 03"), Some(vec![1, 2, 3]));
+        assert_eq!(parse_hex("abc"), None);
+        assert_eq!(parse_hex("zz"), None);
+        assert_eq!(parse_hex(""), None);
+        assert_eq!(parse_hex("é0"), None);
+    }
+
+    #[test]
+    fn inspect_reads_both_byte_orders() {
+        let values = inspect(&[0x01, 0x02, 0x03, 0x04]);
+        let row = |label| values.iter().find(|value| value.label == label).unwrap();
+        assert_eq!(row("uint8").little_endian.as_deref(), Some("1"));
+        assert_eq!(row("uint16").little_endian.as_deref(), Some("513"));
+        assert_eq!(row("uint16").big_endian.as_deref(), Some("258"));
+        assert_eq!(row("uint32").little_endian.as_deref(), Some("67305985"));
+        assert_eq!(row("uint32").big_endian.as_deref(), Some("16909060"));
+        assert_eq!(row("uint64").little_endian, None);
+        assert_eq!(row("Unix time (32-bit)").big_endian.as_deref(), Some("1970-07-15 16:57:40 UTC"));
+
+        let values = inspect("é".as_bytes());
+        let utf8 = values.iter().find(|value| value.label == "UTF-8").unwrap();
+        assert_eq!(utf8.little_endian.as_deref(), Some("'é' U+00E9"));
+    }
+}
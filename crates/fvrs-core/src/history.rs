@@ -0,0 +1,279 @@
+//! Directory history ranked by frecency, and fuzzy matching of paths
+//!
+//! Every visit adds one to a directory's rank. When ranking, the rank is
+//! weighted by how long ago the last visit was, so a folder used a lot
+//! last month sinks below one used a few times today. Once the ranks add
+//! up to more than `MAX_TOTAL_RANK` they are all scaled down and entries
+//! that fall below one are forgotten, which keeps the history bounded
+//! without a hard cut-off.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, Local};
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult};
+
+/// Ranks are scaled down once they add up to more than this
+const MAX_TOTAL_RANK: f64 = 10_000.0;
+/// Number of recent visits kept
+const MAX_RECENT: usize = 200;
+
+/// A directory and how often it was visited
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub path: PathBuf,
+    /// Number of visits, reduced as the history ages
+    pub rank: f64,
+    pub last_visit: DateTime<Local>,
+}
+
+impl HistoryEntry {
+    /// Rank weighted by the time since the last visit
+    pub fn frecency(&self, now: DateTime<Local>) -> f64 {
+        let age = now - self.last_visit;
+        let weight = if age < Duration::hours(1) {
+            4.0
+        } else if age < Duration::days(1) {
+            2.0
+        } else if age < Duration::weeks(1) {
+            0.5
+        } else {
+            0.25
+        };
+        self.rank * weight
+    }
+}
+
+/// One visit in the recent list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentVisit {
+    pub path: PathBuf,
+    pub time: DateTime<Local>,
+}
+
+/// Visited directories, kept across sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectoryHistory {
+    entries: Vec<HistoryEntry>,
+    /// Newest first, one visit per directory
+    recent: Vec<RecentVisit>,
+}
+
+impl DirectoryHistory {
+    /// Read a history saved by `save`; a missing file is an empty history
+    pub fn load(path: &Path) -> FsResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| FsError::Serialization(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the history, replacing the file only once it is complete
+    pub fn save(&self, path: &Path) -> FsResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string(self).map_err(|e| FsError::Serialization(e.to_string()))?;
+        let temp = crate::copy::temp_path(path);
+        std::fs::write(&temp, text)
+            .and_then(|()| std::fs::rename(&temp, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&temp);
+                e.into()
+            })
+    }
+
+    /// Record a visit to `path`
+    pub fn record(&mut self, path: &Path, now: DateTime<Local>) {
+        match self.entries.iter_mut().find(|entry| entry.path == path) {
+            Some(entry) => {
+                entry.rank += 1.0;
+                entry.last_visit = now;
+            }
+            None => self.entries.push(HistoryEntry { path: path.to_path_buf(), rank: 1.0, last_visit: now }),
+        }
+
+        self.recent.retain(|visit| visit.path != path);
+        self.recent.insert(0, RecentVisit { path: path.to_path_buf(), time: now });
+        self.recent.truncate(MAX_RECENT);
+
+        let total: f64 = self.entries.iter().map(|entry| entry.rank).sum();
+        if total > MAX_TOTAL_RANK {
+            let factor = 0.9 * MAX_TOTAL_RANK / total;
+            for entry in &mut self.entries {
+                entry.rank *= factor;
+            }
+            self.entries.retain(|entry| entry.rank >= 1.0);
+        }
+    }
+
+    /// Recently visited directories, newest first
+    pub fn recent(&self) -> &[RecentVisit] {
+        &self.recent
+    }
+
+    /// Directories with their frecency, highest first
+    pub fn ranked(&self, now: DateTime<Local>) -> Vec<(&HistoryEntry, f64)> {
+        let mut ranked: Vec<_> = self.entries.iter().map(|entry| (entry, entry.frecency(now))).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+
+    /// Frecency of every known directory, for combining with other scores
+    pub fn frecencies(&self, now: DateTime<Local>) -> HashMap<&Path, f64> {
+        self.entries.iter().map(|entry| (entry.path.as_path(), entry.frecency(now))).collect()
+    }
+
+    /// Forget one directory
+    pub fn remove(&mut self, path: &Path) {
+        self.entries.retain(|entry| entry.path != path);
+        self.recent.retain(|visit| visit.path != path);
+    }
+
+    /// Forget directories that no longer exist, returning how many were removed
+    pub fn remove_missing(&mut self) -> usize {
+        let before = self.entries.len() + self.recent.len();
+        self.entries.retain(|entry| entry.path.is_dir());
+        self.recent.retain(|visit| visit.path.is_dir());
+        before - self.entries.len() - self.recent.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recent.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.recent.is_empty()
+    }
+}
+
+/// Score for each matched character
+const SCORE_MATCH: i32 = 16;
+/// Extra score for a match right after the previous one
+const BONUS_CONSECUTIVE: i32 = 8;
+/// Extra score for a match at the start of a word
+const BONUS_BOUNDARY: i32 = 8;
+/// Extra score for a match at a lower-to-upper case change
+const BONUS_CAMEL: i32 = 6;
+/// Extra score for a match in the last path component
+const BONUS_FILE_NAME: i32 = 4;
+/// Penalty for skipping characters between two matches
+const PENALTY_GAP_START: i32 = 3;
+/// Further penalty for every skipped character after the first
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+/// Result of `fuzzy_match`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Character (not byte) indices of the matched characters, ascending
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` against `candidate` as a subsequence, like fzf
+///
+/// Words separated by spaces must each match, in any order. The query is
+/// case-insensitive unless it contains an upper-case letter. Matches at the
+/// start of words, in a row and in the last path component score higher.
+/// Returns None if some word does not match.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let case_sensitive = query.chars().any(char::is_uppercase);
+    let chars: Vec<char> = candidate.chars().collect();
+    let folded: Vec<char> = if case_sensitive {
+        chars.clone()
+    } else {
+        chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect()
+    };
+    let file_name_start = chars.iter().rposition(|&c| c == '/' || c == '\\').map_or(0, |i| i + 1);
+
+    let mut result = FuzzyMatch { score: 0, positions: Vec::new() };
+    for word in query.split_whitespace() {
+        let word: Vec<char> = if case_sensitive { word.chars().collect() } else { word.to_lowercase().chars().collect() };
+        let matched = match_word(&word, &chars, &folded, file_name_start)?;
+        result.score += matched.score;
+        result.positions.extend(matched.positions);
+    }
+    result.positions.sort_unstable();
+    result.positions.dedup();
+    Some(result)
+}
+
+/// Best-scoring placement of one word, by dynamic programming over the
+/// query and candidate characters
+fn match_word(word: &[char], chars: &[char], folded: &[char], file_name_start: usize) -> Option<FuzzyMatch> {
+    let (m, n) = (word.len(), chars.len());
+    if m == 0 {
+        return Some(FuzzyMatch { score: 0, positions: Vec::new() });
+    }
+    if m > n {
+        return None;
+    }
+
+    let bonus: Vec<i32> = (0..n)
+        .map(|j| {
+            let mut bonus = if j >= file_name_start { BONUS_FILE_NAME } else { 0 };
+            match j.checked_sub(1).map(|p| chars[p]) {
+                None => bonus += BONUS_BOUNDARY,
+                Some('/' | '\\' | '_' | '-' | '.' | ' ') => bonus += BONUS_BOUNDARY,
+                Some(prev) if prev.is_lowercase() && chars[j].is_uppercase() => bonus += BONUS_CAMEL,
+                _ => {}
+            }
+            bonus
+        })
+        .collect();
+
+    // score[i][j]: best score with word[..=i] matched and word[i] at chars[j]
+    const NONE: i32 = i32::MIN / 2;
+    let mut score = vec![vec![NONE; n]; m];
+    let mut from = vec![vec![usize::MAX; n]; m];
+    for j in 0..n {
+        if folded[j] == word[0] {
+            score[0][j] = SCORE_MATCH + bonus[j];
+        }
+    }
+    for i in 1..m {
+        // Best of score[i - 1][k] + PENALTY_GAP_EXTENSION * k over k < j - 1,
+        // so the gap penalty can be applied in constant time
+        let mut best_far = (NONE, usize::MAX);
+        for j in i..n {
+            if j >= 2 {
+                let k = j - 2;
+                let value = score[i - 1][k] + PENALTY_GAP_EXTENSION * k as i32;
+                if score[i - 1][k] > NONE && value > best_far.0 {
+                    best_far = (value, k);
+                }
+            }
+            if folded[j] != word[i] {
+                continue;
+            }
+            let far = if best_far.0 > NONE {
+                let gap = PENALTY_GAP_EXTENSION * (j as i32 - 1) + PENALTY_GAP_START - PENALTY_GAP_EXTENSION;
+                best_far.0 - gap
+            } else {
+                NONE
+            };
+            let near = if score[i - 1][j - 1] > NONE { score[i - 1][j - 1] + BONUS_CONSECUTIVE } else { NONE };
+            let (prev_score, prev) = if near >= far { (near, j - 1) } else { (far, best_far.1) };
+            if prev_score > NONE {
+                score[i][j] = prev_score + SCORE_MATCH + bonus[j];
+                from[i][j] = prev;
+            }
+        }
+    }
+
+    let (end, &best) = score[m - 1].iter().enumerate().max_by_key(|(_, &s)| s)?;
+    if best <= NONE {
+        return None;
+    }
+    let mut positions = vec![0; m];
+    let mut j = end;
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some(FuzzyMatch { score: best, positions })
+}
@@ -0,0 +1,309 @@
+//! Image metadata and simple transforms
+//!
+//! `read_exif` summarises the EXIF block of a photo (camera, date, exposure,
+//! GPS position and orientation) and keeps every readable field for a
+//! detailed listing. `load_image` decodes an image with its EXIF orientation
+//! already applied, and `save_transformed` rotates or flips an image on disk.
+//! Saving re-encodes the file in its own format: JPEG is recompressed at
+//! high quality, WebP is written losslessly, and the EXIF block is kept with
+//! its orientation reset since the pixels are stored upright afterwards.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use exif::{In, Reader, Tag, Value};
+use image::codecs::gif::GifDecoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{PngDecoder, PngEncoder};
+use image::codecs::webp::{WebPDecoder, WebPEncoder};
+use image::{AnimationDecoder, DynamicImage, ImageDecoder, ImageEncoder, ImageFormat, ImageReader};
+use image::metadata::Orientation;
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult};
+
+/// Formats that can be previewed and transformed
+const SUPPORTED_FORMATS: &[ImageFormat] = &[
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::Gif,
+    ImageFormat::WebP,
+    ImageFormat::Bmp,
+];
+/// Quality used when a JPEG is re-encoded
+const JPEG_QUALITY: u8 = 95;
+/// Longer field values are cut off in `ExifSummary::fields`
+const MAX_FIELD_LENGTH: usize = 256;
+
+/// A rotation or flip applied to the upright image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImageTransform {
+    /// 90° counter-clockwise
+    RotateLeft,
+    /// 90° clockwise
+    RotateRight,
+    Rotate180,
+    /// Mirror left to right
+    FlipHorizontal,
+    /// Mirror top to bottom
+    FlipVertical,
+}
+
+impl ImageTransform {
+    /// Apply this transform to decoded pixels
+    pub fn apply(self, image: &DynamicImage) -> DynamicImage {
+        match self {
+            ImageTransform::RotateLeft => image.rotate270(),
+            ImageTransform::RotateRight => image.rotate90(),
+            ImageTransform::Rotate180 => image.rotate180(),
+            ImageTransform::FlipHorizontal => image.fliph(),
+            ImageTransform::FlipVertical => image.flipv(),
+        }
+    }
+}
+
+/// GPS position recorded in EXIF
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GpsPosition {
+    /// Degrees, negative south of the equator
+    pub latitude: f64,
+    /// Degrees, negative west of Greenwich
+    pub longitude: f64,
+    /// Metres, negative below sea level
+    pub altitude: Option<f64>,
+}
+
+/// The parts of an EXIF block worth showing at a glance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExifSummary {
+    /// Make and model
+    pub camera: Option<String>,
+    pub lens: Option<String>,
+    /// When the photo was taken, as `YYYY-MM-DD hh:mm:ss`
+    pub date_taken: Option<String>,
+    /// Exposure time, aperture, ISO and focal length
+    pub exposure: Option<String>,
+    pub gps: Option<GpsPosition>,
+    /// EXIF orientation value (1 = upright, 2-8 = flipped or rotated)
+    pub orientation: Option<u16>,
+    /// Every field of the primary image as (tag, value)
+    pub fields: Vec<(String, String)>,
+}
+
+/// Whether the file is an image that can be previewed and transformed, judging by its extension
+pub fn is_supported(path: &Path) -> bool {
+    ImageFormat::from_path(path).is_ok_and(|format| SUPPORTED_FORMATS.contains(&format))
+}
+
+/// Read the EXIF block of an image, or `None` if it has none
+pub fn read_exif(path: &Path) -> FsResult<Option<ExifSummary>> {
+    // Only these containers carry EXIF among the supported formats
+    let format = ImageFormat::from_path(path).map_err(image_error)?;
+    if !matches!(format, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP) {
+        return Ok(None);
+    }
+
+    let mut reader = BufReader::new(File::open(path)?);
+    let exif = match Reader::new().read_from_container(&mut reader) {
+        Ok(exif) => exif,
+        Err(exif::Error::NotFound(_)) => return Ok(None),
+        Err(exif::Error::Io(e)) => return Err(FsError::Io(e)),
+        Err(e) => return Err(FsError::Image(format!("{}: {}", path.display(), e))),
+    };
+
+    let text = |tag: Tag| {
+        exif.get_field(tag, In::PRIMARY)
+            .map(|field| field.display_value().with_unit(&exif).to_string())
+            .map(|value| value.trim_matches('"').trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let camera = match (text(Tag::Make), text(Tag::Model)) {
+        // Many cameras repeat the make in the model name
+        (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model),
+    };
+    let lens = text(Tag::LensModel).or_else(|| text(Tag::LensMake));
+    let date_taken = text(Tag::DateTimeOriginal).or_else(|| text(Tag::DateTime));
+    let exposure: Vec<String> = [
+        text(Tag::ExposureTime),
+        text(Tag::FNumber),
+        text(Tag::PhotographicSensitivity).map(|iso| format!("ISO {}", iso)),
+        text(Tag::FocalLength),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let orientation = exif.get_field(Tag::Orientation, In::PRIMARY)
+        .and_then(|field| field.value.get_uint(0))
+        .and_then(|value| u16::try_from(value).ok());
+
+    let fields = exif.fields()
+        .filter(|field| field.ifd_num == In::PRIMARY && field.tag != Tag::MakerNote)
+        .map(|field| {
+            let mut value = field.display_value().with_unit(&exif).to_string();
+            if value.len() > MAX_FIELD_LENGTH {
+                let end = (0..=MAX_FIELD_LENGTH).rev().find(|&i| value.is_char_boundary(i)).unwrap_or(0);
+                value.truncate(end);
+                value.push('…');
+            }
+            (field.tag.to_string(), value)
+        })
+        .collect();
+
+    Ok(Some(ExifSummary {
+        camera,
+        lens,
+        date_taken,
+        exposure: (!exposure.is_empty()).then(|| exposure.join("  ")),
+        gps: gps_position(&exif),
+        orientation,
+        fields,
+    }))
+}
+
+/// Decode an image and turn it upright according to its EXIF orientation
+///
+/// Animated images yield their first frame.
+pub fn load_image(path: &Path) -> FsResult<DynamicImage> {
+    let mut decoder = ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()
+        .map_err(|e| FsError::Image(format!("{}: {}", path.display(), e)))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder)
+        .map_err(|e| FsError::Image(format!("{}: {}", path.display(), e)))?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Rotate or flip an image file in place
+///
+/// The transforms are applied in order to the upright image and the result
+/// is written in the file's own format through a temporary file, keeping the
+/// original's permissions. Animated images are refused, since only their
+/// first frame would survive.
+pub fn save_transformed(path: &Path, transforms: &[ImageTransform]) -> FsResult<()> {
+    if transforms.is_empty() {
+        return Ok(());
+    }
+    // Replace the target of a symlink, not the link itself
+    let path = path.canonicalize()?;
+    let format = ImageReader::open(&path)?
+        .with_guessed_format()?
+        .format()
+        .filter(|format| SUPPORTED_FORMATS.contains(format))
+        .ok_or_else(|| FsError::NotSupported(format!("saving images like {}", path.display())))?;
+    if is_animated(&path, format)? {
+        return Err(FsError::NotSupported("transforming animated images".to_string()));
+    }
+
+    let mut decoder = ImageReader::open(&path)?
+        .with_guessed_format()?
+        .into_decoder()
+        .map_err(image_error)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut exif = decoder.exif_metadata().ok().flatten();
+    let mut image = DynamicImage::from_decoder(decoder).map_err(image_error)?;
+    image.apply_orientation(orientation);
+    for transform in transforms {
+        image = transform.apply(&image);
+    }
+    // The pixels are upright now, so viewers must not rotate them again
+    if let Some(exif) = exif.as_mut() {
+        let _ = Orientation::remove_from_exif_chunk(exif);
+    }
+
+    let permissions = std::fs::metadata(&path)?.permissions();
+    let temp = crate::copy::temp_path(&path);
+    let result = (|| -> FsResult<()> {
+        let mut out = BufWriter::new(File::create(&temp)?);
+        encode(&image, format, exif, &mut out)?;
+        out.flush()?;
+        drop(out);
+        std::fs::set_permissions(&temp, permissions)?;
+        std::fs::rename(&temp, &path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Encode `image` as `format`, carrying the EXIF block over where the format allows it
+fn encode(image: &DynamicImage, format: ImageFormat, exif: Option<Vec<u8>>, out: &mut impl Write) -> FsResult<()> {
+    fn with_exif(mut encoder: impl ImageEncoder, image: &DynamicImage, exif: Option<Vec<u8>>) -> FsResult<()> {
+        if let Some(exif) = exif {
+            // Losing metadata is better than refusing to save
+            let _ = encoder.set_exif_metadata(exif);
+        }
+        image.write_with_encoder(encoder).map_err(image_error)
+    }
+
+    match format {
+        ImageFormat::Jpeg => with_exif(JpegEncoder::new_with_quality(out, JPEG_QUALITY), image, exif),
+        ImageFormat::Png => with_exif(PngEncoder::new(out), image, exif),
+        ImageFormat::WebP => with_exif(WebPEncoder::new_lossless(out), image, exif),
+        _ => {
+            let mut buffer = std::io::Cursor::new(Vec::new());
+            image.write_to(&mut buffer, format).map_err(image_error)?;
+            out.write_all(buffer.get_ref())?;
+            Ok(())
+        }
+    }
+}
+
+/// Whether the file holds more than one frame
+fn is_animated(path: &Path, format: ImageFormat) -> FsResult<bool> {
+    let reader = || -> FsResult<BufReader<File>> { Ok(BufReader::new(File::open(path)?)) };
+    let animated = match format {
+        ImageFormat::Gif => GifDecoder::new(reader()?).map_err(image_error)?
+            .into_frames()
+            .take(2)
+            .count() > 1,
+        ImageFormat::Png => PngDecoder::new(reader()?).map_err(image_error)?
+            .is_apng()
+            .map_err(image_error)?,
+        ImageFormat::WebP => WebPDecoder::new(reader()?).map_err(image_error)?.has_animation(),
+        _ => false,
+    };
+    Ok(animated)
+}
+
+/// Latitude and longitude from the GPS fields, if both are present
+fn gps_position(exif: &exif::Exif) -> Option<GpsPosition> {
+    let degrees = |tag: Tag, reference: Tag, negative: &[u8]| -> Option<f64> {
+        let value = match &exif.get_field(tag, In::PRIMARY)?.value {
+            Value::Rational(parts) if !parts.is_empty() => parts.iter()
+                .zip([1.0, 60.0, 3600.0])
+                .map(|(part, divisor)| part.to_f64() / divisor)
+                .sum::<f64>(),
+            _ => return None,
+        };
+        let reference = match exif.get_field(reference, In::PRIMARY).map(|field| &field.value) {
+            Some(Value::Ascii(texts)) => texts.first().and_then(|text| text.first().copied()),
+            _ => None,
+        };
+        let sign = if reference.is_some_and(|letter| negative.contains(&letter)) { -1.0 } else { 1.0 };
+        value.is_finite().then_some(sign * value)
+    };
+
+    let latitude = degrees(Tag::GPSLatitude, Tag::GPSLatitudeRef, b"Ss")?;
+    let longitude = degrees(Tag::GPSLongitude, Tag::GPSLongitudeRef, b"Ww")?;
+    let altitude = match exif.get_field(Tag::GPSAltitude, In::PRIMARY).map(|field| &field.value) {
+        Some(Value::Rational(parts)) if !parts.is_empty() => {
+            let below_sea_level = exif.get_field(Tag::GPSAltitudeRef, In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0)) == Some(1);
+            let metres = parts[0].to_f64();
+            metres.is_finite().then_some(if below_sea_level { -metres } else { metres })
+        }
+        _ => None,
+    };
+
+    Some(GpsPosition { latitude, longitude, altitude })
+}
+
+fn image_error(e: image::ImageError) -> FsError {
+    FsError::Image(e.to_string())
+}
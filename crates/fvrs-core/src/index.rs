@@ -0,0 +1,796 @@
+//! Persistent index of file names for instant searches
+//!
+//! `FileIndex` records the name, size, modification time and type of every
+//! entry under the configured roots. Entries point to their parent instead
+//! of holding whole paths, which keeps a million of them in memory without
+//! trouble and lets a name search run through all of them in milliseconds
+//! without touching the disk.
+//!
+//! The index is saved compressed in the data directory, so the CLI and the
+//! GUI share it, while the roots live in `index.json` in the configuration
+//! directory. `IndexWatcher` reports changes below the roots; passing them
+//! to `FileIndex::apply` keeps the index current without walking again.
+
+use std::collections::HashMap;
+use std::fs::{File, Metadata};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use chrono::{DateTime, Local};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use regex::RegexBuilder;
+use serde::{Serialize, Deserialize};
+use walkdir::WalkDir;
+use crate::core::{EntryKind, FsError, FsEvent, FsEventType, FsResult, SearchOptions};
+
+/// File the index settings are stored in, inside `config::config_dir`
+pub const SETTINGS_FILE: &str = "index.json";
+/// File the index is stored in, inside `config::data_dir`
+pub const INDEX_FILE: &str = "index.db";
+/// Start of an index file, followed by the format version
+const MAGIC: &[u8; 8] = b"FVRSIDX1";
+/// Parent of a root entry
+const NO_PARENT: u32 = u32::MAX;
+/// Entries added between progress reports and checks of the cancel flag
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+/// Which directories are indexed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexSettings {
+    /// Directories indexed with everything below them
+    pub roots: Vec<PathBuf>,
+    /// Globs for names left out of the index, with everything below them
+    pub exclude: Vec<String>,
+}
+
+impl Default for IndexSettings {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            exclude: vec![".git".to_string(), ".hg".to_string(), ".svn".to_string()],
+        }
+    }
+}
+
+impl IndexSettings {
+    /// Where the settings are stored for the current user
+    pub fn default_path() -> Option<PathBuf> {
+        crate::config::config_dir().map(|dir| dir.join(SETTINGS_FILE))
+    }
+
+    /// Read settings saved by `save`; a missing file means nothing is indexed
+    pub fn load(path: &Path) -> FsResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| FsError::Serialization(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the settings, replacing the file only once it is complete
+    pub fn save(&self, path: &Path) -> FsResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| FsError::Serialization(e.to_string()))?;
+        let temp = crate::copy::temp_path(path);
+        std::fs::write(&temp, text)
+            .and_then(|()| std::fs::rename(&temp, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&temp);
+                e.into()
+            })
+    }
+
+    /// Index `path` as well
+    ///
+    /// Fails unless `path` is an absolute directory outside the existing
+    /// roots. Roots inside `path` are dropped, as it covers them.
+    pub fn add_root(&mut self, path: &Path) -> FsResult<()> {
+        if !path.is_absolute() || !path.is_dir() {
+            return Err(FsError::InvalidPath(format!("{} is not an absolute directory path", path.display())));
+        }
+        if let Some(root) = self.roots.iter().find(|root| path.starts_with(root)) {
+            return Err(FsError::Conflict(format!("{} is already indexed as part of {}", path.display(), root.display())));
+        }
+        self.roots.retain(|root| !root.starts_with(path));
+        self.roots.push(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Stop indexing the root `path`; returns whether it was a root
+    pub fn remove_root(&mut self, path: &Path) -> bool {
+        let count = self.roots.len();
+        self.roots.retain(|root| root != path);
+        self.roots.len() != count
+    }
+}
+
+/// How a query pattern is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuerySyntax {
+    /// Part of the name
+    #[default]
+    Substring,
+    /// `;`-separated wildcards matching the whole name
+    Glob,
+    /// Regular expression
+    Regex,
+}
+
+impl QuerySyntax {
+    /// `pattern` as a regex for `SearchOptions::pattern`
+    pub fn to_regex(self, pattern: &str) -> String {
+        match self {
+            QuerySyntax::Substring => regex::escape(pattern),
+            QuerySyntax::Glob => crate::search::glob_to_regex(pattern),
+            QuerySyntax::Regex => pattern.to_string(),
+        }
+    }
+}
+
+/// An entry found in the index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: DateTime<Local>,
+    pub is_dir: bool,
+}
+
+/// One indexed entry; the name of a root is its whole path
+#[derive(Debug, Clone)]
+struct Node {
+    parent: u32,
+    name: Box<str>,
+    size: u64,
+    /// Seconds since the Unix epoch
+    modified: i64,
+    is_dir: bool,
+    /// Removed since the index was loaded; dropped when it is saved
+    removed: bool,
+}
+
+/// Entries below the indexed roots
+#[derive(Debug, Clone, Default)]
+pub struct FileIndex {
+    /// Settings the index was built with
+    settings: IndexSettings,
+    exclude: Vec<glob::Pattern>,
+    /// Parents always come before their children
+    nodes: Vec<Node>,
+    /// Node ids of the roots
+    roots: Vec<u32>,
+    /// Child node ids of each directory node
+    children: HashMap<u32, Vec<u32>>,
+    /// Number of removed nodes
+    removed: usize,
+    built: Option<DateTime<Local>>,
+    /// Entries that could not be read or have names that are not UTF-8
+    errors: u64,
+    /// Changes were lost and the index should be rebuilt
+    stale: bool,
+}
+
+impl FileIndex {
+    /// Where the index is stored for the current user
+    pub fn default_path() -> Option<PathBuf> {
+        crate::config::data_dir().map(|dir| dir.join(INDEX_FILE))
+    }
+
+    /// Walk the roots in `settings` and index everything below them
+    ///
+    /// `progress` is called now and then with the number of entries so far.
+    /// Returns `FsError::Cancelled` once `cancel` is set.
+    pub fn build(settings: &IndexSettings, cancel: &AtomicBool, mut progress: impl FnMut(u64)) -> FsResult<Self> {
+        let mut index = Self::empty(settings.clone())?;
+        let mut count = 0;
+        for root in &settings.roots {
+            // A root inside another one is already covered
+            if settings.roots.iter().any(|other| other != root && root.starts_with(other)) {
+                continue;
+            }
+            if !root.is_dir() {
+                index.errors += 1;
+                continue;
+            }
+            index.add_tree(root, NO_PARENT, false, &mut count, cancel, &mut progress)?;
+        }
+        index.built = Some(Local::now());
+        Ok(index)
+    }
+
+    fn empty(settings: IndexSettings) -> FsResult<Self> {
+        let exclude = settings
+            .exclude
+            .iter()
+            .filter(|pattern| !pattern.trim().is_empty())
+            .map(|pattern| {
+                glob::Pattern::new(pattern.trim())
+                    .map_err(|e| FsError::Search(format!("Invalid glob pattern '{}': {}", pattern, e)))
+            })
+            .collect::<FsResult<_>>()?;
+        Ok(Self { settings, exclude, ..Self::default() })
+    }
+
+    /// Read an index saved by `save`; a missing file is an empty index
+    pub fn load(path: &Path) -> FsResult<Self> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut input = DeflateDecoder::new(BufReader::new(file));
+        Self::read_from(&mut input).map_err(|e| FsError::Serialization(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Write the index, replacing the file only once it is complete
+    ///
+    /// Removed entries are left out, so the file stays compact.
+    pub fn save(&self, path: &Path) -> FsResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = crate::copy::temp_path(path);
+        let write = || -> std::io::Result<()> {
+            let mut output = DeflateEncoder::new(BufWriter::new(File::create(&temp)?), Compression::fast());
+            self.write_to(&mut output)?;
+            output.finish()?.flush()?;
+            std::fs::rename(&temp, path)
+        };
+        write().map_err(|e| {
+            let _ = std::fs::remove_file(&temp);
+            e.into()
+        })
+    }
+
+    /// Settings the index was built with
+    pub fn settings(&self) -> &IndexSettings {
+        &self.settings
+    }
+
+    /// Number of indexed entries, roots included
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.removed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// When the index was last built from scratch
+    pub fn built(&self) -> Option<DateTime<Local>> {
+        self.built
+    }
+
+    /// Entries left out because they could not be read
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Whether changes were lost, so the index should be rebuilt
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Whether `path` is indexed
+    pub fn contains(&self, path: &Path) -> bool {
+        self.find(path).is_some()
+    }
+
+    /// Entries that pass `options`, below `within` or anywhere in the index
+    ///
+    /// Results come in index order, which keeps the entries of a directory
+    /// together. Only the options about names, kinds, sizes, times, hidden
+    /// entries, depth and the number of results apply: searching contents
+    /// is `FsError::NotSupported`, as is a `within` that is not indexed.
+    pub fn search(&self, within: Option<&Path>, options: &SearchOptions) -> FsResult<Vec<IndexedEntry>> {
+        if options.content_pattern.is_some() || options.search_contents {
+            return Err(FsError::NotSupported("the file index cannot search file contents".to_string()));
+        }
+        let within = match within {
+            Some(path) => Some(
+                self.find(path)
+                    .ok_or_else(|| FsError::NotSupported(format!("{} is not indexed", path.display())))?,
+            ),
+            None => None,
+        };
+        let regex = RegexBuilder::new(&options.pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| FsError::InvalidRegex(e.to_string()))?;
+        let extensions: Option<Vec<String>> = options.extensions.as_ref().map(|extensions| {
+            extensions.iter().map(|ext| ext.trim_start_matches('.').to_lowercase()).collect()
+        });
+        let (after, before) = options.modified_range(Local::now());
+        let (after, before) = (after.map(|time| time.timestamp()), before.map(|time| time.timestamp()));
+        let sized = options.min_size.is_some() || options.max_size.is_some();
+
+        let mut results = Vec::new();
+        for (id, node) in self.nodes.iter().enumerate() {
+            if node.removed || node.parent == NO_PARENT {
+                continue;
+            }
+            let kind_matches = match options.kind {
+                EntryKind::Any => true,
+                EntryKind::Files => !node.is_dir,
+                EntryKind::Directories => node.is_dir,
+            };
+            if !kind_matches
+                || (sized && node.is_dir)
+                || options.min_size.is_some_and(|min| node.size < min)
+                || options.max_size.is_some_and(|max| node.size > max)
+                || after.is_some_and(|after| node.modified < after)
+                || before.is_some_and(|before| node.modified >= before)
+            {
+                continue;
+            }
+            if let Some(extensions) = &extensions {
+                let extension = Path::new(&*node.name).extension().map(|ext| ext.to_string_lossy().to_lowercase());
+                if node.is_dir || !extension.is_some_and(|ext| extensions.contains(&ext)) {
+                    continue;
+                }
+            }
+            if !options.categories.is_empty() && (node.is_dir || !options.matches_category(Path::new(&*node.name))) {
+                continue;
+            }
+            if !regex.is_match(&node.name) {
+                continue;
+            }
+            let Some(depth) = self.depth_below(id as u32, within, options.include_hidden) else {
+                continue;
+            };
+            if options.max_depth.is_some_and(|max| depth > max) {
+                continue;
+            }
+            results.push(self.entry(id as u32));
+            if options.max_results.is_some_and(|max| results.len() >= max) {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Bring the index up to date with a change reported by `IndexWatcher`
+    ///
+    /// Returns whether the index changed. An event without a path means
+    /// changes anywhere may have been lost, which only marks the index stale.
+    pub fn apply(&mut self, event: &FsEvent) -> bool {
+        if event.path.as_os_str().is_empty() {
+            self.stale |= event.event_type == FsEventType::Rescan;
+            return false;
+        }
+        match event.event_type {
+            FsEventType::Access => false,
+            FsEventType::Rescan => self.update_path(&event.path, true),
+            _ => self.update_path(&event.path, false),
+        }
+    }
+
+    /// Re-read `path` from the disk, adding, updating or removing its entry
+    pub fn refresh(&mut self, path: &Path) -> bool {
+        self.update_path(path, false)
+    }
+
+    /// `refresh`, also walking a directory again when `rescan` is set
+    fn update_path(&mut self, path: &Path, rescan: bool) -> bool {
+        let existing = self.find(path);
+        match (existing, std::fs::symlink_metadata(path)) {
+            (None, Err(_)) => false,
+            (Some(id), Err(_)) => {
+                // A root that disappeared stays, so it fills up again if it comes back
+                if self.nodes[id as usize].parent == NO_PARENT {
+                    self.remove_children(id);
+                } else {
+                    self.remove(id);
+                }
+                true
+            }
+            (Some(id), Ok(metadata)) => {
+                let node = &mut self.nodes[id as usize];
+                let was_dir = node.is_dir;
+                node.size = size_of(&metadata);
+                node.modified = modified_of(&metadata);
+                node.is_dir = metadata.is_dir();
+                if was_dir != metadata.is_dir() || (rescan && metadata.is_dir()) {
+                    self.remove_children(id);
+                    if metadata.is_dir() {
+                        let _ = self.add_tree(path, id, true, &mut 0, &AtomicBool::new(false), &mut |_| {});
+                    }
+                }
+                true
+            }
+            (None, Ok(_)) => {
+                let Some(parent) = path.parent().and_then(|parent| self.find(parent)) else {
+                    return false;
+                };
+                let Some(name) = path.file_name() else {
+                    return false;
+                };
+                if self.is_excluded(&name.to_string_lossy()) {
+                    return false;
+                }
+                let _ = self.add_tree(path, parent, false, &mut 0, &AtomicBool::new(false), &mut |_| {});
+                true
+            }
+        }
+    }
+
+    /// Walk `path` and add it, with everything below it, under `parent`
+    ///
+    /// With `existing` set, `path` is already the node `parent` and only
+    /// what is below it is added.
+    fn add_tree(
+        &mut self,
+        path: &Path,
+        parent: u32,
+        existing: bool,
+        count: &mut u64,
+        cancel: &AtomicBool,
+        progress: &mut dyn FnMut(u64),
+    ) -> FsResult<()> {
+        let exclude = self.exclude.clone();
+        let mut walker = WalkDir::new(path).into_iter().filter_entry(move |entry| {
+            entry.depth() == 0 || !exclude.iter().any(|pattern| pattern.matches(&entry.file_name().to_string_lossy()))
+        });
+        // Node ids of the directories leading to the current entry, by depth
+        let mut directories: Vec<u32> = Vec::new();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                self.errors += 1;
+                continue;
+            };
+            let depth = entry.depth();
+            directories.truncate(depth);
+            if depth == 0 && existing {
+                directories.push(parent);
+                continue;
+            }
+            let name = if depth == 0 && parent == NO_PARENT { path.to_str() } else { entry.file_name().to_str() };
+            let (Some(name), Ok(metadata)) = (name, entry.metadata()) else {
+                self.errors += 1;
+                if entry.file_type().is_dir() {
+                    walker.skip_current_dir();
+                }
+                continue;
+            };
+            let parent = if depth == 0 { parent } else { directories[depth - 1] };
+            let id = self.push(parent, name, &metadata);
+            if metadata.is_dir() {
+                directories.push(id);
+            }
+
+            *count += 1;
+            if count.is_multiple_of(PROGRESS_INTERVAL) {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(FsError::Cancelled);
+                }
+                progress(*count);
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, parent: u32, name: &str, metadata: &Metadata) -> u32 {
+        let id = self.nodes.len() as u32;
+        self.nodes.push(Node {
+            parent,
+            name: name.into(),
+            size: size_of(metadata),
+            modified: modified_of(metadata),
+            is_dir: metadata.is_dir(),
+            removed: false,
+        });
+        if parent == NO_PARENT {
+            self.roots.push(id);
+        } else {
+            self.children.entry(parent).or_default().push(id);
+        }
+        id
+    }
+
+    /// Remove a node with everything below it
+    fn remove(&mut self, id: u32) {
+        self.remove_children(id);
+        let parent = self.nodes[id as usize].parent;
+        if let Some(siblings) = self.children.get_mut(&parent) {
+            siblings.retain(|&sibling| sibling != id);
+        }
+        self.nodes[id as usize].removed = true;
+        self.removed += 1;
+    }
+
+    fn remove_children(&mut self, id: u32) {
+        let mut pending = self.children.remove(&id).unwrap_or_default();
+        while let Some(child) = pending.pop() {
+            pending.extend(self.children.remove(&child).unwrap_or_default());
+            self.nodes[child as usize].removed = true;
+            self.removed += 1;
+        }
+    }
+
+    fn is_excluded(&self, name: &str) -> bool {
+        self.exclude.iter().any(|pattern| pattern.matches(name))
+    }
+
+    /// Node id of `path`
+    fn find(&self, path: &Path) -> Option<u32> {
+        let (root, rest) = self.roots.iter().find_map(|&root| {
+            let node = &self.nodes[root as usize];
+            let rest = path.strip_prefix(&*node.name).ok()?;
+            (!node.removed).then_some((root, rest))
+        })?;
+        rest.components().try_fold(root, |id, component| {
+            let name = component.as_os_str().to_str()?;
+            self.children.get(&id)?.iter().copied().find(|&child| &*self.nodes[child as usize].name == name)
+        })
+    }
+
+    /// Depth of `id` below `within` (or below its root), or None if it is
+    /// not inside `within` or, unless `include_hidden` is set, it is hidden
+    fn depth_below(&self, id: u32, within: Option<u32>, include_hidden: bool) -> Option<usize> {
+        let mut depth = 0;
+        let mut current = id;
+        loop {
+            let node = &self.nodes[current as usize];
+            if Some(current) == within {
+                return (depth > 0).then_some(depth);
+            }
+            if node.parent == NO_PARENT {
+                return within.is_none().then_some(depth);
+            }
+            if !include_hidden && node.name.starts_with('.') {
+                return None;
+            }
+            depth += 1;
+            current = node.parent;
+        }
+    }
+
+    fn entry(&self, id: u32) -> IndexedEntry {
+        let node = &self.nodes[id as usize];
+        let mut names = Vec::new();
+        let mut current = id;
+        while current != NO_PARENT {
+            let node = &self.nodes[current as usize];
+            names.push(&*node.name);
+            current = node.parent;
+        }
+        let path = names.iter().rev().collect();
+        let modified = DateTime::from_timestamp(node.modified, 0).unwrap_or_default().with_timezone(&Local);
+        IndexedEntry { path, size: node.size, modified, is_dir: node.is_dir }
+    }
+
+    /// The index format: settings, build time, then the nodes in order with
+    /// parents as the new index plus one (0 for a root)
+    fn write_to(&self, output: &mut impl Write) -> std::io::Result<()> {
+        output.write_all(MAGIC)?;
+        write_text(output, &serde_json::to_string(&self.settings)?)?;
+        write_number(output, self.built.map_or(0, |built| built.timestamp().max(0) as u64))?;
+        write_number(output, self.errors)?;
+        write_number(output, self.len() as u64)?;
+
+        let mut new_ids = vec![NO_PARENT; self.nodes.len()];
+        let mut next = 0;
+        for (id, node) in self.nodes.iter().enumerate() {
+            if node.removed {
+                continue;
+            }
+            new_ids[id] = next;
+            next += 1;
+            let parent = if node.parent == NO_PARENT { 0 } else { new_ids[node.parent as usize] as u64 + 1 };
+            write_number(output, parent)?;
+            output.write_all(&[node.is_dir as u8])?;
+            write_number(output, node.size)?;
+            write_number(output, node.modified.max(0) as u64)?;
+            write_text(output, &node.name)?;
+        }
+        Ok(())
+    }
+
+    fn read_from(input: &mut impl Read) -> std::io::Result<Self> {
+        let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string());
+        let mut magic = [0; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a file index"));
+        }
+        let settings: IndexSettings = serde_json::from_str(&read_text(input)?)?;
+        let mut index = Self::empty(settings).map_err(|e| invalid(&e.to_string()))?;
+        index.built = match read_number(input)? {
+            0 => None,
+            seconds => DateTime::from_timestamp(seconds as i64, 0).map(|built| built.with_timezone(&Local)),
+        };
+        index.errors = read_number(input)?;
+
+        let count = read_number(input)?;
+        index.nodes.reserve(count.min(u32::MAX as u64) as usize);
+        for id in 0..count {
+            let parent = match read_number(input)? {
+                0 => NO_PARENT,
+                parent if parent <= id => (parent - 1) as u32,
+                _ => return Err(invalid("entry listed before its parent")),
+            };
+            let mut is_dir = [0];
+            input.read_exact(&mut is_dir)?;
+            let size = read_number(input)?;
+            let modified = read_number(input)? as i64;
+            let name = read_text(input)?;
+            let id = index.nodes.len() as u32;
+            index.nodes.push(Node { parent, name: name.into(), size, modified, is_dir: is_dir[0] != 0, removed: false });
+            if parent == NO_PARENT {
+                index.roots.push(id);
+            } else {
+                index.children.entry(parent).or_default().push(id);
+            }
+        }
+        Ok(index)
+    }
+}
+
+fn size_of(metadata: &Metadata) -> u64 {
+    if metadata.is_dir() { 0 } else { metadata.len() }
+}
+
+fn modified_of(metadata: &Metadata) -> i64 {
+    metadata.modified().map_or(0, |modified| DateTime::<Local>::from(modified).timestamp())
+}
+
+/// LEB128: seven bits at a time, low bits first
+fn write_number(output: &mut impl Write, mut value: u64) -> std::io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return output.write_all(&[byte]);
+        }
+        output.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_number(input: &mut impl Read) -> std::io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        input.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "number too long"))
+}
+
+fn write_text(output: &mut impl Write, text: &str) -> std::io::Result<()> {
+    write_number(output, text.len() as u64)?;
+    output.write_all(text.as_bytes())
+}
+
+fn read_text(input: &mut impl Read) -> std::io::Result<String> {
+    let len = read_number(input)?;
+    let mut bytes = Vec::new();
+    input.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Watches the roots of an index recursively and queues their change events
+pub struct IndexWatcher {
+    /// Events stop when it is dropped
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<FsEvent>,
+}
+
+impl IndexWatcher {
+    /// Watch `roots`; `on_event` runs on the watcher thread after events are queued
+    ///
+    /// Fails if any root cannot be watched, for example when the system
+    /// limit on watches is too low for the number of directories.
+    pub fn new<F>(roots: &[PathBuf], on_event: F) -> FsResult<Self>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let (tx, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(crate::watcher::event_handler(tx, on_event))?;
+        for root in roots {
+            watcher.watch(root, RecursiveMode::Recursive)?;
+        }
+        Ok(Self { _watcher: watcher, receiver })
+    }
+
+    /// Take all queued events
+    pub fn pending_events(&self) -> Vec<FsEvent> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// root/{src/{main.rs, lib.rs, Mod1.rs}, docs/{README.md, .draft.md}, Cargo.toml, .git/config}
+    fn build() -> (tempfile::TempDir, FileIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["src", "docs", ".git"] {
+            std::fs::create_dir(root.join(sub)).unwrap();
+        }
+        for file in ["src/main.rs", "src/lib.rs", "src/Mod1.rs", "docs/README.md", "docs/.draft.md", "Cargo.toml", ".git/config"] {
+            std::fs::write(root.join(file), file.as_bytes()).unwrap();
+        }
+        let settings = IndexSettings { roots: vec![root.to_path_buf()], ..IndexSettings::default() };
+        let index = FileIndex::build(&settings, &AtomicBool::new(false), |_| {}).unwrap();
+        (dir, index)
+    }
+
+    fn names(index: &FileIndex, within: Option<&Path>, options: &SearchOptions) -> Vec<String> {
+        let mut names: Vec<String> = index
+            .search(within, options)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn query(syntax: QuerySyntax, pattern: &str) -> SearchOptions {
+        SearchOptions { pattern: syntax.to_regex(pattern), case_sensitive: false, ..SearchOptions::default() }
+    }
+
+    #[test]
+    fn substring_glob_and_regex_queries() {
+        let (_dir, index) = build();
+        // .git is excluded by default
+        assert!(names(&index, None, &query(QuerySyntax::Substring, "config")).is_empty());
+        assert_eq!(names(&index, None, &query(QuerySyntax::Substring, "MAIN")), ["main.rs"]);
+        assert_eq!(names(&index, None, &query(QuerySyntax::Substring, ".")), [".draft.md", "Cargo.toml", "Mod1.rs", "README.md", "lib.rs", "main.rs"]);
+        assert_eq!(names(&index, None, &query(QuerySyntax::Glob, "*.toml; *.md")), [".draft.md", "Cargo.toml", "README.md"]);
+        // Globs match the whole name
+        assert!(names(&index, None, &query(QuerySyntax::Glob, "main")).is_empty());
+        assert_eq!(names(&index, None, &query(QuerySyntax::Regex, r"^mod\d")), ["Mod1.rs"]);
+        assert!(matches!(index.search(None, &query(QuerySyntax::Regex, "(")), Err(FsError::InvalidRegex(_))));
+    }
+
+    #[test]
+    fn filters_and_scope() {
+        let (dir, index) = build();
+        let src = dir.path().join("src");
+        assert_eq!(names(&index, Some(&src), &query(QuerySyntax::Substring, "")), ["Mod1.rs", "lib.rs", "main.rs"]);
+        assert!(index.search(Some(Path::new("/not/indexed")), &SearchOptions::default()).is_err());
+
+        let directories = SearchOptions { kind: EntryKind::Directories, ..SearchOptions::default() };
+        assert_eq!(names(&index, None, &directories), ["docs", "src"]);
+
+        let visible = SearchOptions { include_hidden: false, ..query(QuerySyntax::Glob, "*.md") };
+        assert_eq!(names(&index, None, &visible), ["README.md"]);
+
+        let shallow = SearchOptions { max_depth: Some(1), kind: EntryKind::Files, ..SearchOptions::default() };
+        assert_eq!(names(&index, None, &shallow), ["Cargo.toml"]);
+    }
+
+    #[test]
+    fn refresh_and_reload() {
+        let (dir, mut index) = build();
+        let added = dir.path().join("src/new.rs");
+        std::fs::write(&added, b"").unwrap();
+        assert!(index.refresh(&added));
+        std::fs::remove_file(dir.path().join("src/lib.rs")).unwrap();
+        assert!(index.refresh(&dir.path().join("src/lib.rs")));
+        assert_eq!(names(&index, None, &query(QuerySyntax::Glob, "*.rs")), ["Mod1.rs", "main.rs", "new.rs"]);
+
+        let path = dir.path().join("index.db");
+        index.save(&path).unwrap();
+        let loaded = FileIndex::load(&path).unwrap();
+        assert_eq!(loaded.len(), index.len());
+        assert_eq!(names(&loaded, None, &query(QuerySyntax::Glob, "*.rs")), ["Mod1.rs", "main.rs", "new.rs"]);
+    }
+}
@@ -11,14 +11,13 @@ use serde::{Serialize, Deserialize};
 use walkdir::WalkDir;
 use notify::{Watcher, RecursiveMode, Event};
 use std::sync::mpsc;
-use regex::Regex;
 use std::collections::VecDeque;
+#[cfg(windows)]
 use std::ffi::OsStr;
 use notify::event::ModifyKind;
 use md5;
 use hex;
 use std::cmp::min;
-use std::time::SystemTime;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use glob;
@@ -28,7 +27,9 @@ use std::io::BufWriter;
 use notify::EventKind;
 use std::io::BufReader as StdBufReader;
 use tokio::io::BufReader as TokioBufReader;
+#[cfg(windows)]
 use windows::Win32::Storage::FileSystem::{GetFileAttributesW, FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_SYSTEM, FILE_ATTRIBUTE_ARCHIVE};
+#[cfg(windows)]
 use std::os::windows::ffi::OsStrExt;
 use tokio::io::{AsyncReadExt, AsyncBufReadExt};
 use sha1::Digest;
@@ -36,6 +37,7 @@ use sha2::{Sha256, Sha512};
 use blake3;
 use ripemd::Ripemd160;
 use std::io::Read;
+#[cfg(windows)]
 use windows::core::PCWSTR;
 // use base64;
 // use aes;
@@ -125,6 +127,16 @@ pub mod core {
         Monitoring(String),
         #[error("Serialization error: {0}")]
         Serialization(String),
+        #[error("Operation cancelled")]
+        Cancelled,
+        #[error("Thumbnail error: {0}")]
+        Thumbnail(String),
+        #[error("Image error: {0}")]
+        Image(String),
+        #[error("Encoding error: {0}")]
+        Encoding(String),
+        #[error("Conflict: {0}")]
+        Conflict(String),
     }
 
     impl From<notify::Error> for FsError {
@@ -203,6 +215,7 @@ pub mod core {
 
             #[cfg(unix)]
             {
+                use std::os::unix::fs::PermissionsExt;
                 let mode = _permissions.mode();
                 Ok(Self {
                     readable: mode & 0o444 != 0,
@@ -231,30 +244,38 @@ pub mod core {
         }
 
         /// Apply permissions to a file
+        ///
+        /// On Unix a flag only changes its bits when it disagrees with `mode`,
+        /// so permissions read with `from_path` are written back unchanged.
         pub async fn apply(&self, path: &PathBuf) -> FsResult<()> {
-            let permissions = fs::metadata(path).await?.permissions();
-
             #[cfg(unix)]
             {
-                let mut mode = 0;
-                if self.readable {
-                    mode |= 0o444;
-                }
-                if self.writable {
-                    mode |= 0o222;
-                }
-                if self.executable {
-                    mode |= 0o111;
+                use std::os::unix::fs::PermissionsExt;
+                let mut mode = self.mode & crate::attributes::MODE_MASK;
+                for (wanted, bits) in [(self.readable, 0o444), (self.writable, 0o222), (self.executable, 0o111)] {
+                    if wanted != (mode & bits != 0) {
+                        if wanted {
+                            mode |= bits;
+                        } else {
+                            mode &= !bits;
+                        }
+                    }
                 }
-                permissions.set_mode(mode);
+                fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).await?;
             }
 
             #[cfg(windows)]
             {
-                // permissions.file_attributes() や permissions.set_file_attributes(attrs) の行をコメントアウト
+                let changes = crate::attributes::AttributeChanges {
+                    readonly: Some(!self.writable),
+                    hidden: Some(self.hidden),
+                    system: Some(self.system),
+                    archive: Some(self.archive),
+                    ..Default::default()
+                };
+                crate::attributes::apply_changes(path, &changes)?;
             }
 
-            fs::set_permissions(path, permissions).await?;
             Ok(())
         }
     }
@@ -274,7 +295,11 @@ pub mod core {
     }
 
     /// Search options for file search
-    #[derive(Debug, Clone)]
+    ///
+    /// Serializable so a query can be saved, as smart folders do. Missing
+    /// fields take their default values when read back.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
     pub struct SearchOptions {
         /// Search pattern (regex)
         pub pattern: String,
@@ -288,6 +313,35 @@ pub mod core {
         pub extensions: Option<Vec<String>>,
         /// Maximum number of results
         pub max_results: Option<usize>,
+        /// Regex the contents must match, in addition to `pattern` matching the name
+        pub content_pattern: Option<String>,
+        /// Which kinds of entries to return
+        pub kind: EntryKind,
+        /// Minimum file size in bytes (excludes directories)
+        pub min_size: Option<u64>,
+        /// Maximum file size in bytes (excludes directories)
+        pub max_size: Option<u64>,
+        /// Only entries modified at or after this time
+        pub modified_after: Option<DateTime<Local>>,
+        /// Only entries modified before this time
+        pub modified_before: Option<DateTime<Local>>,
+        /// Only entries modified at most this many seconds before the search
+        /// starts, so a saved query keeps meaning "recent"
+        pub max_age_secs: Option<u64>,
+        /// Only entries modified at least this many seconds before the search starts
+        pub min_age_secs: Option<u64>,
+        /// Only files in one of these categories (empty means any, and
+        /// excludes directories otherwise)
+        pub categories: Vec<FileCategory>,
+        /// Skip entries excluded by `.gitignore`, `.ignore` and git's exclude
+        /// files, and the `.git`, `.hg` and `.svn` directories themselves
+        pub respect_ignore: bool,
+        /// Include hidden entries (names starting with `.`)
+        pub include_hidden: bool,
+        /// Skip files that look binary when searching contents
+        pub skip_binary: bool,
+        /// Lines of context reported before and after each matching line
+        pub context_lines: usize,
     }
 
     impl Default for SearchOptions {
@@ -299,10 +353,107 @@ pub mod core {
                 max_depth: None,
                 extensions: None,
                 max_results: None,
+                content_pattern: None,
+                kind: EntryKind::Any,
+                min_size: None,
+                max_size: None,
+                modified_after: None,
+                modified_before: None,
+                max_age_secs: None,
+                min_age_secs: None,
+                categories: Vec::new(),
+                respect_ignore: true,
+                include_hidden: true,
+                skip_binary: true,
+                context_lines: 0,
             }
         }
     }
 
+    impl SearchOptions {
+        /// The modification time range, combining the fixed times with the
+        /// ages counted back from `now`
+        pub fn modified_range(&self, now: DateTime<Local>) -> (Option<DateTime<Local>>, Option<DateTime<Local>>) {
+            let ago = |secs: u64| now - chrono::Duration::seconds(i64::try_from(secs).unwrap_or(i64::MAX / 1000));
+            let after = match (self.modified_after, self.max_age_secs.map(ago)) {
+                (Some(time), Some(age)) => Some(time.max(age)),
+                (time, age) => time.or(age),
+            };
+            let before = match (self.modified_before, self.min_age_secs.map(ago)) {
+                (Some(time), Some(age)) => Some(time.min(age)),
+                (time, age) => time.or(age),
+            };
+            (after, before)
+        }
+
+        /// Whether a file at `path` is in one of `categories` (always true
+        /// when no categories are set)
+        pub fn matches_category(&self, path: &std::path::Path) -> bool {
+            self.categories.is_empty()
+                || FileCategory::of(path).is_some_and(|category| self.categories.contains(&category))
+        }
+    }
+
+    /// Kinds of entries a search returns
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+    pub enum EntryKind {
+        /// Files and directories
+        #[default]
+        Any,
+        /// Files only
+        Files,
+        /// Directories only
+        Directories,
+    }
+
+    /// Broad file types, told apart by extension
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub enum FileCategory {
+        Image,
+        Video,
+        Audio,
+        Document,
+        Archive,
+        /// Source code and other plain text
+        Text,
+    }
+
+    impl FileCategory {
+        pub const ALL: [FileCategory; 6] = [
+            FileCategory::Image,
+            FileCategory::Video,
+            FileCategory::Audio,
+            FileCategory::Document,
+            FileCategory::Archive,
+            FileCategory::Text,
+        ];
+
+        /// Lowercase extensions, without the dot, that belong to the category
+        pub fn extensions(self) -> &'static [&'static str] {
+            match self {
+                FileCategory::Image => &[
+                    "png", "jpg", "jpeg", "gif", "bmp", "webp", "tif", "tiff", "ico", "svg", "heic", "avif", "raw", "cr2", "nef",
+                ],
+                FileCategory::Video => &["mp4", "mkv", "mov", "avi", "wmv", "webm", "m4v", "mpg", "mpeg", "flv", "3gp", "m2ts"],
+                FileCategory::Audio => &["mp3", "wav", "flac", "aac", "ogg", "opus", "m4a", "wma", "aiff", "mid", "midi"],
+                FileCategory::Document => &[
+                    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "odp", "rtf", "epub",
+                ],
+                FileCategory::Archive => &["zip", "7z", "rar", "tar", "gz", "tgz", "bz2", "xz", "zst", "lzh", "cab", "iso"],
+                FileCategory::Text => &[
+                    "txt", "md", "csv", "log", "json", "toml", "yaml", "yml", "xml", "ini", "html", "css", "rs", "c", "h",
+                    "cpp", "hpp", "cs", "java", "kt", "go", "py", "rb", "js", "ts", "tsx", "jsx", "sh", "ps1", "bat", "sql",
+                ],
+            }
+        }
+
+        /// Category of the file at `path`, from its extension
+        pub fn of(path: &std::path::Path) -> Option<FileCategory> {
+            let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+            Self::ALL.into_iter().find(|category| category.extensions().contains(&extension.as_str()))
+        }
+    }
+
     /// Hash algorithm type
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
     pub enum HashAlgorithm {
@@ -333,6 +484,67 @@ pub mod core {
         pub time_ms: u64,
     }
 
+    /// Compute the hash of a file, reading it in blocks
+    pub fn hash_file(path: &std::path::Path, algorithm: HashAlgorithm) -> FsResult<HashResult> {
+        /// Incremental hasher for each supported algorithm
+        enum Hasher {
+            Md5(md5::Context),
+            Sha1(sha1::Sha1),
+            Sha256(Sha256),
+            Sha512(Sha512),
+            Blake3(Box<blake3::Hasher>),
+            Ripemd160(Ripemd160),
+        }
+
+        let start_time = std::time::Instant::now();
+        let mut hasher = match algorithm {
+            HashAlgorithm::MD5 => Hasher::Md5(md5::Context::new()),
+            HashAlgorithm::SHA1 => Hasher::Sha1(sha1::Sha1::new()),
+            HashAlgorithm::SHA256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::SHA512 => Hasher::Sha512(Sha512::new()),
+            HashAlgorithm::BLAKE3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::RIPEMD160 => Hasher::Ripemd160(Ripemd160::new()),
+        };
+
+        let mut file = File::open(path)?;
+        let mut buffer = vec![0u8; 1024 * 1024];
+        let mut size = 0u64;
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            size += read as u64;
+            let chunk = &buffer[..read];
+            match &mut hasher {
+                Hasher::Md5(h) => h.consume(chunk),
+                Hasher::Sha1(h) => h.update(chunk),
+                Hasher::Sha256(h) => h.update(chunk),
+                Hasher::Sha512(h) => h.update(chunk),
+                Hasher::Blake3(h) => {
+                    h.update(chunk);
+                }
+                Hasher::Ripemd160(h) => h.update(chunk),
+            }
+        }
+
+        let hash = match hasher {
+            Hasher::Md5(h) => hex::encode(h.compute().0),
+            Hasher::Sha1(h) => hex::encode(h.finalize()),
+            Hasher::Sha256(h) => hex::encode(h.finalize()),
+            Hasher::Sha512(h) => hex::encode(h.finalize()),
+            Hasher::Blake3(h) => hex::encode(h.finalize().as_bytes()),
+            Hasher::Ripemd160(h) => hex::encode(h.finalize()),
+        };
+
+        Ok(HashResult {
+            algorithm,
+            hash,
+            size,
+            time_ms: start_time.elapsed().as_millis() as u64,
+        })
+    }
+
     /// Comparison type
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum ComparisonType {
@@ -401,6 +613,8 @@ pub mod core {
         Access,
         /// File or directory metadata changed
         Metadata,
+        /// Events were dropped; the affected directory should be reloaded
+        Rescan,
     }
 
     /// File system event
@@ -571,6 +785,8 @@ pub mod core {
         current_dir: PathBuf,
         /// File system event receiver
         event_receiver: Option<mpsc::Receiver<FsEvent>>,
+        /// Active watcher (events stop when it is dropped)
+        watcher: Option<notify::RecommendedWatcher>,
     }
 
     impl FileSystem {
@@ -579,6 +795,7 @@ pub mod core {
             Self {
                 current_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
                 event_receiver: None,
+                watcher: None,
             }
         }
 
@@ -648,18 +865,25 @@ pub mod core {
             Ok(())
         }
 
-        /// Copy a file or directory
-        pub async fn copy(&self, src: &PathBuf, dest: &PathBuf) -> FsResult<()> {
-            if src.is_dir() {
-                return Err(FsError::NotSupported("Directory copy not implemented".into()));
-            }
-            fs::copy(src, dest).await?;
+        /// Copy a file or directory, preserving metadata
+        pub async fn copy(&self, src: &std::path::Path, dest: &std::path::Path) -> FsResult<()> {
+            let (src, dest) = (src.to_path_buf(), dest.to_path_buf());
+            tokio::task::spawn_blocking(move || {
+                crate::copy::copy_tree(&src, &dest, &crate::copy::CopyOptions::default(), &mut |_| true)
+            })
+            .await
+            .map_err(|e| FsError::Io(std::io::Error::other(e)))??;
             Ok(())
         }
 
-        /// Move a file or directory
-        pub async fn move_file(&self, src: &PathBuf, dest: &PathBuf) -> FsResult<()> {
-            fs::rename(src, dest).await?;
+        /// Move a file or directory, also across filesystems
+        pub async fn move_file(&self, src: &std::path::Path, dest: &std::path::Path) -> FsResult<()> {
+            let (src, dest) = (src.to_path_buf(), dest.to_path_buf());
+            tokio::task::spawn_blocking(move || {
+                crate::copy::move_path(&src, &dest, &crate::copy::CopyOptions::default(), &mut |_| true)
+            })
+            .await
+            .map_err(|e| FsError::Io(std::io::Error::other(e)))??;
             Ok(())
         }
 
@@ -680,6 +904,7 @@ pub mod core {
                 }
             })?;
             watcher.watch(path, RecursiveMode::Recursive)?;
+            self.watcher = Some(watcher);
             Ok(())
         }
 
@@ -690,80 +915,15 @@ pub mod core {
         }
 
         /// Search for files matching the given pattern
+        ///
+        /// Collects the results of `search::search` over the current directory,
+        /// sorted by path since the search reports them in no particular order.
         pub async fn search_files(&self, options: SearchOptions) -> FsResult<Vec<FileEntry>> {
-            let pattern = if options.case_sensitive {
-                Regex::new(&options.pattern)
-            } else {
-                Regex::new(&format!("(?i){}", options.pattern))
-            }.map_err(|e| FsError::InvalidRegex(e.to_string()))?;
-
             let mut results = Vec::new();
-            let mut walker = WalkDir::new(&self.current_dir)
-                .min_depth(1);
-
-            if let Some(max_depth) = options.max_depth {
-                walker = walker.max_depth(max_depth);
-            }
-
-            for entry in walker.into_iter().filter_map(|e| e.ok()) {
-                if let Some(max_results) = options.max_results {
-                    if results.len() >= max_results {
-                        break;
-                    }
-                }
-
-                let path = entry.path();
-                let name = entry.file_name().to_string_lossy();
-
-                // Check file extension if specified
-                if let Some(ref extensions) = options.extensions {
-                    if let Some(ext) = path.extension() {
-                        if !extensions.contains(&ext.to_string_lossy().to_lowercase()) {
-                            continue;
-                        }
-                    }
-                }
-
-                // Check filename match
-                if pattern.is_match(&name) {
-                    if let Ok(metadata) = fs::metadata(path).await {
-                        results.push(FileEntry {
-                            name: name.into_owned(),
-                            path: path.to_path_buf(),
-                            size: metadata.len(),
-                            is_dir: metadata.is_dir(),
-                            created: DateTime::from(metadata.created()?),
-                            modified: DateTime::from(metadata.modified()?),
-                            extension: path.extension()
-                                .and_then(|e| e.to_str())
-                                .map(String::from),
-                        });
-                    }
-                    continue;
-                }
-
-                // Check file contents if requested
-                if options.search_contents && !path.is_dir() {
-                    if let Ok(contents) = fs::read_to_string(path).await {
-                        if pattern.is_match(&contents) {
-                            if let Ok(metadata) = fs::metadata(path).await {
-                                results.push(FileEntry {
-                                    name: name.into_owned(),
-                                    path: path.to_path_buf(),
-                                    size: metadata.len(),
-                                    is_dir: metadata.is_dir(),
-                                    created: DateTime::from(metadata.created()?),
-                                    modified: DateTime::from(metadata.modified()?),
-                                    extension: path.extension()
-                                        .and_then(|e| e.to_str())
-                                        .map(String::from),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-
+            crate::search::search(&self.current_dir, &options, &std::sync::atomic::AtomicBool::new(false), |hit| {
+                results.push(hit.entry)
+            })?;
+            results.sort_by(|a, b| a.path.cmp(&b.path));
             Ok(results)
         }
 
@@ -771,11 +931,8 @@ pub mod core {
         pub async fn find_files(&self, pattern: &str) -> FsResult<Vec<FileEntry>> {
             let options = SearchOptions {
                 pattern: pattern.to_string(),
-                search_contents: false,
                 case_sensitive: false,
-                max_depth: None,
-                extensions: None,
-                max_results: None,
+                ..SearchOptions::default()
             };
             self.search_files(options).await
         }
@@ -784,69 +941,20 @@ pub mod core {
         pub async fn find_files_by_extension(&self, extension: &str) -> FsResult<Vec<FileEntry>> {
             let options = SearchOptions {
                 pattern: format!("\\.{}$", extension),
-                search_contents: false,
                 case_sensitive: false,
-                max_depth: None,
                 extensions: Some(vec![extension.to_string()]),
-                max_results: None,
+                ..SearchOptions::default()
             };
             self.search_files(options).await
         }
 
         /// Calculate hash of a file
-        pub async fn calculate_hash(&self, path: &PathBuf, algorithm: HashAlgorithm) -> FsResult<HashResult> {
-            let start_time = SystemTime::now();
-            let mut file = File::open(path)?;
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer)?;
-            let size = buffer.len() as u64;
-
-            let hash = match algorithm {
-                HashAlgorithm::MD5 => {
-                    let digest = md5::compute(&buffer);
-                    hex::encode(digest.0)
-                }
-                HashAlgorithm::SHA1 => {
-                    let mut hasher = sha1::Sha1::new();
-                    hasher.update(&buffer);
-                    hex::encode(hasher.finalize())
-                }
-                HashAlgorithm::SHA256 => {
-                    let mut hasher = Sha256::new();
-                    hasher.update(&buffer);
-                    hex::encode(hasher.finalize())
-                }
-                HashAlgorithm::SHA512 => {
-                    let mut hasher = Sha512::new();
-                    hasher.update(&buffer);
-                    hex::encode(hasher.finalize())
-                }
-                HashAlgorithm::BLAKE3 => {
-                    let mut hasher = blake3::Hasher::new();
-                    hasher.update(&buffer);
-                    hex::encode(hasher.finalize().as_bytes())
-                }
-                HashAlgorithm::RIPEMD160 => {
-                    let mut hasher = Ripemd160::new();
-                    hasher.update(&buffer);
-                    hex::encode(hasher.finalize())
-                }
-            };
-
-            let end_time = SystemTime::now();
-            let duration = end_time.duration_since(start_time).unwrap();
-            let time_ms = duration.as_millis() as u64;
-
-            Ok(HashResult {
-                algorithm,
-                hash,
-                size,
-                time_ms,
-            })
+        pub async fn calculate_hash(&self, path: &std::path::Path, algorithm: HashAlgorithm) -> FsResult<HashResult> {
+            hash_file(path, algorithm)
         }
 
         /// Verify file hash
-        pub async fn verify_hash(&self, path: &PathBuf, expected_hash: &str, algorithm: HashAlgorithm) -> FsResult<bool> {
+        pub async fn verify_hash(&self, path: &std::path::Path, expected_hash: &str, algorithm: HashAlgorithm) -> FsResult<bool> {
             let result = self.calculate_hash(path, algorithm).await?;
             Ok(result.hash == expected_hash)
         }
@@ -1133,12 +1241,13 @@ pub mod core {
             let settings_cloned = settings.clone();
             let mut watcher = notify::recommended_watcher(move |res: std::result::Result<Event, notify::Error>| {
                 if let Ok(event) = res {
-                    if settings_cloned.filter.matches(&event.paths[0]) {
+                    if event.paths.first().is_some_and(|path| settings_cloned.filter.matches(path)) {
                         let _ = tx.send(FsEvent::from(event));
                     }
                 }
             })?;
             watcher.watch(&settings.path, if settings.recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive })?;
+            self.watcher = Some(watcher);
             Ok(())
         }
 
@@ -1164,7 +1273,9 @@ pub mod core {
     impl From<Event> for FsEvent {
         fn from(event: Event) -> Self {
             let event_type = match event.kind {
+                _ if event.need_rescan() => FsEventType::Rescan,
                 EventKind::Create(_) => FsEventType::Create,
+                EventKind::Modify(ModifyKind::Name(_)) => FsEventType::Rename,
                 EventKind::Modify(_) => FsEventType::Modify,
                 EventKind::Remove(_) => FsEventType::Remove,
                 EventKind::Access(_) => FsEventType::Access,
@@ -1192,6 +1303,45 @@ pub mod core {
     }
 }
 
+/// Module for querying volume capacity and file ownership
+pub mod volume;
+/// Module for scanning and caching disk usage
+pub mod disk_usage;
+/// Module for finding duplicate files
+pub mod duplicates;
+/// Module implementing the freedesktop.org trash
+pub mod trash;
+/// Module providing the copy engine for copy and move operations
+pub mod copy;
+/// Module for watching a changing set of directories
+pub mod watcher;
+/// Module for streaming directory enumeration
+pub mod listing;
+/// Module implementing the freedesktop.org thumbnail cache
+pub mod thumbnail;
+/// Module for reading image metadata and rotating or flipping images
+pub mod imaging;
+/// Module for detecting and converting text encodings
+pub mod text;
+/// Module for reading large files a page at a time
+pub mod paged;
+/// Module for editing files byte by byte
+pub mod hexedit;
+/// Module for reading and changing file attributes
+pub mod attributes;
+/// Module for splitting files into parts and joining them
+pub mod split;
+/// Module for ranking visited directories and fuzzy matching paths
+pub mod history;
+/// Module for named directory bookmarks shared by the GUI and CLI
+pub mod bookmarks;
+/// Module for searching file names and contents
+pub mod search;
+/// Module for the persistent index of file names
+pub mod index;
+/// Module for saved searches shown as folders
+pub mod smart_folders;
+
 /// Module providing plugin system functionality
 pub mod plugin {
     /// Basic trait for plugins
@@ -1229,4 +1379,46 @@ pub mod config {
             }
         }
     }
+
+    /// Application name used for per-user directories
+    const APP_DIR_NAME: &str = "fvrs";
+
+    /// Per-user configuration directory
+    ///
+    /// `$XDG_CONFIG_HOME/fvrs` (or `~/.config/fvrs`) on Linux,
+    /// `~/Library/Application Support/fvrs` on macOS and `%APPDATA%\fvrs` on Windows.
+    pub fn config_dir() -> Option<PathBuf> {
+        #[cfg(windows)]
+        let base = std::env::var_os("APPDATA").map(PathBuf::from);
+        #[cfg(target_os = "macos")]
+        let base = std::env::home_dir().map(|home| home.join("Library/Application Support"));
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let base = xdg_dir("XDG_CONFIG_HOME", ".config");
+
+        base.map(|base| base.join(APP_DIR_NAME))
+    }
+
+    /// Per-user data directory (journals, caches that must survive restarts)
+    ///
+    /// `$XDG_DATA_HOME/fvrs` (or `~/.local/share/fvrs`) on Linux,
+    /// `~/Library/Application Support/fvrs` on macOS and `%LOCALAPPDATA%\fvrs` on Windows.
+    pub fn data_dir() -> Option<PathBuf> {
+        #[cfg(windows)]
+        let base = std::env::var_os("LOCALAPPDATA").map(PathBuf::from);
+        #[cfg(target_os = "macos")]
+        let base = std::env::home_dir().map(|home| home.join("Library/Application Support"));
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let base = xdg_dir("XDG_DATA_HOME", ".local/share");
+
+        base.map(|base| base.join(APP_DIR_NAME))
+    }
+
+    /// XDG base directory from `var`, falling back to `~/<fallback>`
+    #[cfg(not(any(windows, target_os = "macos")))]
+    fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| std::env::home_dir().map(|home| home.join(fallback)))
+    }
 } 
\ No newline at end of file
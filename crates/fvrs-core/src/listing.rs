@@ -0,0 +1,106 @@
+//! Streaming directory enumeration
+//!
+//! `read_directory` hands entries to the caller in batches as they are
+//! read, so very large directories can be shown before enumeration
+//! finishes. Metadata for each batch is fetched on several threads, which
+//! matters most on network filesystems where every `stat` is a round trip.
+
+use std::fs::{DirEntry, Metadata};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use crate::core::{FileEntry, FsError, FsResult};
+
+/// Size of the first batch, kept small so the first screen appears quickly
+const FIRST_BATCH_SIZE: usize = 256;
+/// Size of later batches
+const BATCH_SIZE: usize = 4096;
+/// Batches smaller than this are not worth splitting across threads
+const PARALLEL_THRESHOLD: usize = 64;
+
+/// Build a `FileEntry` from already fetched metadata
+///
+/// Directories report a size of zero, and missing timestamps fall back
+/// to the Unix epoch.
+pub fn entry_from_metadata(path: &Path, metadata: &Metadata) -> FileEntry {
+    FileEntry {
+        name: path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path: path.to_path_buf(),
+        size: if metadata.is_file() { metadata.len() } else { 0 },
+        is_dir: metadata.is_dir(),
+        created: metadata.created().unwrap_or(SystemTime::UNIX_EPOCH).into(),
+        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH).into(),
+        extension: path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(String::from),
+    }
+}
+
+/// Enumerate a directory, calling `on_batch` with entries as they are read
+///
+/// Entries are not sorted. Dot files are skipped unless `show_hidden` is
+/// set, and entries whose metadata cannot be read are left out. Returns
+/// the number of entries delivered, or `FsError::Cancelled` once `cancel`
+/// is set.
+pub fn read_directory(
+    path: &Path,
+    show_hidden: bool,
+    cancel: &AtomicBool,
+    mut on_batch: impl FnMut(Vec<FileEntry>),
+) -> FsResult<usize> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut read_dir = std::fs::read_dir(path)?.filter_map(|entry| entry.ok());
+    let mut batch_size = FIRST_BATCH_SIZE;
+    let mut total = 0;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(FsError::Cancelled);
+        }
+
+        let batch: Vec<DirEntry> = read_dir.by_ref()
+            .filter(|entry| show_hidden || !entry.file_name().to_string_lossy().starts_with('.'))
+            .take(batch_size)
+            .collect();
+        if batch.is_empty() {
+            return Ok(total);
+        }
+        let last = batch.len() < batch_size;
+
+        let entries = fetch_metadata(&batch, threads);
+        total += entries.len();
+        on_batch(entries);
+
+        if last {
+            return Ok(total);
+        }
+        batch_size = BATCH_SIZE;
+    }
+}
+
+/// Fetch metadata for a batch, splitting it across `threads` workers
+fn fetch_metadata(batch: &[DirEntry], threads: usize) -> Vec<FileEntry> {
+    let convert = |entries: &[DirEntry]| -> Vec<FileEntry> {
+        entries.iter()
+            .filter_map(|entry| {
+                entry.metadata().ok().map(|metadata| entry_from_metadata(&entry.path(), &metadata))
+            })
+            .collect()
+    };
+
+    if threads <= 1 || batch.len() < PARALLEL_THRESHOLD {
+        return convert(batch);
+    }
+
+    let chunk_size = batch.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = batch.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || convert(chunk)))
+            .collect();
+        workers.into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    })
+}
@@ -0,0 +1,325 @@
+//! Reading large files a page at a time
+//!
+//! Viewers never hold a whole file in memory. Bytes are fetched with
+//! positioned reads, and a `LineIndex` records the offset of every
+//! `LINE_INDEX_STRIDE`-th line so any line can be found by reading forward
+//! from the nearest checkpoint. The index only grows, so a file being
+//! appended to can be followed by scanning just the new bytes.
+
+use std::fs::File;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::core::{FsError, FsResult};
+
+/// Lines between index checkpoints
+pub const LINE_INDEX_STRIDE: u64 = 1024;
+/// Bytes read at a time while scanning or looking for lines
+const CHUNK_SIZE: usize = 1 << 20;
+/// Most bytes `read_lines` reads in one call, so one enormous line cannot stall a viewer
+const READ_BUDGET: u64 = 8 << 20;
+
+/// Sparse index of line start offsets
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// Offset of line `i * LINE_INDEX_STRIDE`
+    checkpoints: Vec<u64>,
+    /// Newlines seen so far
+    newlines: u64,
+    /// Bytes scanned so far
+    scanned: u64,
+    /// Start of the last line seen
+    last_line_start: u64,
+}
+
+impl Default for LineIndex {
+    fn default() -> Self {
+        Self {
+            checkpoints: vec![0],
+            newlines: 0,
+            scanned: 0,
+            last_line_start: 0,
+        }
+    }
+}
+
+impl LineIndex {
+    /// Feed the bytes that directly follow everything scanned so far
+    pub fn extend(&mut self, bytes: &[u8]) {
+        for position in memchr::memchr_iter(b'\n', bytes) {
+            self.newlines += 1;
+            self.last_line_start = self.scanned + position as u64 + 1;
+            if self.newlines.is_multiple_of(LINE_INDEX_STRIDE) {
+                self.checkpoints.push(self.last_line_start);
+            }
+        }
+        self.scanned += bytes.len() as u64;
+    }
+
+    /// Lines found so far, counting an unterminated last line
+    pub fn line_count(&self) -> u64 {
+        self.newlines + u64::from(self.scanned > self.last_line_start)
+    }
+
+    /// Bytes scanned so far
+    pub fn scanned(&self) -> u64 {
+        self.scanned
+    }
+
+    /// Nearest checkpoint at or before `line`, as (line, offset)
+    fn checkpoint(&self, line: u64) -> (u64, u64) {
+        let slot = ((line / LINE_INDEX_STRIDE) as usize).min(self.checkpoints.len() - 1);
+        (slot as u64 * LINE_INDEX_STRIDE, self.checkpoints[slot])
+    }
+}
+
+/// Read up to `len` bytes at `offset` (fewer at the end of the file)
+pub fn read_at(file: &File, offset: u64, len: usize) -> FsResult<Vec<u8>> {
+    let mut buffer = vec![0; len];
+    let mut filled = 0;
+    while filled < len {
+        let read = read_at_raw(file, &mut buffer[filled..], offset + filled as u64)?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    buffer.truncate(filled);
+    Ok(buffer)
+}
+
+/// Scan the file from where `index` stopped up to `end`
+///
+/// The index is locked only to merge each chunk, so readers can use it
+/// while the scan runs. Returns `FsError::Cancelled` once `cancel` is set.
+pub fn scan_lines(file: &File, index: &Mutex<LineIndex>, end: u64, cancel: &AtomicBool) -> FsResult<()> {
+    let mut offset = lock(index).scanned();
+    while offset < end {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(FsError::Cancelled);
+        }
+        let len = (end - offset).min(CHUNK_SIZE as u64) as usize;
+        let chunk = read_at(file, offset, len)?;
+        if chunk.is_empty() {
+            // Truncated while scanning; the caller notices the smaller length
+            break;
+        }
+        lock(index).extend(&chunk);
+        offset += chunk.len() as u64;
+    }
+    Ok(())
+}
+
+/// Read `count` lines starting at line `first`, without their line endings
+///
+/// Only the indexed part of the file is read. Lines longer than
+/// `max_line_len` bytes are cut short, and fewer lines are returned if
+/// reading them would take more than a few megabytes.
+pub fn read_lines(
+    file: &File,
+    index: &LineIndex,
+    first: u64,
+    count: usize,
+    max_line_len: usize,
+) -> FsResult<Vec<Vec<u8>>> {
+    let (mut line, mut offset) = index.checkpoint(first);
+    let end = index.scanned();
+    let budget_end = offset.saturating_add(READ_BUDGET).min(end);
+    let mut lines = Vec::with_capacity(count);
+    let mut current = Vec::new();
+
+    while offset < budget_end && lines.len() < count {
+        let chunk = read_at(file, offset, CHUNK_SIZE.min((budget_end - offset) as usize))?;
+        if chunk.is_empty() {
+            break;
+        }
+        let mut start = 0;
+        for position in memchr::memchr_iter(b'\n', &chunk) {
+            if line >= first {
+                push_limited(&mut current, &chunk[start..position], max_line_len);
+                lines.push(trim_cr(std::mem::take(&mut current)));
+                if lines.len() == count {
+                    return Ok(lines);
+                }
+            }
+            line += 1;
+            start = position + 1;
+        }
+        if line >= first {
+            push_limited(&mut current, &chunk[start..], max_line_len);
+        }
+        offset += chunk.len() as u64;
+    }
+
+    // Unterminated last line
+    if offset >= end && line >= first && lines.len() < count && index.scanned() > index.last_line_start {
+        lines.push(trim_cr(current));
+    }
+    Ok(lines)
+}
+
+/// Offset where `line` starts, if it has been indexed
+pub fn line_offset(file: &File, index: &LineIndex, line: u64) -> FsResult<Option<u64>> {
+    if line >= index.line_count() {
+        return Ok(None);
+    }
+    let (mut current, mut offset) = index.checkpoint(line);
+    while current < line {
+        let chunk = read_at(file, offset, CHUNK_SIZE)?;
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+        match memchr::memchr_iter(b'\n', &chunk).nth((line - current - 1) as usize) {
+            Some(position) => return Ok(Some(offset + position as u64 + 1)),
+            None => {
+                current += memchr::memchr_iter(b'\n', &chunk).count() as u64;
+                offset += chunk.len() as u64;
+            }
+        }
+    }
+    Ok(Some(offset))
+}
+
+/// Line containing the byte at `offset`, if that part has been indexed
+pub fn line_at_offset(file: &File, index: &LineIndex, offset: u64) -> FsResult<Option<u64>> {
+    if offset >= index.scanned() {
+        return Ok(None);
+    }
+    let slot = index.checkpoints.partition_point(|&start| start <= offset) - 1;
+    let mut line = slot as u64 * LINE_INDEX_STRIDE;
+    let mut position = index.checkpoints[slot];
+    while position < offset {
+        let len = (offset - position).min(CHUNK_SIZE as u64) as usize;
+        let chunk = read_at(file, position, len)?;
+        if chunk.is_empty() {
+            break;
+        }
+        line += memchr::memchr_iter(b'\n', &chunk).count() as u64;
+        position += chunk.len() as u64;
+    }
+    Ok(Some(line))
+}
+
+fn push_limited(line: &mut Vec<u8>, bytes: &[u8], max_len: usize) {
+    let room = max_len.saturating_sub(line.len());
+    line.extend_from_slice(&bytes[..bytes.len().min(room)]);
+}
+
+fn trim_cr(mut line: Vec<u8>) -> Vec<u8> {
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    line
+}
+
+/// A panicked reader cannot leave the index half-updated, so poisoning is ignored
+fn lock(index: &Mutex<LineIndex>) -> std::sync::MutexGuard<'_, LineIndex> {
+    index.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(unix)]
+fn read_at_raw(file: &File, buffer: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    use std::os::unix::fs::FileExt;
+    file.read_at(buffer, offset)
+}
+
+#[cfg(windows)]
+fn read_at_raw(file: &File, buffer: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    use std::os::windows::fs::FileExt;
+    file.seek_read(buffer, offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn indexed(contents: &[u8]) -> (File, LineIndex) {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(contents).unwrap();
+        let index = Mutex::new(LineIndex::default());
+        scan_lines(&file, &index, contents.len() as u64, &AtomicBool::new(false)).unwrap();
+        (file, index.into_inner().unwrap())
+    }
+
+    fn lines(file: &File, index: &LineIndex, first: u64, count: usize) -> Vec<String> {
+        read_lines(file, index, first, count, usize::MAX)
+            .unwrap()
+            .into_iter()
+            .map(|line| String::from_utf8(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn empty_file_has_no_lines() {
+        let (file, index) = indexed(b"");
+        assert_eq!(index.line_count(), 0);
+        assert!(lines(&file, &index, 0, 10).is_empty());
+        assert_eq!(line_offset(&file, &index, 0).unwrap(), None);
+        assert_eq!(line_at_offset(&file, &index, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn unterminated_last_line_is_counted() {
+        let (file, index) = indexed(b"one\ntwo");
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(lines(&file, &index, 0, 10), ["one", "two"]);
+        assert_eq!(lines(&file, &index, 1, 10), ["two"]);
+        assert_eq!(line_offset(&file, &index, 1).unwrap(), Some(4));
+        assert_eq!(line_at_offset(&file, &index, 6).unwrap(), Some(1));
+
+        let (file, index) = indexed(b"one\ntwo\n");
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(lines(&file, &index, 0, 10), ["one", "two"]);
+        assert_eq!(line_offset(&file, &index, 2).unwrap(), None);
+    }
+
+    #[test]
+    fn crlf_endings_are_stripped() {
+        let (file, index) = indexed(b"one\r\ntwo\r\nthree");
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(lines(&file, &index, 0, 10), ["one", "two", "three"]);
+        assert_eq!(line_offset(&file, &index, 1).unwrap(), Some(5));
+        assert_eq!(line_at_offset(&file, &index, 4).unwrap(), Some(0));
+        assert_eq!(line_at_offset(&file, &index, 5).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn offsets_around_index_checkpoints() {
+        let total = LINE_INDEX_STRIDE * 2 + 5;
+        let contents: String = (0..total).map(|i| format!("{}\n", i)).collect();
+        let (file, index) = indexed(contents.as_bytes());
+        assert_eq!(index.line_count(), total);
+
+        let start_of = |line: u64| {
+            (0..line).map(|i| format!("{}\n", i).len() as u64).sum::<u64>()
+        };
+        for line in [LINE_INDEX_STRIDE - 1, LINE_INDEX_STRIDE, LINE_INDEX_STRIDE + 1, LINE_INDEX_STRIDE * 2] {
+            let offset = start_of(line);
+            assert_eq!(line_offset(&file, &index, line).unwrap(), Some(offset));
+            assert_eq!(line_at_offset(&file, &index, offset).unwrap(), Some(line));
+        }
+        // The newline ending the line before a checkpoint still belongs to that line
+        let checkpoint = start_of(LINE_INDEX_STRIDE);
+        assert_eq!(line_at_offset(&file, &index, checkpoint - 1).unwrap(), Some(LINE_INDEX_STRIDE - 1));
+
+        let first = LINE_INDEX_STRIDE - 2;
+        let expected: Vec<String> = (first..first + 4).map(|i| i.to_string()).collect();
+        assert_eq!(lines(&file, &index, first, 4), expected);
+    }
+
+    #[test]
+    fn offsets_past_the_end_are_not_found() {
+        let (file, index) = indexed(b"one\ntwo\n");
+        assert_eq!(line_at_offset(&file, &index, 7).unwrap(), Some(1));
+        assert_eq!(line_at_offset(&file, &index, 8).unwrap(), None);
+        assert_eq!(line_at_offset(&file, &index, 1000).unwrap(), None);
+        assert!(lines(&file, &index, 5, 10).is_empty());
+    }
+
+    #[test]
+    fn long_lines_are_cut_short() {
+        let (file, index) = indexed(b"abcdefgh\nij\n");
+        let lines = read_lines(&file, &index, 0, 10, 4).unwrap();
+        assert_eq!(lines, [b"abcd".to_vec(), b"ij".to_vec()]);
+    }
+}
@@ -0,0 +1,589 @@
+//! File search by name and content
+//!
+//! `search` walks a directory tree on several threads and hands every match
+//! to the caller as soon as it is found, so a user interface can show
+//! results while the search is still running and stop it at any point.
+//! The walk honours `.gitignore` and similar files unless told otherwise.
+//!
+//! Contents are searched only in files that do not look binary. Small files
+//! are read in one go and large ones memory-mapped, and either is first
+//! checked as a whole so that files without a match are never split into
+//! lines. A file that cannot be mapped is streamed a line at a time.
+
+use std::collections::VecDeque;
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use chrono::{DateTime, Local};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use memmap2::Mmap;
+use regex::{Regex, RegexBuilder};
+use crate::core::{EntryKind, FileEntry, FsError, FsResult, SearchOptions};
+use crate::listing::entry_from_metadata;
+
+/// Matching lines reported per file; further matches are only counted
+pub const MAX_LINE_MATCHES: usize = 100;
+/// Characters of a line kept for the preview
+pub const MAX_PREVIEW_CHARS: usize = 240;
+/// Characters kept before the first match when the preview has to skip the
+/// start of a long line
+const PREVIEW_LEAD_CHARS: usize = 40;
+/// Bytes at the start of a file checked for NUL to detect binary files
+const BINARY_CHECK_BYTES: usize = 8192;
+/// Files at least this large are memory-mapped instead of read
+const MMAP_MIN_SIZE: u64 = 1024 * 1024;
+/// Lines read between checks of the cancel flag
+const CANCEL_CHECK_LINES: u64 = 4096;
+/// Hits the walker threads may queue before waiting for the caller
+const HIT_QUEUE: usize = 256;
+/// Version control directories skipped together with ignored entries
+const VCS_DIRECTORIES: [&str; 3] = [".git", ".hg", ".svn"];
+
+/// A matching line, or a line of context around one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    /// Line number, starting at 1
+    pub line_number: u64,
+    /// Character column of the first match, starting at 1; 0 for context
+    pub column: usize,
+    /// The line without its line break, shortened to `MAX_PREVIEW_CHARS`
+    /// around the first match
+    pub text: String,
+    /// Byte ranges of the matches within `text`
+    pub matches: Vec<Range<usize>>,
+}
+
+impl LineMatch {
+    /// Whether this line is context rather than a match
+    pub fn is_context(&self) -> bool {
+        self.column == 0
+    }
+}
+
+/// An entry that matched the search
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub entry: FileEntry,
+    /// Matching lines and their context when the contents were searched,
+    /// otherwise empty
+    pub lines: Vec<LineMatch>,
+    /// Number of matching lines, including those beyond `MAX_LINE_MATCHES`
+    pub line_count: u64,
+}
+
+/// Totals for a finished search
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchSummary {
+    /// Entries looked at
+    pub scanned: u64,
+    /// Entries reported as hits
+    pub hits: u64,
+    /// Entries that could not be read
+    pub errors: u64,
+    /// Files whose contents were skipped as binary
+    pub binary: u64,
+}
+
+/// Search `root` recursively, calling `on_hit` for every match
+///
+/// `pattern` is matched against file names. With `content_pattern` set, a
+/// file must also contain a matching line; with `search_contents` set, a
+/// file whose contents match `pattern` is returned even if its name does
+/// not. Hits arrive in no particular order. Returns `FsError::Cancelled`
+/// once `cancel` is set.
+pub fn search(
+    root: &Path,
+    options: &SearchOptions,
+    cancel: &AtomicBool,
+    on_hit: impl FnMut(SearchHit),
+) -> FsResult<SearchSummary> {
+    search_with_progress(root, options, cancel, &AtomicU64::new(0), on_hit)
+}
+
+/// `search`, counting the entries looked at in `scanned` as it goes
+pub fn search_with_progress(
+    root: &Path,
+    options: &SearchOptions,
+    cancel: &AtomicBool,
+    scanned: &AtomicU64,
+    mut on_hit: impl FnMut(SearchHit),
+) -> FsResult<SearchSummary> {
+    let searcher = Searcher::new(options, cancel, scanned)?;
+    let walker = walk_builder(root, options).build_parallel();
+
+    // The walker threads queue hits so that `on_hit` runs on this thread
+    let (sender, receiver) = mpsc::sync_channel(HIT_QUEUE);
+    let searcher = &searcher;
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            walker.run(|| {
+                let sender = sender.clone();
+                Box::new(move |entry| searcher.visit(entry, &sender))
+            })
+        });
+        for hit in receiver {
+            on_hit(hit);
+        }
+    });
+
+    if cancel.load(Ordering::Relaxed) {
+        return Err(FsError::Cancelled);
+    }
+    let hits = searcher.hits.load(Ordering::Relaxed);
+    Ok(SearchSummary {
+        scanned: scanned.load(Ordering::Relaxed),
+        hits: options.max_results.map_or(hits, |max| hits.min(max as u64)),
+        errors: searcher.errors.load(Ordering::Relaxed),
+        binary: searcher.binary.load(Ordering::Relaxed),
+    })
+}
+
+fn walk_builder(root: &Path, options: &SearchOptions) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(!options.include_hidden)
+        .parents(options.respect_ignore)
+        .ignore(options.respect_ignore)
+        .git_ignore(options.respect_ignore)
+        .git_global(options.respect_ignore)
+        .git_exclude(options.respect_ignore)
+        .max_depth(options.max_depth);
+    if options.respect_ignore {
+        builder.filter_entry(|entry| {
+            let is_vcs = entry.depth() > 0
+                && entry.file_type().is_some_and(|file_type| file_type.is_dir())
+                && VCS_DIRECTORIES.iter().any(|name| entry.file_name() == *name);
+            !is_vcs
+        });
+    }
+    builder
+}
+
+/// A pattern searched for in contents, compiled for lines and whole files
+struct ContentPattern {
+    /// Finds the matches within a line
+    line: Regex,
+    /// Whether a line, or any line of a whole file, matches
+    file: regex::bytes::Regex,
+}
+
+impl ContentPattern {
+    fn new(pattern: &str, case_sensitive: bool) -> FsResult<Self> {
+        let file = regex::bytes::RegexBuilder::new(pattern)
+            .case_insensitive(!case_sensitive)
+            .multi_line(true)
+            .crlf(true)
+            .build()
+            .map_err(|e| FsError::InvalidRegex(e.to_string()))?;
+        Ok(Self { line: build_regex(pattern, case_sensitive)?, file })
+    }
+}
+
+/// Filters and counters shared by the walker threads
+struct Searcher<'a> {
+    options: &'a SearchOptions,
+    cancel: &'a AtomicBool,
+    scanned: &'a AtomicU64,
+    name: Regex,
+    /// `content_pattern`, searched in files whose name matches
+    content: Option<ContentPattern>,
+    /// `pattern`, searched in files whose name does not match (`search_contents`)
+    name_in_contents: Option<ContentPattern>,
+    /// Lowercase extensions without the dot
+    extensions: Option<Vec<String>>,
+    /// Modification time range, with the ages resolved when the search started
+    modified_after: Option<DateTime<Local>>,
+    modified_before: Option<DateTime<Local>>,
+    hits: AtomicU64,
+    errors: AtomicU64,
+    binary: AtomicU64,
+}
+
+impl<'a> Searcher<'a> {
+    fn new(options: &'a SearchOptions, cancel: &'a AtomicBool, scanned: &'a AtomicU64) -> FsResult<Self> {
+        let content = options
+            .content_pattern
+            .as_deref()
+            .map(|pattern| ContentPattern::new(pattern, options.case_sensitive))
+            .transpose()?;
+        let name_in_contents = if content.is_none() && options.search_contents {
+            Some(ContentPattern::new(&options.pattern, options.case_sensitive)?)
+        } else {
+            None
+        };
+        let extensions = options.extensions.as_ref().map(|extensions| {
+            extensions.iter().map(|ext| ext.trim_start_matches('.').to_lowercase()).collect()
+        });
+        let (modified_after, modified_before) = options.modified_range(Local::now());
+        Ok(Self {
+            options,
+            cancel,
+            scanned,
+            name: build_regex(&options.pattern, options.case_sensitive)?,
+            content,
+            name_in_contents,
+            extensions,
+            modified_after,
+            modified_before,
+            hits: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            binary: AtomicU64::new(0),
+        })
+    }
+
+    /// Handle one entry from the walker
+    fn visit(&self, entry: Result<DirEntry, ignore::Error>, sender: &SyncSender<SearchHit>) -> WalkState {
+        if self.cancel.load(Ordering::Relaxed) {
+            return WalkState::Quit;
+        }
+        let entry = match entry {
+            Ok(entry) if entry.depth() == 0 => return WalkState::Continue,
+            Ok(entry) => entry,
+            Err(_) => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                return WalkState::Continue;
+            }
+        };
+        self.scanned.fetch_add(1, Ordering::Relaxed);
+        let Some(hit) = self.check(&entry) else {
+            return WalkState::Continue;
+        };
+
+        let max = self.options.max_results.map(|max| max as u64);
+        let taken = self.hits.fetch_add(1, Ordering::Relaxed);
+        if max.is_some_and(|max| taken >= max) || sender.send(hit).is_err() {
+            return WalkState::Quit;
+        }
+        if max.is_some_and(|max| taken + 1 >= max) {
+            return WalkState::Quit;
+        }
+        WalkState::Continue
+    }
+
+    /// The hit for `entry`, if it passes every filter
+    fn check(&self, entry: &DirEntry) -> Option<SearchHit> {
+        let path = entry.path();
+        let is_dir = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+        if let Some(extensions) = &self.extensions {
+            let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+            if is_dir || !extension.is_some_and(|ext| extensions.contains(&ext)) {
+                return None;
+            }
+        }
+        if !self.options.categories.is_empty() && (is_dir || !self.options.matches_category(path)) {
+            return None;
+        }
+
+        // The name is checked first so most entries need no metadata
+        let name_matches = self.name.is_match(&entry.file_name().to_string_lossy());
+        let contents = match (&self.content, &self.name_in_contents) {
+            (Some(pattern), _) if name_matches => Some(pattern),
+            (Some(_), _) => return None,
+            (None, Some(pattern)) if !name_matches => Some(pattern),
+            (None, _) if !name_matches => return None,
+            (None, _) => None,
+        };
+        if contents.is_some() && is_dir {
+            return None;
+        }
+
+        let Ok(metadata) = entry.metadata() else {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        if !self.matches_metadata(&metadata) {
+            return None;
+        }
+        let (lines, line_count) = match contents {
+            Some(_) if !metadata.is_file() => return None,
+            Some(pattern) => match scan_file(path, metadata.len(), pattern, self.options, self.cancel) {
+                Ok(Some((_, 0))) => return None,
+                Ok(Some(found)) => found,
+                Ok(None) => {
+                    self.binary.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+                Err(_) => {
+                    self.errors.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+            },
+            None => (Vec::new(), 0),
+        };
+        Some(SearchHit { entry: entry_from_metadata(path, &metadata), lines, line_count })
+    }
+
+    /// Whether the kind, size and modification time pass the filters
+    fn matches_metadata(&self, metadata: &Metadata) -> bool {
+        let options = self.options;
+        let kind_matches = match options.kind {
+            EntryKind::Any => true,
+            EntryKind::Files => !metadata.is_dir(),
+            EntryKind::Directories => metadata.is_dir(),
+        };
+        if !kind_matches {
+            return false;
+        }
+        if options.min_size.is_some() || options.max_size.is_some() {
+            if metadata.is_dir() {
+                return false;
+            }
+            let size = metadata.len();
+            if options.min_size.is_some_and(|min| size < min) || options.max_size.is_some_and(|max| size > max) {
+                return false;
+            }
+        }
+        if self.modified_after.is_some() || self.modified_before.is_some() {
+            let Ok(modified) = metadata.modified() else {
+                return false;
+            };
+            let modified = DateTime::<Local>::from(modified);
+            if self.modified_after.is_some_and(|after| modified < after)
+                || self.modified_before.is_some_and(|before| modified >= before)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Regex matching any name that matches one of the `;`-separated globs
+///
+/// `*` matches any run of characters, `?` one character and `[...]` a set
+/// (`[!...]` a negated set). An empty glob matches every name.
+pub fn glob_to_regex(globs: &str) -> String {
+    let alternatives: Vec<String> = globs
+        .split(';')
+        .map(str::trim)
+        .filter(|glob| !glob.is_empty())
+        .map(|glob| {
+            let mut regex = String::new();
+            let mut chars = glob.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '*' => regex.push_str(".*"),
+                    '?' => regex.push('.'),
+                    '[' => {
+                        regex.push('[');
+                        if chars.next_if_eq(&'!').is_some() {
+                            regex.push('^');
+                        }
+                        for c in chars.by_ref() {
+                            if c == ']' {
+                                break;
+                            }
+                            if c == '\\' || c == '[' || c == '^' {
+                                regex.push('\\');
+                            }
+                            regex.push(c);
+                        }
+                        regex.push(']');
+                    }
+                    c => regex.push_str(&regex::escape(&c.to_string())),
+                }
+            }
+            regex
+        })
+        .collect();
+    if alternatives.is_empty() {
+        String::new()
+    } else {
+        format!("^(?:{})$", alternatives.join("|"))
+    }
+}
+
+fn build_regex(pattern: &str, case_sensitive: bool) -> FsResult<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| FsError::InvalidRegex(e.to_string()))
+}
+
+/// Matching lines of `path` and how many lines matched in total
+///
+/// Returns `None` for a binary file when `skip_binary` is set. Stops early,
+/// with what was found so far, once `cancel` is set.
+fn scan_file(
+    path: &Path,
+    len: u64,
+    pattern: &ContentPattern,
+    options: &SearchOptions,
+    cancel: &AtomicBool,
+) -> std::io::Result<Option<(Vec<LineMatch>, u64)>> {
+    let mut file = File::open(path)?;
+    if len < MMAP_MIN_SIZE {
+        let mut bytes = Vec::with_capacity(len as usize);
+        file.read_to_end(&mut bytes)?;
+        return Ok(scan_bytes(&bytes, pattern, options, cancel));
+    }
+    // SAFETY: the map is only read while searching. If another program
+    // truncates the file meanwhile, reading past its new end can fault;
+    // like other search tools we accept that for the speed on large files.
+    match unsafe { Mmap::map(&file) } {
+        Ok(map) => Ok(scan_bytes(&map, pattern, options, cancel)),
+        Err(_) => scan_reader(file, pattern, options, cancel),
+    }
+}
+
+/// `scan_file` for contents already in memory
+fn scan_bytes(
+    bytes: &[u8],
+    pattern: &ContentPattern,
+    options: &SearchOptions,
+    cancel: &AtomicBool,
+) -> Option<(Vec<LineMatch>, u64)> {
+    if options.skip_binary && is_binary(bytes) {
+        return None;
+    }
+    let mut collector = LineCollector::new(options.context_lines);
+    if !pattern.file.is_match(bytes) {
+        return Some(collector.finish());
+    }
+
+    let mut start = 0;
+    let mut line_number = 0;
+    while start < bytes.len() {
+        let end = memchr::memchr(b'\n', &bytes[start..]).map_or(bytes.len(), |offset| start + offset);
+        line_number += 1;
+        if line_number % CANCEL_CHECK_LINES == 0 && cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        collector.push(line_number, &bytes[start..end], pattern);
+        start = end + 1;
+        if collector.is_full() {
+            // Only the count is still needed, which the whole-file regex gives faster
+            collector.count += count_matching_lines(&bytes[start.min(bytes.len())..], &pattern.file);
+            break;
+        }
+    }
+    Some(collector.finish())
+}
+
+/// `scan_file` reading a line at a time
+fn scan_reader(
+    file: File,
+    pattern: &ContentPattern,
+    options: &SearchOptions,
+    cancel: &AtomicBool,
+) -> std::io::Result<Option<(Vec<LineMatch>, u64)>> {
+    let mut reader = BufReader::with_capacity(BINARY_CHECK_BYTES * 8, file);
+    if options.skip_binary && is_binary(reader.fill_buf()?) {
+        return Ok(None);
+    }
+    let mut collector = LineCollector::new(options.context_lines);
+    let mut buffer = Vec::new();
+    let mut line_number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line_number += 1;
+        if line_number % CANCEL_CHECK_LINES == 0 && cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+        collector.push(line_number, line, pattern);
+    }
+    Ok(Some(collector.finish()))
+}
+
+/// Whether the start of a file contains a NUL byte, as text never does
+fn is_binary(bytes: &[u8]) -> bool {
+    memchr::memchr(0, &bytes[..bytes.len().min(BINARY_CHECK_BYTES)]).is_some()
+}
+
+/// Number of lines in `bytes` with a match of the whole-file regex
+fn count_matching_lines(bytes: &[u8], regex: &regex::bytes::Regex) -> u64 {
+    let mut count = 0;
+    let mut next = 0;
+    while next < bytes.len() {
+        let Some(found) = regex.find_at(bytes, next) else {
+            break;
+        };
+        count += 1;
+        next = memchr::memchr(b'\n', &bytes[found.start()..]).map_or(bytes.len(), |offset| found.start() + offset + 1);
+    }
+    count
+}
+
+/// Collects matching lines of one file with the context around them
+struct LineCollector {
+    context: usize,
+    lines: Vec<LineMatch>,
+    /// Matching lines kept in `lines`
+    kept: usize,
+    /// Matching lines seen
+    count: u64,
+    /// Lines since the last kept line, for context before the next match
+    before: VecDeque<LineMatch>,
+    /// Lines of context still to keep after the last match
+    after: usize,
+}
+
+impl LineCollector {
+    fn new(context: usize) -> Self {
+        Self { context, lines: Vec::new(), kept: 0, count: 0, before: VecDeque::new(), after: 0 }
+    }
+
+    /// Take line `line_number` (without its line break)
+    fn push(&mut self, line_number: u64, line: &[u8], pattern: &ContentPattern) {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if pattern.file.is_match(line) {
+            self.count += 1;
+            if self.kept < MAX_LINE_MATCHES {
+                self.kept += 1;
+                self.lines.extend(self.before.drain(..));
+                self.lines.push(matching_line(line_number, &String::from_utf8_lossy(line), &pattern.line));
+                self.after = self.context;
+            } else {
+                self.after = 0;
+            }
+        } else if self.after > 0 {
+            self.after -= 1;
+            self.lines.push(context_line(line_number, line));
+        } else if self.context > 0 && self.kept < MAX_LINE_MATCHES {
+            if self.before.len() == self.context {
+                self.before.pop_front();
+            }
+            self.before.push_back(context_line(line_number, line));
+        }
+    }
+
+    /// Whether further lines can only add to the count
+    fn is_full(&self) -> bool {
+        self.kept >= MAX_LINE_MATCHES && self.after == 0
+    }
+
+    fn finish(self) -> (Vec<LineMatch>, u64) {
+        (self.lines, self.count)
+    }
+}
+
+fn context_line(line_number: u64, line: &[u8]) -> LineMatch {
+    let text = String::from_utf8_lossy(line).chars().take(MAX_PREVIEW_CHARS).collect();
+    LineMatch { line_number, column: 0, text, matches: Vec::new() }
+}
+
+/// A matching line, its preview starting shortly before the first match
+/// when that would otherwise be cut off
+fn matching_line(line_number: u64, line: &str, regex: &Regex) -> LineMatch {
+    let found: Vec<Range<usize>> = regex.find_iter(line).map(|found| found.range()).collect();
+    let first = found.first().map_or(0, |range| range.start);
+    let column = line[..first].chars().count() + 1;
+
+    let skip = if column - 1 + PREVIEW_LEAD_CHARS > MAX_PREVIEW_CHARS { column - 1 - PREVIEW_LEAD_CHARS } else { 0 };
+    let byte_at = |chars: usize| line.char_indices().nth(chars).map_or(line.len(), |(index, _)| index);
+    let start = byte_at(skip);
+    let end = start + line[start..].char_indices().nth(MAX_PREVIEW_CHARS).map_or(line.len() - start, |(index, _)| index);
+    let matches = found
+        .into_iter()
+        .filter(|range| range.start < end && range.end > start)
+        .map(|range| range.start.max(start) - start..range.end.min(end) - start)
+        .collect();
+    LineMatch { line_number, column, text: line[start..end].to_string(), matches }
+}
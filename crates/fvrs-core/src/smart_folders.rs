@@ -0,0 +1,175 @@
+//! Saved searches shown as folders
+//!
+//! A smart folder is a named `SearchOptions` query over a directory tree.
+//! Opening it runs the search again, so the contents are always current.
+//! The folders are kept in `smart_folders.json` in the configuration
+//! directory so the GUI and the CLI share them.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult, SearchOptions};
+use crate::search::{self, SearchHit, SearchSummary};
+
+/// File the smart folders are stored in, inside `config::config_dir`
+pub const SMART_FOLDERS_FILE: &str = "smart_folders.json";
+/// Icon used when a smart folder has none
+pub const DEFAULT_ICON: &str = "🔎";
+
+/// A named search over `root`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartFolder {
+    pub name: String,
+    /// Directory searched recursively
+    pub root: PathBuf,
+    #[serde(default = "default_icon")]
+    pub icon: String,
+    #[serde(default)]
+    pub options: SearchOptions,
+}
+
+fn default_icon() -> String {
+    DEFAULT_ICON.to_string()
+}
+
+impl SmartFolder {
+    pub fn new(name: &str, root: &Path, options: SearchOptions) -> Self {
+        Self { name: name.to_string(), root: root.to_path_buf(), icon: default_icon(), options }
+    }
+
+    /// Run the search, calling `on_hit` for every entry currently in the folder
+    ///
+    /// Ages in the options count back from now, so each run sees the
+    /// entries that match at that moment.
+    pub fn run<F>(&self, cancel: &AtomicBool, on_hit: F) -> FsResult<SearchSummary>
+    where
+        F: FnMut(SearchHit),
+    {
+        if !self.root.is_dir() {
+            return Err(FsError::InvalidPath(format!("{} is not a directory", self.root.display())));
+        }
+        search::search(&self.root, &self.options, cancel, on_hit)
+    }
+}
+
+/// All smart folders, in the order they are shown
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmartFolders {
+    folders: Vec<SmartFolder>,
+}
+
+impl SmartFolders {
+    /// Where the smart folders are stored for the current user
+    pub fn default_path() -> Option<PathBuf> {
+        crate::config::config_dir().map(|dir| dir.join(SMART_FOLDERS_FILE))
+    }
+
+    /// Read smart folders saved by `save`; a missing file means none
+    pub fn load(path: &Path) -> FsResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| FsError::Serialization(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the smart folders, replacing the file only once it is complete
+    pub fn save(&self, path: &Path) -> FsResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| FsError::Serialization(e.to_string()))?;
+        let temp = crate::copy::temp_path(path);
+        std::fs::write(&temp, text)
+            .and_then(|()| std::fs::rename(&temp, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&temp);
+                e.into()
+            })
+    }
+
+    pub fn folders(&self) -> &[SmartFolder] {
+        &self.folders
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folders.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SmartFolder> {
+        self.folders.iter().find(|folder| folder.name == name)
+    }
+
+    /// `name`, or `name (2)`, `name (3)`... if it is already taken
+    pub fn unique_name(&self, name: &str) -> String {
+        (1..)
+            .map(|n| if n == 1 { name.to_string() } else { format!("{} ({})", name, n) })
+            .find(|candidate| self.get(candidate).is_none())
+            .unwrap_or_default()
+    }
+
+    /// Add a smart folder at the end; fails if the name is invalid or taken
+    pub fn add(&mut self, folder: SmartFolder) -> FsResult<()> {
+        self.check(&folder, None)?;
+        self.folders.push(folder);
+        Ok(())
+    }
+
+    /// Replace the smart folder called `name`, keeping its position
+    pub fn update(&mut self, name: &str, folder: SmartFolder) -> FsResult<()> {
+        let index = self.index_of(name)?;
+        self.check(&folder, Some(index))?;
+        self.folders[index] = folder;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<SmartFolder> {
+        let index = self.folders.iter().position(|folder| folder.name == name)?;
+        Some(self.folders.remove(index))
+    }
+
+    /// Move the smart folder called `name` one place up or down
+    pub fn move_by_one(&mut self, name: &str, up: bool) -> FsResult<()> {
+        let index = self.index_of(name)?;
+        let neighbour = if up { index.checked_sub(1) } else { Some(index + 1).filter(|&i| i < self.folders.len()) };
+        if let Some(neighbour) = neighbour {
+            self.folders.swap(index, neighbour);
+        }
+        Ok(())
+    }
+
+    fn index_of(&self, name: &str) -> FsResult<usize> {
+        self.folders
+            .iter()
+            .position(|folder| folder.name == name)
+            .ok_or_else(|| FsError::InvalidPath(format!("no smart folder named {}", name)))
+    }
+
+    /// Validate `folder` as the entry at `index` (None when adding)
+    fn check(&self, folder: &SmartFolder, index: Option<usize>) -> FsResult<()> {
+        let name = &folder.name;
+        if name.trim().is_empty() || name.trim() != name {
+            return Err(FsError::InvalidPath(format!("smart folder name {:?} is empty or padded with spaces", name)));
+        }
+        if name.chars().any(|c| c == '/' || c == '\\' || c.is_control()) {
+            return Err(FsError::InvalidPath(format!("smart folder name {:?} must not contain slashes", name)));
+        }
+        if !folder.root.is_absolute() {
+            return Err(FsError::InvalidPath(format!("{} is not an absolute path", folder.root.display())));
+        }
+        for pattern in std::iter::once(&folder.options.pattern).chain(&folder.options.content_pattern) {
+            regex::Regex::new(pattern).map_err(|e| FsError::InvalidRegex(e.to_string()))?;
+        }
+        let taken = self
+            .folders
+            .iter()
+            .enumerate()
+            .any(|(i, other)| Some(i) != index && other.name == *name);
+        if taken {
+            return Err(FsError::Conflict(format!("a smart folder named {} already exists", name)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,487 @@
+//! Splitting files into parts and joining them back
+//!
+//! `split` cuts a file into numbered parts (`name.001`, `name.002`, ...)
+//! next to a manifest, `name.split.json`, that records the size and hash of
+//! every part and of the whole file. `verify` checks the parts against the
+//! manifest and `rejoin` puts the original back together, checking the
+//! result. `join` simply concatenates any files in the order given.
+//!
+//! Hashes are the ones `FileSystem::calculate_hash` computes, so a manifest
+//! can be checked with any of the supported algorithms.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::{Serialize, Deserialize};
+use crate::core::{hash_file, FsError, FsResult, HashAlgorithm};
+
+/// Size of the blocks files are copied in
+const BUFFER_SIZE: usize = 1024 * 1024;
+/// Appended to the original file name to name the manifest
+pub const MANIFEST_SUFFIX: &str = ".split.json";
+
+/// How to cut a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SplitMode {
+    /// Parts of this many bytes; the last one may be shorter
+    Size(u64),
+    /// Parts of this many lines; the last one may be shorter
+    Lines(u64),
+    /// This many parts of about the same size
+    Count(u32),
+}
+
+/// Options for `split`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitOptions {
+    pub mode: SplitMode,
+    /// Algorithm for the hashes in the manifest
+    pub algorithm: HashAlgorithm,
+    /// Where to write the parts and the manifest (None = next to the file)
+    pub output_dir: Option<PathBuf>,
+}
+
+/// One part listed in a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitPart {
+    /// File name, relative to the manifest
+    pub file_name: String,
+    pub size: u64,
+    pub hash: String,
+}
+
+/// Description of a split file, stored next to its parts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitManifest {
+    /// Name of the original file
+    pub file_name: String,
+    pub size: u64,
+    pub algorithm: HashAlgorithm,
+    /// Hash of the whole original file
+    pub hash: String,
+    /// Parts in the order they are joined
+    pub parts: Vec<SplitPart>,
+}
+
+impl SplitManifest {
+    /// Read a manifest written by `split`
+    ///
+    /// File names that are not plain names (`../x`, `/x`, `a/b`) are
+    /// rejected so that a manifest can only refer to files next to it.
+    pub fn load(path: &Path) -> FsResult<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let manifest: Self =
+            serde_json::from_str(&text).map_err(|e| FsError::Serialization(format!("{}: {}", path.display(), e)))?;
+        let names = std::iter::once(&manifest.file_name).chain(manifest.parts.iter().map(|part| &part.file_name));
+        for name in names {
+            if !is_plain_name(name) {
+                return Err(FsError::InvalidPath(format!("{}: invalid file name {:?}", path.display(), name)));
+            }
+        }
+        Ok(manifest)
+    }
+
+    fn save(&self, path: &Path) -> FsResult<()> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| FsError::Serialization(e.to_string()))?;
+        let mut file = create_new(path)?;
+        file.write_all(text.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// State of one part found by `verify`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartStatus {
+    Ok,
+    Missing,
+    /// The part has a different size than recorded
+    WrongSize(u64),
+    /// The part has the recorded size but a different hash
+    WrongHash,
+}
+
+/// Whether `name` is a single normal path component
+fn is_plain_name(name: &str) -> bool {
+    Path::new(name).file_name() == Some(std::ffi::OsStr::new(name))
+}
+
+/// Whether `path` looks like a manifest written by `split`
+pub fn is_manifest(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().ends_with(MANIFEST_SUFFIX))
+}
+
+/// Split `path` into parts and write their manifest, returning the manifest path
+///
+/// Existing files are never overwritten: if a part or the manifest already
+/// exists the split fails with `FsError::Conflict`. Parts written before an
+/// error or cancellation are removed again. `progress` gets the bytes
+/// processed so far and the total, which counts the file twice since
+/// everything is read once more to hash it.
+pub fn split(
+    path: &Path,
+    options: &SplitOptions,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(u64, u64),
+) -> FsResult<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| FsError::InvalidPath(path.display().to_string()))?
+        .to_string_lossy()
+        .into_owned();
+    let dir = match &options.output_dir {
+        Some(dir) => dir.clone(),
+        None => path.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    let size = std::fs::metadata(path)?.len();
+    let total = size * 2;
+
+    let mut written = Vec::new();
+    let prefix = dir.join(&file_name);
+    let result = write_parts(path, &prefix, size, options.mode, cancel, &mut written, |done| {
+        progress(done, total)
+    })
+    .and_then(|()| {
+        let mut parts = Vec::with_capacity(written.len());
+        let mut done = size;
+        for part in &written {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(FsError::Cancelled);
+            }
+            let hashed = hash_file(part, options.algorithm)?;
+            done += hashed.size;
+            progress(done, total);
+            parts.push(SplitPart {
+                file_name: part.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                size: hashed.size,
+                hash: hashed.hash,
+            });
+        }
+        // The whole hash is checked when rejoining, so it is worth the
+        // extra read even though the parts are already hashed
+        let whole = hash_file(path, options.algorithm)?;
+        if whole.size != size {
+            return Err(FsError::Conflict(format!("{} changed while it was being split", path.display())));
+        }
+
+        let manifest = SplitManifest {
+            file_name: file_name.clone(),
+            size,
+            algorithm: options.algorithm,
+            hash: whole.hash,
+            parts,
+        };
+        let manifest_path = dir.join(format!("{}{}", file_name, MANIFEST_SUFFIX));
+        manifest.save(&manifest_path)?;
+        Ok(manifest_path)
+    });
+
+    if result.is_err() {
+        for part in &written {
+            let _ = std::fs::remove_file(part);
+        }
+    }
+    result
+}
+
+/// Write the parts of a split, recording each file as soon as it exists
+fn write_parts(
+    path: &Path,
+    prefix: &Path,
+    size: u64,
+    mode: SplitMode,
+    cancel: &AtomicBool,
+    written: &mut Vec<PathBuf>,
+    mut progress: impl FnMut(u64),
+) -> FsResult<()> {
+    let (part_size, part_lines) = match mode {
+        SplitMode::Size(0) | SplitMode::Lines(0) | SplitMode::Count(0) => {
+            return Err(FsError::NotSupported("splitting into empty parts".to_string()));
+        }
+        SplitMode::Size(bytes) => (bytes, u64::MAX),
+        SplitMode::Lines(lines) => (u64::MAX, lines),
+        SplitMode::Count(count) => (size.div_ceil(u64::from(count)).max(1), u64::MAX),
+    };
+
+    let mut input = File::open(path)?;
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut output: Option<BufWriter<File>> = None;
+    let (mut bytes_in_part, mut lines_in_part, mut done) = (0u64, 0u64, 0u64);
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(FsError::Cancelled);
+        }
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        let mut chunk = &buffer[..read];
+        while !chunk.is_empty() {
+            if output.is_none() {
+                let part = part_path(prefix, written.len() + 1);
+                let file = create_new(&part)?;
+                written.push(part);
+                output = Some(BufWriter::new(file));
+                bytes_in_part = 0;
+                lines_in_part = 0;
+            }
+
+            // Take what still fits in the current part
+            let mut take = chunk.len().min((part_size - bytes_in_part).min(usize::MAX as u64) as usize);
+            let mut full = bytes_in_part + take as u64 == part_size;
+            if part_lines != u64::MAX {
+                let wanted = (part_lines - lines_in_part) as usize;
+                match memchr::memchr_iter(b'\n', &chunk[..take]).nth(wanted - 1) {
+                    Some(end) => {
+                        take = end + 1;
+                        full = true;
+                    }
+                    None => lines_in_part += memchr::memchr_iter(b'\n', &chunk[..take]).count() as u64,
+                }
+            }
+
+            let writer = output.as_mut().expect("a part is open");
+            writer.write_all(&chunk[..take])?;
+            bytes_in_part += take as u64;
+            chunk = &chunk[take..];
+            if full {
+                output.take().expect("a part is open").into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            }
+        }
+        done += read as u64;
+        progress(done);
+    }
+
+    match output {
+        Some(writer) => writer.into_inner().map_err(|e| e.into_error())?.sync_all()?,
+        // An empty file still gets one (empty) part
+        None if written.is_empty() => {
+            let part = part_path(prefix, 1);
+            create_new(&part)?;
+            written.push(part);
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Path of the numbered part, e.g. `name.001`
+fn part_path(prefix: &Path, number: usize) -> PathBuf {
+    let mut path = prefix.as_os_str().to_owned();
+    path.push(format!(".{:03}", number));
+    PathBuf::from(path)
+}
+
+/// Check every part listed in the manifest at `manifest_path`
+pub fn verify(manifest_path: &Path, cancel: &AtomicBool) -> FsResult<Vec<(SplitPart, PartStatus)>> {
+    let manifest = SplitManifest::load(manifest_path)?;
+    let dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let mut statuses = Vec::with_capacity(manifest.parts.len());
+    for part in manifest.parts {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(FsError::Cancelled);
+        }
+        let path = dir.join(&part.file_name);
+        let status = match std::fs::metadata(&path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => PartStatus::Missing,
+            Err(e) => return Err(e.into()),
+            Ok(metadata) if metadata.len() != part.size => PartStatus::WrongSize(metadata.len()),
+            Ok(_) if hash_file(&path, manifest.algorithm)?.hash != part.hash => PartStatus::WrongHash,
+            Ok(_) => PartStatus::Ok,
+        };
+        statuses.push((part, status));
+    }
+    Ok(statuses)
+}
+
+/// Concatenate `sources` in order into a new file `output`
+///
+/// The result is written to a temporary file and renamed into place at the
+/// end, so a failed or cancelled join leaves nothing behind. Fails with
+/// `FsError::Conflict` if `output` already exists. `progress` gets the bytes
+/// written so far and the total. Returns the size of the result.
+pub fn join(
+    sources: &[PathBuf],
+    output: &Path,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(u64, u64),
+) -> FsResult<u64> {
+    if output.exists() {
+        return Err(FsError::Conflict(format!("{} already exists", output.display())));
+    }
+    let mut total = 0;
+    for source in sources {
+        total += std::fs::metadata(source)?.len();
+    }
+
+    let temp = crate::copy::temp_path(output);
+    let result = (|| {
+        let mut writer = BufWriter::new(create_new(&temp)?);
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        let mut done = 0u64;
+        for source in sources {
+            let mut input = File::open(source)?;
+            loop {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(FsError::Cancelled);
+                }
+                let read = input.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                writer.write_all(&buffer[..read])?;
+                done += read as u64;
+                progress(done, total);
+            }
+        }
+        writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        // Checked again in case it appeared while joining
+        if output.exists() {
+            return Err(FsError::Conflict(format!("{} already exists", output.display())));
+        }
+        std::fs::rename(&temp, output)?;
+        Ok(done)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Join the parts listed in a manifest back into `output`, checking the result
+///
+/// Every part is verified first, and the joined file is hashed and removed
+/// again if it does not match the manifest.
+pub fn rejoin(
+    manifest_path: &Path,
+    output: &Path,
+    cancel: &AtomicBool,
+    progress: impl FnMut(u64, u64),
+) -> FsResult<()> {
+    let manifest = SplitManifest::load(manifest_path)?;
+    let bad: Vec<String> = verify(manifest_path, cancel)?
+        .into_iter()
+        .filter(|(_, status)| *status != PartStatus::Ok)
+        .map(|(part, status)| format!("{} ({:?})", part.file_name, status))
+        .collect();
+    if !bad.is_empty() {
+        return Err(FsError::Hash(format!("damaged or missing parts: {}", bad.join(", "))));
+    }
+
+    let dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let sources: Vec<PathBuf> = manifest.parts.iter().map(|part| dir.join(&part.file_name)).collect();
+    join(&sources, output, cancel, progress)?;
+    let joined = hash_file(output, manifest.algorithm)?;
+    if joined.size != manifest.size || joined.hash != manifest.hash {
+        let _ = std::fs::remove_file(output);
+        return Err(FsError::Hash(format!("{} does not match the manifest", output.display())));
+    }
+    Ok(())
+}
+
+/// Create a file that must not exist yet
+fn create_new(path: &Path) -> FsResult<File> {
+    OpenOptions::new().write(true).create_new(true).open(path).map_err(|e| {
+        if e.kind() == ErrorKind::AlreadyExists {
+            FsError::Conflict(format!("{} already exists", path.display()))
+        } else {
+            e.into()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(mode: SplitMode, output_dir: &Path) -> SplitOptions {
+        SplitOptions { mode, algorithm: HashAlgorithm::SHA256, output_dir: Some(output_dir.to_path_buf()) }
+    }
+
+    fn split_file(contents: &[u8], mode: SplitMode) -> (tempfile::TempDir, PathBuf, SplitManifest) {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("data.txt");
+        std::fs::write(&source, contents).unwrap();
+        let parts = dir.path().join("parts");
+        std::fs::create_dir(&parts).unwrap();
+        let manifest_path = split(&source, &options(mode, &parts), &AtomicBool::new(false), |_, _| {}).unwrap();
+        let manifest = SplitManifest::load(&manifest_path).unwrap();
+        (dir, manifest_path, manifest)
+    }
+
+    #[test]
+    fn split_by_lines_and_rejoin() {
+        let contents = b"one\ntwo\nthree\nfour\nfive";
+        let (dir, manifest_path, manifest) = split_file(contents, SplitMode::Lines(2));
+
+        let parts: Vec<Vec<u8>> = manifest
+            .parts
+            .iter()
+            .map(|part| std::fs::read(dir.path().join("parts").join(&part.file_name)).unwrap())
+            .collect();
+        assert_eq!(parts, [b"one\ntwo\n".to_vec(), b"three\nfour\n".to_vec(), b"five".to_vec()]);
+        assert_eq!(manifest.parts[0].file_name, "data.txt.001");
+        assert!(is_manifest(&manifest_path));
+
+        let output = dir.path().join("joined.txt");
+        rejoin(&manifest_path, &output, &AtomicBool::new(false), |_, _| {}).unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), contents);
+    }
+
+    #[test]
+    fn split_by_count_and_rejoin() {
+        let contents: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let (dir, manifest_path, manifest) = split_file(&contents, SplitMode::Count(3));
+        let sizes: Vec<u64> = manifest.parts.iter().map(|part| part.size).collect();
+        assert_eq!(sizes, [3334, 3334, 3332]);
+
+        let output = dir.path().join("joined.bin");
+        rejoin(&manifest_path, &output, &AtomicBool::new(false), |_, _| {}).unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), contents);
+    }
+
+    #[test]
+    fn corrupted_part_is_reported() {
+        let (dir, manifest_path, manifest) = split_file(b"abcdefghij", SplitMode::Size(4));
+        let damaged = dir.path().join("parts").join(&manifest.parts[1].file_name);
+        std::fs::write(&damaged, b"EFGH").unwrap();
+        std::fs::remove_file(dir.path().join("parts").join(&manifest.parts[2].file_name)).unwrap();
+
+        let statuses: Vec<PartStatus> = verify(&manifest_path, &AtomicBool::new(false))
+            .unwrap()
+            .into_iter()
+            .map(|(_, status)| status)
+            .collect();
+        assert_eq!(statuses, [PartStatus::Ok, PartStatus::WrongHash, PartStatus::Missing]);
+
+        let output = dir.path().join("joined");
+        let result = rejoin(&manifest_path, &output, &AtomicBool::new(false), |_, _| {});
+        assert!(matches!(result, Err(FsError::Hash(_))));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn manifest_names_must_stay_in_its_directory() {
+        let (_dir, manifest_path, mut manifest) = split_file(b"abcdefghij", SplitMode::Size(4));
+        for name in ["../secret", "/etc/passwd", "sub/part", "..", ""] {
+            manifest.parts[0].file_name = name.to_string();
+            std::fs::write(&manifest_path, serde_json::to_string(&manifest).unwrap()).unwrap();
+            assert!(matches!(SplitManifest::load(&manifest_path), Err(FsError::InvalidPath(_))), "{}", name);
+            assert!(verify(&manifest_path, &AtomicBool::new(false)).is_err());
+        }
+    }
+
+    #[test]
+    fn split_never_overwrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("data");
+        std::fs::write(&source, b"abcdef").unwrap();
+        std::fs::write(dir.path().join("data.002"), b"keep").unwrap();
+
+        let result = split(&source, &options(SplitMode::Size(2), dir.path()), &AtomicBool::new(false), |_, _| {});
+        assert!(matches!(result, Err(FsError::Conflict(_))));
+        assert!(!dir.path().join("data.001").exists());
+        assert_eq!(std::fs::read(dir.path().join("data.002")).unwrap(), b"keep");
+    }
+}
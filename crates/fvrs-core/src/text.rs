@@ -0,0 +1,371 @@
+//! Text encoding and line-ending detection
+//!
+//! `decode` works out how a text file is stored — a byte order mark,
+//! UTF-8, or one of the Japanese legacy encodings — and returns its
+//! contents with line endings normalised to `\n`. `encode` turns edited
+//! text back into bytes in the same `TextFormat`, so a file keeps its
+//! encoding and line endings when it is saved.
+//!
+//! A file only ever gets a single line ending back: if it mixed styles,
+//! `DecodedText::mixed_line_endings` is set and saving rewrites every line
+//! break in the predominant style.
+
+use std::path::Path;
+use encoding_rs::{Encoding, EUC_JP, ISO_2022_JP, SHIFT_JIS, UTF_8};
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult};
+
+/// Encodings a text file can be read and saved in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    /// UTF-8 with a byte order mark
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    ShiftJis,
+    EucJp,
+    Iso2022Jp,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 7] = [
+        TextEncoding::Utf8,
+        TextEncoding::Utf8Bom,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::ShiftJis,
+        TextEncoding::EucJp,
+        TextEncoding::Iso2022Jp,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 (BOM)",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::ShiftJis => "Shift_JIS",
+            TextEncoding::EucJp => "EUC-JP",
+            TextEncoding::Iso2022Jp => "ISO-2022-JP",
+        }
+    }
+
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => UTF_8,
+            TextEncoding::Utf16Le => encoding_rs::UTF_16LE,
+            TextEncoding::Utf16Be => encoding_rs::UTF_16BE,
+            TextEncoding::ShiftJis => SHIFT_JIS,
+            TextEncoding::EucJp => EUC_JP,
+            TextEncoding::Iso2022Jp => ISO_2022_JP,
+        }
+    }
+
+    fn bom(self) -> &'static [u8] {
+        match self {
+            TextEncoding::Utf8Bom => b"\xEF\xBB\xBF",
+            TextEncoding::Utf16Le => b"\xFF\xFE",
+            TextEncoding::Utf16Be => b"\xFE\xFF",
+            _ => b"",
+        }
+    }
+}
+
+/// Line-ending style of a text file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineEnding {
+    pub const ALL: [LineEnding; 3] = [LineEnding::Lf, LineEnding::CrLf, LineEnding::Cr];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+            LineEnding::Cr => "CR",
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
+/// How a text file is stored on disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextFormat {
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+}
+
+/// Contents of a text file with line endings normalised to `\n`
+#[derive(Debug, Clone, Default)]
+pub struct DecodedText {
+    pub text: String,
+    pub format: TextFormat,
+    /// Some bytes were not valid in the encoding and were replaced with U+FFFD
+    pub had_errors: bool,
+    /// More than one line-ending style was found; `encode` writes them all
+    /// as `format.line_ending`
+    pub mixed_line_endings: bool,
+}
+
+/// Guess the encoding of `bytes`
+///
+/// A byte order mark wins. Otherwise valid UTF-8 is taken as UTF-8 unless
+/// it is 7-bit text with ISO-2022-JP escape sequences, and anything else
+/// is decoded as both Shift_JIS and EUC-JP, keeping whichever yields more
+/// kana and kanji. Falls back to UTF-8 when nothing fits.
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return match encoding.name() {
+            "UTF-16LE" => TextEncoding::Utf16Le,
+            "UTF-16BE" => TextEncoding::Utf16Be,
+            _ => TextEncoding::Utf8Bom,
+        };
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        let escaped = bytes.is_ascii()
+            && [&b"\x1B$B"[..], b"\x1B$@", b"\x1B(J", b"\x1B(I"]
+                .iter()
+                .any(|escape| bytes.windows(escape.len()).any(|window| window == *escape));
+        if escaped && ISO_2022_JP.decode_without_bom_handling_and_without_replacement(bytes).is_some() {
+            return TextEncoding::Iso2022Jp;
+        }
+        return TextEncoding::Utf8;
+    }
+
+    let score = |encoding: &'static Encoding| {
+        encoding.decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|text| japanese_score(&text))
+    };
+    match (score(SHIFT_JIS), score(EUC_JP)) {
+        (Some(sjis), Some(euc)) if euc > sjis => TextEncoding::EucJp,
+        (Some(_), _) => TextEncoding::ShiftJis,
+        (None, Some(_)) => TextEncoding::EucJp,
+        (None, None) => TextEncoding::Utf8,
+    }
+}
+
+/// Most frequent line ending in `text`, `Lf` if there are none
+pub fn detect_line_ending(text: &str) -> LineEnding {
+    let (lf, crlf, cr) = count_line_endings(text);
+    if crlf > lf && crlf >= cr {
+        LineEnding::CrLf
+    } else if cr > lf && cr > crlf {
+        LineEnding::Cr
+    } else {
+        LineEnding::Lf
+    }
+}
+
+/// Number of LF, CRLF and lone CR line endings in `text`
+fn count_line_endings(text: &str) -> (usize, usize, usize) {
+    let bytes = text.as_bytes();
+    let (mut lf, mut crlf, mut cr) = (0usize, 0usize, 0usize);
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                crlf += 1;
+                i += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    (lf, crlf, cr)
+}
+
+/// Decode text, detecting the encoding unless one is given
+///
+/// A byte order mark matching the encoding is skipped. Line endings are
+/// normalised to `\n` and the predominant style is recorded in the format;
+/// `mixed_line_endings` flags files that used more than one.
+pub fn decode(bytes: &[u8], encoding: Option<TextEncoding>) -> DecodedText {
+    let encoding = encoding.unwrap_or_else(|| detect_encoding(bytes));
+    let bom = encoding.bom();
+    let body = if !bom.is_empty() && bytes.starts_with(bom) {
+        &bytes[bom.len()..]
+    } else if encoding == TextEncoding::Utf8 && bytes.starts_with(TextEncoding::Utf8Bom.bom()) {
+        &bytes[3..]
+    } else {
+        bytes
+    };
+
+    let (text, had_errors) = encoding.encoding().decode_without_bom_handling(body);
+    let line_ending = detect_line_ending(&text);
+    let (lf, crlf, cr) = count_line_endings(&text);
+    let mixed_line_endings = [lf, crlf, cr].iter().filter(|&&count| count > 0).count() > 1;
+    let text = if text.contains('\r') {
+        text.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        text.into_owned()
+    };
+
+    DecodedText {
+        text,
+        format: TextFormat { encoding, line_ending },
+        had_errors,
+        mixed_line_endings,
+    }
+}
+
+/// Encode `\n`-separated text in the given format
+///
+/// Fails with `FsError::Encoding` if the text contains characters the
+/// encoding cannot represent, rather than silently replacing them.
+pub fn encode(text: &str, format: TextFormat) -> FsResult<Vec<u8>> {
+    let text = match format.line_ending {
+        LineEnding::Lf => std::borrow::Cow::Borrowed(text),
+        ending => std::borrow::Cow::Owned(text.replace('\n', ending.as_str())),
+    };
+
+    let mut bytes = format.encoding.bom().to_vec();
+    match format.encoding {
+        // encoding_rs only decodes UTF-16
+        TextEncoding::Utf16Le => bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+        TextEncoding::Utf16Be => bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+        encoding => {
+            let (encoded, _, had_errors) = encoding.encoding().encode(&text);
+            if had_errors {
+                let unmappable: String = text.chars()
+                    .filter(|c| {
+                        let mut buffer = [0; 4];
+                        encoding.encoding().encode(c.encode_utf8(&mut buffer)).2
+                    })
+                    .take(5)
+                    .collect();
+                return Err(FsError::Encoding(format!(
+                    "{} cannot represent {:?}",
+                    encoding.label(),
+                    unmappable,
+                )));
+            }
+            bytes.extend_from_slice(&encoded);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Read and decode a text file, detecting its encoding
+pub fn read_text_file(path: &Path) -> FsResult<DecodedText> {
+    Ok(decode(&std::fs::read(path)?, None))
+}
+
+/// Encode and write a text file in the given format
+pub fn write_text_file(path: &Path, text: &str, format: TextFormat) -> FsResult<()> {
+    let bytes = encode(text, format)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Kana and kanji count, less half-width katakana, which mis-decoded text is full of
+fn japanese_score(text: &str) -> i64 {
+    text.chars()
+        .map(|c| match c {
+            '\u{3040}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}' => 1,
+            '\u{FF61}'..='\u{FF9F}' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "これは日本語のテキストです。\n漢字とかなを含みます。\n";
+
+    fn encoded(encoding: &'static Encoding) -> Vec<u8> {
+        let (bytes, _, had_errors) = encoding.encode(SAMPLE);
+        assert!(!had_errors);
+        bytes.into_owned()
+    }
+
+    #[test]
+    fn detects_japanese_legacy_encodings() {
+        assert_eq!(detect_encoding(&encoded(SHIFT_JIS)), TextEncoding::ShiftJis);
+        assert_eq!(detect_encoding(&encoded(EUC_JP)), TextEncoding::EucJp);
+        assert_eq!(detect_encoding(&encoded(ISO_2022_JP)), TextEncoding::Iso2022Jp);
+        assert_eq!(detect_encoding(SAMPLE.as_bytes()), TextEncoding::Utf8);
+        assert_eq!(detect_encoding(b"plain ascii\n"), TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn detects_byte_order_marks() {
+        let mut bytes = b"\xEF\xBB\xBF".to_vec();
+        bytes.extend_from_slice(SAMPLE.as_bytes());
+        assert_eq!(detect_encoding(&bytes), TextEncoding::Utf8Bom);
+        assert_eq!(detect_encoding(b"\xFF\xFEa\0"), TextEncoding::Utf16Le);
+        assert_eq!(detect_encoding(b"\xFE\xFF\0a"), TextEncoding::Utf16Be);
+
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.text, SAMPLE);
+    }
+
+    #[test]
+    fn round_trips_are_byte_exact() {
+        let mut samples = vec![
+            SAMPLE.as_bytes().to_vec(),
+            encoded(SHIFT_JIS),
+            encoded(EUC_JP),
+            encoded(ISO_2022_JP),
+            SAMPLE.replace('\n', "\r\n").into_bytes(),
+            SAMPLE.replace('\n', "\r").into_bytes(),
+        ];
+        let mut bom = b"\xEF\xBB\xBF".to_vec();
+        bom.extend_from_slice(SAMPLE.as_bytes());
+        samples.push(bom);
+        let mut utf16 = b"\xFF\xFE".to_vec();
+        utf16.extend(SAMPLE.encode_utf16().flat_map(u16::to_le_bytes));
+        samples.push(utf16);
+
+        for bytes in samples {
+            let decoded = decode(&bytes, None);
+            assert!(!decoded.had_errors);
+            assert!(!decoded.mixed_line_endings);
+            assert_eq!(decoded.text, SAMPLE);
+            assert_eq!(encode(&decoded.text, decoded.format).unwrap(), bytes, "{:?}", decoded.format);
+        }
+    }
+
+    #[test]
+    fn unrepresentable_characters_are_an_error() {
+        let format = TextFormat { encoding: TextEncoding::ShiftJis, line_ending: LineEnding::Lf };
+        let result = encode("日本語 😀", format);
+        assert!(matches!(&result, Err(FsError::Encoding(message)) if message.contains('😀')));
+        assert!(encode("日本語", format).is_ok());
+    }
+
+    #[test]
+    fn mixed_line_endings_are_flagged_and_unified() {
+        let decoded = decode(b"one\r\ntwo\r\nthree\nfour", None);
+        assert_eq!(decoded.text, "one\ntwo\nthree\nfour");
+        assert_eq!(decoded.format.line_ending, LineEnding::CrLf);
+        assert!(decoded.mixed_line_endings);
+        assert_eq!(encode(&decoded.text, decoded.format).unwrap(), b"one\r\ntwo\r\nthree\r\nfour");
+    }
+
+    #[test]
+    fn line_ending_detection_prefers_the_majority() {
+        assert_eq!(detect_line_ending("no breaks"), LineEnding::Lf);
+        assert_eq!(detect_line_ending("a\r\nb\r\nc\n"), LineEnding::CrLf);
+        assert_eq!(detect_line_ending("a\rb\rc\r\n"), LineEnding::Cr);
+        assert_eq!(detect_line_ending("a\nb\r\n"), LineEnding::Lf);
+    }
+}
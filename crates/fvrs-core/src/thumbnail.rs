@@ -0,0 +1,316 @@
+//! Thumbnails following the freedesktop.org thumbnail specification
+//!
+//! Thumbnails are PNG files under `$XDG_CACHE_HOME/thumbnails/<size>/`,
+//! named after the MD5 of the file's URI and tagged with `Thumb::URI`,
+//! `Thumb::MTime` and `Thumb::Size`, so the cache is shared with other
+//! desktop applications and stale entries are regenerated. Images are
+//! decoded in-process; the first frame of a video comes from `ffmpeg` when
+//! it is installed. Files that cannot be thumbnailed are recorded under
+//! `fail/` and not retried until they change.
+
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult};
+
+/// Extensions handed to `ffmpeg` for a first-frame thumbnail
+const VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "mkv", "webm", "avi", "mov", "wmv", "flv", "mpg", "mpeg", "ogv", "3gp",
+];
+
+/// Thumbnail sizes defined by the specification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ThumbnailSize {
+    /// 128×128
+    Normal,
+    /// 256×256
+    Large,
+    /// 512×512
+    XLarge,
+    /// 1024×1024
+    XXLarge,
+}
+
+impl ThumbnailSize {
+    /// Edge length in pixels
+    pub fn pixels(self) -> u32 {
+        match self {
+            ThumbnailSize::Normal => 128,
+            ThumbnailSize::Large => 256,
+            ThumbnailSize::XLarge => 512,
+            ThumbnailSize::XXLarge => 1024,
+        }
+    }
+
+    /// Smallest size that is at least `pixels` wide
+    pub fn for_pixels(pixels: u32) -> Self {
+        [ThumbnailSize::Normal, ThumbnailSize::Large, ThumbnailSize::XLarge]
+            .into_iter()
+            .find(|size| size.pixels() >= pixels)
+            .unwrap_or(ThumbnailSize::XXLarge)
+    }
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            ThumbnailSize::Normal => "normal",
+            ThumbnailSize::Large => "large",
+            ThumbnailSize::XLarge => "x-large",
+            ThumbnailSize::XXLarge => "xx-large",
+        }
+    }
+}
+
+/// Decoded thumbnail pixels
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    /// Unpremultiplied RGBA, row by row
+    pub rgba: Vec<u8>,
+}
+
+/// Whether a thumbnail can be produced for this file, judging by its extension
+pub fn is_supported(path: &Path) -> bool {
+    if ImageFormat::from_path(path).is_ok_and(|format| format.reading_enabled()) {
+        return true;
+    }
+    is_video(path) && ffmpeg_available()
+}
+
+/// `$XDG_CACHE_HOME/thumbnails`, defaulting to `~/.cache/thumbnails`
+pub fn thumbnail_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::home_dir().map(|home| home.join(".cache")))
+        .map(|cache| cache.join("thumbnails"))
+}
+
+/// `file://` URI of an absolute path, escaped the way GLib does so hashes match other applications
+pub fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().replace('\\', "/").bytes() {
+        if byte.is_ascii_alphanumeric() || b"!$&'()*+,-./:=@_~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// Load a cached thumbnail, generating and caching it if missing or stale
+pub fn load_or_create(path: &Path, size: ThumbnailSize) -> FsResult<Thumbnail> {
+    let root = thumbnail_dir()
+        .ok_or_else(|| FsError::NotSupported("thumbnails without a home directory".to_string()))?;
+    let path = path.canonicalize()?;
+    if path.starts_with(&root) {
+        return Err(FsError::Thumbnail("thumbnails are not made for thumbnails".to_string()));
+    }
+
+    let metadata = std::fs::metadata(&path)?;
+    let source = SourceInfo {
+        uri: file_uri(&path),
+        mtime: metadata.modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+        size: metadata.len(),
+    };
+    let file_name = format!("{:x}.png", md5::compute(source.uri.as_bytes()));
+    let cached = root.join(size.dir_name()).join(&file_name);
+    let failed = root.join("fail").join(fail_dir_name()).join(&file_name);
+
+    if let Some(thumbnail) = read_cached(&cached, &source) {
+        return Ok(thumbnail);
+    }
+    if read_cached(&failed, &source).is_some() {
+        return Err(FsError::Thumbnail(format!("thumbnailing failed before: {}", path.display())));
+    }
+
+    match generate(&path, size.pixels()) {
+        Ok(image) => {
+            let thumbnail = Thumbnail {
+                width: image.width(),
+                height: image.height(),
+                rgba: image.into_rgba8().into_raw(),
+            };
+            // A read-only cache still leaves a usable thumbnail
+            let _ = write_cached(&cached, &source, &thumbnail);
+            Ok(thumbnail)
+        }
+        Err(e) => {
+            let marker = Thumbnail { width: 1, height: 1, rgba: vec![0; 4] };
+            let _ = write_cached(&failed, &source, &marker);
+            Err(e)
+        }
+    }
+}
+
+/// Values that tie a cached thumbnail to one version of a file
+struct SourceInfo {
+    uri: String,
+    mtime: u64,
+    size: u64,
+}
+
+fn fail_dir_name() -> String {
+    format!("fvrs-{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Read a cached PNG if its tags still match the source file
+fn read_cached(cached: &Path, source: &SourceInfo) -> Option<Thumbnail> {
+    let bytes = std::fs::read(cached).ok()?;
+    let reader = png::Decoder::new(Cursor::new(&bytes)).read_info().ok()?;
+    let text = &reader.info().uncompressed_latin1_text;
+    let tag = |key: &str| text.iter().find(|chunk| chunk.keyword == key).map(|chunk| chunk.text.as_str());
+
+    if tag("Thumb::URI") != Some(source.uri.as_str())
+        || tag("Thumb::MTime").and_then(|mtime| mtime.parse().ok()) != Some(source.mtime)
+    {
+        return None;
+    }
+    // Thumb::Size is optional, but must agree when present
+    if tag("Thumb::Size").is_some_and(|size| size.parse().ok() != Some(source.size)) {
+        return None;
+    }
+
+    let image = image::load_from_memory_with_format(&bytes, ImageFormat::Png).ok()?;
+    Some(Thumbnail {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_rgba8().into_raw(),
+    })
+}
+
+/// Write a tagged PNG through a temporary file so readers never see a partial thumbnail
+fn write_cached(cached: &Path, source: &SourceInfo, thumbnail: &Thumbnail) -> FsResult<()> {
+    let dir = cached.parent()
+        .ok_or_else(|| FsError::InvalidPath(cached.display().to_string()))?;
+    create_private_dir_all(dir)?;
+
+    // Unique per process and call, since several workers may write at once
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+    let temp = dir.join(format!(
+        ".{}.fvrs-{}-{}",
+        cached.file_name().unwrap_or_default().to_string_lossy(),
+        std::process::id(),
+        NEXT_TEMP.fetch_add(1, Ordering::Relaxed),
+    ));
+    let result = (|| -> FsResult<()> {
+        let mut out = BufWriter::new(create_private_file(&temp)?);
+        let mut encoder = png::Encoder::new(&mut out, thumbnail.width, thumbnail.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        for (key, value) in [
+            ("Thumb::URI", source.uri.clone()),
+            ("Thumb::MTime", source.mtime.to_string()),
+            ("Thumb::Size", source.size.to_string()),
+            ("Software", "fvrs".to_string()),
+        ] {
+            encoder.add_text_chunk(key.to_string(), value).map_err(png_error)?;
+        }
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&thumbnail.rgba).map_err(png_error)?;
+        writer.finish().map_err(png_error)?;
+        out.flush()?;
+        Ok(())
+    })();
+
+    match result.and_then(|()| std::fs::rename(&temp, cached).map_err(FsError::from)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp);
+            Err(e)
+        }
+    }
+}
+
+fn png_error(e: png::EncodingError) -> FsError {
+    FsError::Thumbnail(e.to_string())
+}
+
+/// Decode the file and scale it to fit within `pixels`×`pixels`, never enlarging
+fn generate(path: &Path, pixels: u32) -> FsResult<DynamicImage> {
+    let image = if is_video(path) {
+        video_first_frame(path, pixels)?
+    } else {
+        ImageReader::open(path)?
+            .with_guessed_format()?
+            .decode()
+            .map_err(|e| FsError::Thumbnail(format!("{}: {}", path.display(), e)))?
+    };
+
+    if image.width() <= pixels && image.height() <= pixels {
+        Ok(image)
+    } else {
+        Ok(image.thumbnail(pixels, pixels))
+    }
+}
+
+fn is_video(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Whether `ffmpeg` can be run (checked once)
+fn ffmpeg_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        Command::new("ffmpeg")
+            .arg("-version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// Ask `ffmpeg` for the first frame as a PNG already scaled to fit
+fn video_first_frame(path: &Path, pixels: u32) -> FsResult<DynamicImage> {
+    if !ffmpeg_available() {
+        return Err(FsError::NotSupported("video thumbnails need ffmpeg".to_string()));
+    }
+    let scale = format!("scale={0}:{0}:force_original_aspect_ratio=decrease", pixels);
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-nostdin", "-i"])
+        .arg(path)
+        .args(["-frames:v", "1", "-vf", &scale, "-f", "image2pipe", "-c:v", "png", "-"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(FsError::Thumbnail(format!("ffmpeg could not decode {}", path.display())));
+    }
+    image::load_from_memory_with_format(&output.stdout, ImageFormat::Png)
+        .map_err(|e| FsError::Thumbnail(e.to_string()))
+}
+
+#[cfg(unix)]
+fn create_private_dir_all(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(path)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir_all(path: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(path)
+}
+
+#[cfg(unix)]
+fn create_private_file(path: &Path) -> std::io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    std::fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)
+}
+
+#[cfg(not(unix))]
+fn create_private_file(path: &Path) -> std::io::Result<File> {
+    File::create(path)
+}
+
@@ -0,0 +1,515 @@
+//! Trash can following the freedesktop.org Trash specification
+//!
+//! Files on the same device as the home trash (`$XDG_DATA_HOME/Trash`) are
+//! moved there. Files on other mounts go to `$topdir/.Trash/$uid` when the
+//! administrator provided a sticky `.Trash` directory, and to
+//! `$topdir/.Trash-$uid` otherwise. Every trashed item gets a `.trashinfo`
+//! file recording its original location and deletion date, so it can be
+//! restored later by this or any other compliant file manager.
+
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult};
+
+/// Format of `DeletionDate` in `.trashinfo` files
+const DELETION_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const INFO_EXTENSION: &str = "trashinfo";
+
+/// An item in one of the trash directories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashItem {
+    /// Original file name
+    pub name: String,
+    /// Location the item was deleted from
+    pub original_path: PathBuf,
+    /// When the item was moved to the trash
+    pub deleted_at: Option<DateTime<Local>>,
+    /// Current location of the item inside `$trash/files`
+    pub trashed_path: PathBuf,
+    /// The `.trashinfo` file describing the item
+    pub info_path: PathBuf,
+    /// Whether the item is a directory
+    pub is_dir: bool,
+    /// Size in bytes (0 for directories)
+    pub size: u64,
+}
+
+/// Whether the platform trash is supported
+pub fn is_supported() -> bool {
+    platform::SUPPORTED
+}
+
+/// Move `path` to the trash and return the new trash entry
+pub fn move_to_trash(path: &Path) -> FsResult<TrashItem> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| FsError::InvalidPath(path.display().to_string()))?;
+    // Resolve the parent only, so a symlink is trashed rather than its target
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .canonicalize()
+        .map_err(|e| FsError::InvalidPath(format!("{}: {}", path.display(), e)))?;
+    let path = parent.join(name);
+    let metadata = std::fs::symlink_metadata(&path)?;
+
+    let (trash_dir, topdir) = platform::trash_dir_for(&path)?;
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    create_private_dir_all(&files_dir)?;
+    create_private_dir_all(&info_dir)?;
+
+    // Paths in a per-mount trash are stored relative to the mount point
+    let recorded = match &topdir {
+        Some(topdir) => path.strip_prefix(topdir).unwrap_or(&path),
+        None => &path,
+    };
+    let deleted_at = Local::now();
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(recorded),
+        deleted_at.format(DELETION_DATE_FORMAT)
+    );
+
+    let (trash_name, info_path) = reserve_info_file(&files_dir, &info_dir, name, &info)?;
+    let trashed_path = files_dir.join(&trash_name);
+    if let Err(e) = std::fs::rename(&path, &trashed_path) {
+        let _ = std::fs::remove_file(&info_path);
+        return Err(e.into());
+    }
+
+    Ok(TrashItem {
+        name: name.to_string_lossy().into_owned(),
+        original_path: path,
+        deleted_at: Some(deleted_at),
+        trashed_path,
+        info_path,
+        is_dir: metadata.is_dir(),
+        size: if metadata.is_dir() { 0 } else { metadata.len() },
+    })
+}
+
+/// Create a directory (and parents) readable only by the current user
+fn create_private_dir_all(path: &Path) -> std::io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(path)
+}
+
+/// Create the `.trashinfo` file under a free name, which also claims that name
+fn reserve_info_file(
+    files_dir: &Path,
+    info_dir: &Path,
+    name: &std::ffi::OsStr,
+    contents: &str,
+) -> FsResult<(String, PathBuf)> {
+    use std::io::Write;
+
+    let name = name.to_string_lossy();
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => (&name[..dot], &name[dot..]),
+        _ => (&name[..], ""),
+    };
+    for attempt in 1..10_000 {
+        let candidate = if attempt == 1 {
+            name.to_string()
+        } else {
+            format!("{}.{}{}", stem, attempt, extension)
+        };
+        if std::fs::symlink_metadata(files_dir.join(&candidate)).is_ok() {
+            continue;
+        }
+        let info_path = info_dir.join(format!("{}.{}", candidate, INFO_EXTENSION));
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&info_path) {
+            Ok(mut file) => {
+                if let Err(e) = file.write_all(contents.as_bytes()) {
+                    let _ = std::fs::remove_file(&info_path);
+                    return Err(e.into());
+                }
+                return Ok((candidate, info_path));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(FsError::Io(std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        format!("no free name in trash for {}", name),
+    )))
+}
+
+/// List the items of every trash directory this user can see
+pub fn list() -> FsResult<Vec<TrashItem>> {
+    let mut items = Vec::new();
+    for trash_dir in platform::trash_dirs()? {
+        let Ok(entries) = std::fs::read_dir(trash_dir.join("info")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let info_path = entry.path();
+            if info_path.extension().is_some_and(|e| e == INFO_EXTENSION) {
+                items.extend(read_item(&trash_dir, &info_path));
+            }
+        }
+    }
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+    Ok(items)
+}
+
+/// Look up the trash entry for a path inside `$trash/files`
+pub fn item_for_trashed_path(trashed_path: &Path) -> Option<TrashItem> {
+    let files_dir = trashed_path.parent()?;
+    if files_dir.file_name()? != "files" {
+        return None;
+    }
+    let trash_dir = files_dir.parent()?;
+    let mut info_name = trashed_path.file_name()?.to_os_string();
+    info_name.push(".");
+    info_name.push(INFO_EXTENSION);
+    read_item(trash_dir, &trash_dir.join("info").join(info_name))
+}
+
+/// Parse a `.trashinfo` file; entries whose file is gone are skipped
+fn read_item(trash_dir: &Path, info_path: &Path) -> Option<TrashItem> {
+    let contents = std::fs::read_to_string(info_path).ok()?;
+    let mut in_section = false;
+    let mut original = None;
+    let mut deleted_at = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line == "[Trash Info]";
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("Path=") {
+            original = Some(decode_path(value));
+        } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+            deleted_at = NaiveDateTime::parse_from_str(value, DELETION_DATE_FORMAT)
+                .ok()
+                .and_then(|date| Local.from_local_datetime(&date).earliest());
+        }
+    }
+
+    let original = original?;
+    let original_path = if original.is_absolute() {
+        original
+    } else {
+        topdir_of(trash_dir).join(original)
+    };
+    let trash_name = info_path.file_stem()?;
+    let trashed_path = trash_dir.join("files").join(trash_name);
+    let metadata = std::fs::symlink_metadata(&trashed_path).ok()?;
+
+    Some(TrashItem {
+        name: original_path
+            .file_name()
+            .unwrap_or(trash_name)
+            .to_string_lossy()
+            .into_owned(),
+        original_path,
+        deleted_at,
+        trashed_path,
+        info_path: info_path.to_path_buf(),
+        is_dir: metadata.is_dir(),
+        size: if metadata.is_dir() { 0 } else { metadata.len() },
+    })
+}
+
+/// Mount point that relative paths in a per-mount trash are based on
+fn topdir_of(trash_dir: &Path) -> PathBuf {
+    let name = trash_dir.file_name().map(|n| n.to_string_lossy().into_owned());
+    let parent = trash_dir.parent();
+    match (name, parent) {
+        // $topdir/.Trash-$uid
+        (Some(name), Some(parent)) if name.starts_with(".Trash-") => parent.to_path_buf(),
+        // $topdir/.Trash/$uid
+        (_, Some(parent)) if parent.file_name().is_some_and(|n| n == ".Trash") => {
+            parent.parent().unwrap_or(parent).to_path_buf()
+        }
+        _ => PathBuf::from("/"),
+    }
+}
+
+/// Move an item back to where it was deleted from
+pub fn restore(item: &TrashItem) -> FsResult<PathBuf> {
+    restore_to(item, &item.original_path)?;
+    Ok(item.original_path.clone())
+}
+
+/// Move an item out of the trash to `destination`
+pub fn restore_to(item: &TrashItem, destination: &Path) -> FsResult<()> {
+    if std::fs::symlink_metadata(destination).is_ok() {
+        return Err(FsError::Io(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", destination.display()),
+        )));
+    }
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(&item.trashed_path, destination)?;
+    remove_info(&item.info_path)
+}
+
+/// Delete an item from the trash for good
+pub fn delete_permanently(item: &TrashItem) -> FsResult<()> {
+    let result = match std::fs::symlink_metadata(&item.trashed_path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(&item.trashed_path),
+        Ok(_) => std::fs::remove_file(&item.trashed_path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    };
+    result?;
+    remove_info(&item.info_path)
+}
+
+/// Delete every item in every trash directory, returning how many were removed
+pub fn empty() -> FsResult<usize> {
+    let items = list()?;
+    let count = items.len();
+    for item in &items {
+        delete_permanently(item)?;
+    }
+    Ok(count)
+}
+
+fn remove_info(info_path: &Path) -> FsResult<()> {
+    match std::fs::remove_file(info_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Percent-encode a path as required for the `Path=` key
+fn encode_path(path: &Path) -> String {
+    let bytes = path_bytes(path);
+    let mut encoded = String::with_capacity(bytes.len());
+    for byte in bytes {
+        if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn decode_path(value: &str) -> PathBuf {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    path_from_bytes(decoded)
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+mod platform {
+    use super::*;
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+    use crate::volume::{self, VolumeInfo};
+
+    pub const SUPPORTED: bool = true;
+
+    /// `S_ISVTX`, required on a shared `$topdir/.Trash`
+    const STICKY_BIT: u32 = 0o1000;
+
+    fn uid() -> u32 {
+        unsafe { libc::getuid() }
+    }
+
+    /// `$XDG_DATA_HOME/Trash`, defaulting to `~/.local/share/Trash`
+    fn home_trash() -> FsResult<PathBuf> {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| std::env::home_dir().map(|home| home.join(".local/share")))
+            .map(|data| data.join("Trash"))
+            .ok_or_else(|| FsError::NotSupported("trash without a home directory".to_string()))
+    }
+
+    /// Device of `path`, or of its nearest existing ancestor
+    fn device_of(path: &Path) -> Option<u64> {
+        path.ancestors()
+            .find_map(|ancestor| std::fs::metadata(ancestor).ok())
+            .map(|metadata| metadata.dev())
+    }
+
+    /// Trash directory for `path`, with the mount point for per-mount trashes
+    pub fn trash_dir_for(path: &Path) -> FsResult<(PathBuf, Option<PathBuf>)> {
+        let home = home_trash()?;
+        let dev = std::fs::symlink_metadata(path)?.dev();
+        if device_of(&home) == Some(dev) {
+            return Ok((home, None));
+        }
+
+        let parent = path.parent().unwrap_or(path);
+        let topdir = VolumeInfo::for_path(parent)?.mount_point;
+        if let Some(trash) = admin_trash(&topdir) {
+            return Ok((trash, Some(topdir)));
+        }
+
+        // $topdir/.Trash-$uid, created by us if needed
+        let trash = topdir.join(format!(".Trash-{}", uid()));
+        match std::fs::DirBuilder::new().mode(0o700).create(&trash) {
+            Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e.into()),
+            _ => {}
+        }
+        let metadata = std::fs::symlink_metadata(&trash)?;
+        if !metadata.is_dir() || metadata.uid() != uid() {
+            return Err(FsError::Permission(format!(
+                "{} is not a usable trash directory",
+                trash.display()
+            )));
+        }
+        Ok((trash, Some(topdir)))
+    }
+
+    /// `$topdir/.Trash/$uid` if the administrator created a sticky `.Trash`
+    fn admin_trash(topdir: &Path) -> Option<PathBuf> {
+        let shared = topdir.join(".Trash");
+        let metadata = std::fs::symlink_metadata(&shared).ok()?;
+        if !metadata.is_dir() || metadata.mode() & STICKY_BIT == 0 {
+            return None;
+        }
+        let trash = shared.join(uid().to_string());
+        match std::fs::DirBuilder::new().mode(0o700).create(&trash) {
+            Ok(()) => Some(trash),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let metadata = std::fs::symlink_metadata(&trash).ok()?;
+                (metadata.is_dir() && metadata.uid() == uid()).then_some(trash)
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// The home trash plus the per-user trash of every mount that has one
+    pub fn trash_dirs() -> FsResult<Vec<PathBuf>> {
+        let mut dirs = vec![home_trash()?];
+        let uid = uid();
+        for topdir in volume::mount_points() {
+            for trash in [
+                topdir.join(".Trash").join(uid.to_string()),
+                topdir.join(format!(".Trash-{}", uid)),
+            ] {
+                if !dirs.contains(&trash) && trash.join("info").is_dir() {
+                    dirs.push(trash);
+                }
+            }
+        }
+        Ok(dirs)
+    }
+}
+
+#[cfg(not(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")))))]
+mod platform {
+    use super::*;
+
+    pub const SUPPORTED: bool = false;
+
+    pub fn trash_dir_for(_path: &Path) -> FsResult<(PathBuf, Option<PathBuf>)> {
+        Err(FsError::NotSupported("trash".to_string()))
+    }
+
+    pub fn trash_dirs() -> FsResult<Vec<PathBuf>> {
+        Err(FsError::NotSupported("trash".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_encoding_round_trips() {
+        let path = Path::new("/home/user/My Files/レポート 100%.txt");
+        let encoded = encode_path(path);
+        assert_eq!(
+            encoded,
+            "/home/user/My%20Files/%E3%83%AC%E3%83%9D%E3%83%BC%E3%83%88%20100%25.txt"
+        );
+        assert_eq!(decode_path(&encoded), path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_encoding_keeps_non_utf8_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"/tmp/\xff\xfe name"));
+        assert_eq!(encode_path(path), "/tmp/%FF%FE%20name");
+        assert_eq!(decode_path(&encode_path(path)), path);
+    }
+
+    #[test]
+    fn malformed_escapes_are_kept_literally() {
+        assert_eq!(decode_path("/a%2"), Path::new("/a%2"));
+        assert_eq!(decode_path("/a%zz%41"), Path::new("/a%zzA"));
+        assert_eq!(decode_path("/a%"), Path::new("/a%"));
+    }
+
+    #[test]
+    fn info_files_are_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash_dir = dir.path().join("Trash");
+        let (files_dir, info_dir) = (trash_dir.join("files"), trash_dir.join("info"));
+        std::fs::create_dir_all(&files_dir).unwrap();
+        std::fs::create_dir_all(&info_dir).unwrap();
+
+        let contents = format!("[Trash Info]\nPath={}\nDeletionDate=2024-03-01T12:30:00\n", encode_path(Path::new("/docs/a b.txt")));
+        let (first, _) = reserve_info_file(&files_dir, &info_dir, "a b.txt".as_ref(), &contents).unwrap();
+        let (second, _) = reserve_info_file(&files_dir, &info_dir, "a b.txt".as_ref(), &contents).unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("a b.txt", "a b.2.txt"));
+
+        std::fs::write(files_dir.join(&second), b"data").unwrap();
+        let item = item_for_trashed_path(&files_dir.join(&second)).unwrap();
+        assert_eq!(item.original_path, Path::new("/docs/a b.txt"));
+        assert_eq!(item.name, "a b.txt");
+        assert_eq!(item.size, 4);
+        assert_eq!(
+            item.deleted_at.map(|date| date.format(DELETION_DATE_FORMAT).to_string()).as_deref(),
+            Some("2024-03-01T12:30:00")
+        );
+        // An info file whose item is gone is skipped
+        assert!(item_for_trashed_path(&files_dir.join(&first)).is_none());
+    }
+}
@@ -0,0 +1,638 @@
+//! Volume and ownership information
+//!
+//! Provides real disk capacity, filesystem type, mount point and block size
+//! for the volume containing a path, and resolves the owner and group of a
+//! file from its uid/gid (Unix) or security descriptor SIDs (Windows).
+
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use crate::core::{FsError, FsResult};
+
+/// Information about the volume (mounted filesystem) containing a path
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumeInfo {
+    /// Mount point (Unix) or volume root such as `C:\` (Windows)
+    pub mount_point: PathBuf,
+    /// Filesystem type, e.g. `ext4`, `apfs` or `NTFS`
+    pub filesystem: String,
+    /// Backing device (Unix) or volume GUID path (Windows)
+    pub device: String,
+    /// Volume label, if the platform reports one
+    pub label: Option<String>,
+    /// Total capacity in bytes
+    pub total_space: u64,
+    /// Free space in bytes, including space reserved for the superuser
+    pub free_space: u64,
+    /// Free space in bytes available to the current user
+    pub available_space: u64,
+    /// Used space in bytes
+    pub used_space: u64,
+    /// Allocation unit (fragment / cluster) size in bytes
+    pub block_size: u64,
+    /// Whether the volume is mounted read-only
+    pub read_only: bool,
+}
+
+impl VolumeInfo {
+    /// Query the volume that contains `path`
+    pub fn for_path(path: &Path) -> FsResult<Self> {
+        let path = path.canonicalize().map_err(|e| {
+            FsError::InvalidPath(format!("{}: {}", path.display(), e))
+        })?;
+        platform::volume_info(&path)
+    }
+
+    /// Fraction of the volume in use, in the range `0.0..=1.0`
+    pub fn usage_ratio(&self) -> f32 {
+        if self.total_space == 0 {
+            0.0
+        } else {
+            (self.used_space as f64 / self.total_space as f64).clamp(0.0, 1.0) as f32
+        }
+    }
+}
+
+/// Owner and group of a file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OwnerInfo {
+    /// Owner account name, if it could be resolved
+    pub user: Option<String>,
+    /// Group name, if it could be resolved
+    pub group: Option<String>,
+    /// Numeric user id (Unix)
+    pub uid: Option<u32>,
+    /// Numeric group id (Unix)
+    pub gid: Option<u32>,
+    /// Owner SID in string form (Windows)
+    pub owner_sid: Option<String>,
+    /// Primary group SID in string form (Windows)
+    pub group_sid: Option<String>,
+}
+
+impl OwnerInfo {
+    /// Resolve the owner and group of `path`
+    pub fn for_path(path: &Path) -> FsResult<Self> {
+        platform::owner_info(path)
+    }
+
+    /// Owner for display: the account name, falling back to the uid or SID
+    pub fn display_user(&self) -> String {
+        Self::display(&self.user, self.uid, &self.owner_sid)
+    }
+
+    /// Group for display: the group name, falling back to the gid or SID
+    pub fn display_group(&self) -> String {
+        Self::display(&self.group, self.gid, &self.group_sid)
+    }
+
+    fn display(name: &Option<String>, id: Option<u32>, sid: &Option<String>) -> String {
+        match (name, id, sid) {
+            (Some(name), Some(id), _) => format!("{} ({})", name, id),
+            (Some(name), None, _) => name.clone(),
+            (None, Some(id), _) => id.to_string(),
+            (None, None, Some(sid)) => sid.clone(),
+            (None, None, None) => "unknown".to_string(),
+        }
+    }
+}
+
+/// Mount points of all mounted filesystems (drive roots on Windows)
+pub fn mount_points() -> Vec<PathBuf> {
+    platform::mount_points()
+}
+
+/// Look up a user id by account name, accepting numeric ids as they are (Unix)
+pub fn user_id(name: &str) -> Option<u32> {
+    name.parse().ok().or_else(|| platform::user_id(name))
+}
+
+/// Look up a group id by name, accepting numeric ids as they are (Unix)
+pub fn group_id(name: &str) -> Option<u32> {
+    name.parse().ok().or_else(|| platform::group_id(name))
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::*;
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    fn c_path(path: &Path) -> FsResult<CString> {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| FsError::InvalidPath(path.display().to_string()))
+    }
+
+    pub fn volume_info(path: &Path) -> FsResult<VolumeInfo> {
+        let c_path = c_path(path)?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let fragment = if stat.f_frsize > 0 { stat.f_frsize as u64 } else { stat.f_bsize as u64 };
+        let total_space = stat.f_blocks as u64 * fragment;
+        let free_space = stat.f_bfree as u64 * fragment;
+        let available_space = stat.f_bavail as u64 * fragment;
+
+        let mut info = VolumeInfo {
+            mount_point: PathBuf::from("/"),
+            total_space,
+            free_space,
+            available_space,
+            used_space: total_space.saturating_sub(free_space),
+            block_size: fragment,
+            read_only: stat.f_flag & libc::ST_RDONLY != 0,
+            ..Default::default()
+        };
+        fill_mount_details(path, &mut info);
+        Ok(info)
+    }
+
+    /// Look up the mount point, filesystem type and device in /proc/self/mountinfo
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn fill_mount_details(path: &Path, info: &mut VolumeInfo) {
+        let dev = std::fs::metadata(path).map(|m| m.dev()).ok();
+        let Ok(mountinfo) = std::fs::read_to_string("/proc/self/mountinfo") else {
+            info.mount_point = find_mount_point(path);
+            return;
+        };
+
+        // (device matches, mount point, fstype, source)
+        let mut best: Option<(bool, PathBuf, String, String)> = None;
+        for line in mountinfo.lines() {
+            let fields: Vec<&str> = line.split(' ').collect();
+            let Some(separator) = fields.iter().position(|f| *f == "-") else {
+                continue;
+            };
+            if fields.len() < 5 || fields.len() < separator + 3 {
+                continue;
+            }
+            let mount_point = PathBuf::from(unescape_mount_field(fields[4]));
+            if !path.starts_with(&mount_point) {
+                continue;
+            }
+            let dev_matches = match (dev, fields[2].split_once(':')) {
+                (Some(dev), Some((major, minor))) => match (major.parse(), minor.parse()) {
+                    (Ok(major), Ok(minor)) => libc::makedev(major, minor) as u64 == dev,
+                    _ => false,
+                },
+                _ => false,
+            };
+            // Prefer entries for the same device, then the deepest mount point
+            let better = match &best {
+                None => true,
+                Some((best_dev, best_mount, _, _)) => {
+                    (dev_matches, mount_point.components().count())
+                        >= (*best_dev, best_mount.components().count())
+                }
+            };
+            if better {
+                best = Some((
+                    dev_matches,
+                    mount_point,
+                    fields[separator + 1].to_string(),
+                    unescape_mount_field(fields[separator + 2]),
+                ));
+            }
+        }
+
+        match best {
+            Some((_, mount_point, filesystem, device)) => {
+                info.mount_point = mount_point;
+                info.filesystem = filesystem;
+                info.device = device;
+            }
+            None => info.mount_point = find_mount_point(path),
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn mount_points() -> Vec<PathBuf> {
+        let Ok(mountinfo) = std::fs::read_to_string("/proc/self/mountinfo") else {
+            return vec![PathBuf::from("/")];
+        };
+        mountinfo
+            .lines()
+            .filter_map(|line| line.split(' ').nth(4))
+            .map(|field| PathBuf::from(unescape_mount_field(field)))
+            .collect()
+    }
+
+    /// Decode the octal escapes (`\040` etc.) used for whitespace in mountinfo
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn unescape_mount_field(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'\\' && i + 3 < bytes.len() && bytes[i + 1..i + 4].iter().all(|b| (b'0'..=b'7').contains(b)) {
+                let value = (bytes[i + 1] - b'0') as u32 * 64
+                    + (bytes[i + 2] - b'0') as u32 * 8
+                    + (bytes[i + 3] - b'0') as u32;
+                out.push(value as u8);
+                i += 4;
+            } else {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// statfs(2) reports the mount point, filesystem and device directly
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd", target_os = "openbsd"))]
+    fn fill_mount_details(path: &Path, info: &mut VolumeInfo) {
+        let Ok(c_path) = c_path(path) else {
+            return;
+        };
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+            info.mount_point = find_mount_point(path);
+            return;
+        }
+        let field = |chars: &[libc::c_char]| {
+            unsafe { CStr::from_ptr(chars.as_ptr()) }.to_string_lossy().into_owned()
+        };
+        info.mount_point = PathBuf::from(field(&stat.f_mntonname));
+        info.filesystem = field(&stat.f_fstypename);
+        info.device = field(&stat.f_mntfromname);
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd", target_os = "openbsd"))]
+    pub fn mount_points() -> Vec<PathBuf> {
+        let mut mounts: *mut libc::statfs = std::ptr::null_mut();
+        let count = unsafe { libc::getmntinfo(&mut mounts, libc::MNT_NOWAIT) };
+        if count <= 0 || mounts.is_null() {
+            return vec![PathBuf::from("/")];
+        }
+        // The buffer is owned by libc and reused by later calls
+        unsafe { std::slice::from_raw_parts(mounts, count as usize) }
+            .iter()
+            .map(|stat| {
+                let name = unsafe { CStr::from_ptr(stat.f_mntonname.as_ptr()) };
+                PathBuf::from(std::ffi::OsStr::from_bytes(name.to_bytes()))
+            })
+            .collect()
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    )))]
+    pub fn mount_points() -> Vec<PathBuf> {
+        vec![PathBuf::from("/")]
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    )))]
+    fn fill_mount_details(path: &Path, info: &mut VolumeInfo) {
+        info.mount_point = find_mount_point(path);
+    }
+
+    /// Walk up from `path` until the device id changes
+    fn find_mount_point(path: &Path) -> PathBuf {
+        let Ok(dev) = std::fs::metadata(path).map(|m| m.dev()) else {
+            return PathBuf::from("/");
+        };
+        let mut mount_point = path.to_path_buf();
+        while let Some(parent) = mount_point.parent() {
+            match std::fs::metadata(parent) {
+                Ok(meta) if meta.dev() == dev => mount_point = parent.to_path_buf(),
+                _ => break,
+            }
+        }
+        mount_point
+    }
+
+    pub fn owner_info(path: &Path) -> FsResult<OwnerInfo> {
+        let metadata = std::fs::symlink_metadata(path)?;
+        let uid = metadata.uid();
+        let gid = metadata.gid();
+        Ok(OwnerInfo {
+            user: user_name(uid),
+            group: group_name(gid),
+            uid: Some(uid),
+            gid: Some(gid),
+            owner_sid: None,
+            group_sid: None,
+        })
+    }
+
+    /// Run a `get*_r` lookup, growing the scratch buffer on ERANGE
+    fn with_buffer<T, F>(mut lookup: F) -> Option<T>
+    where
+        F: FnMut(&mut [libc::c_char]) -> Result<Option<T>, libc::c_int>,
+    {
+        let mut size = 1024;
+        while size <= 1 << 20 {
+            let mut buf = vec![0 as libc::c_char; size];
+            match lookup(&mut buf) {
+                Ok(name) => return name,
+                Err(libc::ERANGE) => size *= 4,
+                Err(_) => return None,
+            }
+        }
+        None
+    }
+
+    fn user_name(uid: u32) -> Option<String> {
+        with_buffer(|buf| {
+            let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::passwd = std::ptr::null_mut();
+            let rc = unsafe {
+                libc::getpwuid_r(uid, &mut passwd, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+            if rc != 0 {
+                return Err(rc);
+            }
+            if result.is_null() || passwd.pw_name.is_null() {
+                return Ok(None);
+            }
+            Ok(Some(unsafe { CStr::from_ptr(passwd.pw_name) }.to_string_lossy().into_owned()))
+        })
+    }
+
+    fn group_name(gid: u32) -> Option<String> {
+        with_buffer(|buf| {
+            let mut group: libc::group = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::group = std::ptr::null_mut();
+            let rc = unsafe {
+                libc::getgrgid_r(gid, &mut group, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+            if rc != 0 {
+                return Err(rc);
+            }
+            if result.is_null() || group.gr_name.is_null() {
+                return Ok(None);
+            }
+            Ok(Some(unsafe { CStr::from_ptr(group.gr_name) }.to_string_lossy().into_owned()))
+        })
+    }
+
+    pub fn user_id(name: &str) -> Option<u32> {
+        let name = CString::new(name).ok()?;
+        with_buffer(|buf| {
+            let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::passwd = std::ptr::null_mut();
+            let rc = unsafe {
+                libc::getpwnam_r(name.as_ptr(), &mut passwd, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+            if rc != 0 {
+                return Err(rc);
+            }
+            Ok((!result.is_null()).then_some(passwd.pw_uid))
+        })
+    }
+
+    pub fn group_id(name: &str) -> Option<u32> {
+        let name = CString::new(name).ok()?;
+        with_buffer(|buf| {
+            let mut group: libc::group = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::group = std::ptr::null_mut();
+            let rc = unsafe {
+                libc::getgrnam_r(name.as_ptr(), &mut group, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+            if rc != 0 {
+                return Err(rc);
+            }
+            Ok((!result.is_null()).then_some(group.gr_gid))
+        })
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::{PCWSTR, PWSTR};
+    use windows::Win32::Foundation::{LocalFree, HLOCAL, PSID};
+    use windows::Win32::Security::{
+        LookupAccountSidW, GROUP_SECURITY_INFORMATION, OWNER_SECURITY_INFORMATION,
+        PSECURITY_DESCRIPTOR, SID_NAME_USE,
+    };
+    use windows::Win32::Security::Authorization::{
+        ConvertSidToStringSidW, GetNamedSecurityInfoW, SE_FILE_OBJECT,
+    };
+    use windows::Win32::Storage::FileSystem::{
+        GetDiskFreeSpaceExW, GetDiskFreeSpaceW, GetLogicalDriveStringsW, GetVolumeInformationW,
+        GetVolumeNameForVolumeMountPointW, GetVolumePathNameW,
+    };
+
+    /// `FILE_READ_ONLY_VOLUME` from winnt.h
+    const FILE_READ_ONLY_VOLUME: u32 = 0x0008_0000;
+
+    fn wide(path: &Path) -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(Some(0)).collect()
+    }
+
+    fn from_wide(buf: &[u16]) -> String {
+        let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+        String::from_utf16_lossy(&buf[..len])
+    }
+
+    fn win_err(e: windows::core::Error) -> FsError {
+        FsError::Io(std::io::Error::from_raw_os_error(e.code().0 & 0xFFFF))
+    }
+
+    pub fn volume_info(path: &Path) -> FsResult<VolumeInfo> {
+        let wide_path = wide(path);
+        let mut root_buf = [0u16; 1024];
+        unsafe { GetVolumePathNameW(PCWSTR(wide_path.as_ptr()), &mut root_buf) }.map_err(win_err)?;
+        let root = from_wide(&root_buf);
+        let wide_root = wide(Path::new(&root));
+        let root_ptr = PCWSTR(wide_root.as_ptr());
+
+        let mut available_space = 0u64;
+        let mut total_space = 0u64;
+        let mut free_space = 0u64;
+        unsafe {
+            GetDiskFreeSpaceExW(
+                root_ptr,
+                Some(&mut available_space),
+                Some(&mut total_space),
+                Some(&mut free_space),
+            )
+        }
+        .map_err(win_err)?;
+
+        let mut sectors_per_cluster = 0u32;
+        let mut bytes_per_sector = 0u32;
+        let block_size = match unsafe {
+            GetDiskFreeSpaceW(root_ptr, Some(&mut sectors_per_cluster), Some(&mut bytes_per_sector), None, None)
+        } {
+            Ok(()) => sectors_per_cluster as u64 * bytes_per_sector as u64,
+            Err(_) => 0,
+        };
+
+        let mut label_buf = [0u16; 261];
+        let mut fs_buf = [0u16; 261];
+        let mut flags = 0u32;
+        let (label, filesystem, read_only) = match unsafe {
+            GetVolumeInformationW(root_ptr, Some(&mut label_buf), None, None, Some(&mut flags), Some(&mut fs_buf))
+        } {
+            Ok(()) => {
+                let label = from_wide(&label_buf);
+                (
+                    (!label.is_empty()).then_some(label),
+                    from_wide(&fs_buf),
+                    flags & FILE_READ_ONLY_VOLUME != 0,
+                )
+            }
+            Err(_) => (None, String::new(), false),
+        };
+
+        let mut guid_buf = [0u16; 64];
+        let device = match unsafe { GetVolumeNameForVolumeMountPointW(root_ptr, &mut guid_buf) } {
+            Ok(()) => from_wide(&guid_buf),
+            Err(_) => String::new(),
+        };
+
+        Ok(VolumeInfo {
+            mount_point: PathBuf::from(root),
+            filesystem,
+            device,
+            label,
+            total_space,
+            free_space,
+            available_space,
+            used_space: total_space.saturating_sub(free_space),
+            block_size,
+            read_only,
+        })
+    }
+
+    pub fn mount_points() -> Vec<PathBuf> {
+        let len = unsafe { GetLogicalDriveStringsW(None) };
+        if len == 0 {
+            return Vec::new();
+        }
+        // Drive roots separated by NULs, e.g. `C:\<NUL>D:\<NUL><NUL>`
+        let mut buf = vec![0u16; len as usize + 1];
+        let written = unsafe { GetLogicalDriveStringsW(Some(&mut buf)) } as usize;
+        buf[..written.min(buf.len())]
+            .split(|&c| c == 0)
+            .filter(|drive| !drive.is_empty())
+            .map(|drive| PathBuf::from(String::from_utf16_lossy(drive)))
+            .collect()
+    }
+
+    pub fn owner_info(path: &Path) -> FsResult<OwnerInfo> {
+        let wide_path = wide(path);
+        let mut owner = PSID::default();
+        let mut group = PSID::default();
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            GetNamedSecurityInfoW(
+                PCWSTR(wide_path.as_ptr()),
+                SE_FILE_OBJECT,
+                OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION,
+                Some(&mut owner),
+                Some(&mut group),
+                None,
+                None,
+                &mut descriptor,
+            )
+        }
+        .map_err(|e| FsError::Permission(e.message().to_string()))?;
+
+        // The SIDs point into the descriptor, so resolve them before freeing it
+        let info = OwnerInfo {
+            user: account_name(owner),
+            group: account_name(group),
+            uid: None,
+            gid: None,
+            owner_sid: sid_string(owner),
+            group_sid: sid_string(group),
+        };
+        unsafe {
+            let _ = LocalFree(HLOCAL(descriptor.0));
+        }
+        Ok(info)
+    }
+
+    /// Resolve a SID to `DOMAIN\name`
+    fn account_name(sid: PSID) -> Option<String> {
+        if sid.is_invalid() {
+            return None;
+        }
+        let mut name = [0u16; 256];
+        let mut domain = [0u16; 256];
+        let mut name_len = name.len() as u32;
+        let mut domain_len = domain.len() as u32;
+        let mut use_ = SID_NAME_USE::default();
+        unsafe {
+            LookupAccountSidW(
+                PCWSTR::null(),
+                sid,
+                PWSTR(name.as_mut_ptr()),
+                &mut name_len,
+                PWSTR(domain.as_mut_ptr()),
+                &mut domain_len,
+                &mut use_,
+            )
+        }
+        .ok()?;
+        let name = from_wide(&name);
+        let domain = from_wide(&domain);
+        Some(if domain.is_empty() { name } else { format!("{}\\{}", domain, name) })
+    }
+
+    /// Format a SID as `S-1-5-...`
+    fn sid_string(sid: PSID) -> Option<String> {
+        if sid.is_invalid() {
+            return None;
+        }
+        let mut string_sid = PWSTR::null();
+        unsafe { ConvertSidToStringSidW(sid, &mut string_sid) }.ok()?;
+        let result = unsafe { string_sid.to_string() }.ok();
+        unsafe {
+            let _ = LocalFree(HLOCAL(string_sid.0 as *mut _));
+        }
+        result
+    }
+
+    /// Ownership is set through security descriptors, not numeric ids
+    pub fn user_id(_name: &str) -> Option<u32> {
+        None
+    }
+
+    pub fn group_id(_name: &str) -> Option<u32> {
+        None
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::*;
+
+    pub fn volume_info(_path: &Path) -> FsResult<VolumeInfo> {
+        Err(FsError::NotSupported("volume information".to_string()))
+    }
+
+    pub fn mount_points() -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    pub fn owner_info(_path: &Path) -> FsResult<OwnerInfo> {
+        Err(FsError::NotSupported("file ownership".to_string()))
+    }
+
+    pub fn user_id(_name: &str) -> Option<u32> {
+        None
+    }
+
+    pub fn group_id(_name: &str) -> Option<u32> {
+        None
+    }
+}
@@ -0,0 +1,161 @@
+//! Watching a changing set of directories
+//!
+//! `DirectoryWatcher` keeps a set of directories under non-recursive watch
+//! and reports one `FsEvent` per affected path, so a consumer holding
+//! cached listings can re-examine just the entries that changed. The set
+//! is usually replaced wholesale with `set_watched` as the user navigates.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use chrono::Local;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use crate::core::{FsError, FsEvent, FsEventType, FsResult};
+
+/// Watches directories (non-recursively) and queues their change events
+pub struct DirectoryWatcher {
+    watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<FsEvent>,
+    /// Directories currently under watch
+    watched: HashSet<PathBuf>,
+    /// Directories that could not be watched; not retried until dropped from the set
+    failed: HashSet<PathBuf>,
+}
+
+impl DirectoryWatcher {
+    /// Create a watcher; `on_event` runs on the watcher thread after events are queued
+    pub fn new<F>(on_event: F) -> FsResult<Self>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let (tx, receiver) = mpsc::channel();
+        let watcher = notify::recommended_watcher(event_handler(tx, on_event))?;
+
+        Ok(Self {
+            watcher,
+            receiver,
+            watched: HashSet::new(),
+            failed: HashSet::new(),
+        })
+    }
+
+    /// Start watching a directory (no-op if it is already watched)
+    pub fn watch(&mut self, path: &Path) -> FsResult<()> {
+        if self.watched.contains(path) {
+            return Ok(());
+        }
+        self.watcher.watch(path, RecursiveMode::NonRecursive)?;
+        self.watched.insert(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Stop watching a directory
+    pub fn unwatch(&mut self, path: &Path) {
+        self.failed.remove(path);
+        if self.watched.remove(path) {
+            // Fails when the directory is already gone, which ends the watch anyway
+            let _ = self.watcher.unwatch(path);
+        }
+    }
+
+    /// Replace the watched set, returning the directories that could not be watched
+    ///
+    /// A directory that failed is reported once and not retried until it
+    /// leaves the set, so this can be called every frame.
+    pub fn set_watched<I>(&mut self, paths: I) -> Vec<(PathBuf, FsError)>
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let wanted: HashSet<PathBuf> = paths.into_iter().collect();
+        let stale: Vec<PathBuf> = self.watched.iter()
+            .chain(self.failed.iter())
+            .filter(|path| !wanted.contains(*path))
+            .cloned()
+            .collect();
+        for path in stale {
+            self.unwatch(&path);
+        }
+
+        let mut errors = Vec::new();
+        for path in wanted {
+            if self.failed.contains(&path) {
+                continue;
+            }
+            if let Err(e) = self.watch(&path) {
+                self.failed.insert(path.clone());
+                errors.push((path, e));
+            }
+        }
+        errors
+    }
+
+    /// Whether a directory is currently watched
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.watched.contains(path)
+    }
+
+    /// Take all queued events
+    ///
+    /// A watched directory that was removed is forgotten, so it is watched
+    /// again if it reappears and is passed to `set_watched`.
+    pub fn pending_events(&mut self) -> Vec<FsEvent> {
+        let events: Vec<FsEvent> = self.receiver.try_iter().collect();
+        for event in &events {
+            if event.event_type == FsEventType::Remove && self.watched.remove(&event.path) {
+                let _ = self.watcher.unwatch(&event.path);
+            }
+        }
+        events
+    }
+}
+
+/// Notify callback that queues one `FsEvent` per path on `tx`, then calls `on_event`
+pub(crate) fn event_handler<F>(
+    tx: mpsc::Sender<FsEvent>,
+    on_event: F,
+) -> impl FnMut(Result<Event, notify::Error>) + Send + 'static
+where
+    F: Fn() + Send + 'static,
+{
+    move |res| {
+        let events = match res {
+            Ok(event) => split_event(event),
+            // Errors may mean lost events, so ask for the affected directories to be reloaded
+            Err(e) => e.paths.into_iter().map(rescan_event).collect(),
+        };
+        if events.is_empty() {
+            return;
+        }
+        for event in events {
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+        on_event();
+    }
+}
+
+/// Split a notify event into one `FsEvent` per path
+///
+/// A rescan request without paths yields a single `Rescan` event with an
+/// empty path, meaning every watched directory should be reloaded.
+fn split_event(event: Event) -> Vec<FsEvent> {
+    let paths = event.paths.clone();
+    let base = FsEvent::from(event);
+    if paths.is_empty() {
+        return if base.event_type == FsEventType::Rescan { vec![base] } else { Vec::new() };
+    }
+    paths.into_iter()
+        .map(|path| FsEvent { path, ..base.clone() })
+        .collect()
+}
+
+/// Event asking for a directory to be reloaded
+fn rescan_event(path: PathBuf) -> FsEvent {
+    FsEvent {
+        event_type: FsEventType::Rescan,
+        path,
+        timestamp: Local::now(),
+        metadata: Default::default(),
+    }
+}
@@ -1,23 +1,76 @@
-use std::collections::{HashMap, VecDeque};
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tokio::runtime::Runtime;
-use fvrs_core::core::{FileEntry, FileSystem};
-use crate::state::{AppState, DragState, FileOperation, SortColumn};
+use fvrs_core::core::{FileEntry, FileSystem, FsEventType, HashAlgorithm};
+use fvrs_core::watcher::DirectoryWatcher;
+use crate::state::{ActivePane, AppState, ClipboardOperation, DragState, FileOperation, PaneSide, PaneState, SortColumn};
 use crate::utils::setup_japanese_fonts;
 use crate::archive::{ArchiveHandler, ArchiveType};
+use crate::file_info::DetailedFileInfo;
+use fvrs_core::volume::VolumeInfo;
+use crate::disk_usage::DiskUsageAnalyzer;
+use crate::duplicates::DuplicateFinder;
+use crate::trash::TrashBin;
+use crate::journal::OperationJournal;
+use crate::jobs::{JobKind, JobQueue};
+use crate::listing::{DirectoryListing, ListingView};
+use crate::thumbnails::ThumbnailCache;
+use crate::image_preview::ImagePreview;
+use crate::paged_viewer::PagedViewer;
+use crate::hex_editor::HexEditor;
+use crate::attribute_editor::AttributeEditor;
+use crate::split_join::SplitJoin;
+use crate::history::{QuickJump, VisitHistory};
+use crate::bookmarks::{BookmarkEditor, BookmarkStore};
+use crate::search::FileSearch;
+use crate::file_index::IndexService;
+use crate::smart_folders::{self, SmartFolderEditor, SmartFolderStore};
 
 
 
 
+/// 監視で検知した変更をこの件数まで差分で反映する（超えたらフォルダーを読み直す）
+const MAX_PATCHED_CHANGES: usize = 256;
+
 pub struct FileVisorApp {
     pub state: AppState,
     pub file_system: Arc<Mutex<FileSystem>>,
     pub runtime: Arc<Runtime>,
     
     // キャッシュとパフォーマンス
-    pub directory_cache: HashMap<PathBuf, Vec<FileEntry>>,
-    pub _thumbnail_cache: HashMap<PathBuf, Vec<u8>>,
+    pub directory_cache: HashMap<PathBuf, DirectoryListing>,
+    /// ペインごとの並べ替え・絞り込み済みの一覧
+    pub list_views: HashMap<PaneSide, ListingView>,
+    pub thumbnails: ThumbnailCache,
+    pub image_preview: ImagePreview,
+    /// 大きなファイル・バイナリのページ表示（ファイル閲覧パネルで使用）
+    pub paged_viewer: Option<PagedViewer>,
+    /// バイナリエディタ（B キー）
+    pub hex_editor: Option<HexEditor>,
+    pub attribute_editor: Option<AttributeEditor>,
+    pub split_join: Option<SplitJoin>,
+    /// 開いたフォルダーの履歴
+    pub history: VisitHistory,
+    pub quick_jump: Option<QuickJump>,
+    pub bookmarks: BookmarkStore,
+    /// ブックマークの追加・編集ダイアログ
+    pub bookmark_editor: Option<BookmarkEditor>,
+    /// ファイルの検索（F キー）
+    pub file_search: FileSearch,
+    /// ファイル名のインデックス
+    pub file_index: IndexService,
+    /// 保存した検索（サイドバーにフォルダーとして表示）
+    pub smart_folders: SmartFolderStore,
+    /// スマートフォルダーの追加・編集ダイアログ
+    pub smart_folder_editor: Option<SmartFolderEditor>,
+    pub file_info_cache: Option<DetailedFileInfo>,
+    pub volume_info: Option<VolumeInfo>,
+    pub volume_info_checked: Option<(PathBuf, std::time::Instant)>,
+    pub disk_usage: DiskUsageAnalyzer,
+    pub duplicates: DuplicateFinder,
+    pub trash: TrashBin,
     
     // UI状態
     pub address_bar_text: String,
@@ -26,9 +79,9 @@ pub struct FileVisorApp {
     pub _drag_state: DragState,
     
     // 高度な機能
-    pub _file_watcher: Option<tokio::sync::mpsc::Receiver<PathBuf>>,
-    pub _undo_stack: Vec<FileOperation>,
-    pub _redo_stack: Vec<FileOperation>,
+    pub file_watcher: Option<DirectoryWatcher>,
+    pub journal: OperationJournal,
+    pub jobs: JobQueue,
     
     // パフォーマンス監視
     pub frame_time_history: VecDeque<f32>,
@@ -43,14 +96,22 @@ impl FileVisorApp {
         // 日本語フォント設定
         setup_japanese_fonts(&cc.egui_ctx);
         
-        // 状態復元の試行
-        let state = if let Some(storage) = cc.storage {
+        // 状態復元の試行（タブもここで復元される）
+        let mut state: AppState = if let Some(storage) = cc.storage {
             storage.get_string("app_state")
                 .and_then(|s| serde_json::from_str(&s).ok())
                 .unwrap_or_default()
         } else {
             AppState::default()
         };
+        state.left_tabs.ensure_tab();
+        state.right_tabs.ensure_tab();
+
+        // 外部での変更を検知したら再描画して一覧を更新する
+        let repaint_ctx = cc.egui_ctx.clone();
+        let file_watcher = DirectoryWatcher::new(move || repaint_ctx.request_repaint())
+            .map_err(|e| tracing::warn!("ファイル監視を開始できません: {}", e))
+            .ok();
 
         let runtime = Arc::new(
             tokio::runtime::Runtime::new()
@@ -58,25 +119,59 @@ impl FileVisorApp {
         );
 
         Self {
-            address_bar_text: state.current_path.to_string_lossy().to_string(),
+            address_bar_text: state.pane().current_path.to_string_lossy().to_string(),
             state,
             file_system: Arc::new(Mutex::new(FileSystem::new())),
             runtime,
             directory_cache: HashMap::new(),
-            _thumbnail_cache: HashMap::new(),
+            list_views: HashMap::new(),
+            thumbnails: ThumbnailCache::default(),
+            image_preview: ImagePreview::default(),
+            paged_viewer: None,
+            hex_editor: None,
+            attribute_editor: None,
+            split_join: None,
+            history: VisitHistory::load(),
+            quick_jump: None,
+            bookmarks: BookmarkStore::load(),
+            bookmark_editor: None,
+            file_search: FileSearch::new(PathBuf::new()),
+            file_index: IndexService::load(&cc.egui_ctx),
+            smart_folders: SmartFolderStore::load(),
+            smart_folder_editor: None,
+            file_info_cache: None,
+            volume_info: None,
+            volume_info_checked: None,
+            disk_usage: DiskUsageAnalyzer::default(),
+            duplicates: DuplicateFinder::default(),
+            trash: TrashBin::default(),
             _search_active: false,
             _context_menu_pos: None,
             _drag_state: DragState::None,
-            _file_watcher: None,
-            _undo_stack: Vec::new(),
-            _redo_stack: Vec::new(),
+            file_watcher,
+            journal: OperationJournal::load(),
+            jobs: JobQueue::default(),
             frame_time_history: VecDeque::with_capacity(60),
             _memory_usage: 0,
         }
     }
 
-    /// ディレクトリ読み込み（キャッシュ付き）- Windows対応改善版
-    pub fn load_directory(&mut self, path: &Path) -> Result<&Vec<FileEntry>, String> {
+    /// ディレクトリ読み込み（キャッシュ付き、大きなフォルダーは読み込まれた分から返す）
+    pub fn load_directory(&mut self, path: &Path) -> Result<&DirectoryListing, String> {
+        // 隠しファイルの表示を切り替えたら読み直す
+        let show_hidden = self.state.show_hidden;
+        if self.directory_cache.get(path).is_some_and(|listing| listing.show_hidden() != show_hidden) {
+            self.directory_cache.remove(path);
+        }
+
+        // スマートフォルダーはフォルダーを読む代わりに検索する
+        if !self.directory_cache.contains_key(path) && smart_folders::smart_name(path).is_some() {
+            let Some(folder) = self.smart_folders.by_path(path) else {
+                return Err(format!("スマートフォルダーが見つかりません: {}", path.display()));
+            };
+            self.directory_cache.insert(path.to_path_buf(), DirectoryListing::search(folder, show_hidden));
+        }
+
         if !self.directory_cache.contains_key(path) {
             // まずパスの存在確認
             if !path.exists() {
@@ -87,80 +182,35 @@ impl FileVisorApp {
                 return Err(format!("ディレクトリではありません: {}", path.display()));
             }
 
-            // 高速なディレクトリ読み込み（直接実装）
-            const MAX_ENTRIES: usize = 1000;
-            
-            match std::fs::read_dir(path) {
-                Ok(entries) => {
-                    let mut file_entries = Vec::new();
-                    
-                    // 親ディレクトリエントリを追加
-                    if path.parent().is_some() {
-                        file_entries.push(FileEntry {
-                            name: "..".to_string(),
-                            path: path.to_path_buf(),
-                            size: 0,
-                            is_dir: true,
-                            created: chrono::Local::now(),
-                            modified: chrono::Local::now(),
-                            extension: None,
-                        });
-                    }
-                    
-                    // エントリを効率的に処理
-                    let dir_entries: Vec<_> = entries
-                        .filter_map(|entry| entry.ok())
-                        .take(MAX_ENTRIES)
-                        .filter(|entry| {
-                            let file_name = entry.file_name();
-                            let name = file_name.to_string_lossy();
-                            self.state.show_hidden || !name.starts_with('.')
-                        })
-                        .collect();
-                    
-                    for entry in dir_entries {
-                        let path = entry.path();
-                        let name = entry.file_name().to_string_lossy().to_string();
-                        
-                        if let Ok(metadata) = entry.metadata() {
-                            let size = if metadata.is_file() { metadata.len() } else { 0 };
-                            let created = metadata.created()
-                                .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-                                .into();
-                            let modified = metadata.modified()
-                                .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-                                .into();
-                            
-                            file_entries.push(FileEntry {
-                                name,
-                                path: path.clone(),
-                                size,
-                                is_dir: metadata.is_dir(),
-                                created,
-                                modified,
-                                extension: path.extension()
-                                    .and_then(|ext| ext.to_str())
-                                    .map(|s| s.to_string()),
-                            });
-                        }
-                    }
+            self.directory_cache.insert(path.to_path_buf(), DirectoryListing::load(path, show_hidden));
+        }
 
-                    // ソート適用
-                    self.sort_entries(&mut file_entries);
-                    self.directory_cache.insert(path.to_path_buf(), file_entries);
-                }
-                Err(e) => {
-                    return Err(format!("ディレクトリアクセスエラー: {} ({})", e, path.display()));
-                }
-            }
+        let listing = self.directory_cache.get_mut(path).unwrap();
+        listing.poll();
+        match listing.error() {
+            Some(error) => Err(error.to_string()),
+            None => Ok(listing),
         }
-        
-        Ok(self.directory_cache.get(path).unwrap())
     }
 
-    /// ファイルソート
-    pub fn sort_entries(&self, entries: &mut Vec<FileEntry>) {
+    /// ファイルソート（フォルダーを先に並べる）
+    pub fn sort_entries<T: Borrow<FileEntry>>(entries: &mut [T], sort_column: &SortColumn, ascending: bool) {
+        // 名前順は小文字化したキーを一度だけ作って比較する
+        if *sort_column == SortColumn::Name {
+            entries.sort_by_cached_key(|entry| {
+                let entry = entry.borrow();
+                (!entry.is_dir, entry.name.to_lowercase())
+            });
+            if !ascending {
+                let dirs = entries.partition_point(|entry| entry.borrow().is_dir);
+                entries[..dirs].reverse();
+                entries[dirs..].reverse();
+            }
+            return;
+        }
+
         entries.sort_by(|a, b| {
+            let (a, b) = (a.borrow(), b.borrow());
             // ディレクトリを最初に
             if a.is_dir && !b.is_dir {
                 return std::cmp::Ordering::Less;
@@ -169,8 +219,8 @@ impl FileVisorApp {
                 return std::cmp::Ordering::Greater;
             }
 
-            let ordering = match self.state.sort_column {
-                SortColumn::Name => a.name.cmp(&b.name),
+            let ordering = match sort_column {
+                SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
                 SortColumn::Size => a.size.cmp(&b.size),
                 SortColumn::Modified => a.modified.cmp(&b.modified),
                 SortColumn::Type => {
@@ -180,7 +230,7 @@ impl FileVisorApp {
                 }
             };
 
-            if self.state.sort_ascending {
+            if ascending {
                 ordering
             } else {
                 ordering.reverse()
@@ -190,112 +240,183 @@ impl FileVisorApp {
 
     /// ナビゲーション
     pub fn navigate_to(&mut self, path: PathBuf) {
-        if path.exists() && path.is_dir() {
-            // 履歴管理
-            if self.state.history_position < self.state.navigation_history.len() {
-                self.state.navigation_history.truncate(self.state.history_position + 1);
-            }
-            
-            self.state.navigation_history.push_back(path.clone());
-            self.state.history_position = self.state.navigation_history.len().saturating_sub(1);
-            
-            // 履歴サイズ制限
-            if self.state.navigation_history.len() > 100 {
-                self.state.navigation_history.pop_front();
-                self.state.history_position = self.state.history_position.saturating_sub(1);
-            }
+        if self.smart_folders.by_path(&path).is_some() {
+            // スマートフォルダーは履歴（よく開くフォルダー）には残さない
+            self.state.pane_mut().navigate_to(path);
+            self.sync_address_bar();
+        } else if path.exists() && path.is_dir() {
+            self.history.record(&path);
+            self.state.pane_mut().navigate_to(path);
+            self.sync_address_bar();
+        }
+    }
+
+    /// 操作対象のペインが新しい項目を置けるフォルダーを表示しているか（置けなければステータスバーで知らせる）
+    ///
+    /// スマートフォルダーは検索結果なので、貼り付けや新規作成の場所にはできない。
+    pub fn can_create_here(&mut self) -> bool {
+        if smart_folders::smart_name(&self.state.pane().current_path).is_none() {
+            return true;
+        }
+        self.journal.last_message = Some("スマートフォルダーには項目を作成・貼り付けできません".to_string());
+        false
+    }
 
-            self.state.current_path = path;
-            self.address_bar_text = self.state.current_path.to_string_lossy().to_string();
-            self.state.selected_items.clear();
-            self.state.last_selected_index = None;
+    /// 表示中のタブの履歴の位置へ移動
+    pub fn go_to_history(&mut self, position: usize) {
+        if self.state.pane_mut().go_to_history(position) {
+            self.sync_address_bar();
         }
     }
 
     pub fn go_back(&mut self) {
-        if self.state.history_position > 0 {
-            self.state.history_position -= 1;
-            if let Some(path) = self.state.navigation_history.get(self.state.history_position) {
-                self.state.current_path = path.clone();
-                self.address_bar_text = self.state.current_path.to_string_lossy().to_string();
-                self.state.selected_items.clear();
-                self.state.last_selected_index = None;
-            }
+        if self.state.pane_mut().go_back() {
+            self.sync_address_bar();
         }
     }
 
     pub fn go_forward(&mut self) {
-        if self.state.history_position < self.state.navigation_history.len().saturating_sub(1) {
-            self.state.history_position += 1;
-            if let Some(path) = self.state.navigation_history.get(self.state.history_position) {
-                self.state.current_path = path.clone();
-                self.address_bar_text = self.state.current_path.to_string_lossy().to_string();
-                self.state.selected_items.clear();
-                self.state.last_selected_index = None;
-            }
+        if self.state.pane_mut().go_forward() {
+            self.sync_address_bar();
+        }
+    }
+
+    /// アドレスバーを操作対象のペインのパスに合わせる
+    fn sync_address_bar(&mut self) {
+        self.address_bar_text = self.state.pane().current_path.to_string_lossy().to_string();
+    }
+
+    /// 2画面表示の切り替え（1 画面に戻すときは左のペインを残す）
+    pub fn set_dual_pane(&mut self, dual_pane: bool) {
+        self.state.dual_pane = dual_pane;
+        if !dual_pane {
+            self.activate_pane(PaneSide::Left);
+        }
+    }
+
+    /// ファイル一覧のペインを操作対象にする
+    pub fn activate_pane(&mut self, side: PaneSide) {
+        self.state.active_pane = ActivePane::MainList;
+        if self.state.active_side != side {
+            self.state.active_side = side;
+            self.pane_changed();
         }
     }
 
+    /// 操作対象のペインに現在のフォルダーを表示する新しいタブを開く
+    pub fn new_tab(&mut self) {
+        let path = self.state.pane().current_path.clone();
+        let mut tab = PaneState {
+            sort_column: self.state.pane().sort_column.clone(),
+            sort_ascending: self.state.pane().sort_ascending,
+            ..PaneState::default()
+        };
+        tab.navigate_to(path);
+        self.state.tabs_mut().open(tab);
+        self.pane_changed();
+    }
+
+    /// タブを閉じる（ペインの最後のタブは閉じない）
+    pub fn close_tab(&mut self, side: PaneSide, index: usize) {
+        if self.state.tabs_at_mut(side).close(index).is_some() && side == self.state.active_side {
+            self.pane_changed();
+        }
+    }
+
+    /// 操作対象のペインで表示中のタブを閉じる
+    pub fn close_current_tab(&mut self) {
+        let index = self.state.tabs().active;
+        self.close_tab(self.state.active_side, index);
+    }
+
+    /// 操作対象のペインで表示中のタブ以外を閉じる
+    pub fn close_other_tabs(&mut self) {
+        self.state.tabs_mut().close_others();
+    }
+
+    /// タブを切り替える
+    pub fn select_tab(&mut self, side: PaneSide, index: usize) {
+        self.activate_pane(side);
+        let tabs = self.state.tabs_mut();
+        if index < tabs.tabs.len() && index != tabs.active {
+            tabs.active = index;
+            self.pane_changed();
+        }
+    }
+
+    /// 前後のタブへ切り替える（Ctrl+Tab / Ctrl+Shift+Tab）
+    pub fn cycle_tab(&mut self, forward: bool) {
+        if self.state.tabs().tabs.len() > 1 {
+            self.state.tabs_mut().cycle(forward);
+            self.pane_changed();
+        }
+    }
+
+    /// 表示するペイン・タブが変わったときの更新
+    fn pane_changed(&mut self) {
+        // ツリーで選んだフォルダーは切り替え前のタブに対するもの
+        self.state.sidebar_selected_item = None;
+        self.sync_address_bar();
+    }
+
     pub fn go_up(&mut self) {
-        if let Some(parent) = self.state.current_path.parent() {
+        if let Some(parent) = self.state.pane().current_path.parent() {
             self.navigate_to(parent.to_path_buf());
         }
     }
 
-    /// 削除確認ダイアログを表示
+    /// 削除確認ダイアログを表示（ゴミ箱へ移動）
     pub fn show_delete_confirmation(&mut self) {
-        if !self.state.selected_items.is_empty() {
-            self.state.delete_dialog_items = self.state.selected_items.clone();
+        if !self.state.pane().selected_items.is_empty() {
+            self.state.delete_dialog_items = self.state.pane().selected_items.clone();
+            self.state.delete_permanently = false;
             self.state.show_delete_dialog = true;
         }
     }
 
-    /// ファイル削除（実際の削除処理）
+    /// 完全削除の確認ダイアログを表示（Shift+Delete）
+    pub fn show_permanent_delete_confirmation(&mut self) {
+        self.show_delete_confirmation();
+        self.state.delete_permanently = true;
+    }
+
+    /// ファイル削除（通常はゴミ箱へ移動するので元に戻せる）
     pub fn delete_selected_files(&mut self) {
-        for path in &self.state.delete_dialog_items {
-            // 標準ライブラリを使用してより確実に削除
-            if path.is_dir() {
-                match std::fs::remove_dir_all(path) {
-                    Ok(_) => {
-                        tracing::info!("フォルダを削除しました: {:?}", path);
-                        self._undo_stack.push(FileOperation::Delete { path: path.clone() });
-                    }
-                    Err(e) => tracing::error!("フォルダ削除エラー: {:?} at {:?}", e, path),
-                }
-            } else {
-                match std::fs::remove_file(path) {
-                    Ok(_) => {
-                        tracing::info!("ファイルを削除しました: {:?}", path);
-                        self._undo_stack.push(FileOperation::Delete { path: path.clone() });
-                    }
-                    Err(e) => tracing::error!("ファイル削除エラー: {:?} at {:?}", e, path),
-                }
-            }
+        let paths = std::mem::take(&mut self.state.delete_dialog_items);
+        if !paths.is_empty() {
+            self.enqueue_job(JobKind::Delete {
+                paths,
+                permanent: self.state.delete_permanently,
+            });
         }
         
         // 状態をクリア
-        self.directory_cache.remove(&self.state.current_path);
-        self.state.selected_items.clear();
-        self.state.last_selected_index = None;
+        self.state.pane_mut().selected_items.clear();
+        self.state.pane_mut().last_selected_index = None;
         self.state.delete_dialog_items.clear();
+        self.state.delete_permanently = false;
         self.state.show_delete_dialog = false;
     }
 
     /// フォルダ作成
     pub fn create_new_folder(&mut self, name: &str) {
-        let new_path = self.state.current_path.join(name);
+        let new_path = self.state.pane().current_path.join(name);
         let fs = self.file_system.lock().unwrap();
         if let Err(e) = self.runtime.block_on(fs.create_dir(&new_path)) {
             tracing::error!("フォルダ作成エラー: {:?}", e);
         } else {
-            self._undo_stack.push(FileOperation::CreateFolder { path: new_path });
-            self.directory_cache.remove(&self.state.current_path);
+            self.journal.record(FileOperation::CreateFolder { path: new_path });
+            self.directory_cache.remove(&self.state.pane().current_path);
         }
     }
     
     /// 新規ファイル作成
     pub fn create_new_file(&mut self, file_name: &str) {
-        let new_file_path = self.state.current_path.join(file_name);
+        if !self.can_create_here() {
+            self.state.show_create_file_dialog = false;
+            return;
+        }
+        let new_file_path = self.state.pane().current_path.join(file_name);
         
         // ファイルが既に存在するかチェック
         if new_file_path.exists() {
@@ -307,13 +428,14 @@ impl FileVisorApp {
         match std::fs::write(&new_file_path, "") {
             Ok(_) => {
                 tracing::info!("新規ファイルを作成しました: {:?}", new_file_path);
+                self.journal.record(FileOperation::CreateFile { path: new_file_path.clone() });
                 // ディレクトリキャッシュを更新
-                self.directory_cache.remove(&self.state.current_path);
+                self.directory_cache.remove(&self.state.pane().current_path);
                 
                 // 作成したファイルを選択状態にする
-                self.state.selected_items.clear();
-                self.state.selected_items.push(new_file_path.clone());
-                self.state.last_selected_index = None;
+                self.state.pane_mut().selected_items.clear();
+                self.state.pane_mut().selected_items.push(new_file_path.clone());
+                self.state.pane_mut().last_selected_index = None;
                 
                 // ダイアログを閉じる
                 self.state.show_create_file_dialog = false;
@@ -327,7 +449,11 @@ impl FileVisorApp {
     
     /// 新規フォルダ作成（ダイアログ経由）
     pub fn create_new_folder_dialog(&mut self, folder_name: &str) {
-        let new_folder_path = self.state.current_path.join(folder_name);
+        if !self.can_create_here() {
+            self.state.show_create_folder_dialog = false;
+            return;
+        }
+        let new_folder_path = self.state.pane().current_path.join(folder_name);
         
         // フォルダが既に存在するかチェック
         if new_folder_path.exists() {
@@ -339,13 +465,14 @@ impl FileVisorApp {
         match std::fs::create_dir(&new_folder_path) {
             Ok(_) => {
                 tracing::info!("新規フォルダを作成しました: {:?}", new_folder_path);
+                self.journal.record(FileOperation::CreateFolder { path: new_folder_path.clone() });
                 // ディレクトリキャッシュを更新
-                self.directory_cache.remove(&self.state.current_path);
+                self.directory_cache.remove(&self.state.pane().current_path);
                 
                 // 作成したフォルダを選択状態にする
-                self.state.selected_items.clear();
-                self.state.selected_items.push(new_folder_path.clone());
-                self.state.last_selected_index = None;
+                self.state.pane_mut().selected_items.clear();
+                self.state.pane_mut().selected_items.push(new_folder_path.clone());
+                self.state.pane_mut().last_selected_index = None;
                 
                 // ダイアログを閉じる
                 self.state.show_create_folder_dialog = false;
@@ -360,11 +487,16 @@ impl FileVisorApp {
     /// 解凍ダイアログを表示
     pub fn show_unpack_dialog(&mut self) {
         // 選択されたファイルが圧縮ファイルかチェック
-        if let Some(selected_path) = self.state.selected_items.first() {
+        if let Some(selected_path) = self.state.pane().selected_items.first() {
             let full_path = selected_path.clone();
             if ArchiveHandler::is_archive(&full_path) {
+                // スマートフォルダーでは圧縮ファイルのあるフォルダーへ展開する
+                let destination = match smart_folders::smart_name(&self.state.pane().current_path) {
+                    Some(_) => full_path.parent().map(PathBuf::from).unwrap_or_default(),
+                    None => self.state.pane().current_path.clone(),
+                };
                 self.state.current_archive = Some(full_path);
-                self.state.unpack_destination = self.state.current_path.to_string_lossy().to_string();
+                self.state.unpack_destination = destination.to_string_lossy().to_string();
                 self.state.show_unpack_dialog = true;
             } else {
                 // self.state.status_message = "選択されたファイルは圧縮ファイルではありません".to_string();
@@ -376,7 +508,7 @@ impl FileVisorApp {
 
     /// 圧縮ダイアログを表示
     pub fn show_pack_dialog(&mut self) {
-        if !self.state.selected_items.is_empty() {
+        if !self.state.pane().selected_items.is_empty() && self.can_create_here() {
             self.state.pack_filename = "archive.zip".to_string();
             self.state.pack_format = ArchiveType::Zip;
             self.state.show_pack_dialog = true;
@@ -404,10 +536,18 @@ impl FileVisorApp {
     pub fn extract_archive(&mut self) {
         if let Some(archive_path) = &self.state.current_archive.clone() {
             let destination = PathBuf::from(&self.state.unpack_destination);
+            let created = ArchiveHandler::new_extraction_paths(archive_path, &destination);
             
             match ArchiveHandler::extract_archive(archive_path, &destination) {
                 Ok(()) => {
                     // self.state.status_message = format!("解凍完了: {}", destination.display());
+                    for path in created {
+                        match std::fs::symlink_metadata(&path) {
+                            Ok(metadata) if metadata.is_dir() => self.journal.record(FileOperation::CreateFolder { path }),
+                            Ok(_) => self.journal.record(FileOperation::CreateFile { path }),
+                            Err(_) => {}
+                        }
+                    }
                     self.state.show_unpack_dialog = false;
                     self.reload_current_directory();
                     tracing::info!("圧縮ファイルを解凍しました: {:?} -> {:?}", archive_path, destination);
@@ -422,18 +562,19 @@ impl FileVisorApp {
 
     /// ファイル・フォルダを圧縮
     pub fn create_archive(&mut self) {
-        let selected_paths: Vec<PathBuf> = self.state.selected_items.clone();
+        let selected_paths: Vec<PathBuf> = self.state.pane().selected_items.clone();
 
         if selected_paths.is_empty() {
             // self.state.status_message = "圧縮するファイルやフォルダを選択してください".to_string();
             return;
         }
 
-        let archive_path = self.state.current_path.join(&self.state.pack_filename);
+        let archive_path = self.state.pane().current_path.join(&self.state.pack_filename);
         
         match ArchiveHandler::create_archive(&selected_paths, &archive_path, self.state.pack_format.clone()) {
             Ok(()) => {
                 // self.state.status_message = format!("圧縮完了: {}", archive_path.display());
+                self.journal.record(FileOperation::CreateFile { path: archive_path.clone() });
                 self.state.show_pack_dialog = false;
                 self.reload_current_directory();
                 tracing::info!("ファイルを圧縮しました: {:?} -> {:?}", selected_paths, archive_path);
@@ -452,20 +593,259 @@ impl FileVisorApp {
         self.state.current_archive = None;
     }
     
+    /// 選択中の項目をクリップボードへ（`cut` なら貼り付け時に移動）
+    pub fn set_clipboard(&mut self, cut: bool) {
+        if self.state.pane().selected_items.is_empty() {
+            return;
+        }
+        let items = self.state.pane().selected_items.clone();
+        tracing::info!("{}個のアイテムを{}しました", items.len(), if cut { "切り取り" } else { "コピー" });
+        self.state.clipboard = Some(if cut {
+            ClipboardOperation::Cut(items)
+        } else {
+            ClipboardOperation::Copy(items)
+        });
+    }
+
+    /// クリップボードの項目を現在のフォルダーへ貼り付け
+    pub fn paste_clipboard(&mut self) {
+        if !self.can_create_here() {
+            return;
+        }
+        let destination = self.state.pane().current_path.clone();
+        let kind = match self.state.clipboard.take() {
+            Some(ClipboardOperation::Copy(sources)) => {
+                // コピーは何度でも貼り付けられるように残す
+                self.state.clipboard = Some(ClipboardOperation::Copy(sources.clone()));
+                JobKind::Copy { sources, destination }
+            }
+            Some(ClipboardOperation::Cut(sources)) => JobKind::Move { sources, destination },
+            None => return,
+        };
+        self.enqueue_job(kind);
+    }
+
+    /// コピー・移動先の指定ダイアログを表示（2画面表示では反対側のペインが既定の移動先）
+    pub fn show_transfer_dialog(&mut self, is_move: bool) {
+        if self.state.pane().selected_items.is_empty() {
+            return;
+        }
+        let destination = if self.state.dual_pane {
+            &self.state.other_pane().current_path
+        } else {
+            &self.state.pane().current_path
+        };
+        self.state.transfer_destination = destination.to_string_lossy().to_string();
+        self.state.transfer_is_move = is_move;
+        self.state.show_transfer_dialog = true;
+    }
+
+    /// 選択中の項目を指定のフォルダーへコピー・移動
+    pub fn transfer_selected(&mut self, destination: PathBuf) {
+        let sources = self.state.pane().selected_items.clone();
+        if sources.is_empty() {
+            return;
+        }
+        self.enqueue_job(if self.state.transfer_is_move {
+            JobKind::Move { sources, destination }
+        } else {
+            JobKind::Copy { sources, destination }
+        });
+    }
+
+    /// ファイル操作をバックグラウンドのキューへ追加
+    pub fn enqueue_job(&mut self, kind: JobKind) {
+        self.jobs.options.verify = self.state.copy_verify.then_some(HashAlgorithm::BLAKE3);
+        self.jobs.enqueue(kind);
+        self.state.show_jobs_panel = true;
+    }
+
+    /// 終了したジョブを履歴に記録し、表示を更新
+    pub fn poll_jobs(&mut self) {
+        for result in self.jobs.poll() {
+            for dir in result.affected_dirs {
+                self.refresh_smart_folders(&dir);
+                self.directory_cache.remove(&dir);
+            }
+            for operation in result.operations {
+                self.record_operation(operation);
+            }
+            self.volume_info_checked = None;
+        }
+    }
+
+    /// 直前のファイル操作を元に戻す
+    pub fn undo(&mut self) {
+        if let Ok(operation) = self.journal.undo() {
+            self.after_journal_operation(&operation);
+        }
+    }
+
+    /// 元に戻したファイル操作をやり直す
+    pub fn redo(&mut self) {
+        if let Ok(operation) = self.journal.redo() {
+            self.after_journal_operation(&operation);
+        }
+    }
+
+    /// 他のビューで行った操作を履歴に記録し、表示を更新
+    pub fn record_operation(&mut self, operation: FileOperation) {
+        self.after_journal_operation(&operation);
+        self.journal.record(operation);
+    }
+
+    fn after_journal_operation(&mut self, operation: &FileOperation) {
+        for dir in operation.affected_dirs() {
+            self.refresh_smart_folders(&dir);
+            self.directory_cache.remove(&dir);
+        }
+        self.volume_info_checked = None;
+        for pane in self.state.all_panes_mut() {
+            pane.selected_items.retain(|path| path.exists());
+        }
+    }
+    
+    /// 表示中のディレクトリを監視し、外部での変更をキャッシュ済みの一覧へ反映（毎フレーム呼ぶ）
+    pub fn poll_file_changes(&mut self) {
+        let visible = self.visible_directories();
+        let Some(watcher) = self.file_watcher.as_mut() else {
+            return;
+        };
+        for (path, e) in watcher.set_watched(visible.iter().cloned()) {
+            tracing::warn!("フォルダーを監視できません: {:?} ({})", path, e);
+        }
+
+        let events = watcher.pending_events();
+        if events.is_empty() {
+            return;
+        }
+
+        // 同じパスへの連続した通知はまとめて一度だけ調べ直す
+        let mut changed = HashSet::new();
+        for event in events {
+            match event.event_type {
+                FsEventType::Access => {}
+                // 取りこぼしがあった場合はフォルダーごと読み直す
+                FsEventType::Rescan if event.path.as_os_str().is_empty() => {
+                    for dir in &visible {
+                        self.directory_cache.remove(dir);
+                    }
+                    for listing in self.directory_cache.values_mut() {
+                        listing.request_refresh();
+                    }
+                }
+                FsEventType::Rescan => {
+                    self.refresh_smart_folders(&event.path);
+                    self.directory_cache.remove(&event.path);
+                }
+                _ => {
+                    changed.insert(event.path);
+                }
+            }
+        }
+
+        // 大量の変更（展開など）は一つずつ反映するより読み直した方が速い
+        if changed.len() > MAX_PATCHED_CHANGES {
+            for dir in changed.iter().filter_map(|path| path.parent()) {
+                self.directory_cache.remove(dir);
+            }
+            for pane in self.state.all_panes_mut() {
+                pane.selected_items.retain(|path| path.exists());
+            }
+            return;
+        }
+
+        let mut removed = HashSet::new();
+        for path in changed {
+            if !self.patch_cached_entry(&path) {
+                removed.insert(path);
+            }
+        }
+        if !removed.is_empty() {
+            self.volume_info_checked = None;
+            for pane in self.state.all_panes_mut() {
+                pane.selected_items.retain(|path| !removed.contains(path));
+            }
+        }
+    }
+
+    /// 監視対象のフォルダー（表示中のペイン、左ペインの選択、展開したツリー）
+    ///
+    /// スマートフォルダーは検索したフォルダーと結果のあるフォルダーを監視する。
+    fn visible_directories(&self) -> HashSet<PathBuf> {
+        let mut dirs: HashSet<PathBuf> = self.state.expanded_folders.iter().cloned().collect();
+        dirs.insert(self.state.pane_at(PaneSide::Left).current_path.clone());
+        if self.state.dual_pane {
+            dirs.insert(self.state.pane_at(PaneSide::Right).current_path.clone());
+        }
+        if let Some(selected) = &self.state.sidebar_selected_item {
+            dirs.insert(selected.clone());
+        }
+        let smart: Vec<PathBuf> = dirs.iter().filter(|dir| smart_folders::smart_name(dir).is_some()).cloned().collect();
+        for path in smart {
+            if let Some(listing) = self.directory_cache.get(&path) {
+                dirs.extend(listing.result_dirs().iter().cloned());
+            }
+        }
+        dirs.retain(|dir| dir.is_dir());
+        dirs
+    }
+
+    /// `path` の変更で結果が変わりうるスマートフォルダーを検索し直す
+    fn refresh_smart_folders(&mut self, path: &Path) {
+        for listing in self.directory_cache.values_mut() {
+            if listing.covers(path) {
+                listing.request_refresh();
+            }
+        }
+    }
+
+    /// 変更のあったパスを調べ直し、親フォルダーのキャッシュを差分更新（存在しなければfalse）
+    fn patch_cached_entry(&mut self, path: &Path) -> bool {
+        self.refresh_smart_folders(path);
+        let metadata = std::fs::symlink_metadata(path).ok();
+        if metadata.is_none() {
+            // 表示中のフォルダー自体が消えた場合は次回の表示でエラーにする
+            self.directory_cache.remove(path);
+        }
+
+        if let Some(listing) = path.parent().and_then(|parent| self.directory_cache.get_mut(parent)) {
+            listing.apply_change(path, metadata.as_ref());
+        }
+        metadata.is_some()
+    }
+
     /// 現在のディレクトリをリロード
     pub fn reload_current_directory(&mut self) {
-        self.directory_cache.remove(&self.state.current_path);
+        self.directory_cache.remove(&self.state.pane().current_path);
         // キャッシュをクリアすることで次回表示時に再読み込みされる
     }
-    
+
+    /// 現在のディレクトリのボリューム情報を取得（数秒ごとに更新）
+    pub fn current_volume_info(&mut self) -> Option<&VolumeInfo> {
+        const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+        let stale = match &self.volume_info_checked {
+            Some((path, checked_at)) => {
+                *path != self.state.pane().current_path || checked_at.elapsed() >= REFRESH_INTERVAL
+            }
+            None => true,
+        };
+        if stale {
+            self.volume_info = VolumeInfo::for_path(&self.state.pane().current_path).ok();
+            self.volume_info_checked = Some((self.state.pane().current_path.clone(), std::time::Instant::now()));
+        }
+        self.volume_info.as_ref()
+    }
+
     /// リネームダイアログを表示
     pub fn show_rename_dialog(&mut self) {
-        if let Some(selected_path) = self.state.selected_items.first() {
-            self.state.rename_target_path = Some(selected_path.clone());
+        if let Some(selected_path) = self.state.pane().selected_items.first().cloned() {
             self.state.rename_new_name = selected_path.file_name()
                 .and_then(|name| name.to_str())
                 .unwrap_or("")
                 .to_string();
+            self.state.rename_target_path = Some(selected_path);
             self.state.show_rename_dialog = true;
         }
     }
@@ -481,14 +861,24 @@ impl FileVisorApp {
             let new_path = old_path.parent()
                 .map(|parent| parent.join(new_name))
                 .unwrap_or_else(|| PathBuf::from(new_name));
+
+            // rename は既存の項目を黙って置き換え、元に戻せなくなるので拒否する
+            // （大文字・小文字だけの変更で同じ項目を指す場合は除く）
+            let same_item = new_path.canonicalize().ok() == old_path.canonicalize().ok();
+            if std::fs::symlink_metadata(&new_path).is_ok() && !same_item {
+                tracing::warn!("リネーム先が既に存在します: {:?}", new_path);
+                self.journal.last_message = Some(format!("既に存在します: {}", new_path.display()));
+                return;
+            }
             
             match std::fs::rename(old_path, &new_path) {
                 Ok(()) => {
                     tracing::info!("リネーム完了: {:?} -> {:?}", old_path, new_path);
+                    self.journal.record(FileOperation::Rename { from: old_path.clone(), to: new_path.clone() });
                     
                     // 選択アイテムを更新
-                    if let Some(index) = self.state.selected_items.iter().position(|path| path == old_path) {
-                        self.state.selected_items[index] = new_path;
+                    if let Some(index) = self.state.pane().selected_items.iter().position(|path| path == old_path) {
+                        self.state.pane_mut().selected_items[index] = new_path;
                     }
                     
                     self.state.show_rename_dialog = false;
@@ -502,4 +892,4 @@ impl FileVisorApp {
             }
         }
     }
-} 
\ No newline at end of file
+} 
@@ -427,6 +427,43 @@ impl ArchiveHandler {
         Ok(entries)
     }
 
+    /// 解凍で新たに作られる項目（既存のフォルダーの中で最上位のもの）
+    ///
+    /// 解凍前に呼び出す。元に戻す履歴に記録するために使う。
+    pub fn new_extraction_paths(archive_path: &Path, extract_to: &Path) -> Vec<PathBuf> {
+        let exists = |path: &Path| std::fs::symlink_metadata(path).is_ok();
+        if !exists(extract_to) {
+            // 解凍先ごと作られるので、まだない最上位のフォルダーだけを返す
+            let mut root = extract_to;
+            while let Some(parent) = root.parent()
+                && !parent.as_os_str().is_empty()
+                && !exists(parent)
+            {
+                root = parent;
+            }
+            return vec![root.to_path_buf()];
+        }
+
+        let entries = Self::list_archive_contents(archive_path).unwrap_or_default();
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for entry in entries {
+            let mut path = extract_to.to_path_buf();
+            for component in entry.path.components() {
+                let std::path::Component::Normal(name) = component else {
+                    continue;
+                };
+                path.push(name);
+                if !exists(&path) {
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                    break;
+                }
+            }
+        }
+        paths
+    }
+
     /// 圧縮ファイルを指定ディレクトリに解凍
     pub fn extract_archive(archive_path: &Path, extract_to: &Path) -> Result<(), String> {
         let archive_type = Self::detect_archive_type(archive_path);
@@ -0,0 +1,317 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use fvrs_core::attributes::{self, AttributeChanges, AttributeReport, AttributeRules, FileAttributes};
+use fvrs_core::core::FsError;
+use fvrs_core::volume::OwnerInfo;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::SystemTime;
+
+/// 日時の入力形式
+pub const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 権限ビットと表示名（所有者・グループ・その他の順）
+pub const MODE_BITS: [(u32, &str); 9] = [
+    (0o400, "読み取り"),
+    (0o200, "書き込み"),
+    (0o100, "実行"),
+    (0o040, "読み取り"),
+    (0o020, "書き込み"),
+    (0o010, "実行"),
+    (0o004, "読み取り"),
+    (0o002, "書き込み"),
+    (0o001, "実行"),
+];
+
+/// 特殊ビットと表示名
+pub const SPECIAL_BITS: [(u32, &str); 3] = [(0o4000, "setuid"), (0o2000, "setgid"), (0o1000, "スティッキー")];
+
+/// ファイルかフォルダーの一方に適用する変更の入力値
+///
+/// どの項目も None・空欄なら変更しない。
+#[derive(Debug, Clone, Default)]
+pub struct ChangeForm {
+    pub readonly: Option<bool>,
+    pub hidden: Option<bool>,
+    pub system: Option<bool>,
+    pub archive: Option<bool>,
+    /// 権限ビットごとの変更（キーはビット値）
+    pub mode_bits: Vec<(u32, bool)>,
+    /// 所有者（名前か数値）
+    pub owner: String,
+    /// グループ（名前か数値）
+    pub group: String,
+    /// 更新日時（TIME_FORMAT）
+    pub modified: String,
+    /// アクセス日時（TIME_FORMAT）
+    pub accessed: String,
+}
+
+impl ChangeForm {
+    /// 権限ビットの変更（None なら変更しない）
+    pub fn mode_bit(&self, bit: u32) -> Option<bool> {
+        self.mode_bits.iter().find(|(b, _)| *b == bit).map(|(_, on)| *on)
+    }
+
+    pub fn set_mode_bit(&mut self, bit: u32, value: Option<bool>) {
+        self.mode_bits.retain(|(b, _)| *b != bit);
+        if let Some(on) = value {
+            self.mode_bits.push((bit, on));
+        }
+    }
+
+    /// 8 進数の指定ですべての権限ビットを決める
+    pub fn set_octal(&mut self, text: &str) -> Result<(), String> {
+        let mode = u32::from_str_radix(text.trim(), 8)
+            .ok()
+            .filter(|&mode| mode <= attributes::MODE_MASK)
+            .ok_or_else(|| format!("8 進数の権限ではありません: {}", text.trim()))?;
+        self.mode_bits = MODE_BITS
+            .iter()
+            .chain(SPECIAL_BITS.iter())
+            .map(|&(bit, _)| (bit, mode & bit != 0))
+            .collect();
+        Ok(())
+    }
+
+    /// 入力値を変更内容に変換する
+    pub fn to_changes(&self) -> Result<AttributeChanges, String> {
+        let mut changes = AttributeChanges {
+            readonly: self.readonly,
+            hidden: self.hidden,
+            system: self.system,
+            archive: self.archive,
+            ..Default::default()
+        };
+        for &(bit, on) in &self.mode_bits {
+            if on {
+                changes.mode_set |= bit;
+            } else {
+                changes.mode_clear |= bit;
+            }
+        }
+
+        let owner = self.owner.trim();
+        if !owner.is_empty() {
+            changes.uid =
+                Some(fvrs_core::volume::user_id(owner).ok_or_else(|| format!("ユーザーが見つかりません: {}", owner))?);
+        }
+        let group = self.group.trim();
+        if !group.is_empty() {
+            changes.gid =
+                Some(fvrs_core::volume::group_id(group).ok_or_else(|| format!("グループが見つかりません: {}", group))?);
+        }
+        changes.modified = parse_time(&self.modified)?;
+        changes.accessed = parse_time(&self.accessed)?;
+        Ok(changes)
+    }
+}
+
+/// 入力された日時を読む（空欄なら None）
+fn parse_time(text: &str) -> Result<Option<SystemTime>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let naive = NaiveDateTime::parse_from_str(text, TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M"))
+        .map_err(|_| format!("日時は YYYY-MM-DD HH:MM:SS の形式で入力してください: {}", text))?;
+    let time = Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("存在しない日時です: {}", text))?;
+    Ok(Some(time.into()))
+}
+
+/// 日時を入力形式で表す
+pub fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time).format(TIME_FORMAT).to_string()
+}
+
+/// 実行中の変更（破棄すると中止される）
+struct RunningApply {
+    cancel: Arc<AtomicBool>,
+    /// 処理した項目数
+    visited: Arc<AtomicUsize>,
+    receiver: mpsc::Receiver<Result<AttributeReport, String>>,
+}
+
+impl Drop for RunningApply {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 属性変更ダイアログの状態
+pub struct AttributeEditor {
+    /// 変更する項目
+    pub targets: Vec<PathBuf>,
+    /// 読み取れた項目の今の属性
+    pub current: Vec<FileAttributes>,
+    /// 項目が 1 つのときの所有者
+    pub owner: Option<OwnerInfo>,
+    /// ファイルへの変更（別の設定を使わなければフォルダーにも適用する）
+    pub files: ChangeForm,
+    pub directories: ChangeForm,
+    /// フォルダーにはファイルと別の設定を使う
+    pub separate_directories: bool,
+    /// フォルダーの設定を編集中
+    pub editing_directories: bool,
+    /// フォルダーの中も変更する
+    pub recursive: bool,
+    /// 8 進数で権限を指定する入力欄
+    pub octal_text: String,
+    running: Option<RunningApply>,
+    pub report: Option<AttributeReport>,
+    pub error: Option<String>,
+}
+
+impl AttributeEditor {
+    pub fn new(targets: Vec<PathBuf>) -> Self {
+        let mut editor = Self {
+            targets,
+            current: Vec::new(),
+            owner: None,
+            files: ChangeForm::default(),
+            directories: ChangeForm::default(),
+            separate_directories: false,
+            editing_directories: false,
+            recursive: false,
+            octal_text: String::new(),
+            running: None,
+            report: None,
+            error: None,
+        };
+        editor.reload();
+        editor
+    }
+
+    /// 今の属性を読み直す
+    pub fn reload(&mut self) {
+        self.current = self
+            .targets
+            .iter()
+            .filter_map(|path| match attributes::read(path) {
+                Ok(attributes) => Some(attributes),
+                Err(e) => {
+                    tracing::warn!("属性を読み取れません: {:?} ({})", path, e);
+                    None
+                }
+            })
+            .collect();
+        self.owner = match self.targets.as_slice() {
+            [path] => OwnerInfo::for_path(path).ok(),
+            _ => None,
+        };
+    }
+
+    pub fn has_directories(&self) -> bool {
+        self.current.iter().any(|attributes| attributes.is_dir)
+    }
+
+    /// すべての項目で同じなら その値（混在していれば None）
+    pub fn common<T: PartialEq + Copy>(&self, value: impl Fn(&FileAttributes) -> Option<T>) -> Option<T> {
+        let mut values = self.current.iter().map(value);
+        let first = values.next()??;
+        values.all(|v| v == Some(first)).then_some(first)
+    }
+
+    /// 編集中の入力値
+    pub fn form_mut(&mut self) -> &mut ChangeForm {
+        if self.separate_directories && self.editing_directories { &mut self.directories } else { &mut self.files }
+    }
+
+    /// 入力値から変更の指定を作る
+    pub fn rules(&self) -> Result<AttributeRules, String> {
+        let files = self.files.to_changes()?;
+        let directories = if self.separate_directories { self.directories.to_changes()? } else { files.clone() };
+        Ok(AttributeRules { files, directories, recursive: self.recursive })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// 処理した項目数
+    pub fn visited(&self) -> usize {
+        self.running.as_ref().map_or(0, |running| running.visited.load(Ordering::Relaxed))
+    }
+
+    /// 変更を別スレッドで始める
+    pub fn start(&mut self, ctx: &egui::Context) {
+        let rules = match self.rules() {
+            Ok(rules) => rules,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        if rules.files.is_empty() && rules.directories.is_empty() {
+            self.error = Some("変更する項目がありません".to_string());
+            return;
+        }
+
+        let targets = self.targets.clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let visited = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel();
+        let thread_cancel = Arc::clone(&cancel);
+        let thread_visited = Arc::clone(&visited);
+        let repaint_ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let result = attributes::apply(&targets, &rules, &thread_cancel, |_| {
+                thread_visited.fetch_add(1, Ordering::Relaxed);
+            });
+            let result = match result {
+                Ok(report) => Ok(report),
+                Err(FsError::Cancelled) => return,
+                Err(e) => Err(format!("属性を変更できません: {}", e)),
+            };
+            let _ = sender.send(result);
+            repaint_ctx.request_repaint();
+        });
+
+        self.report = None;
+        self.error = None;
+        self.running = Some(RunningApply { cancel, visited, receiver });
+    }
+
+    pub fn cancel(&mut self) {
+        if self.running.take().is_some() {
+            tracing::info!("属性の変更を中止しました");
+        }
+    }
+
+    /// 変更の終了を確認する
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        match running.receiver.try_recv() {
+            Ok(result) => {
+                self.running = None;
+                match result {
+                    Ok(report) => {
+                        tracing::info!(
+                            "属性を変更しました: 変更 {} 件, 変更なし {} 件, 失敗 {} 件",
+                            report.changed,
+                            report.unchanged,
+                            report.failed.len()
+                        );
+                        self.report = Some(report);
+                    }
+                    Err(e) => {
+                        tracing::error!("{}", e);
+                        self.error = Some(e);
+                    }
+                }
+                self.reload();
+            }
+            Err(mpsc::TryRecvError::Disconnected) => self.running = None,
+            Err(mpsc::TryRecvError::Empty) => {
+                // 進み具合を表示し続ける
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+        }
+    }
+}
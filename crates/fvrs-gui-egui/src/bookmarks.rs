@@ -0,0 +1,120 @@
+use fvrs_core::bookmarks::{Bookmark, Bookmarks, MAX_SHORTCUT};
+use std::path::{Path, PathBuf};
+
+/// 選べるアイコン
+pub const ICONS: [&str; 12] = ["📁", "⭐", "🏠", "💼", "📄", "🖼", "🎵", "🎬", "💾", "🔧", "📦", "🌐"];
+
+/// サイドバーやファイル一覧からドラッグ中のフォルダー
+#[derive(Debug, Clone)]
+pub struct DraggedFolder(pub PathBuf);
+
+/// ブックマーク（設定フォルダーに保存し、CLI の `@名前` でも使う）
+///
+/// 変更するたびにすぐ保存するので、実行中の CLI にも反映される。
+pub struct BookmarkStore {
+    pub bookmarks: Bookmarks,
+}
+
+impl BookmarkStore {
+    /// 保存済みのブックマークを読み込む（なければ空）
+    pub fn load() -> Self {
+        let bookmarks = match Bookmarks::default_path() {
+            Some(path) => Bookmarks::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("ブックマークの読み込みに失敗: {}", e);
+                Bookmarks::default()
+            }),
+            None => Bookmarks::default(),
+        };
+        Self { bookmarks }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        if self.bookmarks.remove(name).is_some() {
+            self.save();
+        }
+    }
+
+    /// 同じグループの中で 1 つ上か下へ移す
+    pub fn move_within_group(&mut self, name: &str, up: bool) {
+        match self.bookmarks.move_within_group(name, up) {
+            Ok(()) => self.save(),
+            Err(e) => tracing::warn!("ブックマークを移動できません: {}", e),
+        }
+    }
+
+    /// 編集内容を反映して保存する
+    pub fn apply(&mut self, editor: &BookmarkEditor) -> Result<(), String> {
+        let bookmark = editor.to_bookmark();
+        if !bookmark.path.is_dir() {
+            return Err(format!("フォルダーが見つかりません: {}", bookmark.path.display()));
+        }
+        let result = match &editor.original {
+            Some(name) => self.bookmarks.update(name, bookmark),
+            None => self.bookmarks.add(bookmark),
+        };
+        result.map_err(|e| e.to_string())?;
+        self.save();
+        Ok(())
+    }
+
+    fn save(&self) {
+        let Some(path) = Bookmarks::default_path() else {
+            tracing::error!("設定フォルダーが見つからないため、ブックマークを保存できません");
+            return;
+        };
+        if let Err(e) = self.bookmarks.save(&path) {
+            tracing::error!("ブックマークの保存に失敗: {}", e);
+        }
+    }
+}
+
+/// ブックマークの追加・編集ダイアログの入力
+pub struct BookmarkEditor {
+    /// 編集中のブックマークの名前（追加なら None）
+    pub original: Option<String>,
+    pub name: String,
+    pub path: String,
+    pub icon: String,
+    pub group: String,
+    pub shortcut: Option<u8>,
+    pub error: Option<String>,
+}
+
+impl BookmarkEditor {
+    /// `path` を追加する（名前はフォルダー名、ショートカットは空いている番号）
+    pub fn new(bookmarks: &Bookmarks, path: &Path) -> Self {
+        let bookmark = Bookmark::for_path(path);
+        let shortcut = (1..=MAX_SHORTCUT).find(|&number| bookmarks.by_shortcut(number).is_none());
+        Self {
+            original: None,
+            name: bookmarks.unique_name(&bookmark.name),
+            path: path.to_string_lossy().into_owned(),
+            icon: bookmark.icon,
+            group: String::new(),
+            shortcut,
+            error: None,
+        }
+    }
+
+    pub fn edit(bookmark: &Bookmark) -> Self {
+        Self {
+            original: Some(bookmark.name.clone()),
+            name: bookmark.name.clone(),
+            path: bookmark.path.to_string_lossy().into_owned(),
+            icon: bookmark.icon.clone(),
+            group: bookmark.group.clone(),
+            shortcut: bookmark.shortcut,
+            error: None,
+        }
+    }
+
+    fn to_bookmark(&self) -> Bookmark {
+        Bookmark {
+            name: self.name.trim().to_string(),
+            path: PathBuf::from(self.path.trim()),
+            icon: self.icon.clone(),
+            group: self.group.trim().to_string(),
+            shortcut: self.shortcut,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use crate::journal::OperationJournal;
+use fvrs_core::core::FsResult;
+use fvrs_core::disk_usage::{self, DiskUsageCache, DiskUsageNode, DiskUsageSort, ScanOptions, ScanProgress};
+
+/// バックグラウンドで実行中のスキャン
+struct RunningScan {
+    root: PathBuf,
+    progress: Arc<Mutex<ScanProgress>>,
+    cancel: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<(FsResult<DiskUsageNode>, DiskUsageCache)>,
+}
+
+/// ディスク使用量分析の状態（スキャン結果とキャッシュ）
+#[derive(Default)]
+pub struct DiskUsageAnalyzer {
+    /// スキャン間で再利用するキャッシュ（スキャン中はワーカースレッドが保持）
+    cache: Option<DiskUsageCache>,
+    running: Option<RunningScan>,
+    /// 最新のスキャン結果
+    pub result: Option<DiskUsageNode>,
+    /// 結果ツリーに適用済みのソート条件
+    pub sorted_by: Option<(DiskUsageSort, bool)>,
+    /// ドリルダウン中のディレクトリ
+    pub focus: Option<PathBuf>,
+    /// ツリーで展開されているディレクトリ
+    pub expanded: HashSet<PathBuf>,
+    pub selected: Option<PathBuf>,
+    /// 削除確認中のパス
+    pub pending_delete: Option<PathBuf>,
+    pub error: Option<String>,
+}
+
+impl DiskUsageAnalyzer {
+    /// スキャンを開始（キャッシュ済みのディレクトリは差分のみ再計算）
+    pub fn start(&mut self, root: PathBuf, options: ScanOptions) {
+        self.cancel();
+
+        let progress = Arc::new(Mutex::new(ScanProgress::default()));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let mut cache = self.cache.take().unwrap_or_default();
+
+        let thread_root = root.clone();
+        let thread_progress = Arc::clone(&progress);
+        let thread_cancel = Arc::clone(&cancel);
+        std::thread::spawn(move || {
+            let result = disk_usage::scan(&thread_root, &mut cache, &options, &thread_cancel, |p| {
+                if let Ok(mut progress) = thread_progress.lock() {
+                    *progress = p.clone();
+                }
+            });
+            let _ = sender.send((result, cache));
+        });
+
+        tracing::info!("ディスク使用量のスキャンを開始: {:?}", root);
+        self.error = None;
+        self.running = Some(RunningScan { root, progress, cancel, receiver });
+    }
+
+    /// 実行中のスキャンを中止
+    pub fn cancel(&mut self) {
+        if let Some(running) = self.running.take() {
+            running.cancel.store(true, Ordering::Relaxed);
+            // キャッシュを取り戻す（中止はすぐに反映される）
+            if let Ok((_, cache)) = running.receiver.recv() {
+                self.cache = Some(cache);
+            }
+        }
+    }
+
+    /// スキャンの完了を確認（完了した場合 true）
+    pub fn poll(&mut self) -> bool {
+        let Some(running) = &self.running else {
+            return false;
+        };
+        let Ok((result, cache)) = running.receiver.try_recv() else {
+            return false;
+        };
+
+        let root = running.root.clone();
+        self.cache = Some(cache);
+        self.running = None;
+        match result {
+            Ok(tree) => {
+                // 前回のドリルダウン位置がまだ存在すれば維持
+                if self.focus.as_ref().is_none_or(|focus| tree.find(focus).is_none()) {
+                    self.focus = Some(root);
+                }
+                self.result = Some(tree);
+                self.sorted_by = None;
+            }
+            Err(e) => {
+                tracing::error!("ディスク使用量のスキャンに失敗: {}", e);
+                self.error = Some(format!("スキャンエラー: {}", e));
+            }
+        }
+        true
+    }
+
+    /// スキャン中かどうか
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// 実行中のスキャンの対象と進捗
+    pub fn progress(&self) -> Option<(PathBuf, ScanProgress)> {
+        self.running.as_ref().map(|running| {
+            let progress = running.progress.lock().map(|p| p.clone()).unwrap_or_default();
+            (running.root.clone(), progress)
+        })
+    }
+
+    /// 表示中のルート（ドリルダウン先）
+    pub fn focused_node(&self) -> Option<&DiskUsageNode> {
+        let result = self.result.as_ref()?;
+        match &self.focus {
+            Some(focus) => result.find(focus).or(Some(result)),
+            None => Some(result),
+        }
+    }
+
+    /// ファイルまたはディレクトリをゴミ箱へ移動し、結果ツリーとキャッシュを更新
+    ///
+    /// 戻り値はゴミ箱内のパス
+    pub fn delete(&mut self, path: &Path) -> Result<PathBuf, String> {
+        let trashed = OperationJournal::move_to_trash(path)?;
+        tracing::info!("ディスク使用量ビューからゴミ箱へ移動: {:?}", path);
+
+        if let Some(tree) = &mut self.result {
+            tree.remove(path);
+        }
+        if let Some(cache) = &mut self.cache {
+            cache.invalidate(path);
+            if let Some(parent) = path.parent() {
+                cache.invalidate(parent);
+            }
+        }
+        if self.selected.as_deref() == Some(path) {
+            self.selected = None;
+        }
+        if self.focus.as_ref().is_some_and(|focus| focus.starts_with(path)) {
+            self.focus = path.parent().map(Path::to_path_buf);
+        }
+        Ok(trashed)
+    }
+}
@@ -0,0 +1,278 @@
+use crate::journal::OperationJournal;
+use crate::state::FileOperation;
+use fvrs_core::copy::{self, CopyOptions};
+use fvrs_core::core::FsResult;
+use fvrs_core::duplicates::{self, DuplicateOptions, DuplicateProgress, DuplicateReport};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+
+/// 重複ファイル（残すもの以外）の処理方法
+#[derive(Debug, Clone, PartialEq)]
+pub enum DuplicateResolution {
+    /// ゴミ箱へ移動する
+    Delete,
+    /// 残すファイルへのハードリンクに置き換える
+    HardLink,
+    /// 指定フォルダーへ移動する
+    MoveTo(PathBuf),
+}
+
+impl DuplicateResolution {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DuplicateResolution::Delete => "ゴミ箱へ移動",
+            DuplicateResolution::HardLink => "ハードリンクに置き換え",
+            DuplicateResolution::MoveTo(_) => "移動",
+        }
+    }
+}
+
+/// バックグラウンドで実行中の検索
+struct RunningSearch {
+    progress: Arc<Mutex<DuplicateProgress>>,
+    cancel: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<FsResult<DuplicateReport>>,
+}
+
+/// 重複ファイル検索の状態
+#[derive(Default)]
+pub struct DuplicateFinder {
+    running: Option<RunningSearch>,
+    /// 検索対象のフォルダー
+    pub roots: Vec<PathBuf>,
+    /// 最新の検索結果
+    pub report: Option<DuplicateReport>,
+    /// グループごとに残すファイルのインデックス
+    pub keep: Vec<usize>,
+    /// 確認中の処理
+    pub pending: Option<DuplicateResolution>,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+impl DuplicateFinder {
+    /// 検索を開始
+    pub fn start(&mut self, options: DuplicateOptions) {
+        self.cancel();
+
+        let progress = Arc::new(Mutex::new(DuplicateProgress::default()));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let roots = self.roots.clone();
+        let thread_progress = Arc::clone(&progress);
+        let thread_cancel = Arc::clone(&cancel);
+        std::thread::spawn(move || {
+            let result = duplicates::find_duplicates(&roots, &options, &thread_cancel, |p| {
+                if let Ok(mut progress) = thread_progress.lock() {
+                    *progress = p.clone();
+                }
+            });
+            let _ = sender.send(result);
+        });
+
+        tracing::info!("重複ファイルの検索を開始: {:?}", self.roots);
+        self.error = None;
+        self.status = None;
+        self.running = Some(RunningSearch {
+            progress,
+            cancel,
+            receiver,
+        });
+    }
+
+    /// 実行中の検索を中止
+    pub fn cancel(&mut self) {
+        if let Some(running) = self.running.take() {
+            running.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// 検索の完了を確認（完了した場合 true）
+    pub fn poll(&mut self) -> bool {
+        let Some(running) = &self.running else {
+            return false;
+        };
+        let Ok(result) = running.receiver.try_recv() else {
+            return false;
+        };
+
+        self.running = None;
+        match result {
+            Ok(report) => {
+                // 既定では最も古いファイルを残す
+                self.keep = vec![0; report.groups.len()];
+                self.report = Some(report);
+            }
+            Err(e) => {
+                tracing::error!("重複ファイルの検索に失敗: {}", e);
+                self.error = Some(format!("検索エラー: {}", e));
+            }
+        }
+        true
+    }
+
+    /// 検索中かどうか
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// 実行中の検索の進捗
+    pub fn progress(&self) -> Option<DuplicateProgress> {
+        self.running.as_ref().map(|running| {
+            running
+                .progress
+                .lock()
+                .map(|p| p.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    /// 残すファイル以外を処理し、変更のあったフォルダーを返す
+    ///
+    /// 個々のファイルの失敗は `error` に記録し、処理を続ける
+    pub fn resolve(
+        &mut self,
+        resolution: &DuplicateResolution,
+    ) -> Result<Vec<FileOperation>, String> {
+        let Some(report) = &mut self.report else {
+            return Ok(Vec::new());
+        };
+        if let DuplicateResolution::MoveTo(destination) = resolution {
+            std::fs::create_dir_all(destination)
+                .map_err(|e| format!("移動先を作成できません: {}", e))?;
+        }
+
+        let mut operations = Vec::new();
+        let mut processed = 0usize;
+        let mut failures = Vec::new();
+        let mut kept = Vec::new();
+
+        for (group, &keep) in report.groups.iter_mut().zip(&self.keep) {
+            let keep = keep.min(group.files.len().saturating_sub(1));
+            let original = group.files[keep].path.clone();
+            let mut remaining = Vec::new();
+            let mut kept_index = 0;
+            let mut linked = Vec::new();
+
+            for (index, mut file) in group.files.drain(..).enumerate() {
+                if index == keep {
+                    kept_index = remaining.len();
+                    remaining.push(file);
+                    continue;
+                }
+                // ハードリンクで共有されているパスもまとめて処理しないと容量は解放されない
+                let paths: Vec<PathBuf> = std::iter::once(file.path.clone())
+                    .chain(file.hard_links.iter().cloned())
+                    .collect();
+                let mut left = Vec::new();
+                for path in paths {
+                    // ハードリンクへの置き換えは元に戻せないので履歴には残さない
+                    let result = match resolution {
+                        DuplicateResolution::Delete => {
+                            OperationJournal::move_to_trash(&path).map(|trashed| {
+                                Some(FileOperation::Delete {
+                                    path: path.clone(),
+                                    trashed,
+                                })
+                            })
+                        }
+                        DuplicateResolution::HardLink => {
+                            duplicates::replace_with_hard_link(&original, &path)
+                                .map(|()| None)
+                                .map_err(|e| e.to_string())
+                        }
+                        DuplicateResolution::MoveTo(destination) => move_into(&path, destination)
+                            .map(|to| {
+                                Some(FileOperation::Move {
+                                    from: path.clone(),
+                                    to,
+                                })
+                            }),
+                    };
+                    match result {
+                        Ok(operation) => {
+                            processed += 1;
+                            match operation {
+                                Some(operation) => operations.push(operation),
+                                None => linked.push(path),
+                            }
+                        }
+                        Err(e) => {
+                            failures.push(format!("{}: {}", path.display(), e));
+                            left.push(path);
+                        }
+                    }
+                }
+                // 処理できなかったパスは重複として残す
+                if !left.is_empty() {
+                    file.path = left.remove(0);
+                    file.hard_links = left;
+                    remaining.push(file);
+                }
+            }
+
+            remaining[kept_index].hard_links.extend(linked);
+            group.files = remaining;
+            kept.push(kept_index);
+        }
+
+        // 重複がなくなったグループを取り除く
+        let mut kept = kept.into_iter();
+        self.keep.clear();
+        report.groups.retain(|group| {
+            let kept_index = kept.next().unwrap_or(0);
+            let has_duplicates = group.files.len() > 1;
+            if has_duplicates {
+                self.keep.push(kept_index);
+            }
+            has_duplicates
+        });
+        report.wasted_space = report.groups.iter().map(|g| g.wasted_space()).sum();
+
+        tracing::info!("重複ファイルを{}: {} 件", resolution.label(), processed);
+        self.status = Some(format!(
+            "{}: {} 件のファイルを処理しました",
+            resolution.label(),
+            processed
+        ));
+        if !failures.is_empty() {
+            tracing::warn!("重複ファイルの処理に失敗: {:?}", failures);
+            self.error = Some(format!(
+                "{} 件のファイルを処理できませんでした: {}",
+                failures.len(),
+                failures.join(", ")
+            ));
+        }
+        Ok(operations)
+    }
+}
+
+fn path_exists(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok()
+}
+
+/// ファイルをフォルダーへ移動し、移動先を返す（同名ファイルがあれば番号を付ける）
+fn move_into(path: &Path, destination: &Path) -> Result<PathBuf, String> {
+    let file_name = path.file_name().ok_or_else(|| "無効なパス".to_string())?;
+    let mut target = destination.join(file_name);
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut counter = 1;
+    while path_exists(&target) {
+        target = destination.join(format!("{} ({}){}", stem, counter, extension));
+        counter += 1;
+    }
+
+    // 別のファイルシステムへはコピーしてから削除
+    copy::move_path(path, &target, &CopyOptions::default(), &mut |_| true)
+        .map_err(|e| format!("移動エラー: {}", e))?;
+    Ok(target)
+}
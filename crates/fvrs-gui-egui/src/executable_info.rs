@@ -0,0 +1,739 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+/// 実行可能ファイルの形式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExecutableFormat {
+    /// Windows PE (exe / dll / sys)
+    Pe,
+    /// ELF (Linux / BSD)
+    Elf,
+    /// Mach-O (macOS)
+    MachO,
+    /// Mach-O ユニバーサルバイナリ
+    MachOUniversal,
+}
+
+impl ExecutableFormat {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ExecutableFormat::Pe => "PE (Windows)",
+            ExecutableFormat::Elf => "ELF",
+            ExecutableFormat::MachO => "Mach-O",
+            ExecutableFormat::MachOUniversal => "Mach-O ユニバーサル",
+        }
+    }
+}
+
+/// 実行可能ファイルの解析結果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutableInfo {
+    pub format: Option<ExecutableFormat>,
+    pub architecture: String,
+    pub bits: u8,
+    pub endianness: String,
+    pub kind: String,
+
+    // PE バージョンリソース
+    pub file_version: Option<String>,
+    pub product_version: Option<String>,
+    pub product_name: Option<String>,
+    pub company: Option<String>,
+    pub description: Option<String>,
+    pub copyright: Option<String>,
+    pub original_filename: Option<String>,
+    pub subsystem: Option<String>,
+    pub is_dotnet: bool,
+
+    // 署名（有無のみ、検証はしない）
+    pub signed: bool,
+
+    // ELF / Mach-O
+    pub interpreter: Option<String>,
+    pub soname: Option<String>,
+    pub dependencies: Vec<String>,
+    pub build_id: Option<String>,
+    pub uuid: Option<String>,
+    pub universal_architectures: Vec<String>,
+}
+
+/// 解析時に読み込むセクションの上限（壊れたヘッダーで巨大な確保をしないため）
+const MAX_SECTION_READ: u64 = 64 * 1024 * 1024;
+
+/// ファイルから任意位置のバイト列を読み出す補助
+struct BinaryReader {
+    file: File,
+    len: u64,
+}
+
+impl BinaryReader {
+    fn open(path: &Path) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        let len = file.metadata().ok()?.len();
+        Some(Self { file, len })
+    }
+
+    fn bytes(&mut self, offset: u64, size: u64) -> Option<Vec<u8>> {
+        if size > MAX_SECTION_READ || offset.checked_add(size)? > self.len {
+            return None;
+        }
+        self.file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut buffer = vec![0u8; size as usize];
+        self.file.read_exact(&mut buffer).ok()?;
+        Some(buffer)
+    }
+}
+
+/// エンディアンを考慮した整数読み出し
+#[derive(Clone, Copy)]
+struct Endian {
+    little: bool,
+}
+
+impl Endian {
+    fn u16(&self, data: &[u8], offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.little { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn u32(&self, data: &[u8], offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn u64(&self, data: &[u8], offset: usize) -> Option<u64> {
+        let bytes: [u8; 8] = data.get(offset..offset + 8)?.try_into().ok()?;
+        Some(if self.little { u64::from_le_bytes(bytes) } else { u64::from_be_bytes(bytes) })
+    }
+}
+
+const LE: Endian = Endian { little: true };
+const BE: Endian = Endian { little: false };
+
+/// NUL 終端の文字列を取り出す
+fn c_string(data: &[u8], offset: usize) -> Option<String> {
+    let tail = data.get(offset..)?;
+    let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+    Some(String::from_utf8_lossy(&tail[..end]).to_string())
+}
+
+fn align4(value: usize) -> usize {
+    (value + 3) & !3
+}
+
+/// 実行可能ファイル解析ユーティリティ
+pub struct ExecutableInspector;
+
+impl ExecutableInspector {
+    /// マジックナンバーから形式を判定して解析する
+    pub fn inspect(path: &Path) -> Option<ExecutableInfo> {
+        if !path.is_file() {
+            return None;
+        }
+
+        let mut reader = BinaryReader::open(path)?;
+        let magic = reader.bytes(0, 8.min(reader.len))?;
+        if magic.len() < 4 {
+            return None;
+        }
+
+        match magic.as_slice() {
+            [b'M', b'Z', ..] => Self::inspect_pe(&mut reader),
+            [0x7f, b'E', b'L', b'F', ..] => Self::inspect_elf(&mut reader),
+            [0xca, 0xfe, 0xba, 0xbe, ..] => Self::inspect_macho_universal(&mut reader),
+            [0xfe, 0xed, 0xfa, 0xce | 0xcf, ..] | [0xce | 0xcf, 0xfa, 0xed, 0xfe, ..] => {
+                Self::inspect_macho(&mut reader, 0)
+            }
+            _ => None,
+        }
+    }
+
+    // ===== PE =====
+
+    fn inspect_pe(reader: &mut BinaryReader) -> Option<ExecutableInfo> {
+        let dos_header = reader.bytes(0, 64)?;
+        let pe_offset = LE.u32(&dos_header, 0x3c)? as u64;
+
+        let headers = reader.bytes(pe_offset, 24)?;
+        if &headers[0..4] != b"PE\0\0" {
+            return None;
+        }
+
+        let machine = LE.u16(&headers, 4)?;
+        let section_count = LE.u16(&headers, 6)? as u64;
+        let optional_size = LE.u16(&headers, 20)? as u64;
+        let characteristics = LE.u16(&headers, 22)?;
+
+        let optional = reader.bytes(pe_offset + 24, optional_size)?;
+        let optional_magic = LE.u16(&optional, 0)?;
+        let (bits, dir_count_offset, dirs_offset) = match optional_magic {
+            0x10b => (32, 92, 96),
+            0x20b => (64, 108, 112),
+            _ => return None,
+        };
+
+        let subsystem = LE.u16(&optional, 68).map(|s| match s {
+            1 => "ネイティブ".to_string(),
+            2 => "Windows GUI".to_string(),
+            3 => "Windows コンソール".to_string(),
+            9 => "Windows CE".to_string(),
+            10 => "EFI アプリケーション".to_string(),
+            11 => "EFI ブートサービスドライバ".to_string(),
+            12 => "EFI ランタイムドライバ".to_string(),
+            16 => "Windows ブートアプリケーション".to_string(),
+            other => format!("不明 ({})", other),
+        });
+
+        let dir_count = LE.u32(&optional, dir_count_offset).unwrap_or(0) as usize;
+        let data_directory = |index: usize| -> Option<(u32, u32)> {
+            if index >= dir_count {
+                return None;
+            }
+            let base = dirs_offset + index * 8;
+            Some((LE.u32(&optional, base)?, LE.u32(&optional, base + 4)?))
+        };
+
+        // セクションテーブル（RVA → ファイルオフセット変換用）
+        let section_table = reader.bytes(pe_offset + 24 + optional_size, section_count * 40)?;
+        let sections: Vec<(u32, u32, u32, u32)> = section_table
+            .chunks_exact(40)
+            .filter_map(|s| Some((LE.u32(s, 12)?, LE.u32(s, 8)?, LE.u32(s, 20)?, LE.u32(s, 16)?)))
+            .collect();
+        let rva_to_offset = |rva: u32| -> Option<u64> {
+            sections.iter().find_map(|&(va, vsize, raw_ptr, raw_size)| {
+                let span = vsize.max(raw_size);
+                if rva < va || rva >= va.checked_add(span)? {
+                    return None;
+                }
+                raw_ptr.checked_add(rva - va).map(u64::from)
+            })
+        };
+
+        let mut info = ExecutableInfo {
+            format: Some(ExecutableFormat::Pe),
+            architecture: Self::pe_machine_name(machine),
+            bits,
+            endianness: "リトルエンディアン".to_string(),
+            kind: if characteristics & 0x2000 != 0 {
+                "ダイナミックリンクライブラリ (DLL)".to_string()
+            } else if characteristics & 0x0002 != 0 {
+                "実行ファイル".to_string()
+            } else {
+                "オブジェクト".to_string()
+            },
+            subsystem,
+            ..Default::default()
+        };
+
+        // セキュリティディレクトリ（Authenticode 署名）。VirtualAddress はファイルオフセット
+        info.signed = data_directory(4).map(|(offset, size)| offset != 0 && size != 0).unwrap_or(false);
+        // CLR ランタイムヘッダー
+        info.is_dotnet = data_directory(14).map(|(rva, size)| rva != 0 && size != 0).unwrap_or(false);
+
+        // バージョンリソース
+        if let Some((resource_rva, resource_size)) = data_directory(2)
+            && resource_rva != 0
+            && resource_size != 0
+            && let Some(resource_offset) = rva_to_offset(resource_rva)
+            && let Some(resources) = reader.bytes(resource_offset, resource_size as u64)
+            && let Some((data_rva, data_size)) = Self::find_version_resource(&resources)
+            && let Some(version_data) = rva_to_offset(data_rva)
+                .and_then(|offset| reader.bytes(offset, data_size as u64))
+        {
+            Self::parse_version_info(&version_data, &mut info);
+        }
+
+        Some(info)
+    }
+
+    fn pe_machine_name(machine: u16) -> String {
+        match machine {
+            0x014c => "x86 (i386)".to_string(),
+            0x8664 => "x86-64 (AMD64)".to_string(),
+            0xaa64 => "ARM64".to_string(),
+            0x01c0 | 0x01c4 => "ARM".to_string(),
+            0x0200 => "IA-64".to_string(),
+            0x5064 => "RISC-V 64".to_string(),
+            0x0ebc => "EFI バイトコード".to_string(),
+            other => format!("不明 (0x{:04x})", other),
+        }
+    }
+
+    /// リソースディレクトリから RT_VERSION (16) のデータエントリを探す
+    fn find_version_resource(resources: &[u8]) -> Option<(u32, u32)> {
+        const RT_VERSION: u32 = 16;
+
+        let entries = |dir_offset: usize| -> Vec<(u32, u32)> {
+            let named = LE.u16(resources, dir_offset + 12).unwrap_or(0) as usize;
+            let ids = LE.u16(resources, dir_offset + 14).unwrap_or(0) as usize;
+            (0..named + ids)
+                .filter_map(|i| {
+                    let base = dir_offset + 16 + i * 8;
+                    Some((LE.u32(resources, base)?, LE.u32(resources, base + 4)?))
+                })
+                .collect()
+        };
+
+        // 第1階層: 種類
+        let (_, type_target) = entries(0).into_iter().find(|&(id, _)| id == RT_VERSION)?;
+        if type_target & 0x8000_0000 == 0 {
+            return None;
+        }
+
+        // 第2階層: 名前 → 第3階層: 言語（いずれも最初のエントリを使用）
+        let mut target = type_target;
+        for _ in 0..2 {
+            if target & 0x8000_0000 == 0 {
+                break;
+            }
+            let (_, next) = *entries((target & 0x7fff_ffff) as usize).first()?;
+            target = next;
+        }
+        if target & 0x8000_0000 != 0 {
+            return None;
+        }
+
+        let data_entry = target as usize;
+        Some((LE.u32(resources, data_entry)?, LE.u32(resources, data_entry + 4)?))
+    }
+
+    /// VS_VERSIONINFO ブロックを解析
+    fn parse_version_info(data: &[u8], info: &mut ExecutableInfo) {
+        let Some(root) = VersionBlock::parse(data, 0) else {
+            return;
+        };
+        if root.key != "VS_VERSION_INFO" {
+            return;
+        }
+
+        // VS_FIXEDFILEINFO
+        let fixed = data.get(root.value_start..root.value_end.min(data.len())).unwrap_or_default();
+        if LE.u32(fixed, 0) == Some(0xfeef_04bd) {
+            let version = |ms: Option<u32>, ls: Option<u32>| -> Option<String> {
+                let (ms, ls) = (ms?, ls?);
+                Some(format!("{}.{}.{}.{}", ms >> 16, ms & 0xffff, ls >> 16, ls & 0xffff))
+            };
+            info.file_version = version(LE.u32(fixed, 8), LE.u32(fixed, 12));
+            info.product_version = version(LE.u32(fixed, 16), LE.u32(fixed, 20));
+        }
+
+        // StringFileInfo → StringTable → String
+        for child in root.children(data) {
+            if child.key != "StringFileInfo" {
+                continue;
+            }
+            // 最初の言語テーブルのみ使用
+            if let Some(table) = child.children(data).into_iter().next() {
+                for entry in table.children(data) {
+                    let value = entry.text_value(data);
+                    if value.is_empty() {
+                        continue;
+                    }
+                    match entry.key.as_str() {
+                        "FileVersion" => info.file_version = Some(value),
+                        "ProductVersion" => info.product_version = Some(value),
+                        "ProductName" => info.product_name = Some(value),
+                        "CompanyName" => info.company = Some(value),
+                        "FileDescription" => info.description = Some(value),
+                        "LegalCopyright" => info.copyright = Some(value),
+                        "OriginalFilename" => info.original_filename = Some(value),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    // ===== ELF =====
+
+    fn inspect_elf(reader: &mut BinaryReader) -> Option<ExecutableInfo> {
+        let ident = reader.bytes(0, 64.min(reader.len))?;
+        let is_64 = match ident.get(4)? {
+            1 => false,
+            2 => true,
+            _ => return None,
+        };
+        let endian = match ident.get(5)? {
+            1 => LE,
+            2 => BE,
+            _ => return None,
+        };
+
+        let e_type = endian.u16(&ident, 16)?;
+        let e_machine = endian.u16(&ident, 18)?;
+        let (ph_offset, ph_entry_size, ph_count) = if is_64 {
+            (endian.u64(&ident, 32)?, endian.u16(&ident, 54)? as u64, endian.u16(&ident, 56)? as u64)
+        } else {
+            (endian.u32(&ident, 28)? as u64, endian.u16(&ident, 42)? as u64, endian.u16(&ident, 44)? as u64)
+        };
+
+        let mut info = ExecutableInfo {
+            format: Some(ExecutableFormat::Elf),
+            architecture: Self::elf_machine_name(e_machine),
+            bits: if is_64 { 64 } else { 32 },
+            endianness: if endian.little { "リトルエンディアン" } else { "ビッグエンディアン" }.to_string(),
+            ..Default::default()
+        };
+
+        // プログラムヘッダー: (type, offset, vaddr, filesz)
+        let mut segments = Vec::new();
+        if let Some(table) = reader.bytes(ph_offset, ph_entry_size * ph_count) {
+            for header in table.chunks_exact(ph_entry_size.max(1) as usize) {
+                let segment = if is_64 {
+                    (endian.u32(header, 0), endian.u64(header, 8), endian.u64(header, 16), endian.u64(header, 32))
+                } else {
+                    (
+                        endian.u32(header, 0),
+                        endian.u32(header, 4).map(u64::from),
+                        endian.u32(header, 8).map(u64::from),
+                        endian.u32(header, 16).map(u64::from),
+                    )
+                };
+                if let (Some(kind), Some(offset), Some(vaddr), Some(size)) = segment {
+                    segments.push((kind, offset, vaddr, size));
+                }
+            }
+        }
+
+        const PT_LOAD: u32 = 1;
+        const PT_DYNAMIC: u32 = 2;
+        const PT_INTERP: u32 = 3;
+        const PT_NOTE: u32 = 4;
+
+        for &(kind, offset, _, size) in &segments {
+            match kind {
+                PT_INTERP => {
+                    info.interpreter = reader.bytes(offset, size).and_then(|data| c_string(&data, 0));
+                }
+                PT_NOTE => {
+                    if let Some(notes) = reader.bytes(offset, size)
+                        && let Some(build_id) = Self::find_gnu_build_id(&notes, endian)
+                    {
+                        info.build_id = Some(build_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // 動的セクション（DT_NEEDED / DT_SONAME）
+        let vaddr_to_offset = |address: u64| -> Option<u64> {
+            segments.iter().find_map(|&(kind, offset, vaddr, size)| {
+                if kind != PT_LOAD || address < vaddr || address >= vaddr.checked_add(size)? {
+                    return None;
+                }
+                offset.checked_add(address - vaddr)
+            })
+        };
+        if let Some(&(_, offset, _, size)) = segments.iter().find(|s| s.0 == PT_DYNAMIC)
+            && let Some(dynamic) = reader.bytes(offset, size)
+        {
+            let entry_size = if is_64 { 16 } else { 8 };
+            let mut needed = Vec::new();
+            let mut soname = None;
+            let mut strtab = None;
+            let mut strsz = None;
+            for entry in dynamic.chunks_exact(entry_size) {
+                let (tag, value) = if is_64 {
+                    (endian.u64(entry, 0).unwrap_or(0), endian.u64(entry, 8).unwrap_or(0))
+                } else {
+                    (endian.u32(entry, 0).unwrap_or(0) as u64, endian.u32(entry, 4).unwrap_or(0) as u64)
+                };
+                match tag {
+                    0 => break,
+                    1 => needed.push(value),
+                    5 => strtab = Some(value),
+                    10 => strsz = Some(value),
+                    14 => soname = Some(value),
+                    _ => {}
+                }
+            }
+
+            if let (Some(strtab_offset), Some(strtab_size)) = (strtab.and_then(vaddr_to_offset), strsz)
+                && let Some(strings) = reader.bytes(strtab_offset, strtab_size)
+            {
+                info.dependencies = needed
+                    .iter()
+                    .filter_map(|&index| c_string(&strings, index as usize))
+                    .collect();
+                info.soname = soname.and_then(|index| c_string(&strings, index as usize));
+            }
+        }
+
+        info.kind = match e_type {
+            1 => "再配置可能オブジェクト".to_string(),
+            2 => "実行ファイル".to_string(),
+            3 if info.interpreter.is_some() => "実行ファイル (PIE)".to_string(),
+            3 => "共有ライブラリ".to_string(),
+            4 => "コアダンプ".to_string(),
+            other => format!("不明 ({})", other),
+        };
+        if e_type == 2 && !segments.iter().any(|s| s.0 == PT_DYNAMIC) {
+            info.kind.push_str("（静的リンク）");
+        }
+
+        Some(info)
+    }
+
+    fn find_gnu_build_id(notes: &[u8], endian: Endian) -> Option<String> {
+        const NT_GNU_BUILD_ID: u32 = 3;
+
+        let mut offset = 0;
+        while offset + 12 <= notes.len() {
+            let name_size = endian.u32(notes, offset)? as usize;
+            let desc_size = endian.u32(notes, offset + 4)? as usize;
+            let note_type = endian.u32(notes, offset + 8)?;
+            let name_start = offset + 12;
+            let desc_start = name_start + align4(name_size);
+            let next = desc_start + align4(desc_size);
+
+            if note_type == NT_GNU_BUILD_ID && notes.get(name_start..name_start + name_size) == Some(b"GNU\0") {
+                let desc = notes.get(desc_start..desc_start + desc_size)?;
+                return Some(desc.iter().map(|b| format!("{:02x}", b)).collect());
+            }
+            offset = next;
+        }
+        None
+    }
+
+    fn elf_machine_name(machine: u16) -> String {
+        match machine {
+            2 => "SPARC".to_string(),
+            3 => "x86 (i386)".to_string(),
+            8 => "MIPS".to_string(),
+            20 => "PowerPC".to_string(),
+            21 => "PowerPC64".to_string(),
+            22 => "IBM S/390".to_string(),
+            40 => "ARM".to_string(),
+            43 => "SPARC V9".to_string(),
+            50 => "IA-64".to_string(),
+            62 => "x86-64".to_string(),
+            183 => "AArch64".to_string(),
+            243 => "RISC-V".to_string(),
+            247 => "BPF".to_string(),
+            258 => "LoongArch".to_string(),
+            other => format!("不明 ({})", other),
+        }
+    }
+
+    // ===== Mach-O =====
+
+    fn inspect_macho_universal(reader: &mut BinaryReader) -> Option<ExecutableInfo> {
+        let header = reader.bytes(0, 8)?;
+        let arch_count = BE.u32(&header, 4)? as u64;
+        // Java クラスファイルも同じマジック（CAFEBABE）を持つため、アーキテクチャ数で区別する
+        if arch_count == 0 || arch_count > 30 {
+            return None;
+        }
+
+        let table = reader.bytes(8, arch_count * 20)?;
+        let slices: Vec<(u32, u64)> = table
+            .chunks_exact(20)
+            .filter_map(|arch| Some((BE.u32(arch, 0)?, BE.u32(arch, 8)? as u64)))
+            .collect();
+
+        let (_, first_offset) = *slices.first()?;
+        let mut info = Self::inspect_macho(reader, first_offset)?;
+        info.format = Some(ExecutableFormat::MachOUniversal);
+        info.universal_architectures = slices
+            .iter()
+            .map(|&(cpu, _)| Self::macho_cpu_name(cpu))
+            .collect();
+        info.architecture = info.universal_architectures.join(", ");
+        Some(info)
+    }
+
+    fn inspect_macho(reader: &mut BinaryReader, base: u64) -> Option<ExecutableInfo> {
+        let magic_bytes = reader.bytes(base, 4)?;
+        let (endian, is_64) = match magic_bytes.as_slice() {
+            [0xfe, 0xed, 0xfa, 0xce] => (BE, false),
+            [0xfe, 0xed, 0xfa, 0xcf] => (BE, true),
+            [0xce, 0xfa, 0xed, 0xfe] => (LE, false),
+            [0xcf, 0xfa, 0xed, 0xfe] => (LE, true),
+            _ => return None,
+        };
+
+        let header_size = if is_64 { 32 } else { 28 };
+        let header = reader.bytes(base, header_size)?;
+        let cpu_type = endian.u32(&header, 4)?;
+        let file_type = endian.u32(&header, 12)?;
+        let command_count = endian.u32(&header, 16)?;
+        let commands_size = endian.u32(&header, 20)? as u64;
+
+        let mut info = ExecutableInfo {
+            format: Some(ExecutableFormat::MachO),
+            architecture: Self::macho_cpu_name(cpu_type),
+            bits: if is_64 { 64 } else { 32 },
+            endianness: if endian.little { "リトルエンディアン" } else { "ビッグエンディアン" }.to_string(),
+            kind: match file_type {
+                1 => "オブジェクト".to_string(),
+                2 => "実行ファイル".to_string(),
+                6 => "ダイナミックライブラリ (dylib)".to_string(),
+                7 => "ダイナミックリンカ".to_string(),
+                8 => "バンドル".to_string(),
+                10 => "デバッグシンボル (dSYM)".to_string(),
+                11 => "カーネル拡張".to_string(),
+                other => format!("不明 ({})", other),
+            },
+            ..Default::default()
+        };
+
+        const LC_LOAD_DYLIB: u32 = 0x0c;
+        const LC_ID_DYLIB: u32 = 0x0d;
+        const LC_LOAD_DYLINKER: u32 = 0x0e;
+        const LC_UUID: u32 = 0x1b;
+        const LC_CODE_SIGNATURE: u32 = 0x1d;
+        const LC_LOAD_WEAK_DYLIB: u32 = 0x8000_0018;
+        const LC_REEXPORT_DYLIB: u32 = 0x8000_001f;
+
+        let commands = reader.bytes(base + header_size, commands_size)?;
+        let mut offset = 0usize;
+        for _ in 0..command_count {
+            let Some(command) = endian.u32(&commands, offset) else { break };
+            let Some(size) = endian.u32(&commands, offset + 4).map(|s| s as usize) else { break };
+            if size < 8 || offset + size > commands.len() {
+                break;
+            }
+            let body = &commands[offset..offset + size];
+            let lc_string = |field: usize| -> Option<String> {
+                let string_offset = endian.u32(body, field)? as usize;
+                c_string(body, string_offset)
+            };
+
+            match command {
+                LC_LOAD_DYLIB | LC_LOAD_WEAK_DYLIB | LC_REEXPORT_DYLIB => {
+                    if let Some(name) = lc_string(8) {
+                        info.dependencies.push(name);
+                    }
+                }
+                LC_ID_DYLIB => info.soname = lc_string(8),
+                LC_LOAD_DYLINKER => info.interpreter = lc_string(8),
+                LC_UUID => {
+                    if let Some(uuid) = body.get(8..24) {
+                        let hex: Vec<String> = uuid.iter().map(|b| format!("{:02X}", b)).collect();
+                        info.uuid = Some(format!(
+                            "{}-{}-{}-{}-{}",
+                            hex[0..4].concat(),
+                            hex[4..6].concat(),
+                            hex[6..8].concat(),
+                            hex[8..10].concat(),
+                            hex[10..16].concat()
+                        ));
+                    }
+                }
+                LC_CODE_SIGNATURE => info.signed = true,
+                _ => {}
+            }
+            offset += size;
+        }
+
+        Some(info)
+    }
+
+    fn macho_cpu_name(cpu_type: u32) -> String {
+        match cpu_type {
+            7 => "x86 (i386)".to_string(),
+            0x0100_0007 => "x86-64".to_string(),
+            12 => "ARM".to_string(),
+            0x0100_000c => "ARM64".to_string(),
+            0x0200_000c => "ARM64_32".to_string(),
+            18 => "PowerPC".to_string(),
+            0x0100_0012 => "PowerPC64".to_string(),
+            other => format!("不明 (0x{:x})", other),
+        }
+    }
+}
+
+/// VS_VERSIONINFO 系の可変長ブロック
+struct VersionBlock {
+    key: String,
+    value_type: u16,
+    value_start: usize,
+    value_end: usize,
+    children_start: usize,
+    end: usize,
+}
+
+impl VersionBlock {
+    fn parse(data: &[u8], offset: usize) -> Option<Self> {
+        let length = LE.u16(data, offset)? as usize;
+        let value_length = LE.u16(data, offset + 2)? as usize;
+        let value_type = LE.u16(data, offset + 4)?;
+        if length < 6 {
+            return None;
+        }
+        let end = (offset + length).min(data.len());
+
+        // UTF-16 のキー（NUL 終端）
+        let mut key_units = Vec::new();
+        let mut position = offset + 6;
+        while position + 2 <= end {
+            let unit = LE.u16(data, position)?;
+            position += 2;
+            if unit == 0 {
+                break;
+            }
+            key_units.push(unit);
+        }
+        let key = String::from_utf16_lossy(&key_units);
+
+        let value_start = align4(position);
+        // テキスト値の長さは WORD 単位
+        let value_bytes = if value_type == 1 { value_length * 2 } else { value_length };
+        let value_end = (value_start + value_bytes).min(end);
+        let children_start = align4(value_end);
+
+        Some(Self { key, value_type, value_start, value_end, children_start, end })
+    }
+
+    fn children(&self, data: &[u8]) -> Vec<VersionBlock> {
+        let mut children = Vec::new();
+        let mut offset = self.children_start;
+        while offset + 6 <= self.end {
+            let Some(child) = VersionBlock::parse(data, offset) else { break };
+            let next = align4(child.end);
+            children.push(child);
+            if next <= offset {
+                break;
+            }
+            offset = next;
+        }
+        children
+    }
+
+    fn text_value(&self, data: &[u8]) -> String {
+        if self.value_type != 1 {
+            return String::new();
+        }
+        // wValueLength が不正確なファイルがあるため、ブロック終端まで NUL を探す
+        let units: Vec<u16> = data[self.value_start.min(self.end)..self.end]
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect();
+        String::from_utf16_lossy(&units).trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_version_info_is_ignored() {
+        // wLength=39 のため値の開始位置（40）がブロック終端を越える
+        let mut data = vec![39, 0, 52, 0, 0, 0];
+        for unit in "VS_VERSION_INFO".encode_utf16().chain([0]) {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+        data.push(0);
+        assert_eq!(data.len(), 39);
+
+        let mut info = ExecutableInfo::default();
+        ExecutableInspector::parse_version_info(&data, &mut info);
+        assert_eq!(info.file_version, None);
+        assert_eq!(info.product_version, None);
+    }
+}
@@ -0,0 +1,271 @@
+use fvrs_core::core::{FsEvent, SearchOptions};
+use fvrs_core::index::{FileIndex, IndexSettings, IndexWatcher, IndexedEntry};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+/// 変更があったときに保存する間隔
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 実行中のインデックス作成（破棄すると中止される）
+struct RunningBuild {
+    cancel: Arc<AtomicBool>,
+    /// これまでに登録した項目の数（作成スレッドが更新する）
+    progress: Arc<AtomicU64>,
+    receiver: mpsc::Receiver<Result<FileIndex, String>>,
+    started: Instant,
+}
+
+impl Drop for RunningBuild {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// ファイル名のインデックス（CLI の `locate` と共有する）
+///
+/// 起動中は変更を監視してインデックスに反映し、ときどき保存する。
+/// 閉じている間の変更は拾えないので、起動時にバックグラウンドで作り直す
+/// （できるまでは保存済みのインデックスで検索する）。
+pub struct IndexService {
+    pub settings: IndexSettings,
+    /// 除外する名前の入力欄（「;」区切り）
+    pub exclude_input: String,
+    /// 保存スレッドと共有するので、変更するときは複製される
+    index: Arc<FileIndex>,
+    watcher: Option<IndexWatcher>,
+    building: Option<RunningBuild>,
+    /// 作成中に届いた変更（作成後に反映する）
+    queued: Vec<FsEvent>,
+    /// 前回の保存から変わった
+    modified: bool,
+    last_saved: Instant,
+    saving: Arc<AtomicBool>,
+    pub error: Option<String>,
+    ctx: egui::Context,
+}
+
+impl IndexService {
+    /// 保存済みの設定とインデックスを読み込み、監視と作り直しを始める
+    pub fn load(ctx: &egui::Context) -> Self {
+        let settings = match IndexSettings::default_path() {
+            Some(path) => IndexSettings::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("インデックスの設定の読み込みに失敗: {}", e);
+                IndexSettings::default()
+            }),
+            None => IndexSettings::default(),
+        };
+        let index = match FileIndex::default_path() {
+            Some(path) => FileIndex::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("インデックスの読み込みに失敗: {}", e);
+                FileIndex::default()
+            }),
+            None => FileIndex::default(),
+        };
+        let mut service = Self {
+            exclude_input: settings.exclude.join("; "),
+            settings,
+            index: Arc::new(index),
+            watcher: None,
+            building: None,
+            queued: Vec::new(),
+            modified: false,
+            last_saved: Instant::now(),
+            saving: Arc::new(AtomicBool::new(false)),
+            error: None,
+            ctx: ctx.clone(),
+        };
+        if !service.settings.roots.is_empty() {
+            service.start_watching();
+            service.start_rebuild();
+        }
+        service
+    }
+
+    pub fn index(&self) -> &FileIndex {
+        &self.index
+    }
+
+    /// `path` がインデックスに入っている（検索に使える）
+    pub fn covers(&self, path: &Path) -> bool {
+        self.index.contains(path)
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    pub fn search(&self, root: &Path, options: &SearchOptions) -> Result<Vec<IndexedEntry>, String> {
+        self.index.search(Some(root), options).map_err(|e| e.to_string())
+    }
+
+    pub fn add_root(&mut self, path: &Path) -> Result<(), String> {
+        self.settings.add_root(path).map_err(|e| e.to_string())?;
+        self.apply_settings();
+        Ok(())
+    }
+
+    pub fn remove_root(&mut self, path: &Path) {
+        if self.settings.remove_root(path) {
+            self.apply_settings();
+        }
+    }
+
+    /// 除外する名前の入力欄を設定に反映する
+    pub fn apply_exclude(&mut self) {
+        self.settings.exclude = self
+            .exclude_input
+            .split(';')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(String::from)
+            .collect();
+        self.apply_settings();
+    }
+
+    /// 設定を保存し、監視とインデックスを作り直す
+    fn apply_settings(&mut self) {
+        if let Some(path) = IndexSettings::default_path()
+            && let Err(e) = self.settings.save(&path)
+        {
+            tracing::error!("インデックスの設定の保存に失敗: {}", e);
+        }
+        self.exclude_input = self.settings.exclude.join("; ");
+        self.start_watching();
+        self.start_rebuild();
+    }
+
+    fn start_watching(&mut self) {
+        self.watcher = None;
+        if self.settings.roots.is_empty() {
+            return;
+        }
+        let ctx = self.ctx.clone();
+        match IndexWatcher::new(&self.settings.roots, move || ctx.request_repaint()) {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => {
+                tracing::warn!("インデックスの監視を開始できません: {}", e);
+                self.error = Some(format!("変更を監視できません（作り直すまで検索結果が古くなります）: {}", e));
+            }
+        }
+    }
+
+    /// インデックスを最初から作り直す（実行中の作成は中止する）
+    pub fn start_rebuild(&mut self) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let progress = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = mpsc::channel();
+        let settings = self.settings.clone();
+        let thread_cancel = Arc::clone(&cancel);
+        let thread_progress = Arc::clone(&progress);
+        let ctx = self.ctx.clone();
+        std::thread::spawn(move || {
+            let result = FileIndex::build(&settings, &thread_cancel, |count| thread_progress.store(count, Ordering::Relaxed));
+            if thread_cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let _ = sender.send(result.map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+
+        tracing::info!("インデックスの作成を開始: {:?}", self.settings.roots);
+        self.queued.clear();
+        self.building = Some(RunningBuild { cancel, progress, receiver, started: Instant::now() });
+    }
+
+    pub fn cancel_rebuild(&mut self) {
+        if self.building.take().is_some() {
+            tracing::info!("インデックスの作成を中止しました");
+        }
+    }
+
+    /// 作成中のインデックスに登録した項目の数と経過時間
+    pub fn build_progress(&self) -> Option<(u64, Duration)> {
+        self.building
+            .as_ref()
+            .map(|building| (building.progress.load(Ordering::Relaxed), building.started.elapsed()))
+    }
+
+    /// 作成の結果と監視した変更を受け取る（毎フレーム呼ぶ）
+    pub fn poll(&mut self) {
+        if let Some(building) = &self.building {
+            match building.receiver.try_recv() {
+                Ok(Ok(index)) => {
+                    tracing::info!("インデックスを作成しました: {} 項目（{:.1} 秒）", index.len(), building.started.elapsed().as_secs_f32());
+                    self.building = None;
+                    self.index = Arc::new(index);
+                    if self.watcher.is_some() {
+                        self.error = None;
+                    }
+                    let queued = std::mem::take(&mut self.queued);
+                    self.apply_events(queued);
+                    self.modified = true;
+                    self.save_in_background();
+                }
+                Ok(Err(e)) => {
+                    self.building = None;
+                    self.error = Some(format!("インデックスを作成できません: {}", e));
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => self.building = None,
+            }
+        }
+
+        let events = self.watcher.as_ref().map(IndexWatcher::pending_events).unwrap_or_default();
+        if self.building.is_some() {
+            self.queued.extend(events);
+        } else if !events.is_empty() {
+            self.apply_events(events);
+            if self.index.is_stale() {
+                tracing::info!("変更の通知が失われたため、インデックスを作り直します");
+                self.start_rebuild();
+            }
+        }
+
+        if self.modified && self.last_saved.elapsed() >= SAVE_INTERVAL {
+            self.save_in_background();
+        }
+    }
+
+    fn apply_events(&mut self, events: Vec<FsEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let index = Arc::make_mut(&mut self.index);
+        for event in &events {
+            self.modified |= index.apply(event);
+        }
+    }
+
+    /// 変わっていれば保存する
+    pub fn save_if_modified(&mut self) {
+        self.save_in_background();
+    }
+
+    /// 画面を止めないよう、別スレッドで保存する
+    fn save_in_background(&mut self) {
+        if !self.modified || self.saving.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let Some(path) = FileIndex::default_path() else {
+            self.saving.store(false, Ordering::SeqCst);
+            return;
+        };
+        self.modified = false;
+        self.last_saved = Instant::now();
+        let index = Arc::clone(&self.index);
+        let saving = Arc::clone(&self.saving);
+        std::thread::spawn(move || {
+            if let Err(e) = index.save(&path) {
+                tracing::error!("インデックスの保存に失敗: {}", e);
+            }
+            saving.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// インデックスに追加できるフォルダーの候補（表示中のフォルダー）
+    pub fn can_add(&self, path: &Path) -> bool {
+        path.is_absolute() && path.is_dir() && !self.settings.roots.iter().any(|root| path.starts_with(root))
+    }
+}
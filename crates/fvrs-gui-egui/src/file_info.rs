@@ -3,6 +3,8 @@ use std::fs::{self, Metadata};
 use std::time::SystemTime;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use fvrs_core::volume::{OwnerInfo, VolumeInfo};
+use crate::executable_info::{ExecutableInfo, ExecutableInspector};
 
 /// ファイル情報構造体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,30 +32,24 @@ pub struct DetailedFileInfo {
     // 権限・セキュリティ情報
     pub permissions: String,
     pub owner: String,
+    pub group: String,
     
     // メタデータ情報
     pub version: Option<String>,
     pub company: Option<String>,
     pub description: Option<String>,
     pub copyright: Option<String>,
+    pub executable: Option<ExecutableInfo>,
     
     // 関連付け情報
     pub associated_program: Option<String>,
     pub open_with_command: Option<String>,
     
     // システム情報
-    pub disk_space: Option<DiskSpaceInfo>,
+    pub disk_space: Option<VolumeInfo>,
     pub computer_name: String,
 }
 
-/// ディスク容量情報
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DiskSpaceInfo {
-    pub total_space: u64,
-    pub free_space: u64,
-    pub used_space: u64,
-}
-
 /// ファイル情報取得ユーティリティ
 pub struct FileInfoCollector;
 
@@ -72,7 +68,8 @@ impl FileInfoCollector {
         
         // 基本情報
         let size = metadata.len();
-        let size_on_disk = Self::calculate_size_on_disk(&metadata);
+        let disk_space = VolumeInfo::for_path(path).ok();
+        let size_on_disk = Self::calculate_size_on_disk(&metadata, disk_space.as_ref());
         let is_directory = metadata.is_dir();
         let (is_hidden, is_readonly, is_system) = Self::get_file_attributes(&metadata);
         
@@ -91,16 +88,16 @@ impl FileInfoCollector {
         
         // 権限・セキュリティ情報
         let permissions = Self::get_permissions_string(&metadata);
-        let owner = Self::get_file_owner(path);
+        let (owner, group) = Self::get_file_owner(path);
         
-        // メタデータ情報（Windows PE ファイルの場合）
-        let (version, company, description, copyright) = Self::get_pe_metadata(path);
+        // メタデータ情報（PE / ELF / Mach-O の場合）
+        let executable = ExecutableInspector::inspect(path);
+        let (version, company, description, copyright) = Self::get_version_metadata(executable.as_ref());
         
         // 関連付け情報
         let (associated_program, open_with_command) = Self::get_file_associations(&file_extension);
         
         // システム情報
-        let disk_space = Self::get_disk_space_info(path);
         let computer_name = Self::get_computer_name();
         
         Ok(DetailedFileInfo {
@@ -120,10 +117,12 @@ impl FileInfoCollector {
             mime_type,
             permissions,
             owner,
+            group,
             version,
             company,
             description,
             copyright,
+            executable,
             associated_program,
             open_with_command,
             disk_space,
@@ -132,15 +131,29 @@ impl FileInfoCollector {
     }
     
     /// ディスク上のサイズを計算（クラスターサイズを考慮）
-    fn calculate_size_on_disk(metadata: &Metadata) -> u64 {
-        let size = metadata.len();
-        if size == 0 {
-            return 0;
+    #[cfg_attr(unix, allow(unused_variables))]
+    fn calculate_size_on_disk(metadata: &Metadata, volume: Option<&VolumeInfo>) -> u64 {
+        // Unix系では実際に割り当てられたブロック数（512バイト単位）を使用
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            metadata.blocks() * 512
         }
         
-        // 簡略化：4KB クラスターサイズと仮定
-        let cluster_size = 4096u64;
-        ((size + cluster_size - 1) / cluster_size) * cluster_size
+        #[cfg(not(unix))]
+        {
+            let size = metadata.len();
+            if size == 0 {
+                return 0;
+            }
+            
+            // ボリュームのクラスターサイズに切り上げ（取得できない場合は 4KB と仮定）
+            let cluster_size = volume
+                .map(|v| v.block_size)
+                .filter(|&b| b > 0)
+                .unwrap_or(4096);
+            size.div_ceil(cluster_size) * cluster_size
+        }
     }
     
     /// ファイル属性を取得
@@ -265,50 +278,24 @@ impl FileInfoCollector {
         }
     }
     
-    /// ファイル所有者を取得
-    fn get_file_owner(_path: &Path) -> String {
-        #[cfg(windows)]
-        {
-            // Windows実装は複雑なので簡略化
-            "管理者".to_string()
-        }
-        
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::MetadataExt;
-            if let Ok(metadata) = std::fs::metadata(_path) {
-                let uid = metadata.uid();
-                format!("UID: {}", uid)
-            } else {
-                "不明".to_string()
-            }
-        }
-        
-        #[cfg(not(any(unix, windows)))]
-        {
-            "不明".to_string()
+    /// ファイル所有者とグループを取得（uid/gid または SID から解決）
+    fn get_file_owner(path: &Path) -> (String, String) {
+        match OwnerInfo::for_path(path) {
+            Ok(owner) => (owner.display_user(), owner.display_group()),
+            Err(_) => ("不明".to_string(), "不明".to_string()),
         }
     }
     
-    /// PE（実行可能）ファイルのメタデータを取得
-    fn get_pe_metadata(path: &Path) -> (Option<String>, Option<String>, Option<String>, Option<String>) {
-        // 実装は複雑なので、拡張子による簡易判定
-        let ext = path.extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-        
-        match ext.as_str() {
-            "exe" | "dll" => {
-                // 実際の実装では PE ヘッダーを解析
-                (
-                    Some("1.0.0".to_string()),
-                    Some("Unknown Company".to_string()),
-                    Some("Windows Application".to_string()),
-                    Some("Copyright © 2024".to_string())
-                )
-            }
-            _ => (None, None, None, None)
+    /// 実行可能ファイルのバージョン情報を取得（PE バージョンリソースのみ）
+    fn get_version_metadata(executable: Option<&ExecutableInfo>) -> (Option<String>, Option<String>, Option<String>, Option<String>) {
+        match executable {
+            Some(info) => (
+                info.file_version.clone(),
+                info.company.clone(),
+                info.description.clone(),
+                info.copyright.clone(),
+            ),
+            None => (None, None, None, None),
         }
     }
     
@@ -335,16 +322,6 @@ impl FileInfoCollector {
         (Some(program.to_string()), Some(command.to_string()))
     }
     
-    /// ディスク容量情報を取得
-    fn get_disk_space_info(_path: &Path) -> Option<DiskSpaceInfo> {
-        // 実装は複雑なのでプレースホルダー
-        Some(DiskSpaceInfo {
-            total_space: 1_000_000_000_000, // 1TB
-            free_space: 500_000_000_000,    // 500GB
-            used_space: 500_000_000_000,    // 500GB
-        })
-    }
-    
     /// コンピューター名を取得
     fn get_computer_name() -> String {
         std::env::var("COMPUTERNAME")
@@ -1,7 +1,10 @@
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::time::SystemTime;
+use fvrs_core::core::FsError;
+use fvrs_core::listing;
 
 /// File operation error types
 #[derive(Debug)]
@@ -61,10 +64,8 @@ impl FileEntry {
     }
 }
 
-/// 高速なファイル一覧取得（表示数制限付き）
+/// 高速なファイル一覧取得（メタデータは並列に取得、件数の上限なし）
 pub fn get_entries_fast(path: &Path, show_hidden: bool) -> FileOpResult<Vec<FileEntry>> {
-    const MAX_ENTRIES: usize = 1000; // 最大表示数を制限してパフォーマンス向上
-    
     let mut entries = Vec::new();
     
     // 親ディレクトリエントリを追加
@@ -78,41 +79,21 @@ pub fn get_entries_fast(path: &Path, show_hidden: bool) -> FileOpResult<Vec<File
         });
     }
     
-    // ディレクトリエントリを効率的に読み込み
-    let dir_entries: Vec<_> = fs::read_dir(path)?
-        .filter_map(|entry| entry.ok())
-        .take(MAX_ENTRIES)
-        .collect();
-    
-    // 隠しファイル/フォルダのフィルタリング
-    let filtered_entries: Vec<_> = dir_entries
-        .into_iter()
-        .filter(|entry| {
-            let file_name = entry.file_name();
-            let name = file_name.to_string_lossy();
-            show_hidden || !name.starts_with('.')
-        })
-        .collect();
-    
-    // 並列処理的にメタデータ取得（実際の並列処理はせずに効率化）
-    for entry in filtered_entries {
-        let path = entry.path();
-        let name = entry.file_name().to_string_lossy().to_string();
-        
-        // 軽量なメタデータ取得
-        if let Ok(metadata) = entry.metadata() {
-            let size = if metadata.is_file() { metadata.len() } else { 0 };
-            let modified = metadata.modified().unwrap_or(SystemTime::now());
-            
-            entries.push(FileEntry {
-                path,
-                name,
-                is_dir: metadata.is_dir(),
-                size,
-                modified,
-            });
-        }
-    }
+    // 読み込まれた分から順に変換
+    let cancel = AtomicBool::new(false);
+    listing::read_directory(path, show_hidden, &cancel, |batch| {
+        entries.extend(batch.into_iter().map(|entry| FileEntry {
+            path: entry.path,
+            name: entry.name,
+            is_dir: entry.is_dir,
+            size: entry.size,
+            modified: entry.modified.into(),
+        }));
+    })
+    .map_err(|e| match e {
+        FsError::Io(e) => FileOpError::Io(e),
+        e => FileOpError::Io(io::Error::other(e.to_string())),
+    })?;
     
     // 効率的なソート：ディレクトリ優先、その後名前順
     entries.sort_unstable_by(|a, b| {
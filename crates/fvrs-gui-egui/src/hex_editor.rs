@@ -0,0 +1,442 @@
+use fvrs_core::core::FsError;
+use fvrs_core::hexedit::{self, ByteDocument};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+
+/// 1 行のバイト数
+pub const ROW_BYTES: u64 = 16;
+/// コピーできる最大バイト数（クリップボードに載せる量を抑える）
+const MAX_COPY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// 実行中の検索（破棄すると中止される）
+struct RunningSearch {
+    cancel: Arc<AtomicBool>,
+    pattern_len: u64,
+    receiver: mpsc::Receiver<Result<Option<u64>, String>>,
+}
+
+impl Drop for RunningSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 保存スレッドの結果（保存中は文書をスレッドに渡している）
+type SaveResult = (ByteDocument, Result<(), String>);
+
+/// バイナリエディタの状態
+pub struct HexEditor {
+    /// 保存中は None
+    document: Option<ByteDocument>,
+    path: PathBuf,
+    search: Option<RunningSearch>,
+    saving: Option<mpsc::Receiver<SaveResult>>,
+    /// カーソル位置（末尾の次まで）
+    pub cursor: u64,
+    /// 選択の起点（None なら選択なし）
+    pub anchor: Option<u64>,
+    /// 挿入モード（false なら上書き）
+    pub insert_mode: bool,
+    /// ASCII 欄で入力する
+    pub text_column: bool,
+    /// 上位 4 ビットを入力済み
+    pub low_nibble: bool,
+    /// 先頭に表示する行
+    pub top: u64,
+    /// スクロールの端数（1 行未満の分を持ち越す）
+    pub scroll_remainder: f32,
+    /// 次のフレームでカーソルが見える位置までスクロールする
+    pub scroll_to_cursor: bool,
+    pub find_text: String,
+    /// 検索文字列を 16 進として扱う
+    pub find_hex: bool,
+    /// 検索欄にフォーカスを移す
+    pub focus_find: bool,
+    /// 未保存の変更を破棄して閉じるかの確認中
+    pub confirm_close: bool,
+    /// 保存が終わったら閉じる
+    close_after_save: bool,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+impl HexEditor {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let document = ByteDocument::open(path).map_err(|e| format!("開けません: {} ({})", e, path.display()))?;
+        Ok(Self {
+            path: document.path().to_path_buf(),
+            document: Some(document),
+            search: None,
+            saving: None,
+            cursor: 0,
+            anchor: None,
+            insert_mode: false,
+            text_column: false,
+            low_nibble: false,
+            top: 0,
+            scroll_remainder: 0.0,
+            scroll_to_cursor: false,
+            find_text: String::new(),
+            find_hex: true,
+            focus_find: false,
+            confirm_close: false,
+            close_after_save: false,
+            status: None,
+            error: None,
+        })
+    }
+
+    /// 検索・保存の結果を取り込む（閉じてよくなったら true）
+    pub fn poll(&mut self, ctx: &egui::Context) -> bool {
+        if let Some(search) = &self.search {
+            match search.receiver.try_recv() {
+                Ok(result) => {
+                    let pattern_len = search.pattern_len;
+                    self.search = None;
+                    match result {
+                        Ok(Some(found)) => {
+                            self.select(found..found + pattern_len);
+                            self.status = Some(format!("0x{:X} で見つかりました", found));
+                        }
+                        Ok(None) => self.status = Some("見つかりません".to_string()),
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.search = None,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
+        if let Some(receiver) = &self.saving {
+            match receiver.try_recv() {
+                Ok((document, result)) => {
+                    self.saving = None;
+                    self.document = Some(document);
+                    match result {
+                        Ok(()) => {
+                            self.status = Some("保存しました".to_string());
+                            tracing::info!("バイナリ編集の内容を保存しました: {:?}", self.path);
+                            if self.close_after_save {
+                                return true;
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("{}", e);
+                            self.error = Some(e);
+                        }
+                    }
+                    self.close_after_save = false;
+                    self.clamp_cursor();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.saving = None;
+                    self.error = Some("保存が中断されました".to_string());
+                    // 文書はスレッドと一緒に失われたので開き直す
+                    match ByteDocument::open(&self.path) {
+                        Ok(document) => self.document = Some(document),
+                        Err(e) => self.error = Some(format!("開き直せません: {}", e)),
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
+        if self.is_busy() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        false
+    }
+
+    pub fn document(&self) -> Option<&ByteDocument> {
+        self.document.as_ref()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.document.as_ref().map_or(0, ByteDocument::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.document.as_ref().is_some_and(ByteDocument::is_modified)
+    }
+
+    pub fn is_saving(&self) -> bool {
+        self.saving.is_some()
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.is_saving() || self.is_searching()
+    }
+
+    /// 選択範囲（カーソルと起点の両端のバイトを含む）
+    pub fn selection(&self) -> Option<Range<u64>> {
+        let anchor = self.anchor?;
+        let start = anchor.min(self.cursor);
+        let end = (anchor.max(self.cursor) + 1).min(self.len());
+        (start < end).then_some(start..end)
+    }
+
+    /// カーソルを動かす（extend なら選択を広げる）
+    pub fn move_cursor(&mut self, to: u64, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = to.min(self.len());
+        self.low_nibble = false;
+        self.scroll_to_cursor = true;
+    }
+
+    /// 範囲を選択してカーソルを先頭に置く
+    pub fn select(&mut self, range: Range<u64>) {
+        self.anchor = Some(range.end.saturating_sub(1).max(range.start));
+        self.cursor = range.start;
+        self.low_nibble = false;
+        self.scroll_to_cursor = true;
+    }
+
+    pub fn select_all(&mut self) {
+        if !self.is_empty() {
+            self.select(0..self.len());
+        }
+    }
+
+    /// 16 進の 1 桁を入力する（2 桁で 1 バイト）
+    pub fn type_hex_digit(&mut self, digit: u8) {
+        self.delete_selection_for_typing();
+        let cursor = self.cursor;
+        let low_nibble = self.low_nibble;
+        let insert_mode = self.insert_mode;
+        let Some(document) = self.document.as_mut() else {
+            return;
+        };
+
+        if low_nibble {
+            let current = document.content().read(cursor, 1).ok().and_then(|b| b.first().copied()).unwrap_or(0);
+            document.overwrite(cursor, &[(current & 0xF0) | digit]);
+            self.cursor += 1;
+            self.low_nibble = false;
+        } else {
+            if insert_mode || cursor >= document.len() {
+                document.insert(cursor, &[digit << 4]);
+            } else {
+                let current = document.content().read(cursor, 1).ok().and_then(|b| b.first().copied()).unwrap_or(0);
+                document.overwrite(cursor, &[(digit << 4) | (current & 0x0F)]);
+            }
+            self.low_nibble = true;
+        }
+        self.scroll_to_cursor = true;
+    }
+
+    /// 文字を入力する（UTF-8 のバイト列として書き込む）
+    pub fn type_bytes(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        self.delete_selection_for_typing();
+        let cursor = self.cursor;
+        let Some(document) = self.document.as_mut() else {
+            return;
+        };
+        if self.insert_mode {
+            document.insert(cursor, bytes);
+        } else {
+            document.overwrite(cursor, bytes);
+        }
+        self.cursor += bytes.len() as u64;
+        self.low_nibble = false;
+        self.scroll_to_cursor = true;
+    }
+
+    /// 貼り付け（16 進欄では 16 進の文字列として読む）
+    pub fn paste(&mut self, text: &str) {
+        let bytes = if self.text_column {
+            text.as_bytes().to_vec()
+        } else {
+            match hexedit::parse_hex(text) {
+                Some(bytes) => bytes,
+                None => {
+                    self.error = Some("16 進の文字列ではないので貼り付けられません".to_string());
+                    return;
+                }
+            }
+        };
+        self.type_bytes(&bytes);
+    }
+
+    /// 選択範囲を 16 進（ASCII 欄では文字）として取り出す
+    pub fn copy_selection(&mut self) -> Option<String> {
+        let range = self.selection()?;
+        if range.end - range.start > MAX_COPY_BYTES {
+            self.error = Some("選択範囲が大きすぎるためコピーできません".to_string());
+            return None;
+        }
+        let bytes = match self.document.as_ref()?.content().read(range.start, (range.end - range.start) as usize) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.error = Some(format!("読み込みエラー: {}", e));
+                return None;
+            }
+        };
+        Some(if self.text_column {
+            String::from_utf8_lossy(&bytes).into_owned()
+        } else {
+            bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
+        })
+    }
+
+    /// Delete（前方）・Backspace（後方）
+    pub fn delete(&mut self, backward: bool) {
+        if let Some(range) = self.selection() {
+            self.delete_range(range);
+            return;
+        }
+        let cursor = self.cursor;
+        let Some(document) = self.document.as_mut() else {
+            return;
+        };
+        if backward {
+            if cursor > 0 {
+                document.delete(cursor - 1..cursor);
+                self.cursor -= 1;
+            }
+        } else {
+            document.delete(cursor..cursor + 1);
+        }
+        self.low_nibble = false;
+        self.scroll_to_cursor = true;
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(range) = self.document.as_mut().and_then(ByteDocument::undo) {
+            self.move_cursor(range.start, false);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(range) = self.document.as_mut().and_then(ByteDocument::redo) {
+            self.move_cursor(range.end, false);
+        }
+    }
+
+    /// 検索文字列のバイト列
+    pub fn find_pattern(&self) -> Result<Vec<u8>, String> {
+        if self.find_hex {
+            hexedit::parse_hex(&self.find_text).ok_or_else(|| "16 進の文字列を入力してください（例: DE AD BE EF）".to_string())
+        } else if self.find_text.is_empty() {
+            Err("検索する文字列を入力してください".to_string())
+        } else {
+            Ok(self.find_text.as_bytes().to_vec())
+        }
+    }
+
+    /// カーソル位置から次（前）を検索する（末尾で先頭に戻る）
+    pub fn find(&mut self, ctx: &egui::Context, backward: bool) {
+        let pattern = match self.find_pattern() {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        let Some(document) = &self.document else {
+            return;
+        };
+        // 今の一致の次から探す
+        let from = match (self.selection(), backward) {
+            (Some(range), false) => range.start + 1,
+            (Some(range), true) => range.start,
+            (None, _) => self.cursor,
+        };
+
+        let content = document.content().clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let thread_cancel = Arc::clone(&cancel);
+        let repaint_ctx = ctx.clone();
+        let pattern_len = pattern.len() as u64;
+        std::thread::spawn(move || {
+            let result = match content.find(&pattern, from, backward, &thread_cancel) {
+                Ok(found) => Ok(found),
+                Err(FsError::Cancelled) => return,
+                Err(e) => Err(format!("検索できません: {}", e)),
+            };
+            let _ = sender.send(result);
+            repaint_ctx.request_repaint();
+        });
+        self.status = None;
+        self.error = None;
+        self.search = Some(RunningSearch { cancel, pattern_len, receiver });
+    }
+
+    /// 一時ファイルに書き出してから置き換える（書き込み中は編集できない）
+    pub fn save(&mut self, ctx: &egui::Context) {
+        let Some(mut document) = self.document.take() else {
+            return;
+        };
+        self.search = None;
+        let (sender, receiver) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let result = document
+                .save(&AtomicBool::new(false))
+                .map_err(|e| format!("保存できません: {} ({})", e, document.path().display()));
+            let _ = sender.send((document, result));
+            repaint_ctx.request_repaint();
+        });
+        self.status = None;
+        self.error = None;
+        self.saving = Some(receiver);
+    }
+
+    /// 保存して閉じる
+    pub fn save_and_close(&mut self, ctx: &egui::Context) {
+        self.close_after_save = true;
+        self.confirm_close = false;
+        self.save(ctx);
+    }
+
+    /// 入力の前に選択範囲を消す（挿入モードのみ。上書きでは先頭から書く）
+    fn delete_selection_for_typing(&mut self) {
+        if let Some(range) = self.selection() {
+            if self.insert_mode {
+                self.delete_range(range);
+            } else {
+                self.cursor = range.start;
+                self.anchor = None;
+            }
+            self.low_nibble = false;
+        }
+        self.anchor = None;
+    }
+
+    fn delete_range(&mut self, range: Range<u64>) {
+        if let Some(document) = self.document.as_mut() {
+            document.delete(range.clone());
+        }
+        self.anchor = None;
+        self.cursor = range.start;
+        self.low_nibble = false;
+        self.scroll_to_cursor = true;
+    }
+
+    fn clamp_cursor(&mut self) {
+        let len = self.len();
+        self.cursor = self.cursor.min(len);
+        self.anchor = self.anchor.map(|anchor| anchor.min(len));
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::Local;
+use fvrs_core::bookmarks::Bookmarks;
+use fvrs_core::history::{self, DirectoryHistory};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// 履歴を保存するファイル（データフォルダー内）
+const HISTORY_FILE: &str = "history.json";
+/// クイックジャンプに表示する候補の数
+const MAX_CANDIDATES: usize = 50;
+/// クイックジャンプで「最近」として示す訪問の数
+const RECENT_CANDIDATES: usize = 10;
+/// クイックジャンプでブックマークに上乗せするスコア
+const BOOKMARK_BONUS: f64 = 16.0;
+
+/// 履歴ダイアログのタブ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryTab {
+    /// 最近開いたフォルダー
+    Recent,
+    /// よく使うフォルダー（frecency 順）
+    Frequent,
+    /// 表示中のタブの戻る・進むの履歴
+    Pane,
+}
+
+/// 開いたフォルダーの履歴（セッションをまたいで保存する）
+pub struct VisitHistory {
+    pub history: DirectoryHistory,
+    pub tab: HistoryTab,
+    /// 履歴ダイアログの絞り込み
+    pub filter: String,
+    /// 前回の保存から変わった
+    modified: bool,
+}
+
+impl VisitHistory {
+    /// 保存済みの履歴を読み込む（なければ空）
+    pub fn load() -> Self {
+        let history = match history_path() {
+            Some(path) => DirectoryHistory::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("履歴の読み込みに失敗: {}", e);
+                DirectoryHistory::default()
+            }),
+            None => DirectoryHistory::default(),
+        };
+        Self { history, tab: HistoryTab::Recent, filter: String::new(), modified: false }
+    }
+
+    /// 変わっていれば保存する
+    pub fn save_if_modified(&mut self) {
+        if !self.modified {
+            return;
+        }
+        let Some(path) = history_path() else {
+            return;
+        };
+        match self.history.save(&path) {
+            Ok(()) => self.modified = false,
+            Err(e) => tracing::error!("履歴の保存に失敗: {}", e),
+        }
+    }
+
+    /// フォルダーを開いたことを記録
+    pub fn record(&mut self, path: &Path) {
+        self.history.record(path, Local::now());
+        self.modified = true;
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.history.remove(path);
+        self.modified = true;
+    }
+
+    /// 存在しないフォルダーを履歴から消し、消した数を返す
+    pub fn remove_missing(&mut self) -> usize {
+        let removed = self.history.remove_missing();
+        self.modified |= removed > 0;
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.modified = true;
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    fvrs_core::config::data_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+/// クイックジャンプの候補の出どころ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpSource {
+    /// ブックマーク
+    Bookmark,
+    /// 直近に開いた
+    Recent,
+    /// 履歴にある
+    Frequent,
+}
+
+/// クイックジャンプの候補
+#[derive(Debug, Clone)]
+pub struct JumpCandidate {
+    pub path: PathBuf,
+    /// 表示するパス
+    pub label: String,
+    pub source: JumpSource,
+    /// ブックマークの名前
+    pub bookmark: Option<String>,
+    /// 一致した文字の位置（文字単位）
+    pub positions: Vec<usize>,
+    pub exists: bool,
+}
+
+/// 履歴とブックマークからフォルダーをあいまい検索して移動する（Ctrl+P）
+#[derive(Default)]
+pub struct QuickJump {
+    pub query: String,
+    pub selected: usize,
+    pub candidates: Vec<JumpCandidate>,
+    /// 候補を作ったときの入力
+    matched_query: Option<String>,
+}
+
+impl QuickJump {
+    /// 入力が変わっていれば候補を作り直す
+    pub fn update(&mut self, history: &DirectoryHistory, bookmarks: &Bookmarks) {
+        if self.matched_query.as_deref() == Some(self.query.as_str()) {
+            return;
+        }
+        self.matched_query = Some(self.query.clone());
+        self.selected = 0;
+
+        let now = Local::now();
+        let frecencies = history.frecencies(now);
+        let latest: HashSet<&Path> =
+            history.recent().iter().take(RECENT_CANDIDATES).map(|visit| visit.path.as_path()).collect();
+        let mut seen = HashSet::new();
+        let mut scored: Vec<(f64, JumpCandidate)> = Vec::new();
+
+        // ブックマークは名前でもパスでも探せる
+        for bookmark in bookmarks.bookmarks() {
+            if !seen.insert(bookmark.path.as_path()) {
+                continue;
+            }
+            let label = bookmark.path.to_string_lossy().into_owned();
+            let by_path = history::fuzzy_match(&self.query, &label);
+            let by_name = history::fuzzy_match(&self.query, &bookmark.name);
+            let (score, positions) = match (by_path, by_name) {
+                (Some(path), Some(name)) if name.score > path.score => (name.score, Vec::new()),
+                (Some(path), _) => (path.score, path.positions),
+                (None, Some(name)) => (name.score, Vec::new()),
+                (None, None) => continue,
+            };
+            let frecency = frecencies.get(bookmark.path.as_path()).copied().unwrap_or(0.0);
+            let score = f64::from(score) + BOOKMARK_BONUS + 8.0 * (1.0 + frecency).ln();
+            let candidate = JumpCandidate {
+                path: bookmark.path.clone(),
+                label,
+                source: JumpSource::Bookmark,
+                bookmark: Some(format!("{} {}", bookmark.icon, bookmark.name)),
+                positions,
+                exists: true,
+            };
+            scored.push((score, candidate));
+        }
+
+        let ranked = history.ranked(now);
+        let sources = ranked
+            .iter()
+            .map(|(entry, _)| entry.path.as_path())
+            .chain(history.recent().iter().map(|visit| visit.path.as_path()))
+            .map(|path| (path, if latest.contains(path) { JumpSource::Recent } else { JumpSource::Frequent }));
+
+        // 一致の良さに、よく使うフォルダーほど少し上乗せする
+        scored.extend(
+            sources
+                .filter(|(path, _)| seen.insert(*path))
+                .filter_map(|(path, source)| {
+                    let label = path.to_string_lossy().into_owned();
+                    let matched = history::fuzzy_match(&self.query, &label)?;
+                    let frecency = frecencies.get(path).copied().unwrap_or(0.0);
+                    let score = f64::from(matched.score) + 8.0 * (1.0 + frecency).ln();
+                    let candidate = JumpCandidate {
+                        path: path.to_path_buf(),
+                        label,
+                        source,
+                        bookmark: None,
+                        positions: matched.positions,
+                        exists: true,
+                    };
+                    Some((score, candidate))
+                }),
+        );
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        self.candidates = scored.into_iter().take(MAX_CANDIDATES).map(|(_, candidate)| candidate).collect();
+        for candidate in &mut self.candidates {
+            candidate.exists = candidate.path.is_dir();
+        }
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        if self.candidates.is_empty() {
+            return;
+        }
+        self.selected = if down {
+            (self.selected + 1).min(self.candidates.len() - 1)
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+
+    pub fn selected_path(&self) -> Option<&Path> {
+        self.candidates.get(self.selected).map(|candidate| candidate.path.as_path())
+    }
+}
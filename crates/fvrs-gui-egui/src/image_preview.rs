@@ -0,0 +1,232 @@
+use fvrs_core::imaging::{self, ExifSummary, ImageTransform};
+use image::DynamicImage;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::SystemTime;
+
+/// 表示用テクスチャの一辺の上限（大きな画像は縮小して保持する）
+const MAX_PREVIEW_SIDE: u32 = 4096;
+
+/// 読み込みスレッドの結果
+struct LoadedImage {
+    /// 表示用に縮小した、向きを補正済みの画像
+    image: DynamicImage,
+    /// 元の画像の 1 ピクセルあたりの表示用画像のピクセル数
+    scale: f32,
+    exif: Option<ExifSummary>,
+}
+
+/// 表示中のファイル（更新されたら読み込み直す）
+#[derive(Clone, PartialEq)]
+struct PreviewTarget {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// 画像プレビューの状態
+#[derive(Default)]
+pub struct ImagePreview {
+    target: Option<PreviewTarget>,
+    loading: Option<mpsc::Receiver<Result<LoadedImage, String>>>,
+    saving: Option<mpsc::Receiver<Result<(), String>>>,
+    image: Option<DynamicImage>,
+    texture: Option<egui::TextureHandle>,
+    scale: f32,
+    pub exif: Option<ExifSummary>,
+    /// 保存前の回転・反転（適用した順）
+    pub transforms: Vec<ImageTransform>,
+    /// 表示倍率（None なら枠に合わせる）
+    pub zoom: Option<f32>,
+    /// 中央からのずれ
+    pub pan: egui::Vec2,
+    /// 上書き保存の確認中
+    pub confirm_save: bool,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ImagePreview {
+    /// 表示するファイルを指定（変わっていれば読み込みを開始）
+    pub fn set_target(&mut self, ctx: &egui::Context, path: Option<&Path>) {
+        let target = path.map(|path| PreviewTarget {
+            path: path.to_path_buf(),
+            modified: std::fs::metadata(path).and_then(|m| m.modified()).ok(),
+        });
+        if target == self.target {
+            return;
+        }
+        // 保存中は書き込みが終わるまで待つ（保存による更新で未保存の操作を失わないように）
+        if self.saving.is_some() {
+            return;
+        }
+
+        let same_file = self.target.as_ref().map(|t| &t.path) == target.as_ref().map(|t| &t.path);
+        self.clear();
+        if !same_file {
+            self.status = None;
+        }
+        self.target = target.clone();
+        let Some(target) = target else {
+            return;
+        };
+
+        let max_side = (ctx.input(|i| i.max_texture_side) as u32).min(MAX_PREVIEW_SIDE);
+        let (sender, receiver) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(load(&target.path, max_side));
+            repaint_ctx.request_repaint();
+        });
+        self.loading = Some(receiver);
+    }
+
+    /// 読み込み・保存の結果を取り込む
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        if let Some(receiver) = &self.loading {
+            match receiver.try_recv() {
+                Ok(Ok(loaded)) => {
+                    self.loading = None;
+                    self.scale = loaded.scale;
+                    self.exif = loaded.exif;
+                    self.image = Some(loaded.image);
+                    self.update_texture(ctx);
+                }
+                Ok(Err(e)) => {
+                    self.loading = None;
+                    self.error = Some(e);
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.loading = None,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
+        if let Some(receiver) = &self.saving {
+            match receiver.try_recv() {
+                Ok(Ok(())) => {
+                    self.saving = None;
+                    self.transforms.clear();
+                    self.status = Some("保存しました".to_string());
+                    // 更新日時が変わるので次のフレームで読み込み直される
+                }
+                Ok(Err(e)) => {
+                    self.saving = None;
+                    tracing::error!("{}", e);
+                    self.error = Some(e);
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.saving = None,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+    }
+
+    /// 表示をやめてテクスチャを解放する
+    pub fn clear(&mut self) {
+        self.target = None;
+        self.loading = None;
+        self.image = None;
+        self.texture = None;
+        self.exif = None;
+        self.transforms.clear();
+        self.zoom = None;
+        self.pan = egui::Vec2::ZERO;
+        self.confirm_save = false;
+        self.error = None;
+    }
+
+    /// 回転・反転を表示に適用する（保存するまでファイルは変わらない）
+    pub fn apply(&mut self, ctx: &egui::Context, transform: ImageTransform) {
+        let Some(image) = &self.image else {
+            return;
+        };
+        self.image = Some(transform.apply(image));
+        self.transforms.push(transform);
+        self.zoom = None;
+        self.pan = egui::Vec2::ZERO;
+        self.status = None;
+        self.update_texture(ctx);
+    }
+
+    /// 回転・反転を取り消してファイルから読み込み直す
+    pub fn revert(&mut self, ctx: &egui::Context) {
+        let path = self.target.as_ref().map(|target| target.path.clone());
+        self.target = None;
+        self.set_target(ctx, path.as_deref());
+    }
+
+    /// 回転・反転をファイルに書き込む
+    pub fn save(&mut self) {
+        let Some(target) = &self.target else {
+            return;
+        };
+        if self.transforms.is_empty() || self.saving.is_some() {
+            return;
+        }
+
+        let path = target.path.clone();
+        let transforms = self.transforms.clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = imaging::save_transformed(&path, &transforms)
+                .map_err(|e| format!("画像を保存できません: {} ({})", e, path.display()));
+            let _ = sender.send(result);
+        });
+        tracing::info!("画像を回転・反転して保存: {:?} {:?}", target.path, self.transforms);
+        self.confirm_save = false;
+        self.error = None;
+        self.saving = Some(receiver);
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.target.as_ref().map(|target| target.path.as_path())
+    }
+
+    pub fn texture(&self) -> Option<&egui::TextureHandle> {
+        self.texture.as_ref()
+    }
+
+    /// 元の画像での大きさ（回転・反転を反映）
+    pub fn image_size(&self) -> Option<egui::Vec2> {
+        let image = self.image.as_ref()?;
+        Some(egui::vec2(image.width() as f32, image.height() as f32) / self.scale)
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    pub fn is_saving(&self) -> bool {
+        self.saving.is_some()
+    }
+
+    fn update_texture(&mut self, ctx: &egui::Context) {
+        let Some(image) = &self.image else {
+            return;
+        };
+        let rgba = image.to_rgba8();
+        let color_image = egui::ColorImage::from_rgba_unmultiplied(
+            [rgba.width() as usize, rgba.height() as usize],
+            rgba.as_raw(),
+        );
+        match &mut self.texture {
+            Some(texture) => texture.set(color_image, egui::TextureOptions::LINEAR),
+            None => self.texture = Some(ctx.load_texture("image_preview", color_image, egui::TextureOptions::LINEAR)),
+        }
+    }
+}
+
+/// 画像と EXIF を読み込み、表示用の大きさに縮小する
+fn load(path: &Path, max_side: u32) -> Result<LoadedImage, String> {
+    let image = imaging::load_image(path).map_err(|e| format!("画像を読み込めません: {}", e))?;
+    let exif = imaging::read_exif(path).unwrap_or_else(|e| {
+        tracing::debug!("EXIF を読み込めません: {:?} ({})", path, e);
+        None
+    });
+
+    let (width, height) = (image.width(), image.height());
+    if width <= max_side && height <= max_side {
+        return Ok(LoadedImage { image, scale: 1.0, exif });
+    }
+    let image = image.resize(max_side, max_side, image::imageops::FilterType::Triangle);
+    let scale = image.width() as f32 / width as f32;
+    Ok(LoadedImage { image, scale, exif })
+}
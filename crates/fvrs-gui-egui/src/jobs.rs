@@ -0,0 +1,939 @@
+use crate::journal::OperationJournal;
+use crate::state::FileOperation;
+use fvrs_core::copy::{self, CopyOptions, SymlinkPolicy};
+use fvrs_core::core::FsError;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant, SystemTime};
+
+/// 同時に実行するジョブ数
+const MAX_RUNNING_JOBS: usize = 2;
+/// 一時停止中・衝突の応答待ちで中止を確認する間隔
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// キューに入れるファイル操作
+#[derive(Debug, Clone)]
+pub enum JobKind {
+    Copy {
+        sources: Vec<PathBuf>,
+        destination: PathBuf,
+    },
+    Move {
+        sources: Vec<PathBuf>,
+        destination: PathBuf,
+    },
+    /// `permanent` が false ならゴミ箱へ移動する
+    Delete {
+        paths: Vec<PathBuf>,
+        permanent: bool,
+    },
+}
+
+impl JobKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobKind::Copy { .. } => "コピー",
+            JobKind::Move { .. } => "移動",
+            JobKind::Delete {
+                permanent: false, ..
+            } => "ゴミ箱へ移動",
+            JobKind::Delete {
+                permanent: true, ..
+            } => "完全に削除",
+        }
+    }
+
+    /// 表示を更新する必要があるフォルダー
+    pub fn affected_dirs(&self) -> Vec<PathBuf> {
+        let (sources, destination) = match self {
+            JobKind::Copy {
+                sources,
+                destination,
+            }
+            | JobKind::Move {
+                sources,
+                destination,
+            } => (sources, Some(destination)),
+            JobKind::Delete { paths, .. } => (paths, None),
+        };
+        let mut dirs: Vec<PathBuf> = sources
+            .iter()
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .chain(destination.cloned())
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    /// 一覧に表示する説明
+    pub fn description(&self) -> String {
+        let (sources, destination) = match self {
+            JobKind::Copy {
+                sources,
+                destination,
+            }
+            | JobKind::Move {
+                sources,
+                destination,
+            } => (sources, Some(destination)),
+            JobKind::Delete { paths, .. } => (paths, None),
+        };
+        let name = match sources.as_slice() {
+            [single] => crate::utils::get_display_name(single),
+            items => format!("{} 項目", items.len()),
+        };
+        match destination {
+            Some(destination) => format!("{}: {} → {}", self.label(), name, destination.display()),
+            None => format!("{}: {}", self.label(), name),
+        }
+    }
+}
+
+/// 終了したジョブの結果
+pub struct JobResult {
+    pub affected_dirs: Vec<PathBuf>,
+    /// 履歴に記録する操作
+    pub operations: Vec<FileOperation>,
+}
+
+/// ジョブの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Queued,
+    Scanning,
+    Running,
+    Paused,
+    /// 衝突の解決を待っている
+    WaitingForUser,
+    Completed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobState::Queued => "待機中",
+            JobState::Scanning => "準備中",
+            JobState::Running => "実行中",
+            JobState::Paused => "一時停止",
+            JobState::WaitingForUser => "確認待ち",
+            JobState::Completed => "完了",
+            JobState::Cancelled => "中止",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobState::Completed | JobState::Cancelled)
+    }
+}
+
+/// 同名の項目があったときの処理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// 既存の項目をゴミ箱へ移動してから上書き
+    Overwrite,
+    Skip,
+    /// 番号を付けた別名で保存
+    Rename,
+    /// 元の項目のほうが新しいときだけ上書き
+    NewerOnly,
+}
+
+impl ConflictResolution {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConflictResolution::Overwrite => "上書き",
+            ConflictResolution::Skip => "スキップ",
+            ConflictResolution::Rename => "名前を変えて保存",
+            ConflictResolution::NewerOnly => "新しい場合のみ上書き",
+        }
+    }
+}
+
+/// 衝突ダイアログでの選択
+#[derive(Debug, Clone, Copy)]
+pub struct ConflictChoice {
+    pub resolution: ConflictResolution,
+    /// 以降の衝突にも同じ処理を適用する
+    pub apply_to_all: bool,
+}
+
+/// 解決待ちの衝突
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub source_size: u64,
+    pub source_modified: Option<SystemTime>,
+    pub destination_size: u64,
+    pub destination_modified: Option<SystemTime>,
+}
+
+/// ジョブの進捗
+#[derive(Debug, Clone)]
+pub struct JobProgress {
+    pub state: JobState,
+    pub files_total: u64,
+    pub files_done: u64,
+    pub bytes_total: u64,
+    pub bytes_done: u64,
+    /// 処理中のファイル
+    pub current: Option<PathBuf>,
+    pub current_size: u64,
+    pub current_done: u64,
+    /// 一時停止・確認待ちを除いた転送速度（バイト/秒）
+    pub throughput: f64,
+    pub conflict: Option<Conflict>,
+    pub errors: Vec<String>,
+}
+
+impl JobProgress {
+    fn new() -> Self {
+        Self {
+            state: JobState::Queued,
+            files_total: 0,
+            files_done: 0,
+            bytes_total: 0,
+            bytes_done: 0,
+            current: None,
+            current_size: 0,
+            current_done: 0,
+            throughput: 0.0,
+            conflict: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// 全体の進捗率（0.0～1.0）
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total > 0 {
+            self.bytes_done as f32 / self.bytes_total as f32
+        } else if self.files_total > 0 {
+            self.files_done as f32 / self.files_total as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// 残り時間の見積もり
+    pub fn eta(&self) -> Option<Duration> {
+        if self.throughput <= 0.0 || self.bytes_done >= self.bytes_total {
+            return None;
+        }
+        let remaining = (self.bytes_total - self.bytes_done) as f64;
+        Some(Duration::from_secs_f64(remaining / self.throughput))
+    }
+}
+
+/// ワーカーとの間で共有する制御フラグ
+#[derive(Default)]
+struct JobControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+/// キュー内のジョブ
+pub struct Job {
+    pub id: u64,
+    pub kind: JobKind,
+    /// 追加した時点のコピー設定
+    options: CopyOptions,
+    progress: Arc<Mutex<JobProgress>>,
+    control: Arc<JobControl>,
+    reply: mpsc::Sender<ConflictChoice>,
+    /// 開始前のみ保持（ワーカーへ渡す）
+    reply_receiver: Option<mpsc::Receiver<ConflictChoice>>,
+    done: Option<mpsc::Receiver<Vec<FileOperation>>>,
+}
+
+impl Job {
+    pub fn progress(&self) -> JobProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    pub fn state(&self) -> JobState {
+        self.progress.lock().unwrap().state
+    }
+}
+
+/// バックグラウンドのファイル操作キュー
+#[derive(Default)]
+pub struct JobQueue {
+    jobs: Vec<Job>,
+    next_id: u64,
+    /// これから追加するジョブのコピー設定
+    pub options: CopyOptions,
+}
+
+impl JobQueue {
+    /// ジョブを追加（実行枠が空けば `poll` で開始される）
+    pub fn enqueue(&mut self, kind: JobKind) -> u64 {
+        self.next_id += 1;
+        let (reply, reply_receiver) = mpsc::channel();
+        tracing::info!("ジョブを追加: {}", kind.description());
+        self.jobs.push(Job {
+            id: self.next_id,
+            kind,
+            options: self.options.clone(),
+            progress: Arc::new(Mutex::new(JobProgress::new())),
+            control: Arc::new(JobControl::default()),
+            reply,
+            reply_receiver: Some(reply_receiver),
+            done: None,
+        });
+        self.next_id
+    }
+
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    /// 実行中（未完了）のジョブがあるか
+    pub fn has_active(&self) -> bool {
+        self.jobs.iter().any(|job| !job.state().is_finished())
+    }
+
+    /// 待機中のジョブを開始し、終了したジョブの結果を返す
+    pub fn poll(&mut self) -> Vec<JobResult> {
+        let mut results = Vec::new();
+        for job in &mut self.jobs {
+            let Some(done) = &job.done else {
+                continue;
+            };
+            match done.try_recv() {
+                Ok(operations) => {
+                    results.push(JobResult {
+                        affected_dirs: job.kind.affected_dirs(),
+                        operations,
+                    });
+                    job.done = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    job.done = None;
+                    let mut progress = job.progress.lock().unwrap();
+                    progress.state = JobState::Cancelled;
+                    progress.errors.push("ジョブが異常終了しました".to_string());
+                }
+            }
+        }
+
+        let mut running = self.jobs.iter().filter(|job| job.done.is_some()).count();
+        for job in &mut self.jobs {
+            if job.reply_receiver.is_none() {
+                continue;
+            }
+            if job.control.cancelled.load(Ordering::Relaxed) {
+                job.reply_receiver = None;
+                job.progress.lock().unwrap().state = JobState::Cancelled;
+                continue;
+            }
+            if running >= MAX_RUNNING_JOBS {
+                continue;
+            }
+            let Some(reply_receiver) = job.reply_receiver.take() else {
+                continue;
+            };
+            let (sender, receiver) = mpsc::channel();
+            let mut options = job.options.clone();
+            if matches!(job.kind, JobKind::Move { .. }) {
+                // 移動ではリンクをリンクのまま移す
+                options.symlinks = SymlinkPolicy::Preserve;
+            }
+            let worker = Worker {
+                kind: job.kind.clone(),
+                options,
+                progress: job.progress.clone(),
+                control: job.control.clone(),
+                replies: reply_receiver,
+                apply_to_all: None,
+                active_time: Duration::ZERO,
+                active_since: None,
+            };
+            std::thread::spawn(move || {
+                let _ = sender.send(worker.run());
+            });
+            job.done = Some(receiver);
+            running += 1;
+        }
+        results
+    }
+
+    pub fn pause(&self, id: u64) {
+        if let Some(job) = self.find(id) {
+            job.control.paused.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn resume(&self, id: u64) {
+        if let Some(job) = self.find(id) {
+            job.control.paused.store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub fn cancel(&self, id: u64) {
+        if let Some(job) = self.find(id) {
+            job.control.cancelled.store(true, Ordering::Relaxed);
+            job.control.paused.store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_paused(&self, id: u64) -> bool {
+        self.find(id)
+            .is_some_and(|job| job.control.paused.load(Ordering::Relaxed))
+    }
+
+    /// 解決待ちの衝突（古いジョブから順に一件ずつ）
+    pub fn pending_conflict(&self) -> Option<(u64, Conflict)> {
+        self.jobs.iter().find_map(|job| {
+            let progress = job.progress.lock().unwrap();
+            progress.conflict.clone().map(|conflict| (job.id, conflict))
+        })
+    }
+
+    pub fn resolve_conflict(&self, id: u64, choice: ConflictChoice) {
+        if let Some(job) = self.find(id) {
+            job.progress.lock().unwrap().conflict = None;
+            let _ = job.reply.send(choice);
+        }
+    }
+
+    /// 完了・中止したジョブを一覧から取り除く
+    pub fn clear_finished(&mut self) {
+        self.jobs
+            .retain(|job| job.done.is_some() || !job.state().is_finished());
+    }
+
+    fn find(&self, id: u64) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+}
+
+/// 衝突した項目の扱い
+enum ConflictAction {
+    Overwrite,
+    Skip,
+    RenameTo(PathBuf),
+}
+
+/// ワーカースレッドで実行される処理
+struct Worker {
+    kind: JobKind,
+    options: CopyOptions,
+    progress: Arc<Mutex<JobProgress>>,
+    control: Arc<JobControl>,
+    replies: mpsc::Receiver<ConflictChoice>,
+    apply_to_all: Option<ConflictResolution>,
+    /// 一時停止・確認待ちを除いた経過時間
+    active_time: Duration,
+    active_since: Option<Instant>,
+}
+
+/// 中止を表す内部エラー
+struct Cancelled;
+
+impl Worker {
+    fn run(mut self) -> Vec<FileOperation> {
+        let mut operations = Vec::new();
+        self.set_state(JobState::Scanning);
+        let sources = match &self.kind {
+            JobKind::Copy { sources, .. } | JobKind::Move { sources, .. } => sources.clone(),
+            JobKind::Delete { paths, .. } => paths.clone(),
+        };
+        let (files, bytes) = sources
+            .iter()
+            .map(|path| scan(path))
+            .fold((0, 0), |(f, b), (f2, b2)| (f + f2, b + b2));
+        {
+            let mut progress = self.progress.lock().unwrap();
+            progress.files_total = files;
+            progress.bytes_total = bytes;
+        }
+        self.set_state(JobState::Running);
+        self.active_since = Some(Instant::now());
+
+        let result = match self.kind.clone() {
+            JobKind::Copy {
+                sources,
+                destination,
+            } => self.transfer_all(&sources, &destination, false, &mut operations),
+            JobKind::Move {
+                sources,
+                destination,
+            } => self.transfer_all(&sources, &destination, true, &mut operations),
+            JobKind::Delete { paths, permanent } => {
+                self.delete_all(&paths, permanent, &mut operations)
+            }
+        };
+
+        let mut progress = self.progress.lock().unwrap();
+        progress.current = None;
+        progress.conflict = None;
+        progress.state = match result {
+            Ok(()) => JobState::Completed,
+            Err(Cancelled) => JobState::Cancelled,
+        };
+        tracing::info!(
+            "ジョブ終了: {} ({}, エラー {} 件)",
+            self.kind.description(),
+            progress.state.label(),
+            progress.errors.len()
+        );
+        operations
+    }
+
+    fn transfer_all(
+        &mut self,
+        sources: &[PathBuf],
+        destination: &Path,
+        is_move: bool,
+        operations: &mut Vec<FileOperation>,
+    ) -> Result<(), Cancelled> {
+        for source in sources {
+            let Some(name) = source.file_name() else {
+                self.error(source, "無効なパス");
+                continue;
+            };
+            if destination.starts_with(source) {
+                self.error(
+                    source,
+                    "フォルダーをその中へコピー・移動することはできません",
+                );
+                continue;
+            }
+            let target = destination.join(name);
+            if target == *source {
+                if is_move {
+                    // 同じ場所への移動は何もしない
+                    self.skip(source);
+                    continue;
+                }
+                // 同じフォルダーへの貼り付けは別名で複製する
+                let renamed = unique_name(&target);
+                self.transfer_new(source, &renamed, is_move, operations)?;
+                continue;
+            }
+            self.transfer(source, &target, is_move, operations)?;
+        }
+        Ok(())
+    }
+
+    /// 項目をコピー・移動する（既存のフォルダーとは中身を統合する）
+    fn transfer(
+        &mut self,
+        source: &Path,
+        target: &Path,
+        is_move: bool,
+        operations: &mut Vec<FileOperation>,
+    ) -> Result<(), Cancelled> {
+        self.check_control()?;
+        let Ok(target_meta) = std::fs::symlink_metadata(target) else {
+            return self.transfer_new(source, target, is_move, operations);
+        };
+        let source_is_dir = std::fs::symlink_metadata(source).is_ok_and(|m| m.is_dir());
+
+        if source_is_dir && target_meta.is_dir() {
+            let entries = match std::fs::read_dir(source) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    self.error(source, &e.to_string());
+                    return Ok(());
+                }
+            };
+            for entry in entries.flatten() {
+                self.transfer(
+                    &entry.path(),
+                    &target.join(entry.file_name()),
+                    is_move,
+                    operations,
+                )?;
+            }
+            if is_move {
+                // 統合後に空になった移動元を片付ける
+                let _ = std::fs::remove_dir(source);
+            }
+            return Ok(());
+        }
+
+        match self.resolve_conflict(source, target)? {
+            ConflictAction::Skip => {
+                self.skip(source);
+                Ok(())
+            }
+            ConflictAction::RenameTo(renamed) => {
+                self.transfer_new(source, &renamed, is_move, operations)
+            }
+            ConflictAction::Overwrite => {
+                // 上書きされる項目はゴミ箱へ移しておき、元に戻せるようにする
+                match OperationJournal::move_to_trash(target) {
+                    Ok(trashed) => operations.push(FileOperation::Delete {
+                        path: target.to_path_buf(),
+                        trashed,
+                    }),
+                    Err(e) => {
+                        self.error(target, &e);
+                        self.skip(source);
+                        return Ok(());
+                    }
+                }
+                self.transfer_new(source, target, is_move, operations)
+            }
+        }
+    }
+
+    /// 存在しない場所へ項目をコピー・移動する
+    fn transfer_new(
+        &mut self,
+        source: &Path,
+        target: &Path,
+        is_move: bool,
+        operations: &mut Vec<FileOperation>,
+    ) -> Result<(), Cancelled> {
+        if is_move {
+            match std::fs::rename(source, target) {
+                Ok(()) => {
+                    let (files, bytes) = scan(target);
+                    self.advance(files, bytes);
+                    operations.push(FileOperation::Move {
+                        from: source.to_path_buf(),
+                        to: target.to_path_buf(),
+                    });
+                    return Ok(());
+                }
+                Err(e) if !copy::is_cross_device(&e) => {
+                    self.error(source, &e.to_string());
+                    self.skip(source);
+                    return Ok(());
+                }
+                Err(_) => {}
+            }
+        }
+
+        // 別のファイルシステムへの移動はコピーしてから削除する
+        match self.copy_tree(source, target) {
+            Ok(true) => {}
+            Ok(false) => {
+                // 一部だけコピーできた場合は移動元を残し、コピーとして記録する
+                if std::fs::symlink_metadata(target).is_ok() {
+                    operations.push(FileOperation::Copy {
+                        from: source.to_path_buf(),
+                        to: target.to_path_buf(),
+                    });
+                }
+                return Ok(());
+            }
+            Err(Cancelled) => {
+                // 途中まで書き込んだものは残さない
+                let _ = remove_path(target);
+                return Err(Cancelled);
+            }
+        }
+        if is_move {
+            if let Err(e) = remove_path(source) {
+                self.error(source, &format!("移動元を削除できません: {}", e));
+            }
+            operations.push(FileOperation::Move {
+                from: source.to_path_buf(),
+                to: target.to_path_buf(),
+            });
+        } else {
+            operations.push(FileOperation::Copy {
+                from: source.to_path_buf(),
+                to: target.to_path_buf(),
+            });
+        }
+        Ok(())
+    }
+
+    /// ツリーをコピー（一部でも失敗したら false）
+    fn copy_tree(&mut self, source: &Path, target: &Path) -> Result<bool, Cancelled> {
+        self.check_control()?;
+        let meta = match std::fs::symlink_metadata(source) {
+            Ok(meta) => meta,
+            Err(e) => {
+                self.error(source, &e.to_string());
+                return Ok(false);
+            }
+        };
+        let follow = meta.file_type().is_symlink()
+            && self.options.symlinks == SymlinkPolicy::Follow
+            && source.is_dir();
+
+        if meta.is_dir() || follow {
+            if let Err(e) = std::fs::create_dir(target) {
+                self.error(target, &e.to_string());
+                return Ok(false);
+            }
+            let entries = match std::fs::read_dir(source) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    self.error(source, &e.to_string());
+                    return Ok(false);
+                }
+            };
+            let mut complete = true;
+            for entry in entries.flatten() {
+                complete &= self.copy_tree(&entry.path(), &target.join(entry.file_name()))?;
+            }
+            // 子の書き込みで更新日時が変わるので最後に設定する
+            if let Err(e) = copy::copy_metadata(source, target, &self.options) {
+                self.error(target, &format!("属性をコピーできません: {}", e));
+            }
+            return Ok(complete);
+        }
+
+        let size = meta.len();
+        {
+            let mut progress = self.progress.lock().unwrap();
+            progress.current = Some(source.to_path_buf());
+            progress.current_size = size;
+            progress.current_done = 0;
+        }
+        let options = self.options.clone();
+        match copy::copy_file(source, target, &options, &mut |bytes| self.copied(bytes)) {
+            Ok(_) => {
+                self.finish_file(size);
+                Ok(true)
+            }
+            Err(FsError::Cancelled) => Err(Cancelled),
+            Err(e) => {
+                self.error(source, &e.to_string());
+                self.finish_file(size);
+                Ok(false)
+            }
+        }
+    }
+
+    /// コピーエンジンからの進捗（false を返すと中止）
+    fn copied(&mut self, bytes: u64) -> bool {
+        if self.check_control().is_err() {
+            return false;
+        }
+        let elapsed = self.elapsed();
+        let mut progress = self.progress.lock().unwrap();
+        progress.current_done += bytes;
+        progress.bytes_done += bytes;
+        progress.throughput = throughput(progress.bytes_done, elapsed);
+        true
+    }
+
+    fn delete_all(
+        &mut self,
+        paths: &[PathBuf],
+        permanent: bool,
+        operations: &mut Vec<FileOperation>,
+    ) -> Result<(), Cancelled> {
+        for path in paths {
+            self.check_control()?;
+            self.progress.lock().unwrap().current = Some(path.clone());
+            let (files, bytes) = scan(path);
+            if permanent {
+                match remove_path(path) {
+                    Ok(()) => tracing::info!("完全に削除しました: {:?}", path),
+                    Err(e) => self.error(path, &e.to_string()),
+                }
+            } else {
+                match OperationJournal::move_to_trash(path) {
+                    Ok(trashed) => {
+                        tracing::info!("ゴミ箱へ移動しました: {:?}", path);
+                        operations.push(FileOperation::Delete {
+                            path: path.clone(),
+                            trashed,
+                        });
+                    }
+                    Err(e) => self.error(path, &e),
+                }
+            }
+            self.advance(files, bytes);
+        }
+        Ok(())
+    }
+
+    /// 衝突の処理を決める（必要ならダイアログの応答を待つ）
+    fn resolve_conflict(
+        &mut self,
+        source: &Path,
+        target: &Path,
+    ) -> Result<ConflictAction, Cancelled> {
+        let source_meta = std::fs::symlink_metadata(source).ok();
+        let target_meta = std::fs::symlink_metadata(target).ok();
+        let resolution = match self.apply_to_all {
+            Some(resolution) => resolution,
+            None => {
+                let conflict = Conflict {
+                    source: source.to_path_buf(),
+                    destination: target.to_path_buf(),
+                    source_size: source_meta.as_ref().map_or(0, |m| m.len()),
+                    source_modified: source_meta.as_ref().and_then(|m| m.modified().ok()),
+                    destination_size: target_meta.as_ref().map_or(0, |m| m.len()),
+                    destination_modified: target_meta.as_ref().and_then(|m| m.modified().ok()),
+                };
+                let choice = self.ask(conflict)?;
+                if choice.apply_to_all {
+                    self.apply_to_all = Some(choice.resolution);
+                }
+                choice.resolution
+            }
+        };
+
+        Ok(match resolution {
+            ConflictResolution::Overwrite => ConflictAction::Overwrite,
+            ConflictResolution::Skip => ConflictAction::Skip,
+            ConflictResolution::Rename => ConflictAction::RenameTo(unique_name(target)),
+            ConflictResolution::NewerOnly => {
+                let source_modified = source_meta.and_then(|m| m.modified().ok());
+                let target_modified = target_meta.and_then(|m| m.modified().ok());
+                match (source_modified, target_modified) {
+                    (Some(s), Some(t)) if s > t => ConflictAction::Overwrite,
+                    _ => ConflictAction::Skip,
+                }
+            }
+        })
+    }
+
+    /// GUI に衝突を知らせて応答を待つ
+    fn ask(&mut self, conflict: Conflict) -> Result<ConflictChoice, Cancelled> {
+        self.pause_clock();
+        {
+            let mut progress = self.progress.lock().unwrap();
+            progress.conflict = Some(conflict);
+            progress.state = JobState::WaitingForUser;
+        }
+        let choice = loop {
+            if self.control.cancelled.load(Ordering::Relaxed) {
+                return Err(Cancelled);
+            }
+            match self.replies.recv_timeout(WAIT_INTERVAL) {
+                Ok(choice) => break choice,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Err(Cancelled),
+            }
+        };
+        self.set_state(JobState::Running);
+        self.resume_clock();
+        Ok(choice)
+    }
+
+    /// 一時停止中は待ち、中止されていればエラーを返す
+    fn check_control(&mut self) -> Result<(), Cancelled> {
+        if self.control.paused.load(Ordering::Relaxed) {
+            self.pause_clock();
+            self.set_state(JobState::Paused);
+            while self.control.paused.load(Ordering::Relaxed)
+                && !self.control.cancelled.load(Ordering::Relaxed)
+            {
+                std::thread::sleep(WAIT_INTERVAL);
+            }
+            self.set_state(JobState::Running);
+            self.resume_clock();
+        }
+        if self.control.cancelled.load(Ordering::Relaxed) {
+            return Err(Cancelled);
+        }
+        Ok(())
+    }
+
+    fn set_state(&self, state: JobState) {
+        self.progress.lock().unwrap().state = state;
+    }
+
+    fn pause_clock(&mut self) {
+        if let Some(since) = self.active_since.take() {
+            self.active_time += since.elapsed();
+        }
+    }
+
+    fn resume_clock(&mut self) {
+        self.active_since = Some(Instant::now());
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.active_time + self.active_since.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+
+    /// ファイルのコピーを終えた（失敗時は残りのバイト数も処理済みとする）
+    fn finish_file(&mut self, size: u64) {
+        let mut progress = self.progress.lock().unwrap();
+        let remaining = size.saturating_sub(progress.current_done);
+        progress.bytes_done += remaining;
+        progress.files_done += 1;
+        progress.current_done = 0;
+        progress.throughput = throughput(progress.bytes_done, self.elapsed());
+    }
+
+    /// 処理しなかった項目の分だけ進捗を進める
+    fn skip(&mut self, path: &Path) {
+        let (files, bytes) = scan(path);
+        self.advance(files, bytes);
+    }
+
+    fn advance(&mut self, files: u64, bytes: u64) {
+        let mut progress = self.progress.lock().unwrap();
+        progress.files_done += files;
+        progress.bytes_done += bytes;
+        progress.throughput = throughput(progress.bytes_done, self.elapsed());
+    }
+
+    fn error(&self, path: &Path, message: &str) {
+        tracing::warn!("ジョブのエラー: {:?}: {}", path, message);
+        self.progress
+            .lock()
+            .unwrap()
+            .errors
+            .push(format!("{}: {}", path.display(), message));
+    }
+}
+
+fn throughput(bytes: u64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        bytes as f64 / seconds
+    } else {
+        0.0
+    }
+}
+
+/// ファイル数と合計サイズを数える（シンボリックリンクはたどらない）
+fn scan(path: &Path) -> (u64, u64) {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return (0, 0);
+    };
+    if !meta.is_dir() {
+        return (1, meta.len());
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| scan(&entry.path()))
+                .fold((0, 0), |(f, b), (f2, b2)| (f + f2, b + b2))
+        })
+        .unwrap_or((0, 0))
+}
+
+/// 「名前 (2).拡張子」のように重ならない名前を作る
+fn unique_name(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| parent.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| std::fs::symlink_metadata(candidate).is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
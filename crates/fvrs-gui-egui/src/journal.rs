@@ -0,0 +1,334 @@
+use crate::state::FileOperation;
+use fvrs_core::copy::{self, CopyOptions};
+use fvrs_core::core::FsResult;
+use fvrs_core::trash;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 保持する操作の上限（超えた分は履歴から外すが、退避ファイルは残す）
+const MAX_ENTRIES: usize = 200;
+/// ジャーナルのファイル名（データフォルダー内）
+const JOURNAL_FILE: &str = "journal.json";
+/// システムのゴミ箱が使えない場合や、作成・コピーを元に戻した際の退避先（データフォルダー内）
+const TRASH_AREA_DIR: &str = "undo-trash";
+
+/// 退避先のフォルダー名を一意にするためのカウンター
+static TRASH_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+impl FileOperation {
+    /// 操作の説明（メニュー表示用）
+    pub fn description(&self) -> String {
+        let name = |path: &Path| {
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string())
+        };
+        match self {
+            FileOperation::Move { from, .. } => format!("移動: {}", name(from)),
+            FileOperation::Copy { to, .. } => format!("コピー: {}", name(to)),
+            FileOperation::Delete { path, .. } => format!("削除: {}", name(path)),
+            FileOperation::Restore { path, .. } => format!("復元: {}", name(path)),
+            FileOperation::Rename { from, to } => {
+                format!("名前の変更: {} → {}", name(from), name(to))
+            }
+            FileOperation::CreateFolder { path } => format!("フォルダ作成: {}", name(path)),
+            FileOperation::CreateFile { path } => format!("ファイル作成: {}", name(path)),
+        }
+    }
+
+    /// 操作で内容が変わるフォルダー（キャッシュの破棄用）
+    pub fn affected_dirs(&self) -> Vec<PathBuf> {
+        let paths: Vec<&PathBuf> = match self {
+            FileOperation::Move { from, to }
+            | FileOperation::Copy { from, to }
+            | FileOperation::Rename { from, to } => vec![from, to],
+            FileOperation::Delete { path, .. }
+            | FileOperation::Restore { path, .. }
+            | FileOperation::CreateFolder { path }
+            | FileOperation::CreateFile { path } => vec![path],
+        };
+        paths
+            .into_iter()
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .collect()
+    }
+}
+
+/// ファイル操作のジャーナル（元に戻す・やり直し）
+///
+/// 元に戻すと逆操作を実行し、その逆操作をやり直しスタックに積む。
+/// やり直しも同様に逆操作を実行するため、両方向で同じ処理を使う。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OperationJournal {
+    pub undo_stack: Vec<FileOperation>,
+    pub redo_stack: Vec<FileOperation>,
+    /// 直近の元に戻す・やり直しの結果
+    #[serde(skip)]
+    pub last_message: Option<String>,
+}
+
+impl OperationJournal {
+    /// 保存済みのジャーナルを読み込む（なければ空）
+    pub fn load() -> Self {
+        let Some(path) = journal_path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                tracing::warn!("ジャーナルの読み込みに失敗: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// ジャーナルを保存
+    fn save(&self) {
+        let Some(path) = journal_path() else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+                std::fs::write(&path, json)
+            });
+        if let Err(e) = result {
+            tracing::error!("ジャーナルの保存に失敗: {}", e);
+        }
+    }
+
+    /// 実行済みの操作を記録
+    pub fn record(&mut self, operation: FileOperation) {
+        tracing::debug!("操作を記録: {:?}", operation);
+        // 新しい操作でやり直しはできなくなる。
+        // 退避ファイルは他に残っていないことがあるため、ここでは削除せず
+        // 利用者が退避領域を空にするまで残す
+        self.redo_stack.clear();
+        self.undo_stack.push(operation);
+        if self.undo_stack.len() > MAX_ENTRIES {
+            let excess = self.undo_stack.len() - MAX_ENTRIES;
+            self.undo_stack.drain(..excess);
+        }
+        self.save();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// 直前の操作を元に戻し、実行した逆操作を返す
+    pub fn undo(&mut self) -> Result<FileOperation, String> {
+        let operation = self.undo_stack.pop().ok_or("元に戻す操作がありません")?;
+        match execute_inverse(&operation) {
+            Ok(inverse) => {
+                tracing::info!("元に戻しました: {}", operation.description());
+                self.last_message = Some(format!("元に戻しました（{}）", operation.description()));
+                self.redo_stack.push(inverse.clone());
+                self.save();
+                Ok(inverse)
+            }
+            Err(e) => {
+                tracing::error!("元に戻せません: {} ({})", operation.description(), e);
+                self.last_message = Some(format!("元に戻せません: {}", e));
+                self.undo_stack.push(operation);
+                Err(e)
+            }
+        }
+    }
+
+    /// 元に戻した操作をやり直し、実行した操作を返す
+    pub fn redo(&mut self) -> Result<FileOperation, String> {
+        let operation = self.redo_stack.pop().ok_or("やり直す操作がありません")?;
+        match execute_inverse(&operation) {
+            Ok(inverse) => {
+                tracing::info!("やり直しました: {}", inverse.description());
+                self.last_message = Some(format!("やり直しました（{}）", inverse.description()));
+                self.undo_stack.push(inverse.clone());
+                self.save();
+                Ok(inverse)
+            }
+            Err(e) => {
+                tracing::error!("やり直せません: {} ({})", operation.description(), e);
+                self.last_message = Some(format!("やり直せません: {}", e));
+                self.redo_stack.push(operation);
+                Err(e)
+            }
+        }
+    }
+
+    /// ファイルまたはフォルダーをゴミ箱へ移動し、移動先を返す
+    ///
+    /// システムのゴミ箱に対応していない環境では退避領域を使う
+    pub fn move_to_trash(path: &Path) -> Result<PathBuf, String> {
+        if trash::is_supported() {
+            trash::move_to_trash(path)
+                .map(|item| item.trashed_path)
+                .map_err(|e| format!("ゴミ箱へ移動できません: {}", e))
+        } else {
+            Self::move_to_trash_area(path)
+        }
+    }
+
+    /// ファイルまたはフォルダーを退避領域へ移動し、退避先を返す
+    pub fn move_to_trash_area(path: &Path) -> Result<PathBuf, String> {
+        let data_dir = fvrs_core::config::data_dir().ok_or("データフォルダーが見つかりません")?;
+        let name = path
+            .file_name()
+            .ok_or_else(|| format!("無効なパス: {}", path.display()))?;
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let slot = data_dir.join(TRASH_AREA_DIR).join(format!(
+            "{}-{}",
+            stamp,
+            TRASH_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&slot).map_err(|e| format!("退避先を作成できません: {}", e))?;
+
+        let trashed = slot.join(name);
+        if let Err(e) = move_path(path, &trashed) {
+            let _ = std::fs::remove_dir(&slot);
+            return Err(format!("退避できません: {}", e));
+        }
+        Ok(trashed)
+    }
+
+    /// 退避領域のフォルダー
+    pub fn trash_area_dir() -> Option<PathBuf> {
+        fvrs_core::config::data_dir().map(|dir| dir.join(TRASH_AREA_DIR))
+    }
+
+    /// 退避領域にある項目の数
+    pub fn trash_area_count() -> usize {
+        Self::trash_area_dir()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+            .map_or(0, |entries| entries.filter_map(Result::ok).count())
+    }
+
+    /// 退避領域を空にし、退避ファイルを参照していた操作を履歴から外す
+    ///
+    /// 戻り値は削除した項目の数
+    pub fn empty_trash_area(&mut self) -> Result<usize, String> {
+        let dir = Self::trash_area_dir().ok_or("データフォルダーが見つかりません")?;
+        let count = Self::trash_area_count();
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("退避領域を空にできません: {}", e))?;
+        }
+        self.undo_stack.retain(|operation| !uses_trash_area(operation));
+        self.redo_stack.retain(|operation| !uses_trash_area(operation));
+        self.save();
+        tracing::info!("退避領域を空にしました: {} 件", count);
+        Ok(count)
+    }
+}
+
+fn journal_path() -> Option<PathBuf> {
+    fvrs_core::config::data_dir().map(|dir| dir.join(JOURNAL_FILE))
+}
+
+/// 操作の逆操作を実行し、実行した操作を返す
+fn execute_inverse(operation: &FileOperation) -> Result<FileOperation, String> {
+    match operation {
+        FileOperation::Move { from, to } => {
+            move_checked(to, from)?;
+            Ok(FileOperation::Move {
+                from: to.clone(),
+                to: from.clone(),
+            })
+        }
+        FileOperation::Rename { from, to } => {
+            move_checked(to, from)?;
+            Ok(FileOperation::Rename {
+                from: to.clone(),
+                to: from.clone(),
+            })
+        }
+        FileOperation::Delete { path, trashed } => {
+            match trash::item_for_trashed_path(trashed) {
+                // システムのゴミ箱からは .trashinfo も合わせて取り除く
+                Some(item) => trash::restore_to(&item, path).map_err(|e| e.to_string())?,
+                None => {
+                    move_checked(trashed, path)?;
+                    if in_trash_area(trashed)
+                        && let Some(slot) = trashed.parent()
+                    {
+                        let _ = std::fs::remove_dir(slot);
+                    }
+                }
+            }
+            Ok(FileOperation::Restore {
+                trashed: trashed.clone(),
+                path: path.clone(),
+            })
+        }
+        FileOperation::Restore { trashed, path } if in_trash_area(trashed) => {
+            if let Some(slot) = trashed.parent() {
+                std::fs::create_dir_all(slot).map_err(|e| e.to_string())?;
+            }
+            move_checked(path, trashed)?;
+            Ok(FileOperation::Delete {
+                path: path.clone(),
+                trashed: trashed.clone(),
+            })
+        }
+        FileOperation::Restore { path, .. } => {
+            let trashed = OperationJournal::move_to_trash(path)?;
+            Ok(FileOperation::Delete {
+                path: path.clone(),
+                trashed,
+            })
+        }
+        // 作成・コピーしたものは退避領域へ移すので、やり直しで内容ごと戻せる
+        FileOperation::Copy { to: path, .. }
+        | FileOperation::CreateFolder { path }
+        | FileOperation::CreateFile { path } => {
+            if std::fs::symlink_metadata(path).is_err() {
+                return Err(format!("見つかりません: {}", path.display()));
+            }
+            let trashed = OperationJournal::move_to_trash_area(path)?;
+            Ok(FileOperation::Delete {
+                path: path.clone(),
+                trashed,
+            })
+        }
+    }
+}
+
+/// 移動先が空いていることを確認してから移動
+fn move_checked(from: &Path, to: &Path) -> Result<(), String> {
+    if std::fs::symlink_metadata(from).is_err() {
+        return Err(format!("見つかりません: {}", from.display()));
+    }
+    if std::fs::symlink_metadata(to).is_ok() {
+        return Err(format!("既に存在します: {}", to.display()));
+    }
+    move_path(from, to).map_err(|e| e.to_string())
+}
+
+/// 名前の変更で移動し、別のファイルシステムへはコピーしてから削除
+fn move_path(from: &Path, to: &Path) -> FsResult<()> {
+    copy::move_path(from, to, &CopyOptions::default(), &mut |_| true).map(|_| ())
+}
+
+/// 退避領域内のパスかどうか
+fn in_trash_area(path: &Path) -> bool {
+    OperationJournal::trash_area_dir().is_some_and(|dir| path.starts_with(dir))
+}
+
+/// 退避ファイルを参照する操作かどうか
+fn uses_trash_area(operation: &FileOperation) -> bool {
+    match operation {
+        FileOperation::Delete { trashed, .. } | FileOperation::Restore { trashed, .. } => {
+            in_trash_area(trashed)
+        }
+        _ => false,
+    }
+}
@@ -4,4 +4,22 @@ pub mod utils;
 pub mod ui;
 pub mod archive;
 pub mod file_info;
+pub mod executable_info;
+pub mod disk_usage;
+pub mod duplicates;
+pub mod journal;
+pub mod trash;
+pub mod jobs;
+pub mod listing;
+pub mod thumbnails;
+pub mod image_preview;
+pub mod paged_viewer;
+pub mod hex_editor;
+pub mod attribute_editor;
+pub mod split_join;
+pub mod history;
+pub mod bookmarks;
+pub mod search;
+pub mod file_index;
+pub mod smart_folders;
 pub mod file_ops; 
\ No newline at end of file
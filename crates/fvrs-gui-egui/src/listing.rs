@@ -0,0 +1,461 @@
+use crate::app::FileVisorApp;
+use crate::state::SortColumn;
+use fvrs_core::core::{FileEntry, FsError};
+use fvrs_core::listing::{self, entry_from_metadata};
+use fvrs_core::smart_folders::SmartFolder;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+/// この件数以下なら並べ替え・絞り込みをその場で行う（フォルダー移動時のちらつき防止）
+const SYNC_VIEW_LIMIT: usize = 5000;
+/// スマートフォルダーの結果を送るまとまりの大きさ
+const SEARCH_BATCH: usize = 256;
+/// スマートフォルダーの中身が変わってから検索し直すまでの間隔
+const REFRESH_DELAY: Duration = Duration::from_secs(2);
+/// スマートフォルダーの結果のうち、変更を監視するフォルダーの数の上限
+const MAX_WATCHED_DIRS: usize = 256;
+
+/// 読み込みスレッドからの通知
+enum LoadMessage {
+    Batch(Vec<FileEntry>),
+    Finished(Result<usize, String>),
+}
+
+/// 実行中の読み込み（破棄すると中止される）
+struct RunningLoad {
+    cancel: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<LoadMessage>,
+}
+
+impl Drop for RunningLoad {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// フォルダーの内容（バックグラウンドで少しずつ読み込まれる）
+pub struct DirectoryListing {
+    entries: Vec<Arc<FileEntry>>,
+    /// 名前から `entries` の位置を引く索引（変更通知の反映用）
+    positions: HashMap<OsString, usize>,
+    /// 内容が変わるたびに増える
+    generation: u64,
+    show_hidden: bool,
+    loading: Option<RunningLoad>,
+    error: Option<String>,
+    /// 読み込み中に通知された変更（完了後に反映）
+    deferred_changes: HashSet<PathBuf>,
+    /// スマートフォルダーなら検索の条件（フォルダーの読み込みの代わりに検索する）
+    smart_folder: Option<SmartFolder>,
+    /// 検索し直している間の結果（終わるまでは前回の結果を表示する）
+    refreshed: Option<Vec<Arc<FileEntry>>>,
+    /// 検索し直すよう求められた時刻
+    refresh_requested: Option<Instant>,
+    /// 結果のあるフォルダー（変更を監視する）
+    result_dirs: Vec<PathBuf>,
+}
+
+impl DirectoryListing {
+    /// 読み込みを開始
+    pub fn load(path: &Path, show_hidden: bool) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let path = path.to_path_buf();
+        let thread_cancel = Arc::clone(&cancel);
+        std::thread::spawn(move || {
+            let result = listing::read_directory(&path, show_hidden, &thread_cancel, |batch| {
+                let _ = sender.send(LoadMessage::Batch(batch));
+            });
+            let result = match result {
+                Ok(count) => Ok(count),
+                Err(FsError::Cancelled) => return,
+                Err(e) => Err(format!("ディレクトリアクセスエラー: {} ({})", e, path.display())),
+            };
+            let _ = sender.send(LoadMessage::Finished(result));
+        });
+
+        Self {
+            entries: Vec::new(),
+            positions: HashMap::new(),
+            generation: 0,
+            show_hidden,
+            loading: Some(RunningLoad { cancel, receiver }),
+            error: None,
+            deferred_changes: HashSet::new(),
+            smart_folder: None,
+            refreshed: None,
+            refresh_requested: None,
+            result_dirs: Vec::new(),
+        }
+    }
+
+    /// スマートフォルダーの検索を開始（隠しファイルは両方の指定で許されたときだけ含める）
+    pub fn search(folder: &SmartFolder, show_hidden: bool) -> Self {
+        let mut folder = folder.clone();
+        folder.options.include_hidden &= show_hidden;
+        Self {
+            entries: Vec::new(),
+            positions: HashMap::new(),
+            generation: 0,
+            show_hidden,
+            loading: Some(Self::start_search(&folder)),
+            error: None,
+            deferred_changes: HashSet::new(),
+            smart_folder: Some(folder),
+            refreshed: None,
+            refresh_requested: None,
+            result_dirs: Vec::new(),
+        }
+    }
+
+    fn start_search(folder: &SmartFolder) -> RunningLoad {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let folder = folder.clone();
+        let thread_cancel = Arc::clone(&cancel);
+        std::thread::spawn(move || {
+            let mut batch = Vec::new();
+            let result = folder.run(&thread_cancel, |hit| {
+                batch.push(hit.entry);
+                if batch.len() >= SEARCH_BATCH {
+                    let _ = sender.send(LoadMessage::Batch(std::mem::take(&mut batch)));
+                }
+            });
+            if !batch.is_empty() {
+                let _ = sender.send(LoadMessage::Batch(batch));
+            }
+            let result = match result {
+                Ok(summary) => Ok(summary.hits as usize),
+                Err(FsError::Cancelled) => return,
+                Err(e) => Err(format!("スマートフォルダーの検索エラー: {} ({})", e, folder.root.display())),
+            };
+            let _ = sender.send(LoadMessage::Finished(result));
+        });
+
+        RunningLoad { cancel, receiver }
+    }
+
+    /// 読み込まれた分を取り込む
+    pub fn poll(&mut self) {
+        self.start_requested_refresh();
+        let Some(loading) = &self.loading else {
+            return;
+        };
+
+        let mut finished = None;
+        for message in loading.receiver.try_iter() {
+            match message {
+                LoadMessage::Batch(batch) => match &mut self.refreshed {
+                    Some(refreshed) => refreshed.extend(batch.into_iter().map(Arc::new)),
+                    None => {
+                        let start = self.entries.len();
+                        self.entries.extend(batch.into_iter().map(Arc::new));
+                        index_entries(&mut self.positions, &self.entries, start);
+                        self.generation += 1;
+                    }
+                },
+                LoadMessage::Finished(result) => finished = Some(result),
+            }
+        }
+
+        if let Some(result) = finished {
+            self.loading = None;
+            if let Some(refreshed) = self.refreshed.take() {
+                self.entries = refreshed;
+                self.positions.clear();
+                index_entries(&mut self.positions, &self.entries, 0);
+                self.generation += 1;
+            }
+            match result {
+                Ok(_) => self.error = None,
+                Err(e) => {
+                    tracing::error!("{}", e);
+                    self.error = Some(e);
+                }
+            }
+            if self.smart_folder.is_some() {
+                self.collect_result_dirs();
+                return;
+            }
+            for path in std::mem::take(&mut self.deferred_changes) {
+                let metadata = std::fs::symlink_metadata(&path).ok();
+                self.apply_change(&path, metadata.as_ref());
+            }
+        }
+    }
+
+    /// スマートフォルダーの中身が変わったかもしれないので、少し待ってから検索し直す
+    pub fn request_refresh(&mut self) {
+        if self.smart_folder.is_some() && self.refresh_requested.is_none() {
+            self.refresh_requested = Some(Instant::now());
+        }
+    }
+
+    /// 求められていた検索し直しを始める（変更が続いても `REFRESH_DELAY` ごとに 1 回）
+    fn start_requested_refresh(&mut self) {
+        let due = self.refresh_requested.is_some_and(|requested| requested.elapsed() >= REFRESH_DELAY);
+        if !due || self.loading.is_some() {
+            return;
+        }
+        let Some(folder) = &self.smart_folder else {
+            return;
+        };
+        self.refresh_requested = None;
+        self.refreshed = Some(Vec::new());
+        self.loading = Some(Self::start_search(folder));
+    }
+
+    /// 検索し直しを待っている（再描画を続ける必要がある）
+    pub fn is_refresh_pending(&self) -> bool {
+        self.refresh_requested.is_some()
+    }
+
+    /// スマートフォルダーの結果が `path` の変更で変わりうるか
+    pub fn covers(&self, path: &Path) -> bool {
+        self.smart_folder.as_ref().is_some_and(|folder| path.starts_with(&folder.root))
+    }
+
+    /// スマートフォルダーの結果のあるフォルダー（検索したフォルダーを含む）
+    pub fn result_dirs(&self) -> &[PathBuf] {
+        &self.result_dirs
+    }
+
+    fn collect_result_dirs(&mut self) {
+        let mut dirs = HashSet::new();
+        if let Some(folder) = &self.smart_folder {
+            dirs.insert(folder.root.clone());
+        }
+        for entry in &self.entries {
+            if dirs.len() >= MAX_WATCHED_DIRS {
+                break;
+            }
+            if let Some(parent) = entry.path.parent() {
+                dirs.insert(parent.to_path_buf());
+            }
+        }
+        self.result_dirs = dirs.into_iter().collect();
+    }
+
+    /// 変更のあった項目を差し替える（metadata が None なら削除）
+    pub fn apply_change(&mut self, path: &Path, metadata: Option<&Metadata>) {
+        // 読み込み中は重複を避けるため完了後に反映する
+        if self.loading.is_some() {
+            self.deferred_changes.insert(path.to_path_buf());
+            return;
+        }
+
+        let Some(name) = path.file_name() else {
+            return;
+        };
+        let position = self.positions.get(name).copied();
+        let metadata = metadata.filter(|_| self.show_hidden || !name.to_string_lossy().starts_with('.'));
+
+        match (metadata, position) {
+            (Some(metadata), Some(index)) => {
+                self.entries[index] = Arc::new(entry_from_metadata(path, metadata));
+            }
+            (Some(metadata), None) => {
+                self.entries.push(Arc::new(entry_from_metadata(path, metadata)));
+                self.positions.insert(name.to_os_string(), self.entries.len() - 1);
+            }
+            // 並び順は表示時に決まるので末尾と入れ替えて消す
+            (None, Some(index)) => {
+                self.entries.swap_remove(index);
+                self.positions.remove(name);
+                if let Some(moved) = self.entries.get(index).and_then(|entry| entry.path.file_name()) {
+                    self.positions.insert(moved.to_os_string(), index);
+                }
+            }
+            (None, None) => return,
+        }
+        self.generation += 1;
+    }
+
+    /// 読み込み済みの項目（並び順は不定、「..」は含まない）
+    pub fn entries(&self) -> &[Arc<FileEntry>] {
+        &self.entries
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+/// 並べ替え・絞り込みの条件
+#[derive(Clone, PartialEq)]
+struct ViewKey {
+    path: PathBuf,
+    generation: u64,
+    sort_column: SortColumn,
+    ascending: bool,
+    query: String,
+}
+
+/// ペインに表示する並べ替え・絞り込み済みの一覧
+///
+/// 件数が多い場合はバックグラウンドで再計算し、終わるまでは前回の結果を表示する。
+#[derive(Default)]
+pub struct ListingView {
+    key: Option<ViewKey>,
+    rows: Vec<Arc<FileEntry>>,
+    pending: Option<(ViewKey, mpsc::Receiver<Vec<Arc<FileEntry>>>)>,
+}
+
+impl ListingView {
+    /// 条件が変わっていれば再計算する（件数が多ければ別スレッド）
+    pub fn update(
+        &mut self,
+        path: &Path,
+        listing: &DirectoryListing,
+        sort_column: &SortColumn,
+        ascending: bool,
+        query: &str,
+    ) {
+        // 終わった再計算を反映
+        if let Some((key, receiver)) = &self.pending {
+            match receiver.try_recv() {
+                Ok(rows) => {
+                    self.key = Some(key.clone());
+                    self.rows = rows;
+                    self.pending = None;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
+        let key = ViewKey {
+            path: path.to_path_buf(),
+            generation: listing.generation(),
+            sort_column: sort_column.clone(),
+            ascending,
+            query: query.to_lowercase(),
+        };
+        let waiting = self.pending.as_ref().is_some_and(|(pending, _)| *pending == key);
+        if self.key.as_ref() == Some(&key) || waiting {
+            return;
+        }
+
+        if listing.entries().len() <= SYNC_VIEW_LIMIT {
+            self.rows = build_rows(listing.entries().to_vec(), &key);
+            self.key = Some(key);
+            self.pending = None;
+        } else if self.pending.is_none() {
+            // 実行中の再計算が終わってから次を始める
+            let entries = listing.entries().to_vec();
+            let (sender, receiver) = mpsc::channel();
+            let thread_key = key.clone();
+            std::thread::spawn(move || {
+                let _ = sender.send(build_rows(entries, &thread_key));
+            });
+            self.pending = Some((key, receiver));
+        }
+    }
+
+    /// 表示する一覧（別のフォルダーの結果しかない場合は None）
+    pub fn rows(&self, path: &Path) -> Option<&[Arc<FileEntry>]> {
+        self.key
+            .as_ref()
+            .is_some_and(|key| key.path == path)
+            .then_some(self.rows.as_slice())
+    }
+
+    /// 再計算中かどうか
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+/// `start` 以降の項目を名前の索引に加える
+fn index_entries(positions: &mut HashMap<OsString, usize>, entries: &[Arc<FileEntry>], start: usize) {
+    for (index, entry) in entries.iter().enumerate().skip(start) {
+        if let Some(name) = entry.path.file_name() {
+            positions.insert(name.to_os_string(), index);
+        }
+    }
+}
+
+/// 絞り込んでから並べ替える
+fn build_rows(mut entries: Vec<Arc<FileEntry>>, key: &ViewKey) -> Vec<Arc<FileEntry>> {
+    if !key.query.is_empty() {
+        entries.retain(|entry| entry.name.to_lowercase().contains(&key.query));
+    }
+    FileVisorApp::sort_entries(&mut entries, &key.sort_column, key.ascending);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded(dir: &Path) -> DirectoryListing {
+        let mut listing = DirectoryListing::load(dir, false);
+        let started = Instant::now();
+        while listing.is_loading() {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(5));
+            listing.poll();
+        }
+        listing
+    }
+
+    fn names(listing: &DirectoryListing) -> Vec<String> {
+        let mut names: Vec<_> = listing.entries().iter().map(|entry| entry.name.clone()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn changes_update_the_name_index() {
+        let dir = std::env::temp_dir().join(format!("fvrs-listing-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["a", "b", "c"] {
+            std::fs::write(dir.join(name), name).unwrap();
+        }
+        let mut listing = loaded(&dir);
+        assert_eq!(names(&listing), ["a", "b", "c"]);
+
+        // 先頭を消すと末尾の項目が移動するので、索引もそれに合わせる
+        let first = listing.entries()[0].path.clone();
+        std::fs::remove_file(&first).unwrap();
+        listing.apply_change(&first, None);
+        for (index, entry) in listing.entries().iter().enumerate() {
+            assert_eq!(listing.positions[entry.path.file_name().unwrap()], index);
+        }
+
+        std::fs::write(dir.join("d"), "d").unwrap();
+        let metadata = std::fs::symlink_metadata(dir.join("d")).unwrap();
+        listing.apply_change(&dir.join("d"), Some(&metadata));
+        listing.apply_change(&dir.join("d"), Some(&metadata));
+        let moved = listing.entries()[0].path.clone();
+        listing.apply_change(&moved, None);
+        let gone = [first.file_name().unwrap(), moved.file_name().unwrap()];
+        let expected: Vec<_> = ["a", "b", "c", "d"]
+            .into_iter()
+            .filter(|name| !gone.contains(&std::ffi::OsStr::new(name)))
+            .collect();
+        assert_eq!(names(&listing), expected);
+        assert_eq!(listing.positions.len(), listing.entries().len());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
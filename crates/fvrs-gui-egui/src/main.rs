@@ -4,19 +4,41 @@ mod app;
 mod ui;
 mod archive;
 mod file_info;
+mod executable_info;
+mod disk_usage;
+mod duplicates;
+mod journal;
+mod trash;
+mod jobs;
+mod listing;
+mod thumbnails;
+mod image_preview;
+mod paged_viewer;
+mod hex_editor;
+mod attribute_editor;
+mod split_join;
+mod history;
+mod bookmarks;
+mod search;
+mod file_index;
+mod smart_folders;
 
 use std::path::PathBuf;
-use fvrs_core::core::FileEntry;
 
 use app::FileVisorApp;
-use state::{ViewMode, SortColumn, ActivePane};
-use ui::{FileListUI, DialogsUI, ShortcutHandler, FileViewerUI, FileInfoDialog, ExplorerTreeUI};
+use state::{ViewMode, SortColumn, ActivePane, PaneSide};
+use ui::{FileListUI, DialogsUI, ShortcutHandler, FileViewerUI, FileInfoDialog, ExplorerTreeUI, VolumeBar, DiskUsageUI, DuplicatesUI, TrashUI, JobsUI, ImagePreviewUI, HexEditorUI, AttributesDialog, SplitJoinDialog, HistoryUI, QuickJumpUI, BookmarksUI, SearchUI, IndexUI, SmartFoldersUI};
 
 impl eframe::App for FileVisorApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // パフォーマンス監視
         let frame_start = std::time::Instant::now();
 
+        // 外部でのファイル変更を一覧へ反映
+        self.poll_file_changes();
+        self.file_index.poll();
+        self.thumbnails.begin_frame(ctx);
+
         // キーボードショートカット
         ShortcutHandler::handle_shortcuts(self, ctx);
         
@@ -41,10 +63,25 @@ impl eframe::App for FileVisorApp {
                         }
                     });
                     ui.menu_button("コピー・移動", |ui| {
-                        if ui.button("コピー").clicked() { ui.close_menu(); }
-                        if ui.button("移動").clicked() { ui.close_menu(); }
+                        let has_selection = !self.state.pane().selected_items.is_empty();
+                        if ui.add_enabled(has_selection, egui::Button::new("コピー...").shortcut_text("C")).clicked() {
+                            self.show_transfer_dialog(false);
+                            ui.close_menu();
+                        }
+                        if ui.add_enabled(has_selection, egui::Button::new("移動...").shortcut_text("M")).clicked() {
+                            self.show_transfer_dialog(true);
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        if ui.button("ファイル操作の一覧").clicked() {
+                            self.state.show_jobs_panel = true;
+                            ui.close_menu();
+                        }
                     });
-                    if ui.button("属性の変更").clicked() { ui.close_menu(); }
+                    if ui.add_enabled(!self.state.pane().selected_items.is_empty(), egui::Button::new("属性の変更").shortcut_text("A")).clicked() {
+                        AttributesDialog::open(self);
+                        ui.close_menu();
+                    }
                     if ui.button("名前の変更").clicked() { ui.close_menu(); }
                     ui.separator();
                     if ui.button("削除").clicked() {
@@ -53,7 +90,10 @@ impl eframe::App for FileVisorApp {
                     }
                     if ui.button("一括削除").clicked() { ui.close_menu(); }
                     ui.separator();
-                    if ui.button("連結と分割").clicked() { ui.close_menu(); }
+                    if ui.add_enabled(!self.state.pane().selected_items.is_empty(), egui::Button::new("連結と分割").shortcut_text("H")).clicked() {
+                        SplitJoinDialog::open(self);
+                        ui.close_menu();
+                    }
                     ui.menu_button("圧縮書庫ファイルの操作", |ui| {
                         if ui.button("圧縮").clicked() { ui.close_menu(); }
                         if ui.button("展開").clicked() { ui.close_menu(); }
@@ -74,11 +114,49 @@ impl eframe::App for FileVisorApp {
 
                 // 編集メニュー
                 ui.menu_button("編集", |ui| {
-                    if ui.button("オブジェクトの切り取り").clicked() { ui.close_menu(); }
-                    if ui.button("オブジェクトのコピー").clicked() { ui.close_menu(); }
-                    if ui.button("オブジェクトの貼り付け").clicked() { ui.close_menu(); }
+                    let undo_text = self.journal.undo_stack.last()
+                        .map(|op| format!("元に戻す - {}", op.description()))
+                        .unwrap_or_else(|| "元に戻す".to_string());
+                    if ui.add_enabled(self.journal.can_undo(), egui::Button::new(undo_text).shortcut_text("Ctrl+Z")).clicked() {
+                        self.undo();
+                        ui.close_menu();
+                    }
+                    let redo_text = self.journal.redo_stack.last()
+                        .map(|op| format!("やり直し - {}", op.description()))
+                        .unwrap_or_else(|| "やり直し".to_string());
+                    if ui.add_enabled(self.journal.can_redo(), egui::Button::new(redo_text).shortcut_text("Ctrl+Y")).clicked() {
+                        self.redo();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    let has_selection = !self.state.pane().selected_items.is_empty();
+                    if ui.add_enabled(has_selection, egui::Button::new("オブジェクトの切り取り").shortcut_text("Ctrl+X")).clicked() {
+                        self.set_clipboard(true);
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(has_selection, egui::Button::new("オブジェクトのコピー").shortcut_text("Ctrl+C")).clicked() {
+                        self.set_clipboard(false);
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(self.state.clipboard.is_some(), egui::Button::new("オブジェクトの貼り付け").shortcut_text("Ctrl+V")).clicked() {
+                        self.paste_clipboard();
+                        ui.close_menu();
+                    }
                     if ui.button("ショートカットの貼り付け").clicked() { ui.close_menu(); }
                     ui.separator();
+                    if ui.add_enabled(has_selection, egui::Button::new("ゴミ箱へ移動").shortcut_text("Delete")).clicked() {
+                        self.show_delete_confirmation();
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(has_selection, egui::Button::new("完全に削除").shortcut_text("Shift+Delete")).clicked() {
+                        self.show_permanent_delete_confirmation();
+                        ui.close_menu();
+                    }
+                    if ui.button("🗑 ゴミ箱を開く").clicked() {
+                        TrashUI::open(self);
+                        ui.close_menu();
+                    }
+                    ui.separator();
                     if ui.button("パス名をコピー").clicked() { ui.close_menu(); }
                     ui.menu_button("すべて選択・選択を反転", |ui| {
                         if ui.button("すべて選択").clicked() { ui.close_menu(); }
@@ -95,6 +173,14 @@ impl eframe::App for FileVisorApp {
                     if ui.button("ボリュームラベル").clicked() { ui.close_menu(); }
                     if ui.button("ディスクフォーマット").clicked() { ui.close_menu(); }
                     if ui.button("チェックディスク").clicked() { ui.close_menu(); }
+                    if ui.button("ディスク使用量の分析").clicked() {
+                        DiskUsageUI::open(self);
+                        ui.close_menu();
+                    }
+                    if ui.button("重複ファイルの検索").clicked() {
+                        DuplicatesUI::open(self);
+                        ui.close_menu();
+                    }
                     ui.separator();
                     ui.menu_button("ディスクイメージの作成と復元", |ui| {
                         if ui.button("イメージ作成").clicked() { ui.close_menu(); }
@@ -116,7 +202,10 @@ impl eframe::App for FileVisorApp {
                         ui.close_menu();
                     }
                     if ui.button("指定のフォルダを開く").clicked() { ui.close_menu(); }
-                    if ui.button("検索").clicked() { ui.close_menu(); }
+                    if ui.add(egui::Button::new("検索").shortcut_text("F")).clicked() {
+                        SearchUI::open(self);
+                        ui.close_menu();
+                    }
                     ui.separator();
                     if ui.button("システムフォルダ").clicked() { ui.close_menu(); }
                     if ui.button("ごみ箱を空にする").clicked() { ui.close_menu(); }
@@ -124,7 +213,34 @@ impl eframe::App for FileVisorApp {
                     if ui.button("フォルダの同期").clicked() { ui.close_menu(); }
                     if ui.button("フォルダの同期スクリプト").clicked() { ui.close_menu(); }
                     ui.separator();
-                    if ui.button("履歴").clicked() { ui.close_menu(); }
+                    if ui.add(egui::Button::new("履歴").shortcut_text("G")).clicked() {
+                        HistoryUI::open(self);
+                        ui.close_menu();
+                    }
+                    if ui.add(egui::Button::new("フォルダーへすばやく移動").shortcut_text("Ctrl+P")).clicked() {
+                        QuickJumpUI::open(self);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.add(egui::Button::new("ブックマークに追加").shortcut_text("Ctrl+D")).clicked() {
+                        BookmarksUI::add_current(self);
+                        ui.close_menu();
+                    }
+                    let bookmarks = self.bookmarks.bookmarks.bookmarks().to_vec();
+                    ui.add_enabled_ui(!bookmarks.is_empty(), |ui| {
+                        ui.menu_button("ブックマーク", |ui| {
+                            for bookmark in bookmarks {
+                                let mut button = egui::Button::new(format!("{} {}", bookmark.icon, bookmark.name));
+                                if let Some(number) = bookmark.shortcut {
+                                    button = button.shortcut_text(format!("Ctrl+{}", number));
+                                }
+                                if ui.add(button).on_hover_text(bookmark.path.display().to_string()).clicked() {
+                                    self.navigate_to(bookmark.path);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
                 });
 
                 // 表示メニュー
@@ -134,6 +250,11 @@ impl eframe::App for FileVisorApp {
                         if ui.button("ステータスバー").clicked() { ui.close_menu(); }
                         if ui.button("アドレスバー").clicked() { ui.close_menu(); }
                     });
+                    let mut dual_pane = self.state.dual_pane;
+                    if ui.checkbox(&mut dual_pane, "2画面表示").changed() {
+                        self.set_dual_pane(dual_pane);
+                    }
+                    ui.checkbox(&mut self.state.show_image_preview, "画像プレビュー");
                     ui.menu_button("表示する種類やプロパティ", |ui| {
                         ui.radio_value(&mut self.state.view_mode, ViewMode::List, "リスト");
                         ui.radio_value(&mut self.state.view_mode, ViewMode::Grid, "グリッド");
@@ -141,25 +262,35 @@ impl eframe::App for FileVisorApp {
                     });
                     ui.menu_button("ソート方法", |ui| {
                         if ui.button("名前順").clicked() {
-                            self.state.sort_column = SortColumn::Name;
+                            self.state.pane_mut().sort_column = SortColumn::Name;
                             ui.close_menu();
                         }
                         if ui.button("サイズ順").clicked() {
-                            self.state.sort_column = SortColumn::Size;
+                            self.state.pane_mut().sort_column = SortColumn::Size;
                             ui.close_menu();
                         }
                         if ui.button("日付順").clicked() {
-                            self.state.sort_column = SortColumn::Modified;
+                            self.state.pane_mut().sort_column = SortColumn::Modified;
                             ui.close_menu();
                         }
                         if ui.button("種類順").clicked() {
-                            self.state.sort_column = SortColumn::Type;
+                            self.state.pane_mut().sort_column = SortColumn::Type;
                             ui.close_menu();
                         }
                     });
                     ui.separator();
                     if ui.button("アイコンの表示").clicked() { ui.close_menu(); }
-                    if ui.button("サムネイルサイズ").clicked() { ui.close_menu(); }
+                    ui.menu_button("サムネイルサイズ", |ui| {
+                        for (label, size) in [("小", 64.0), ("中", 96.0), ("大", 160.0), ("特大", 256.0)] {
+                            if ui.radio(self.state.grid_item_size == size, label).clicked() {
+                                self.state.grid_item_size = size;
+                                self.state.view_mode = ViewMode::Grid;
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        ui.add(egui::Slider::new(&mut self.state.grid_item_size, 48.0..=320.0).suffix(" px"));
+                    });
                     if ui.button("基本の表示スタイルを更新").clicked() { ui.close_menu(); }
                     ui.separator();
                     if ui.button("ファイル情報").clicked() { ui.close_menu(); }
@@ -179,24 +310,39 @@ impl eframe::App for FileVisorApp {
                 // ツールメニュー
                 ui.menu_button("ツール", |ui| {
                     if ui.button("ファイル閲覧").clicked() { 
-                        if let Some(selected_file) = self.state.selected_items.first().cloned() {
-                            if selected_file.is_file() {
-                                FileViewerUI::open_file_for_viewing(self, selected_file);
-                            }
+                        if let Some(selected_file) = self.state.pane().selected_items.first().cloned()
+                            && selected_file.is_file()
+                        {
+                            FileViewerUI::open_file_for_viewing(self, selected_file);
+                        }
+                        ui.close_menu(); 
+                    }
+                    if ui.button("バイナリ編集").clicked() { 
+                        if let Some(selected_file) = self.state.pane().selected_items.first().cloned()
+                            && selected_file.is_file()
+                        {
+                            HexEditorUI::open(self, selected_file);
                         }
                         ui.close_menu(); 
                     }
-                    if ui.button("バイナリ編集").clicked() { ui.close_menu(); }
                     if ui.button("エディタで編集").clicked() { 
-                        if let Some(selected_file) = self.state.selected_items.first().cloned() {
-                            if selected_file.is_file() {
-                                FileViewerUI::open_file_for_editing(self, selected_file);
-                            }
+                        if let Some(selected_file) = self.state.pane().selected_items.first().cloned()
+                            && selected_file.is_file()
+                        {
+                            FileViewerUI::open_file_for_editing(self, selected_file);
                         }
                         ui.close_menu(); 
                     }
                     ui.separator();
-                    if ui.button("ファイルから文字列を検索").clicked() { ui.close_menu(); }
+                    if ui.button("ファイルから文字列を検索").clicked() {
+                        SearchUI::open(self);
+                        self.file_search.kind = fvrs_core::core::EntryKind::Files;
+                        ui.close_menu();
+                    }
+                    if ui.button("ファイルインデックス...").clicked() {
+                        IndexUI::open(self);
+                        ui.close_menu();
+                    }
                     if ui.button("コマンドプロンプトを開く").clicked() { ui.close_menu(); }
                     ui.separator();
                     if ui.button("形式を指定してリスト出力").clicked() { ui.close_menu(); }
@@ -214,19 +360,39 @@ impl eframe::App for FileVisorApp {
                 // ウィンドウメニュー
                 ui.menu_button("ウィンドウ", |ui| {
                     if ui.button("最新の情報に更新").clicked() {
-                        self.directory_cache.remove(&self.state.current_path);
+                        self.directory_cache.remove(&self.state.pane().current_path);
                         ui.close_menu();
                     }
                     ui.separator();
                     if ui.button("新しいウィンドウ").clicked() { ui.close_menu(); }
-                    if ui.button("新しいタブ").clicked() { ui.close_menu(); }
+                    if ui.add(egui::Button::new("新しいタブ").shortcut_text("Ctrl+T")).clicked() {
+                        self.new_tab();
+                        ui.close_menu();
+                    }
+                    let has_other_tabs = self.state.tabs().tabs.len() > 1;
+                    if ui.add_enabled(has_other_tabs, egui::Button::new("タブを閉じる").shortcut_text("Ctrl+W")).clicked() {
+                        self.close_current_tab();
+                        ui.close_menu();
+                    }
                     if ui.button("最近開いたタブ").clicked() { ui.close_menu(); }
                     ui.separator();
                     if ui.button("タブグループ").clicked() { ui.close_menu(); }
                     if ui.button("タブを分離").clicked() { ui.close_menu(); }
                     ui.separator();
-                    if ui.button("前の・次のタブ").clicked() { ui.close_menu(); }
-                    if ui.button("他のタブを閉じる").clicked() { ui.close_menu(); }
+                    ui.menu_button("前の・次のタブ", |ui| {
+                        if ui.add_enabled(has_other_tabs, egui::Button::new("前のタブ").shortcut_text("Ctrl+Shift+Tab")).clicked() {
+                            self.cycle_tab(false);
+                            ui.close_menu();
+                        }
+                        if ui.add_enabled(has_other_tabs, egui::Button::new("次のタブ").shortcut_text("Ctrl+Tab")).clicked() {
+                            self.cycle_tab(true);
+                            ui.close_menu();
+                        }
+                    });
+                    if ui.add_enabled(has_other_tabs, egui::Button::new("他のタブを閉じる")).clicked() {
+                        self.close_other_tabs();
+                        ui.close_menu();
+                    }
                     ui.separator();
                     ui.menu_button("並べて表示・他", |ui| {
                         if ui.button("縦に並べて表示").clicked() { ui.close_menu(); }
@@ -249,8 +415,8 @@ impl eframe::App for FileVisorApp {
         egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 // ナビゲーションボタン
-                let back_enabled = self.state.history_position > 0;
-                let forward_enabled = self.state.history_position < self.state.navigation_history.len().saturating_sub(1);
+                let back_enabled = self.state.pane().can_go_back();
+                let forward_enabled = self.state.pane().can_go_forward();
                 
                 if ui.add_enabled(back_enabled, egui::Button::new("←")).clicked() {
                     self.go_back();
@@ -262,7 +428,13 @@ impl eframe::App for FileVisorApp {
                     self.go_up();
                 }
                 if ui.button("🔄").clicked() {
-                    self.directory_cache.remove(&self.state.current_path);
+                    self.directory_cache.remove(&self.state.pane().current_path);
+                }
+                if ui.selectable_label(self.state.dual_pane, "◫").on_hover_text("2画面表示").clicked() {
+                    self.set_dual_pane(!self.state.dual_pane);
+                }
+                if ui.selectable_label(self.state.show_image_preview, "🖼").on_hover_text("画像プレビュー").clicked() {
+                    self.state.show_image_preview = !self.state.show_image_preview;
                 }
 
                 ui.separator();
@@ -272,17 +444,20 @@ impl eframe::App for FileVisorApp {
                 let response = ui.add(
                     egui::TextEdit::singleline(&mut self.address_bar_text)
                         .desired_width(300.0)
-                );
+                ).on_hover_text("@名前 でブックマークしたフォルダーへ移動");
                 
                 if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    let path = PathBuf::from(&self.address_bar_text);
+                    // 「@名前」はブックマークのフォルダー
+                    let path = self.bookmarks.bookmarks.resolve(&self.address_bar_text)
+                        .unwrap_or_else(|_| PathBuf::from(&self.address_bar_text));
                     if path.exists() {
                         self.navigate_to(path);
                     }
                 }
 
                 if ui.button("移動").clicked() {
-                    let path = PathBuf::from(&self.address_bar_text);
+                    let path = self.bookmarks.bookmarks.resolve(&self.address_bar_text)
+                        .unwrap_or_else(|_| PathBuf::from(&self.address_bar_text));
                     if path.exists() {
                         self.navigate_to(path);
                     }
@@ -324,172 +499,62 @@ impl eframe::App for FileVisorApp {
                 });
         }
 
-        // メイン表示エリア（ファイルリスト）
+        // 画像プレビューパネル（右側）
+        ImagePreviewUI::show(ctx, self);
+
+        // メイン表示エリア（ファイルリスト、2画面表示では左右に並べる）
         egui::CentralPanel::default().show(ctx, |ui| {
-            // 表示するディレクトリを決定（左ペインの選択があればそれを使用、なければ現在のパス）
-            let display_path = self.state.sidebar_selected_item
-                .as_ref()
-                .unwrap_or(&self.state.current_path)
-                .clone();
-            
-            let search_query = self.state.search_query.clone();
-            
-            // entriesをクローンして所有権を取得し、借用の問題を回避
-            let entries = match self.load_directory(&display_path) {
-                Ok(entries) => entries.clone(),
-                Err(error_msg) => {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(50.0);
-                        ui.colored_label(egui::Color32::RED, "❌ ディレクトリアクセスエラー");
-                        ui.label(&error_msg);
-                        ui.add_space(10.0);
-                        
-                        ui.horizontal(|ui| {
-                            if ui.button("再試行").clicked() {
-                                self.directory_cache.remove(&display_path);
-                            }
-                            if ui.button("ホームに戻る").clicked() {
-                                if let Ok(home_dir) = std::env::home_dir().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Home directory not found")) {
-                                    self.navigate_to(home_dir);
-                                }
-                            }
-                            if ui.button("Cドライブに移動").clicked() {
-                                self.navigate_to(PathBuf::from("C:\\"));
-                            }
-                        });
-                        
-                        ui.add_space(10.0);
-                        ui.colored_label(egui::Color32::GRAY, "💡 ヒント: パスが存在するか、アクセス権限があるか確認してください");
-                    });
-                    return;
-                }
-            };
-            
-            // 親ディレクトリエントリを作成（ルートでない場合）
-            let mut all_entries = Vec::new();
-            if display_path.parent().is_some() {
-                let parent_entry = FileEntry {
-                    name: "..".to_string(),
-                    path: display_path.parent().unwrap().to_path_buf(),
-                    size: 0,
-                    is_dir: true,
-                    created: chrono::DateTime::from(std::time::SystemTime::UNIX_EPOCH),
-                    modified: chrono::DateTime::from(std::time::SystemTime::UNIX_EPOCH),
-                    extension: None,
-                };
-                all_entries.push(parent_entry);
-            }
-            
-            // エントリをソート：フォルダーを先に、その後ファイル
-            let mut sorted_entries = entries.clone();
-            sorted_entries.sort_by(|a, b| {
-                match (a.is_dir, b.is_dir) {
-                    (true, false) => std::cmp::Ordering::Less,   // フォルダーが先
-                    (false, true) => std::cmp::Ordering::Greater, // ファイルが後
-                    _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()), // 同じ種類なら名前順
-                }
-            });
-            
-            // ソートされたエントリを追加
-            all_entries.extend(sorted_entries);
-            
-            let filtered_entries: Vec<&FileEntry> = all_entries
-                .iter()
-                .filter(|entry| {
-                    search_query.is_empty() ||
-                    entry.name.to_lowercase().contains(&search_query.to_lowercase())
-                })
-                .collect();
-
-            // 借用チェッカー対応：必要な値をコピー
-            let view_mode = self.state.view_mode.clone();
-            let display_path_for_ui = display_path.clone();
-            
-            // ナビゲーション用の一時的な変数
-            let mut navigation_target: Option<PathBuf> = None;
-            let mut file_open_target: Option<PathBuf> = None;
-            let mut activate_main_pane = false;
-            let mut update_sidebar_selection: Option<PathBuf> = None;
-            
-            {
-                let mut navigate_callback = |path: PathBuf| {
-                    navigation_target = Some(path.clone());
-                    update_sidebar_selection = Some(path);
-                };
-                
-                let mut file_open_callback = |path: PathBuf| {
-                    file_open_target = Some(path);
-                };
-                
-                let mut pane_activate_callback = || {
-                    activate_main_pane = true;
-                };
-
-                FileListUI::show_file_list(
-                    ui,
-                    &filtered_entries,
-                    view_mode,
-                    &display_path_for_ui,
-                    &mut self.state.selected_items,
-                    &mut self.state.last_selected_index,
-                    &mut self.state.sort_column,
-                    &mut self.state.sort_ascending,
-                    &mut self.directory_cache,
-                    &mut navigate_callback,
-                    &mut file_open_callback,
-                    &self.state.active_pane,
-                    &mut pane_activate_callback,
-                );
-            }
-            
-            // サイドバー選択の更新
-            if let Some(path) = update_sidebar_selection {
-                self.state.sidebar_selected_item = Some(path);
-            }
-            
-            // ペインアクティブ化
-            if activate_main_pane {
-                self.state.active_pane = ActivePane::MainList;
-            }
-            
-            // ナビゲーションの実行
-            if let Some(target) = navigation_target {
-                self.navigate_to(target);
-            }
-            
-            // ファイル閲覧の実行
-            if let Some(target) = file_open_target {
-                // 圧縮ファイルかどうかをチェック
-                if crate::archive::ArchiveHandler::is_archive(&target) {
-                    tracing::info!("圧縮ファイルビューアを表示: {:?}", target);
-                    self.show_archive_viewer(target);
-                } else {
-                    FileViewerUI::open_file_for_viewing(self, target);
-                }
+            if self.state.dual_pane {
+                ui.columns(2, |columns| {
+                    FileListUI::show_pane(&mut columns[0], self, PaneSide::Left);
+                    FileListUI::show_pane(&mut columns[1], self, PaneSide::Right);
+                });
+            } else {
+                FileListUI::show_pane(ui, self, PaneSide::Left);
             }
         });
 
         // ステータスバー
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label(format!("📁 {}", self.state.current_path.display()));
+                ui.label(format!("📁 {}", self.state.pane().current_path.display()));
                 ui.separator();
                 
                 // 借用チェッカー対応：パスをコピーしてentriesをクローン
-                let current_path = self.state.current_path.clone();
-                if let Ok(entries) = self.load_directory(&current_path) {
-                    let entries = entries.clone();
-                    let dirs = entries.iter().filter(|e| e.is_dir).count();
-                    let files = entries.len() - dirs;
+                let current_path = self.state.pane().current_path.clone();
+                if let Ok(listing) = self.load_directory(&current_path) {
+                    let dirs = listing.entries().iter().filter(|e| e.is_dir).count();
+                    let files = listing.entries().len() - dirs;
                     ui.label(format!("📁 {} フォルダー, 📄 {} ファイル", dirs, files));
+                    if listing.is_loading() {
+                        ui.spinner();
+                        ui.label("読み込み中...");
+                    }
+                }
+                if let Some(message) = &self.journal.last_message {
+                    ui.separator();
+                    ui.label(message);
+                }
+                let active_jobs = self.jobs.jobs().iter().filter(|job| !job.state().is_finished()).count();
+                if active_jobs > 0 {
+                    ui.separator();
+                    if ui.link(format!("⏳ {} 件のファイル操作を実行中", active_jobs)).clicked() {
+                        self.state.show_jobs_panel = true;
+                    }
                 }
                 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if let Some(selected_count) = (!self.state.selected_items.is_empty()).then_some(self.state.selected_items.len()) {
+                    if let Some(selected_count) = (!self.state.pane().selected_items.is_empty()).then_some(self.state.pane().selected_items.len()) {
                         ui.label(format!("🔹 {} 個選択", selected_count));
                         ui.separator();
                     }
                     
+                    // ディスク使用率
+                    if let Some(volume) = self.current_volume_info() {
+                        VolumeBar::show(ui, volume, 180.0);
+                        ui.separator();
+                    }
+
                     // パフォーマンス情報
                     if !self.frame_time_history.is_empty() {
                         let avg_frame_time = self.frame_time_history.iter().sum::<f32>() / self.frame_time_history.len() as f32;
@@ -510,12 +575,14 @@ impl eframe::App for FileVisorApp {
             let mut cancel_callback = || {
                 cancel_requested = true;
             };
+            let current_path = self.state.pane().current_path.clone();
 
             DialogsUI::show_delete_dialog(
                 ctx,
                 &mut self.state.show_delete_dialog,
                 &self.state.delete_dialog_items,
-                &self.state.current_path,
+                self.state.delete_permanently,
+                &current_path,
                 &mut delete_callback,
                 &mut cancel_callback,
             );
@@ -603,9 +670,31 @@ impl eframe::App for FileVisorApp {
         
         // リネームダイアログ
         DialogsUI::show_rename_dialog(ctx, self);
+        DialogsUI::show_transfer_dialog(ctx, self);
         
         // ファイル情報ダイアログ
         FileInfoDialog::show(ctx, self);
+
+        // ディスク使用量ビュー
+        DiskUsageUI::show(ctx, self);
+        DuplicatesUI::show(ctx, self);
+        HexEditorUI::show(ctx, self);
+        AttributesDialog::show(ctx, self);
+        SplitJoinDialog::show(ctx, self);
+        HistoryUI::show(ctx, self);
+        QuickJumpUI::show(ctx, self);
+        BookmarksUI::show_editor(ctx, self);
+        SearchUI::show(ctx, self);
+        IndexUI::show(ctx, self);
+        SmartFoldersUI::show_editor(ctx, self);
+        TrashUI::show(ctx, self);
+        
+        // ファイル操作キュー（OS からドロップされたファイルは現在のフォルダーへコピー）
+        let dropped: Vec<PathBuf> = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|f| f.path.clone()).collect());
+        if !dropped.is_empty() && self.can_create_here() {
+            self.enqueue_job(jobs::JobKind::Copy { sources: dropped, destination: self.state.pane().current_path.clone() });
+        }
+        JobsUI::show(ctx, self);
         
         // ダイアログアクションの実行
         if delete_requested {
@@ -658,6 +747,8 @@ impl eframe::App for FileVisorApp {
         if let Ok(state_json) = serde_json::to_string(&self.state) {
             storage.set_string("app_state", state_json);
         }
+        self.history.save_if_modified();
+        self.file_index.save_if_modified();
     }
 }
 
@@ -0,0 +1,353 @@
+use fvrs_core::core::FsError;
+use fvrs_core::paged::{self, LineIndex};
+use fvrs_core::text::{self, TextEncoding};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+
+/// 1 行に表示する最大バイト数（超えた分は省略）
+const MAX_LINE_BYTES: usize = 16 * 1024;
+/// 文字コードの判定に使う先頭部分の大きさ
+const DETECT_SAMPLE: usize = 64 * 1024;
+/// ファイルの伸び縮みを確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+/// 16 進表示の 1 行のバイト数
+pub const HEX_ROW_BYTES: u64 = 16;
+
+/// 表示方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PagedMode {
+    Text,
+    Hex,
+}
+
+/// 実行中の行の索引作成（破棄すると中止される）
+struct RunningScan {
+    cancel: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<Result<(), String>>,
+}
+
+impl Drop for RunningScan {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 表示中の行のキャッシュの条件
+#[derive(Clone, PartialEq)]
+struct PageKey {
+    first: u64,
+    count: usize,
+    mode: PagedMode,
+    encoding: TextEncoding,
+    scanned: u64,
+    len: u64,
+}
+
+/// ファイル全体を読み込まずに見える部分だけを読む閲覧ビュー
+pub struct PagedViewer {
+    path: PathBuf,
+    file: Arc<File>,
+    len: u64,
+    index: Arc<Mutex<LineIndex>>,
+    scan: Option<RunningScan>,
+    last_check: Instant,
+    cache: Option<(PageKey, Vec<String>)>,
+    pub mode: PagedMode,
+    pub encoding: TextEncoding,
+    /// 先頭に表示する行（16 進表示では 16 バイト単位）
+    pub top: u64,
+    /// 横方向のずれ（ピクセル）
+    pub scroll_x: f32,
+    /// スクロールの端数（1 行未満の分を持ち越す）
+    pub scroll_remainder: f32,
+    /// 追記に合わせて末尾を表示し続ける（tail -f）
+    pub follow: bool,
+    pub goto_text: String,
+    pub error: Option<String>,
+}
+
+impl PagedViewer {
+    /// ファイルを開いて行の索引作成を始める
+    pub fn open(path: &Path, mode: PagedMode) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("ファイルを開けません: {} ({})", e, path.display()))?;
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let sample = paged::read_at(&file, 0, DETECT_SAMPLE).unwrap_or_default();
+
+        let mut viewer = Self {
+            path: path.to_path_buf(),
+            file: Arc::new(file),
+            len,
+            index: Arc::new(Mutex::new(LineIndex::default())),
+            scan: None,
+            last_check: Instant::now(),
+            cache: None,
+            mode,
+            encoding: text::detect_encoding(&sample),
+            top: 0,
+            scroll_x: 0.0,
+            scroll_remainder: 0.0,
+            follow: false,
+            goto_text: String::new(),
+            error: None,
+        };
+        // 改行が 2 バイトになる UTF-16 は行に分けられない
+        if viewer.mode == PagedMode::Text && !viewer.supports_text() {
+            viewer.mode = PagedMode::Hex;
+            viewer.error = Some("UTF-16 のファイルは 16 進表示のみです".to_string());
+        }
+        viewer.start_scan();
+        Ok(viewer)
+    }
+
+    /// 索引作成の完了とファイルの伸び縮みを確認する
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        if let Some(scan) = &self.scan {
+            match scan.receiver.try_recv() {
+                Ok(result) => {
+                    self.scan = None;
+                    match result {
+                        // 調べている間に伸びた分
+                        Ok(()) => self.start_scan(),
+                        Err(e) => {
+                            tracing::error!("{}", e);
+                            self.error = Some(e);
+                        }
+                    }
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.scan = None,
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint_after(Duration::from_millis(100));
+                }
+            }
+        }
+
+        if self.last_check.elapsed() >= CHECK_INTERVAL {
+            self.last_check = Instant::now();
+            self.check_length();
+        }
+        if self.follow {
+            ctx.request_repaint_after(CHECK_INTERVAL);
+        }
+    }
+
+    /// 表示する行（text は行、16 進表示は整形済みの行）
+    pub fn rows(&mut self, first: u64, count: usize) -> Vec<String> {
+        let key = PageKey {
+            first,
+            count,
+            mode: self.mode,
+            encoding: self.encoding,
+            scanned: self.lock_index().scanned(),
+            len: self.len,
+        };
+        if let Some((cached, rows)) = &self.cache
+            && *cached == key
+        {
+            return rows.clone();
+        }
+
+        let result = match self.mode {
+            PagedMode::Text => {
+                let index = self.lock_index();
+                paged::read_lines(&self.file, &index, first, count, MAX_LINE_BYTES).map(|lines| {
+                    lines
+                        .into_iter()
+                        .map(|line| {
+                            let mut decoded = text::decode(&line, Some(self.encoding)).text;
+                            if line.len() >= MAX_LINE_BYTES {
+                                decoded.push_str(" …");
+                            }
+                            decoded
+                        })
+                        .collect()
+                })
+            }
+            PagedMode::Hex => {
+                let offset = first.saturating_mul(HEX_ROW_BYTES);
+                let len = (count as u64 * HEX_ROW_BYTES).min(self.len.saturating_sub(offset)) as usize;
+                paged::read_at(&self.file, offset, len).map(|bytes| {
+                    bytes
+                        .chunks(HEX_ROW_BYTES as usize)
+                        .enumerate()
+                        .map(|(i, chunk)| format_hex_row(offset + i as u64 * HEX_ROW_BYTES, chunk))
+                        .collect()
+                })
+            }
+        };
+
+        let rows = result.unwrap_or_else(|e| {
+            self.error = Some(format!("読み込みエラー: {}", e));
+            Vec::new()
+        });
+        self.cache = Some((key, rows.clone()));
+        rows
+    }
+
+    /// 全体の行数（16 進表示では 16 バイト単位、テキストは索引作成済みの分）
+    pub fn row_count(&self) -> u64 {
+        match self.mode {
+            PagedMode::Text => self.lock_index().line_count(),
+            PagedMode::Hex => self.len.div_ceil(HEX_ROW_BYTES),
+        }
+    }
+
+    /// 行番号（1 から）かオフセット（0x で始まる 16 進、16 進表示では 10 進も）へ移動
+    pub fn goto(&mut self, input: &str) -> Result<(), String> {
+        let input = input.trim();
+        let offset = match input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+            Some(hex) => Some(u64::from_str_radix(hex, 16).map_err(|_| format!("オフセットが正しくありません: {}", input))?),
+            None if self.mode == PagedMode::Hex => {
+                Some(input.parse().map_err(|_| format!("オフセットが正しくありません: {}", input))?)
+            }
+            None => None,
+        };
+
+        self.follow = false;
+        match (self.mode, offset) {
+            (PagedMode::Hex, Some(offset)) => {
+                self.top = offset.min(self.len.saturating_sub(1)) / HEX_ROW_BYTES;
+            }
+            (PagedMode::Text, Some(offset)) => {
+                let index = self.lock_index();
+                let line = paged::line_at_offset(&self.file, &index, offset)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| "まだ索引が作成されていない位置です".to_string())?;
+                drop(index);
+                self.top = line;
+            }
+            (_, None) => {
+                let line: u64 = input.parse().map_err(|_| format!("行番号が正しくありません: {}", input))?;
+                let line = line.saturating_sub(1);
+                if line >= self.row_count() {
+                    return Err("まだ索引が作成されていない行です".to_string());
+                }
+                self.top = line;
+            }
+        }
+        Ok(())
+    }
+
+    /// 表示方法を切り替える（同じ位置が見えるように先頭を合わせる）
+    pub fn set_mode(&mut self, mode: PagedMode) {
+        if mode == self.mode || (mode == PagedMode::Text && !self.supports_text()) {
+            return;
+        }
+        let index = self.lock_index();
+        let top = match mode {
+            PagedMode::Hex => paged::line_offset(&self.file, &index, self.top)
+                .ok()
+                .flatten()
+                .map_or(0, |offset| offset / HEX_ROW_BYTES),
+            PagedMode::Text => paged::line_at_offset(&self.file, &index, self.top * HEX_ROW_BYTES)
+                .ok()
+                .flatten()
+                .unwrap_or(0),
+        };
+        drop(index);
+        self.top = top;
+        self.scroll_x = 0.0;
+        self.mode = mode;
+    }
+
+    /// 行に分けて表示できる文字コードか
+    pub fn supports_text(&self) -> bool {
+        !matches!(self.encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be)
+    }
+
+    /// ファイルの大きさ（最後に確認したとき）
+    pub fn file_size(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_indexing(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    /// 索引作成済みの割合
+    pub fn indexed_fraction(&self) -> f32 {
+        if self.len == 0 {
+            1.0
+        } else {
+            self.lock_index().scanned() as f32 / self.len as f32
+        }
+    }
+
+    /// ファイルが伸びていれば続きを索引に加え、縮んでいれば作り直す
+    fn check_length(&mut self) {
+        let Ok(metadata) = std::fs::metadata(&self.path) else {
+            return;
+        };
+        let len = metadata.len();
+        if len < self.lock_index().scanned() {
+            // 切り詰められたか置き換えられた（ログのローテーションなど）
+            self.scan = None;
+            match File::open(&self.path) {
+                Ok(file) => self.file = Arc::new(file),
+                Err(e) => {
+                    self.error = Some(format!("ファイルを開けません: {}", e));
+                    return;
+                }
+            }
+            self.index = Arc::new(Mutex::new(LineIndex::default()));
+            self.top = 0;
+        }
+        self.len = len;
+        self.start_scan();
+    }
+
+    /// 索引作成済みの位置からファイル末尾までを調べる
+    /// 進み具合は poll が定期的に再描画して表示する
+    fn start_scan(&mut self) {
+        // 実行中なら終わったときに続きを調べる
+        if self.scan.is_some() || self.lock_index().scanned() >= self.len {
+            return;
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let file = Arc::clone(&self.file);
+        let index = Arc::clone(&self.index);
+        let thread_cancel = Arc::clone(&cancel);
+        let end = self.len;
+        let path = self.path.clone();
+        std::thread::spawn(move || {
+            let result = match paged::scan_lines(&file, &index, end, &thread_cancel) {
+                Ok(()) => Ok(()),
+                Err(FsError::Cancelled) => return,
+                Err(e) => Err(format!("行の索引を作成できません: {} ({})", e, path.display())),
+            };
+            let _ = sender.send(result);
+        });
+        self.scan = Some(RunningScan { cancel, receiver });
+    }
+
+    fn lock_index(&self) -> std::sync::MutexGuard<'_, LineIndex> {
+        self.index.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 16 進表示の 1 行（オフセット、16 進、ASCII）
+fn format_hex_row(offset: u64, chunk: &[u8]) -> String {
+    let mut row = format!("{:010X}  ", offset);
+    for i in 0..HEX_ROW_BYTES as usize {
+        if i == 8 {
+            row.push(' '); // 8 バイト目で区切り
+        }
+        match chunk.get(i) {
+            Some(byte) => row.push_str(&format!("{:02X} ", byte)),
+            None => row.push_str("   "),
+        }
+    }
+    row.push(' ');
+    row.extend(chunk.iter().map(|&byte| {
+        if byte.is_ascii_graphic() || byte == b' ' {
+            byte as char
+        } else {
+            '.'
+        }
+    }));
+    row
+}
@@ -1,217 +1,365 @@
-use native_windows_gui as nwg;
-use native_windows_derive as nwd;
-use std::path::PathBuf;
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::sync::mpsc;
-use std::thread;
-use thiserror::Error;
-use walkdir::WalkDir;
-use regex::Regex;
-use std::fs;
-use std::io::Read;
-use std::collections::HashMap;
-
-use crate::Result;
-
-#[derive(Debug, Error)]
-pub enum SearchError {
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
-    
-    #[error("Invalid regex: {0}")]
-    InvalidRegex(#[from] regex::Error),
-    
-    #[error("Search cancelled")]
-    Cancelled,
+use crate::file_index::IndexService;
+use crate::split_join::SizeUnit;
+use fvrs_core::core::{EntryKind, FileCategory, FsError, SearchOptions};
+use fvrs_core::listing;
+use fvrs_core::search::{self, SearchHit, SearchSummary};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::Instant;
+
+/// 表示する結果の上限（これを超えたら検索を打ち切る）
+const MAX_HITS: usize = 10_000;
+/// 1 フレームで受け取る結果の上限（画面の更新を止めないため）
+const HITS_PER_FRAME: usize = 2_000;
+/// 一致行の前後に表示できる行数の上限
+pub const MAX_CONTEXT_LINES: usize = 10;
+
+/// 1 日の秒数
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// ファイル名の指定方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameSyntax {
+    /// ワイルドカード（`*.rs;*.toml`）
+    Glob,
+    /// 正規表現
+    Regex,
 }
 
-/// Search panel structure
-#[derive(Default, NwgUi)]
-pub struct SearchPanel {
-    #[nwg_control(parent: window, size: (780, 150), position: (10, 10))]
-    panel: nwg::Panel,
-    
-    #[nwg_control(parent: panel, text: "Search:", size: (60, 20), position: (10, 10))]
-    search_label: nwg::Label,
-    
-    #[nwg_control(parent: panel, size: (500, 20), position: (80, 10))]
-    search_input: nwg::TextInput,
-    
-    #[nwg_control(parent: panel, text: "Case sensitive", size: (100, 20), position: (590, 10))]
-    case_sensitive: nwg::CheckBox,
-    
-    #[nwg_control(parent: panel, text: "Use regex", size: (100, 20), position: (590, 40))]
-    use_regex: nwg::CheckBox,
-    
-    #[nwg_control(parent: panel, text: "Search in content", size: (120, 20), position: (590, 70))]
-    search_content: nwg::CheckBox,
-    
-    #[nwg_control(parent: panel, text: "Start", size: (80, 30), position: (690, 10))]
-    start_button: nwg::Button,
-    
-    #[nwg_control(parent: panel, text: "Stop", size: (80, 30), position: (690, 50))]
-    stop_button: nwg::Button,
-    
-    #[nwg_control(parent: panel, text: "Results: 0", size: (200, 20), position: (10, 120))]
-    results_label: nwg::Label,
-    
-    // Internal state
-    current_path: RefCell<PathBuf>,
-    search_running: RefCell<bool>,
-    search_results: RefCell<HashMap<PathBuf, Vec<(usize, String)>>>,
-    search_tx: RefCell<Option<mpsc::Sender<()>>>,
+/// 更新日時の条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifiedWithin {
+    Any,
+    Day,
+    Week,
+    Month,
+    Year,
+    /// 1 年以上前
+    OlderThanYear,
 }
 
-impl SearchPanel {
-    pub fn new() -> Result<Self> {
-        let mut panel = Self::default();
-        panel.build()?;
-        
-        // Initialize state
-        *panel.search_running.borrow_mut() = false;
-        *panel.search_results.borrow_mut() = HashMap::new();
-        
-        Ok(panel)
-    }
-    
-    /// Set the current path
-    pub fn set_path(&self, path: &PathBuf) {
-        *self.current_path.borrow_mut() = path.clone();
-    }
-    
-    /// Start the search
-    pub fn start_search(&self) -> Result<()> {
-        if *self.search_running.borrow() {
-            return Ok(());
+impl ModifiedWithin {
+    pub const ALL: [ModifiedWithin; 6] = [
+        ModifiedWithin::Any,
+        ModifiedWithin::Day,
+        ModifiedWithin::Week,
+        ModifiedWithin::Month,
+        ModifiedWithin::Year,
+        ModifiedWithin::OlderThanYear,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ModifiedWithin::Any => "指定なし",
+            ModifiedWithin::Day => "24 時間以内",
+            ModifiedWithin::Week => "1 週間以内",
+            ModifiedWithin::Month => "1 か月以内",
+            ModifiedWithin::Year => "1 年以内",
+            ModifiedWithin::OlderThanYear => "1 年以上前",
         }
-        
-        let pattern = self.search_input.text();
-        if pattern.is_empty() {
-            return Ok(());
+    }
+
+    /// 検索時点からの経過秒数の上限と下限（保存した検索でも「最近」の意味が変わらない）
+    pub fn ages(self) -> (Option<u64>, Option<u64>) {
+        match self {
+            ModifiedWithin::Any => (None, None),
+            ModifiedWithin::Day => (Some(DAY_SECS), None),
+            ModifiedWithin::Week => (Some(7 * DAY_SECS), None),
+            ModifiedWithin::Month => (Some(30 * DAY_SECS), None),
+            ModifiedWithin::Year => (Some(365 * DAY_SECS), None),
+            ModifiedWithin::OlderThanYear => (None, Some(365 * DAY_SECS)),
+        }
+    }
+
+    /// 検索条件の経過秒数に当たる選択肢（どれにも当たらなければ指定なし）
+    pub fn from_ages(max_age_secs: Option<u64>, min_age_secs: Option<u64>) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|modified| modified.ages() == (max_age_secs, min_age_secs))
+            .unwrap_or(ModifiedWithin::Any)
+    }
+}
+
+/// ファイルの種類の表示名
+pub fn category_label(category: FileCategory) -> &'static str {
+    match category {
+        FileCategory::Image => "画像",
+        FileCategory::Video => "動画",
+        FileCategory::Audio => "音声",
+        FileCategory::Document => "文書",
+        FileCategory::Archive => "圧縮ファイル",
+        FileCategory::Text => "テキスト・ソース",
+    }
+}
+
+/// 名前の入力を正規表現にする
+pub fn name_pattern(name: &str, syntax: NameSyntax) -> String {
+    let name = name.trim();
+    match syntax {
+        // 「*」や「?」のない語は名前の一部として探す
+        NameSyntax::Glob if !name.is_empty() && !name.contains(['*', '?', '[']) => regex::escape(name),
+        NameSyntax::Glob => search::glob_to_regex(name),
+        NameSyntax::Regex => name.to_string(),
+    }
+}
+
+/// 検索スレッドからの通知
+enum SearchMessage {
+    Hit(SearchHit),
+    Finished(Result<SearchSummary, String>),
+}
+
+/// 実行中の検索（破棄すると中止される）
+struct RunningSearch {
+    cancel: Arc<AtomicBool>,
+    /// 調べた項目の数（検索スレッドが更新する）
+    scanned: Arc<AtomicU64>,
+    receiver: mpsc::Receiver<SearchMessage>,
+    started: Instant,
+}
+
+impl Drop for RunningSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// ファイル検索ダイアログの状態（F キー）
+pub struct FileSearch {
+    /// 検索するフォルダー
+    pub root: PathBuf,
+    pub name: String,
+    pub syntax: NameSyntax,
+    /// ファイルの中身から探す文字列（空なら名前だけで探す）
+    pub content: String,
+    pub content_regex: bool,
+    pub case_sensitive: bool,
+    pub kind: EntryKind,
+    /// ファイルの種類（画像・動画など）
+    pub category: Option<FileCategory>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub size_unit: SizeUnit,
+    pub modified: ModifiedWithin,
+    /// 一致行の前後に表示する行数
+    pub context_lines: usize,
+    /// .gitignore などで除外された項目を飛ばす
+    pub respect_ignore: bool,
+    pub include_hidden: bool,
+    /// バイナリファイルの中身は探さない
+    pub skip_binary: bool,
+    /// 名前だけで探すときはファイルインデックスを使う
+    pub use_index: bool,
+    /// 最後の結果はインデックスから得た
+    pub from_index: bool,
+    pub hits: Vec<SearchHit>,
+    /// 最後に終わった検索の集計
+    pub summary: Option<SearchSummary>,
+    /// 最後の検索にかかった時間
+    pub elapsed: Option<std::time::Duration>,
+    pub truncated: bool,
+    pub error: Option<String>,
+    running: Option<RunningSearch>,
+}
+
+impl FileSearch {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            name: String::new(),
+            syntax: NameSyntax::Glob,
+            content: String::new(),
+            content_regex: false,
+            case_sensitive: false,
+            kind: EntryKind::Any,
+            category: None,
+            min_size: None,
+            max_size: None,
+            size_unit: SizeUnit::KiB,
+            modified: ModifiedWithin::Any,
+            context_lines: 0,
+            respect_ignore: true,
+            include_hidden: true,
+            skip_binary: true,
+            use_index: true,
+            from_index: false,
+            hits: Vec::new(),
+            summary: None,
+            elapsed: None,
+            truncated: false,
+            error: None,
+            running: None,
         }
-        
-        let case_sensitive = self.case_sensitive.checked();
-        let use_regex = self.use_regex.checked();
-        let search_content = self.search_content.checked();
-        let path = self.current_path.borrow().clone();
-        
-        // Create regex if needed
-        let regex = if use_regex {
-            Some(Regex::new(&pattern).map_err(|e| SearchError::InvalidRegex(e))?)
-        } else {
-            None
+    }
+
+    /// 入力から検索条件を作る
+    pub fn options(&self) -> SearchOptions {
+        let content = self.content.trim();
+        let content_pattern = match content {
+            "" => None,
+            content if self.content_regex => Some(content.to_string()),
+            content => Some(regex::escape(content)),
         };
-        
-        // Clear previous results
-        self.search_results.borrow_mut().clear();
-        self.results_label.set_text("Results: 0");
-        
-        // Create channel for cancellation
-        let (tx, rx) = mpsc::channel();
-        *self.search_tx.borrow_mut() = Some(tx);
-        *self.search_running.borrow_mut() = true;
-        
-        // Start search in a separate thread
-        let search_results = self.search_results.clone();
-        let results_label = self.results_label.handle.clone();
-        thread::spawn(move || {
-            let mut results = HashMap::new();
-            let mut count = 0;
-            
-            for entry in WalkDir::new(&path).into_iter().filter_map(|e| e.ok()) {
-                // Check for cancellation
-                if rx.try_recv().is_ok() {
-                    break;
-                }
-                
-                let path = entry.path();
-                if path.is_dir() {
-                    continue;
-                }
-                
-                // Search in filename
-                let name = path.file_name().unwrap().to_str().unwrap();
-                let name_to_check = if case_sensitive { name } else { &name.to_lowercase() };
-                
-                let matches = if let Some(ref regex) = regex {
-                    regex.is_match(name_to_check)
-                } else {
-                    name_to_check.contains(&pattern)
-                };
-                
-                if matches {
-                    results.insert(path.to_path_buf(), vec![(0, "Filename match".to_string())]);
-                    count += 1;
-                    nwg::dispatch_thread_events();
-                    nwg::Control::set_text(&results_label, &format!("Results: {}", count));
-                }
-                
-                // Search in content if enabled
-                if search_content && path.is_file() {
-                    if let Ok(mut file) = fs::File::open(path) {
-                        let mut content = String::new();
-                        if file.read_to_string(&mut content).is_ok() {
-                            let content_to_check = if case_sensitive { &content } else { &content.to_lowercase() };
-                            
-                            if let Some(ref regex) = regex {
-                                for (i, line) in content_to_check.lines().enumerate() {
-                                    if regex.is_match(line) {
-                                        results.entry(path.to_path_buf())
-                                            .or_insert_with(Vec::new)
-                                            .push((i + 1, line.trim().to_string()));
-                                        count += 1;
-                                        nwg::dispatch_thread_events();
-                                        nwg::Control::set_text(&results_label, &format!("Results: {}", count));
-                                    }
-                                }
-                            } else {
-                                for (i, line) in content_to_check.lines().enumerate() {
-                                    if line.contains(&pattern) {
-                                        results.entry(path.to_path_buf())
-                                            .or_insert_with(Vec::new)
-                                            .push((i + 1, line.trim().to_string()));
-                                        count += 1;
-                                        nwg::dispatch_thread_events();
-                                        nwg::Control::set_text(&results_label, &format!("Results: {}", count));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            
-            *search_results.borrow_mut() = results;
-            nwg::dispatch_thread_events();
-            nwg::Control::set_text(&results_label, &format!("Results: {}", count));
+        let (max_age_secs, min_age_secs) = self.modified.ages();
+        let unit = self.size_unit.bytes();
+        SearchOptions {
+            pattern: name_pattern(&self.name, self.syntax),
+            case_sensitive: self.case_sensitive,
+            max_results: Some(MAX_HITS),
+            content_pattern,
+            kind: self.kind,
+            min_size: self.min_size.map(|size| size * unit),
+            max_size: self.max_size.map(|size| size * unit),
+            max_age_secs,
+            min_age_secs,
+            categories: self.category.into_iter().collect(),
+            respect_ignore: self.respect_ignore,
+            include_hidden: self.include_hidden,
+            skip_binary: self.skip_binary,
+            context_lines: self.context_lines.min(MAX_CONTEXT_LINES),
+            ..SearchOptions::default()
+        }
+    }
+
+    /// インデックスで探せる条件か
+    pub fn can_use_index(&self, index: &IndexService) -> bool {
+        self.content.trim().is_empty() && index.covers(&self.root)
+    }
+
+    /// 検索を始める（実行中の検索は中止する）
+    pub fn start(&mut self, index: &IndexService) {
+        if !self.root.is_dir() {
+            self.error = Some(format!("フォルダーが見つかりません: {}", self.root.display()));
+            return;
+        }
+        let options = self.options();
+        if self.use_index && self.can_use_index(index) {
+            self.search_index(index, &options);
+            return;
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        let scanned = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = mpsc::channel();
+        let root = self.root.clone();
+        let thread_cancel = Arc::clone(&cancel);
+        let thread_scanned = Arc::clone(&scanned);
+        std::thread::spawn(move || {
+            let hit_sender = sender.clone();
+            let result = search::search_with_progress(&root, &options, &thread_cancel, &thread_scanned, |hit| {
+                let _ = hit_sender.send(SearchMessage::Hit(hit));
+            });
+            let result = match result {
+                Ok(summary) => Ok(summary),
+                Err(FsError::Cancelled) => return,
+                Err(e) => Err(format!("検索に失敗しました: {}", e)),
+            };
+            let _ = sender.send(SearchMessage::Finished(result));
         });
-        
-        Ok(())
-    }
-    
-    /// Stop the search
-    pub fn stop_search(&self) -> Result<()> {
-        if let Some(tx) = self.search_tx.borrow_mut().take() {
-            let _ = tx.send(());
+
+        tracing::info!("検索を開始: {:?} 名前={:?} 内容={:?}", self.root, self.name, self.content);
+        self.hits.clear();
+        self.summary = None;
+        self.elapsed = None;
+        self.truncated = false;
+        self.error = None;
+        self.from_index = false;
+        self.running = Some(RunningSearch { cancel, scanned, receiver, started: Instant::now() });
+    }
+
+    /// インデックスから探す（すぐに終わるので別スレッドは使わない）
+    fn search_index(&mut self, index: &IndexService, options: &SearchOptions) {
+        self.running = None;
+        let started = Instant::now();
+        tracing::info!("インデックスから検索: {:?} 名前={:?}", self.root, self.name);
+        let result = index.search(&self.root, options);
+        self.hits.clear();
+        self.error = None;
+        self.from_index = true;
+        match result {
+            Ok(entries) => {
+                // インデックスは古いことがあるので、今ある項目だけを最新の情報で返す
+                self.hits = entries
+                    .into_iter()
+                    .filter_map(|entry| {
+                        let metadata = std::fs::symlink_metadata(&entry.path).ok()?;
+                        Some(SearchHit {
+                            entry: listing::entry_from_metadata(&entry.path, &metadata),
+                            lines: Vec::new(),
+                            line_count: 0,
+                        })
+                    })
+                    .collect();
+                self.hits.sort_by(|a, b| a.entry.path.cmp(&b.entry.path));
+                self.truncated = self.hits.len() >= MAX_HITS;
+                self.summary = Some(SearchSummary {
+                    scanned: index.index().len() as u64,
+                    hits: self.hits.len() as u64,
+                    ..SearchSummary::default()
+                });
+            }
+            Err(e) => {
+                self.summary = None;
+                self.truncated = false;
+                self.error = Some(format!("検索に失敗しました: {}", e));
+            }
         }
-        *self.search_running.borrow_mut() = false;
-        Ok(())
+        self.elapsed = Some(started.elapsed());
     }
-    
-    /// Get the search results
-    pub fn get_results(&self) -> HashMap<PathBuf, Vec<(usize, String)>> {
-        self.search_results.borrow().clone()
+
+    pub fn cancel(&mut self) {
+        if let Some(running) = self.running.take() {
+            self.elapsed = Some(running.started.elapsed());
+            tracing::info!("検索を中止しました（{} 件）", self.hits.len());
+        }
     }
-    
-    /// Check if search is running
+
     pub fn is_running(&self) -> bool {
-        *self.search_running.borrow()
+        self.running.is_some()
     }
-}
 
-impl Drop for SearchPanel {
-    fn drop(&mut self) {
-        self.stop_search();
+    /// 実行中の検索の経過時間
+    pub fn running_time(&self) -> Option<std::time::Duration> {
+        self.running.as_ref().map(|running| running.started.elapsed())
+    }
+
+    /// 実行中の検索がこれまでに調べた項目の数
+    pub fn scanned(&self) -> u64 {
+        self.running.as_ref().map_or(0, |running| running.scanned.load(Ordering::Relaxed))
     }
-} 
\ No newline at end of file
+
+    /// 見つかった結果を受け取る
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        for _ in 0..HITS_PER_FRAME {
+            match running.receiver.try_recv() {
+                Ok(SearchMessage::Hit(hit)) => self.hits.push(hit),
+                Ok(SearchMessage::Finished(result)) => {
+                    self.elapsed = Some(running.started.elapsed());
+                    self.running = None;
+                    match result {
+                        Ok(summary) => {
+                            self.truncated = self.hits.len() >= MAX_HITS;
+                            self.summary = Some(summary);
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                    return;
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.running = None;
+                    return;
+                }
+            }
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+
+    /// 結果のパスを検索したフォルダーからの相対パスで表す
+    pub fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+}
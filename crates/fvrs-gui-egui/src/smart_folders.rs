@@ -0,0 +1,220 @@
+use crate::search::{FileSearch, ModifiedWithin, NameSyntax, name_pattern};
+use crate::split_join::SizeUnit;
+use fvrs_core::core::{EntryKind, FileCategory, SearchOptions};
+use fvrs_core::smart_folders::{SmartFolder, SmartFolders};
+use std::path::{Path, PathBuf};
+
+/// スマートフォルダーを開いたときのパス（「smart:名前」）の接頭辞
+pub const SMART_PATH_PREFIX: &str = "smart:";
+/// スマートフォルダーに表示する項目の上限
+const MAX_RESULTS: usize = 10_000;
+
+/// 選べるアイコン
+pub const ICONS: [&str; 8] = ["🔎", "⭐", "🕘", "🖼", "🎵", "🎬", "📄", "📦"];
+
+/// スマートフォルダーを表示するためのパス
+pub fn smart_path(name: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}", SMART_PATH_PREFIX, name))
+}
+
+/// `path` がスマートフォルダーのパスならその名前
+pub fn smart_name(path: &Path) -> Option<&str> {
+    path.to_str()?.strip_prefix(SMART_PATH_PREFIX)
+}
+
+/// スマートフォルダー（保存した検索、設定フォルダーに保存して CLI の `smart` でも使う）
+pub struct SmartFolderStore {
+    pub folders: SmartFolders,
+}
+
+impl SmartFolderStore {
+    /// 保存済みのスマートフォルダーを読み込む（なければ空）
+    pub fn load() -> Self {
+        let folders = match SmartFolders::default_path() {
+            Some(path) => SmartFolders::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("スマートフォルダーの読み込みに失敗: {}", e);
+                SmartFolders::default()
+            }),
+            None => SmartFolders::default(),
+        };
+        Self { folders }
+    }
+
+    /// パスが指すスマートフォルダー
+    pub fn by_path(&self, path: &Path) -> Option<&SmartFolder> {
+        smart_name(path).and_then(|name| self.folders.get(name))
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        if self.folders.remove(name).is_some() {
+            self.save();
+        }
+    }
+
+    /// 1 つ上か下へ移す
+    pub fn move_by_one(&mut self, name: &str, up: bool) {
+        match self.folders.move_by_one(name, up) {
+            Ok(()) => self.save(),
+            Err(e) => tracing::warn!("スマートフォルダーを移動できません: {}", e),
+        }
+    }
+
+    /// 編集内容を反映して保存する
+    pub fn apply(&mut self, editor: &SmartFolderEditor) -> Result<(), String> {
+        let folder = editor.to_folder();
+        if !folder.root.is_dir() {
+            return Err(format!("フォルダーが見つかりません: {}", folder.root.display()));
+        }
+        let result = match &editor.original {
+            Some(name) => self.folders.update(name, folder),
+            None => self.folders.add(folder),
+        };
+        result.map_err(|e| e.to_string())?;
+        self.save();
+        Ok(())
+    }
+
+    fn save(&self) {
+        let Some(path) = SmartFolders::default_path() else {
+            tracing::error!("設定フォルダーが見つからないため、スマートフォルダーを保存できません");
+            return;
+        };
+        if let Err(e) = self.folders.save(&path) {
+            tracing::error!("スマートフォルダーの保存に失敗: {}", e);
+        }
+    }
+}
+
+/// スマートフォルダーの追加・編集ダイアログの入力
+pub struct SmartFolderEditor {
+    /// 編集中のスマートフォルダーの名前（追加なら None）
+    pub original: Option<String>,
+    pub name: String,
+    pub root: String,
+    pub icon: String,
+    pub pattern: String,
+    pub syntax: NameSyntax,
+    pub content: String,
+    pub content_regex: bool,
+    pub case_sensitive: bool,
+    /// 拡張子（「;」区切り）
+    pub extensions: String,
+    pub categories: Vec<FileCategory>,
+    pub kind: EntryKind,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub size_unit: SizeUnit,
+    pub modified: ModifiedWithin,
+    pub respect_ignore: bool,
+    pub include_hidden: bool,
+    /// 入力欄にない条件（編集前の値を引き継ぐ）
+    base: SearchOptions,
+    pub error: Option<String>,
+}
+
+impl SmartFolderEditor {
+    /// 検索ダイアログの条件を保存する
+    pub fn from_search(folders: &SmartFolders, search: &FileSearch) -> Self {
+        let name = match search.name.trim() {
+            "" => "新しいスマートフォルダー",
+            name => name,
+        };
+        Self {
+            original: None,
+            name: folders.unique_name(name),
+            root: search.root.to_string_lossy().into_owned(),
+            icon: fvrs_core::smart_folders::DEFAULT_ICON.to_string(),
+            pattern: search.name.clone(),
+            syntax: search.syntax,
+            content: search.content.clone(),
+            content_regex: search.content_regex,
+            case_sensitive: search.case_sensitive,
+            extensions: String::new(),
+            categories: search.category.into_iter().collect(),
+            kind: search.kind,
+            min_size: search.min_size,
+            max_size: search.max_size,
+            size_unit: search.size_unit,
+            modified: search.modified,
+            respect_ignore: search.respect_ignore,
+            include_hidden: search.include_hidden,
+            base: search.options(),
+            error: None,
+        }
+    }
+
+    /// 保存済みの条件は正規表現なので、そのまま正規表現として編集する
+    pub fn edit(folder: &SmartFolder) -> Self {
+        let options = &folder.options;
+        // 上限・下限が両方割り切れるいちばん大きな単位で表示する
+        let size_unit = SizeUnit::ALL
+            .into_iter()
+            .rev()
+            .find(|unit| [options.min_size, options.max_size].iter().flatten().all(|size| size.is_multiple_of(unit.bytes())))
+            .unwrap_or(SizeUnit::KiB);
+        let in_unit = |size: u64| size.div_ceil(size_unit.bytes());
+        Self {
+            original: Some(folder.name.clone()),
+            name: folder.name.clone(),
+            root: folder.root.to_string_lossy().into_owned(),
+            icon: folder.icon.clone(),
+            pattern: options.pattern.clone(),
+            syntax: NameSyntax::Regex,
+            content: options.content_pattern.clone().unwrap_or_default(),
+            content_regex: true,
+            case_sensitive: options.case_sensitive,
+            extensions: options.extensions.as_ref().map(|extensions| extensions.join("; ")).unwrap_or_default(),
+            categories: options.categories.clone(),
+            kind: options.kind,
+            min_size: options.min_size.map(in_unit),
+            max_size: options.max_size.map(in_unit),
+            size_unit,
+            modified: ModifiedWithin::from_ages(options.max_age_secs, options.min_age_secs),
+            respect_ignore: options.respect_ignore,
+            include_hidden: options.include_hidden,
+            base: options.clone(),
+            error: None,
+        }
+    }
+
+    fn to_folder(&self) -> SmartFolder {
+        let content = self.content.trim();
+        let extensions: Vec<String> = self
+            .extensions
+            .split(';')
+            .map(|ext| ext.trim().trim_start_matches('.').to_string())
+            .filter(|ext| !ext.is_empty())
+            .collect();
+        let unit = self.size_unit.bytes();
+        let (max_age_secs, min_age_secs) = self.modified.ages();
+        let options = SearchOptions {
+            pattern: name_pattern(&self.pattern, self.syntax),
+            case_sensitive: self.case_sensitive,
+            max_results: Some(MAX_RESULTS),
+            content_pattern: match content {
+                "" => None,
+                content if self.content_regex => Some(content.to_string()),
+                content => Some(regex::escape(content)),
+            },
+            extensions: (!extensions.is_empty()).then_some(extensions),
+            categories: self.categories.clone(),
+            kind: self.kind,
+            min_size: self.min_size.map(|size| size * unit),
+            max_size: self.max_size.map(|size| size * unit),
+            modified_after: None,
+            modified_before: None,
+            max_age_secs,
+            min_age_secs,
+            respect_ignore: self.respect_ignore,
+            include_hidden: self.include_hidden,
+            context_lines: 0,
+            ..self.base.clone()
+        };
+        SmartFolder {
+            name: self.name.trim().to_string(),
+            root: PathBuf::from(self.root.trim()),
+            icon: self.icon.clone(),
+            options,
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use fvrs_core::core::{FsError, FsResult, HashAlgorithm};
+use fvrs_core::split::{self, PartStatus, SplitManifest, SplitMode, SplitOptions, SplitPart};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, mpsc};
+
+/// マニフェストに使えるハッシュ
+pub const ALGORITHMS: [HashAlgorithm; 6] = [
+    HashAlgorithm::SHA256,
+    HashAlgorithm::BLAKE3,
+    HashAlgorithm::SHA512,
+    HashAlgorithm::SHA1,
+    HashAlgorithm::MD5,
+    HashAlgorithm::RIPEMD160,
+];
+
+/// 表示中のタブ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitJoinTab {
+    Join,
+    Split,
+    Rejoin,
+}
+
+/// 分割の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    Size,
+    Lines,
+    Count,
+}
+
+/// 分割サイズの単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    KiB,
+    MiB,
+    GiB,
+}
+
+impl SizeUnit {
+    pub const ALL: [SizeUnit; 3] = [SizeUnit::KiB, SizeUnit::MiB, SizeUnit::GiB];
+
+    pub fn bytes(self) -> u64 {
+        match self {
+            SizeUnit::KiB => 1024,
+            SizeUnit::MiB => 1024 * 1024,
+            SizeUnit::GiB => 1024 * 1024 * 1024,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeUnit::KiB => "KB",
+            SizeUnit::MiB => "MB",
+            SizeUnit::GiB => "GB",
+        }
+    }
+}
+
+/// 別スレッドの処理の結果
+enum TaskResult {
+    Joined(PathBuf, u64),
+    Split(PathBuf),
+    Verified(Vec<(SplitPart, PartStatus)>),
+    Rejoined(PathBuf),
+}
+
+/// 実行中の処理（破棄すると中止される）
+struct RunningTask {
+    cancel: Arc<AtomicBool>,
+    done: Arc<AtomicU64>,
+    total: Arc<AtomicU64>,
+    label: &'static str,
+    receiver: mpsc::Receiver<Result<TaskResult, String>>,
+}
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 連結と分割ダイアログの状態
+pub struct SplitJoin {
+    pub tab: SplitJoinTab,
+    /// 出力先のフォルダー
+    pub directory: PathBuf,
+    /// 連結するファイル（この順に連結する）
+    pub join_sources: Vec<PathBuf>,
+    /// 連結したファイルの名前
+    pub join_output: String,
+    /// 分割するファイル
+    pub split_source: Option<PathBuf>,
+    pub split_by: SplitBy,
+    pub part_size: u64,
+    pub size_unit: SizeUnit,
+    pub part_lines: u64,
+    pub part_count: u32,
+    pub algorithm: HashAlgorithm,
+    /// 復元するマニフェスト
+    pub manifest_path: Option<PathBuf>,
+    pub manifest: Option<SplitManifest>,
+    /// 検証の結果
+    pub part_statuses: Option<Vec<(SplitPart, PartStatus)>>,
+    /// 復元したファイルの名前
+    pub rejoin_output: String,
+    task: Option<RunningTask>,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+impl SplitJoin {
+    /// 選択中の項目に合わせて開く
+    ///
+    /// マニフェストを含めば復元、ファイル 1 つなら分割、複数なら連結のタブを開く。
+    pub fn new(directory: PathBuf, selected: &[PathBuf]) -> Self {
+        let (manifests, mut files): (Vec<PathBuf>, Vec<PathBuf>) =
+            selected.iter().filter(|path| path.is_file()).cloned().partition(|path| split::is_manifest(path));
+        files.sort();
+
+        let mut dialog = Self {
+            tab: SplitJoinTab::Join,
+            directory,
+            join_output: join_output_name(&files),
+            join_sources: Vec::new(),
+            split_source: None,
+            split_by: SplitBy::Size,
+            part_size: 100,
+            size_unit: SizeUnit::MiB,
+            part_lines: 10000,
+            part_count: 2,
+            algorithm: HashAlgorithm::SHA256,
+            manifest_path: None,
+            manifest: None,
+            part_statuses: None,
+            rejoin_output: String::new(),
+            task: None,
+            status: None,
+            error: None,
+        };
+        match (manifests.as_slice(), files.as_slice()) {
+            ([manifest], _) => dialog.load_manifest(manifest.clone()),
+            (_, [path]) => {
+                dialog.tab = SplitJoinTab::Split;
+                dialog.split_source = Some(path.clone());
+            }
+            _ => {}
+        }
+        dialog.join_sources = files;
+        dialog
+    }
+
+    /// マニフェストを読み込んで復元のタブを開く
+    pub fn load_manifest(&mut self, path: PathBuf) {
+        self.tab = SplitJoinTab::Rejoin;
+        self.part_statuses = None;
+        match SplitManifest::load(&path) {
+            Ok(manifest) => {
+                self.rejoin_output = manifest.file_name.clone();
+                if let Some(dir) = path.parent() {
+                    self.directory = dir.to_path_buf();
+                }
+                self.manifest = Some(manifest);
+            }
+            Err(e) => {
+                self.manifest = None;
+                self.error = Some(format!("マニフェストを読み込めません: {}", e));
+            }
+        }
+        self.manifest_path = Some(path);
+    }
+
+    pub fn move_source(&mut self, index: usize, up: bool) {
+        let other = if up { index.checked_sub(1) } else { Some(index + 1) };
+        if let Some(other) = other.filter(|&other| other < self.join_sources.len()) {
+            self.join_sources.swap(index, other);
+        }
+    }
+
+    pub fn split_mode(&self) -> SplitMode {
+        match self.split_by {
+            SplitBy::Size => SplitMode::Size(self.part_size.saturating_mul(self.size_unit.bytes())),
+            SplitBy::Lines => SplitMode::Lines(self.part_lines),
+            SplitBy::Count => SplitMode::Count(self.part_count),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// 実行中の処理の名前と進み具合（0.0～1.0）
+    pub fn progress(&self) -> Option<(&'static str, f32)> {
+        self.task.as_ref().map(|task| {
+            let total = task.total.load(Ordering::Relaxed);
+            let done = task.done.load(Ordering::Relaxed);
+            let fraction = if total == 0 { 0.0 } else { done as f32 / total as f32 };
+            (task.label, fraction)
+        })
+    }
+
+    pub fn cancel(&mut self) {
+        if self.task.take().is_some() {
+            self.status = Some("中止しました".to_string());
+        }
+    }
+
+    /// 選んだ順にファイルを連結する
+    pub fn start_join(&mut self, ctx: &egui::Context) {
+        if self.join_sources.is_empty() {
+            self.error = Some("連結するファイルがありません".to_string());
+            return;
+        }
+        let output = match output_path(&self.directory, &self.join_output) {
+            Ok(output) => output,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        let sources = self.join_sources.clone();
+        self.spawn(ctx, "連結中", move |cancel, progress| {
+            split::join(&sources, &output, cancel, progress)
+                .map(|size| TaskResult::Joined(output.clone(), size))
+        });
+    }
+
+    /// ファイルを分割してマニフェストを書く
+    pub fn start_split(&mut self, ctx: &egui::Context) {
+        let Some(source) = self.split_source.clone() else {
+            return;
+        };
+        let options = SplitOptions {
+            mode: self.split_mode(),
+            algorithm: self.algorithm,
+            output_dir: Some(self.directory.clone()),
+        };
+        self.spawn(ctx, "分割中", move |cancel, progress| {
+            split::split(&source, &options, cancel, progress).map(TaskResult::Split)
+        });
+    }
+
+    /// 部品をマニフェストと照合する
+    pub fn start_verify(&mut self, ctx: &egui::Context) {
+        let Some(manifest_path) = self.manifest_path.clone() else {
+            return;
+        };
+        self.part_statuses = None;
+        self.spawn(ctx, "検証中", move |cancel, _| {
+            split::verify(&manifest_path, cancel).map(TaskResult::Verified)
+        });
+    }
+
+    /// 部品を照合してから元のファイルに戻す
+    pub fn start_rejoin(&mut self, ctx: &egui::Context) {
+        let Some(manifest_path) = self.manifest_path.clone() else {
+            return;
+        };
+        let output = match output_path(&self.directory, &self.rejoin_output) {
+            Ok(output) => output,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        self.spawn(ctx, "復元中", move |cancel, progress| {
+            split::rejoin(&manifest_path, &output, cancel, progress)
+                .map(|()| TaskResult::Rejoined(output.clone()))
+        });
+    }
+
+    fn spawn<F>(&mut self, ctx: &egui::Context, label: &'static str, work: F)
+    where
+        F: FnOnce(&AtomicBool, &mut dyn FnMut(u64, u64)) -> FsResult<TaskResult> + Send + 'static,
+    {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let done = Arc::new(AtomicU64::new(0));
+        let total = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = mpsc::channel();
+        let thread_cancel = Arc::clone(&cancel);
+        let thread_done = Arc::clone(&done);
+        let thread_total = Arc::clone(&total);
+        let repaint_ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let mut progress = |done: u64, total: u64| {
+                thread_done.store(done, Ordering::Relaxed);
+                thread_total.store(total, Ordering::Relaxed);
+            };
+            let result = match work(&thread_cancel, &mut progress) {
+                Ok(result) => Ok(result),
+                Err(FsError::Cancelled) => return,
+                Err(e) => Err(format!("{}に失敗しました: {}", label.trim_end_matches('中'), e)),
+            };
+            let _ = sender.send(result);
+            repaint_ctx.request_repaint();
+        });
+
+        self.status = None;
+        self.error = None;
+        self.task = Some(RunningTask { cancel, done, total, label, receiver });
+    }
+
+    /// 処理の終了を確認する
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        let Some(task) = &self.task else {
+            return;
+        };
+        let result = match task.receiver.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.task = None;
+                return;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                return;
+            }
+        };
+        self.task = None;
+
+        match result {
+            Ok(TaskResult::Joined(path, size)) => {
+                tracing::info!("ファイルを連結しました: {:?} ({} バイト)", path, size);
+                self.status = Some(format!("{} に連結しました", display_name(&path)));
+            }
+            Ok(TaskResult::Split(manifest_path)) => {
+                tracing::info!("ファイルを分割しました: {:?}", manifest_path);
+                let parts = SplitManifest::load(&manifest_path).map(|m| m.parts.len()).unwrap_or_default();
+                self.status =
+                    Some(format!("{} 個に分割し、{} を作成しました", parts, display_name(&manifest_path)));
+            }
+            Ok(TaskResult::Verified(statuses)) => {
+                let bad = statuses.iter().filter(|(_, status)| *status != PartStatus::Ok).count();
+                self.status = Some(if bad == 0 {
+                    "すべての部品が正常です".to_string()
+                } else {
+                    format!("{} 個の部品に問題があります", bad)
+                });
+                self.part_statuses = Some(statuses);
+            }
+            Ok(TaskResult::Rejoined(path)) => {
+                tracing::info!("分割したファイルを復元しました: {:?}", path);
+                self.status = Some(format!("{} を復元し、ハッシュが一致しました", display_name(&path)));
+            }
+            Err(e) => {
+                tracing::error!("{}", e);
+                self.error = Some(e);
+            }
+        }
+    }
+}
+
+/// 連結したファイルの既定の名前（部品の番号を除く）
+fn join_output_name(files: &[PathBuf]) -> String {
+    let Some(first) = files.first() else {
+        return String::new();
+    };
+    let name = display_name(first);
+    match name.rsplit_once('.') {
+        Some((stem, number)) if !stem.is_empty() && !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) => {
+            stem.to_string()
+        }
+        Some((stem, extension)) if !stem.is_empty() => format!("{}_連結.{}", stem, extension),
+        _ => format!("{}_連結", name),
+    }
+}
+
+/// 出力先フォルダーの中のパス
+fn output_path(directory: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return Err(format!("出力するファイル名が正しくありません: {}", name));
+    }
+    Ok(directory.join(name))
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
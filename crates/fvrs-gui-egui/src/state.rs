@@ -1,6 +1,8 @@
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
+use fvrs_core::disk_usage::DiskUsageSort;
+use fvrs_core::text::TextFormat;
 
 use crate::archive::{ArchiveEntry, ArchiveType};
 
@@ -10,67 +12,195 @@ pub enum ActivePane {
     MainList,
 }
 
+/// 2画面表示の左右どちらのファイル一覧か
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PaneSide {
+    Left,
+    Right,
+}
+
+impl PaneSide {
+    /// 反対側のペイン
+    pub fn other(self) -> Self {
+        match self {
+            PaneSide::Left => PaneSide::Right,
+            PaneSide::Right => PaneSide::Left,
+        }
+    }
+}
+
+/// ファイル一覧ペインごとの状態（パス・履歴・ソート・選択）
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AppState {
+#[serde(default)]
+pub struct PaneState {
     pub current_path: PathBuf,
     pub navigation_history: VecDeque<PathBuf>,
     pub history_position: usize,
-    pub search_query: String,
-    pub view_mode: ViewMode,
     pub sort_column: SortColumn,
     pub sort_ascending: bool,
     pub selected_items: Vec<PathBuf>,
     pub last_selected_index: Option<usize>,
+}
+
+/// 1 つのペインに開いているタブ（各タブがパス・履歴・ソート・選択を持つ）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaneTabs {
+    pub tabs: Vec<PaneState>,
+    pub active: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppState {
+    pub search_query: String,
+    pub view_mode: ViewMode,
+    /// グリッド表示の項目の大きさ（サムネイルもこの大きさで表示）
+    pub grid_item_size: f32,
     pub clipboard: Option<ClipboardOperation>,
     pub show_hidden: bool,
     pub sidebar_width: f32,
+    #[serde(skip)]
     pub show_delete_dialog: bool,
+    #[serde(skip)]
     pub delete_dialog_items: Vec<PathBuf>,
+    /// ゴミ箱を経由せずに削除する（Shift+Delete）
+    #[serde(skip)]
+    pub delete_permanently: bool,
+    #[serde(skip)]
     pub show_shortcuts_dialog: bool,
     
     // ペイン管理
     pub active_pane: ActivePane,
+    /// 左右 2 つのファイル一覧を並べて表示する
+    pub dual_pane: bool,
+    pub left_tabs: PaneTabs,
+    pub right_tabs: PaneTabs,
+    /// 操作対象のファイル一覧（1 画面表示では常に左）
+    pub active_side: PaneSide,
+    #[serde(skip)]
     pub sidebar_selected_item: Option<PathBuf>,
     pub sidebar_last_selected_index: Option<usize>,
     pub expanded_folders: std::collections::HashSet<PathBuf>,
     
     // ファイル閲覧・編集機能
+    #[serde(skip)]
     pub show_file_viewer: bool,
+    #[serde(skip)]
     pub viewed_file_path: Option<PathBuf>,
+    #[serde(skip)]
     pub viewed_file_content: String,
+    /// 開いているファイルの文字コードと改行コード（保存時もこの形式で書き込む）
+    #[serde(skip)]
+    pub viewed_file_format: TextFormat,
+    /// 読み込み・保存時の警告
+    #[serde(skip)]
+    pub viewed_file_message: Option<String>,
     pub file_viewer_width: f32,
+    #[serde(skip)]
     pub is_file_modified: bool,
+    #[serde(skip)]
     pub view_mode_text: bool, // true: 編集モード, false: 閲覧モード
     // 未保存変更確認ダイアログ
+    #[serde(skip)]
     pub show_unsaved_dialog: bool,
+    #[serde(skip)]
     pub pending_close_action: bool,
     // エディタオプション
     pub show_line_numbers: bool,
+    /// 言語に応じて色分けする
+    pub syntax_highlighting: bool,
     // 新規ファイル作成ダイアログ
+    #[serde(skip)]
     pub show_create_file_dialog: bool,
+    #[serde(skip)]
     pub new_file_name: String,
     // 新規フォルダ作成ダイアログ
+    #[serde(skip)]
     pub show_create_folder_dialog: bool,
+    #[serde(skip)]
     pub new_folder_name: String,
     
     // リネームダイアログ
+    #[serde(skip)]
     pub show_rename_dialog: bool,
+    #[serde(skip)]
     pub rename_new_name: String,
+    #[serde(skip)]
     pub rename_target_path: Option<PathBuf>,
     
+    // コピー・移動先の指定ダイアログ
+    #[serde(skip)]
+    pub show_transfer_dialog: bool,
+    #[serde(skip)]
+    pub transfer_is_move: bool,
+    #[serde(skip)]
+    pub transfer_destination: String,
+    
     // 圧縮ファイル関連
+    #[serde(skip)]
     pub show_unpack_dialog: bool,
+    #[serde(skip)]
     pub show_pack_dialog: bool,
+    #[serde(skip)]
     pub show_archive_viewer: bool,
+    #[serde(skip)]
     pub archive_entries: Vec<ArchiveEntry>,
+    #[serde(skip)]
     pub current_archive: Option<PathBuf>,
+    #[serde(skip)]
     pub unpack_destination: String,
+    #[serde(skip)]
     pub pack_filename: String,
     pub pack_format: ArchiveType,
     
     // ファイル情報ダイアログ
+    #[serde(skip)]
     pub show_file_info_dialog: bool,
+    #[serde(skip)]
     pub file_info_target: Option<PathBuf>,
+    pub file_info_tab: FileInfoTab,
+    
+    // ディスク使用量分析
+    #[serde(skip)]
+    pub show_disk_usage_view: bool,
+    pub disk_usage_show_treemap: bool,
+    pub disk_usage_sort: DiskUsageSort,
+    pub disk_usage_sort_ascending: bool,
+    pub disk_usage_one_file_system: bool,
+    
+    // 重複ファイルの検索
+    #[serde(skip)]
+    pub show_duplicates_view: bool,
+    pub duplicates_min_size: u64,
+    pub duplicates_include: String,
+    pub duplicates_exclude: String,
+    pub duplicates_hardlinks: bool,
+    
+    // ゴミ箱
+    #[serde(skip)]
+    pub show_trash_view: bool,
+
+    // フォルダーの履歴
+    #[serde(skip)]
+    pub show_history_view: bool,
+
+    // ファイルの検索
+    #[serde(skip)]
+    pub show_search_view: bool,
+
+    // ファイル名のインデックス
+    #[serde(skip)]
+    pub show_index_view: bool,
+    
+    // ファイル操作キュー
+    #[serde(skip)]
+    pub show_jobs_panel: bool,
+    /// コピー後に内容を照合する
+    pub copy_verify: bool,
+
+    // 画像プレビュー
+    pub show_image_preview: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -80,6 +210,13 @@ pub enum ViewMode {
     Details,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FileInfoTab {
+    General,
+    Details,
+    Security,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SortColumn {
     Name,
@@ -100,16 +237,221 @@ pub enum DragState {
     Dragging { items: Vec<PathBuf>, start_pos: egui::Pos2 },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FileOperation {
     Move { from: PathBuf, to: PathBuf },
     Copy { from: PathBuf, to: PathBuf },
-    Delete { path: PathBuf },
+    /// 削除（`trashed` は退避先）
+    Delete { path: PathBuf, trashed: PathBuf },
+    /// 退避先からの復元
+    Restore { trashed: PathBuf, path: PathBuf },
     Rename { from: PathBuf, to: PathBuf },
     CreateFolder { path: PathBuf },
+    CreateFile { path: PathBuf },
 }
 
-impl Default for AppState {
+impl PaneState {
+    /// 指定のフォルダーへ移動し、履歴に追加
+    pub fn navigate_to(&mut self, path: PathBuf) {
+        // 履歴管理
+        if self.history_position < self.navigation_history.len() {
+            self.navigation_history.truncate(self.history_position + 1);
+        }
+
+        self.navigation_history.push_back(path.clone());
+        self.history_position = self.navigation_history.len().saturating_sub(1);
+
+        // 履歴サイズ制限
+        if self.navigation_history.len() > 100 {
+            self.navigation_history.pop_front();
+            self.history_position = self.history_position.saturating_sub(1);
+        }
+
+        self.show_path(path);
+    }
+
+    /// 履歴を戻る（戻れなければ false）
+    pub fn go_back(&mut self) -> bool {
+        if self.history_position == 0 {
+            return false;
+        }
+        self.history_position -= 1;
+        self.show_history_entry()
+    }
+
+    /// 履歴を進む（進めなければ false）
+    pub fn go_forward(&mut self) -> bool {
+        if self.history_position >= self.navigation_history.len().saturating_sub(1) {
+            return false;
+        }
+        self.history_position += 1;
+        self.show_history_entry()
+    }
+
+    /// 履歴の指定の位置へ移動（範囲外なら false）
+    pub fn go_to_history(&mut self, position: usize) -> bool {
+        if position >= self.navigation_history.len() {
+            return false;
+        }
+        self.history_position = position;
+        self.show_history_entry()
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.history_position > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.history_position < self.navigation_history.len().saturating_sub(1)
+    }
+
+    fn show_history_entry(&mut self) -> bool {
+        match self.navigation_history.get(self.history_position) {
+            Some(path) => {
+                self.show_path(path.clone());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn show_path(&mut self, path: PathBuf) {
+        self.current_path = path;
+        self.selected_items.clear();
+        self.last_selected_index = None;
+    }
+}
+
+impl PaneTabs {
+    /// 表示中のタブ
+    pub fn current(&self) -> &PaneState {
+        &self.tabs[self.active_index()]
+    }
+
+    pub fn current_mut(&mut self) -> &mut PaneState {
+        let index = self.active_index();
+        &mut self.tabs[index]
+    }
+
+    /// 復元した状態が壊れていても範囲内に収める
+    fn active_index(&self) -> usize {
+        self.active.min(self.tabs.len() - 1)
+    }
+
+    /// 復元した状態にタブがなければ 1 つ開く
+    pub fn ensure_tab(&mut self) {
+        if self.tabs.is_empty() {
+            self.tabs.push(PaneState::default());
+            self.active = 0;
+        }
+    }
+
+    /// 表示中のタブの右に新しいタブを開いて切り替える
+    pub fn open(&mut self, pane: PaneState) {
+        let index = (self.active_index() + 1).min(self.tabs.len());
+        self.tabs.insert(index, pane);
+        self.active = index;
+    }
+
+    /// タブを閉じる（最後の 1 つは閉じない）
+    pub fn close(&mut self, index: usize) -> Option<PaneState> {
+        if self.tabs.len() <= 1 || index >= self.tabs.len() {
+            return None;
+        }
+        let closed = self.tabs.remove(index);
+        if self.active > index || self.active >= self.tabs.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+        Some(closed)
+    }
+
+    /// 表示中のタブ以外を閉じる
+    pub fn close_others(&mut self) {
+        let current = self.tabs.swap_remove(self.active_index());
+        self.tabs = vec![current];
+        self.active = 0;
+    }
+
+    /// 前後のタブへ切り替える（端では反対側へ回る）
+    pub fn cycle(&mut self, forward: bool) {
+        let len = self.tabs.len();
+        let index = self.active_index();
+        self.active = if forward { (index + 1) % len } else { (index + len - 1) % len };
+    }
+
+    /// タブの並べ替え（表示中のタブはそのまま追従する）
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        if from >= self.tabs.len() || to >= self.tabs.len() || from == to {
+            return;
+        }
+        let active = self.active_index();
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        self.active = if active == from {
+            to
+        } else if from < active && active <= to {
+            active - 1
+        } else if to <= active && active < from {
+            active + 1
+        } else {
+            active
+        };
+    }
+}
+
+impl AppState {
+    /// 操作対象のファイル一覧の状態（表示中のタブ）
+    pub fn pane(&self) -> &PaneState {
+        self.pane_at(self.active_side)
+    }
+
+    pub fn pane_mut(&mut self) -> &mut PaneState {
+        self.pane_at_mut(self.active_side)
+    }
+
+    /// 操作対象でない側のファイル一覧の状態
+    pub fn other_pane(&self) -> &PaneState {
+        self.pane_at(self.active_side.other())
+    }
+
+    pub fn pane_at(&self, side: PaneSide) -> &PaneState {
+        self.tabs_at(side).current()
+    }
+
+    pub fn pane_at_mut(&mut self, side: PaneSide) -> &mut PaneState {
+        self.tabs_at_mut(side).current_mut()
+    }
+
+    /// 操作対象のペインのタブ
+    pub fn tabs(&self) -> &PaneTabs {
+        self.tabs_at(self.active_side)
+    }
+
+    pub fn tabs_mut(&mut self) -> &mut PaneTabs {
+        self.tabs_at_mut(self.active_side)
+    }
+
+    pub fn tabs_at(&self, side: PaneSide) -> &PaneTabs {
+        match side {
+            PaneSide::Left => &self.left_tabs,
+            PaneSide::Right => &self.right_tabs,
+        }
+    }
+
+    pub fn tabs_at_mut(&mut self, side: PaneSide) -> &mut PaneTabs {
+        match side {
+            PaneSide::Left => &mut self.left_tabs,
+            PaneSide::Right => &mut self.right_tabs,
+        }
+    }
+
+    /// すべてのタブの状態（両方のペイン）
+    pub fn all_panes_mut(&mut self) -> impl Iterator<Item = &mut PaneState> {
+        self.left_tabs.tabs.iter_mut().chain(self.right_tabs.tabs.iter_mut())
+    }
+}
+
+impl Default for PaneState {
     fn default() -> Self {
         // より安全なデフォルトパス選択
         let default_path = std::env::current_dir()
@@ -127,21 +469,43 @@ impl Default for AppState {
             current_path: default_path,
             navigation_history: VecDeque::with_capacity(100),
             history_position: 0,
-            search_query: String::new(),
-            view_mode: ViewMode::Details,
             sort_column: SortColumn::Name,
             sort_ascending: true,
             selected_items: Vec::new(),
             last_selected_index: None,
+        }
+    }
+}
+
+impl Default for PaneTabs {
+    fn default() -> Self {
+        Self {
+            tabs: vec![PaneState::default()],
+            active: 0,
+        }
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            search_query: String::new(),
+            view_mode: ViewMode::Details,
+            grid_item_size: 96.0,
             clipboard: None,
             show_hidden: false,
             sidebar_width: 250.0,
             show_delete_dialog: false,
             delete_dialog_items: Vec::new(),
+            delete_permanently: false,
             show_shortcuts_dialog: false,
             
             // ペイン管理
             active_pane: ActivePane::MainList,
+            dual_pane: false,
+            left_tabs: PaneTabs::default(),
+            right_tabs: PaneTabs::default(),
+            active_side: PaneSide::Left,
             sidebar_selected_item: None,
             sidebar_last_selected_index: None,
             expanded_folders: std::collections::HashSet::new(),
@@ -150,6 +514,8 @@ impl Default for AppState {
             show_file_viewer: false,
             viewed_file_path: None,
             viewed_file_content: String::new(),
+            viewed_file_format: TextFormat::default(),
+            viewed_file_message: None,
             file_viewer_width: 800.0,
             is_file_modified: false,
             view_mode_text: false, // true: 編集モード, false: 閲覧モード
@@ -158,6 +524,7 @@ impl Default for AppState {
             pending_close_action: false,
             // エディタオプション
             show_line_numbers: false,
+            syntax_highlighting: true,
             // 新規ファイル作成ダイアログ
             show_create_file_dialog: false,
             new_file_name: String::new(),
@@ -170,6 +537,11 @@ impl Default for AppState {
             rename_new_name: String::new(),
             rename_target_path: None,
             
+            // コピー・移動先の指定ダイアログ
+            show_transfer_dialog: false,
+            transfer_is_move: false,
+            transfer_destination: String::new(),
+            
             // 圧縮ファイル関連
             show_unpack_dialog: false,
             show_pack_dialog: false,
@@ -183,6 +555,98 @@ impl Default for AppState {
             // ファイル情報ダイアログ
             show_file_info_dialog: false,
             file_info_target: None,
+            file_info_tab: FileInfoTab::General,
+            
+            // ディスク使用量分析
+            show_disk_usage_view: false,
+            disk_usage_show_treemap: true,
+            disk_usage_sort: DiskUsageSort::Size,
+            disk_usage_sort_ascending: false,
+            disk_usage_one_file_system: true,
+            
+            // 重複ファイルの検索
+            show_duplicates_view: false,
+            duplicates_min_size: 1,
+            duplicates_include: String::new(),
+            duplicates_exclude: String::new(),
+            duplicates_hardlinks: false,
+            
+            // ゴミ箱
+            show_trash_view: false,
+
+            // フォルダーの履歴
+            show_history_view: false,
+
+            // ファイルの検索
+            show_search_view: false,
+
+            // ファイル名のインデックス
+            show_index_view: false,
+            
+            // ファイル操作キュー
+            show_jobs_panel: false,
+            copy_verify: false,
+
+            // 画像プレビュー
+            show_image_preview: false,
+        }
+    }
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabs(names: &[&str], active: usize) -> PaneTabs {
+        let tabs = names
+            .iter()
+            .map(|name| PaneState { current_path: PathBuf::from(name), ..PaneState::default() })
+            .collect();
+        PaneTabs { tabs, active }
+    }
+
+    fn order(tabs: &PaneTabs) -> Vec<String> {
+        tabs.tabs.iter().map(|tab| tab.current_path.display().to_string()).collect()
+    }
+
+    #[test]
+    fn move_tab_keeps_the_active_tab() {
+        // (from, to, 並べ替え後, 表示中のタブ)
+        let cases = [
+            (0, 2, ["b", "c", "a", "d"], "b"),
+            (2, 0, ["c", "a", "b", "d"], "b"),
+            (1, 3, ["a", "c", "d", "b"], "b"),
+            (3, 1, ["a", "d", "b", "c"], "b"),
+            (2, 3, ["a", "b", "d", "c"], "b"),
+        ];
+        for (from, to, expected, active) in cases {
+            let mut tabs = tabs(&["a", "b", "c", "d"], 1);
+            tabs.move_tab(from, to);
+            assert_eq!(order(&tabs), expected, "{} -> {}", from, to);
+            assert_eq!(tabs.current().current_path, PathBuf::from(active), "{} -> {}", from, to);
         }
+
+        let mut tabs = tabs(&["a", "b"], 0);
+        tabs.move_tab(0, 5);
+        assert_eq!(order(&tabs), ["a", "b"]);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn active_index_is_clamped() {
+        let mut tabs = tabs(&["a", "b"], 7);
+        assert_eq!(tabs.current().current_path, PathBuf::from("b"));
+        tabs.cycle(true);
+        assert_eq!(tabs.active, 0);
+        tabs.cycle(false);
+        assert_eq!(tabs.active, 1);
+    }
+
+    #[test]
+    fn close_follows_the_active_tab() {
+        let mut tabs = tabs(&["a", "b", "c"], 2);
+        assert!(tabs.close(0).is_some());
+        assert_eq!(tabs.current().current_path, PathBuf::from("c"));
+        assert!(tabs.close(1).is_some());
+        assert_eq!(tabs.current().current_path, PathBuf::from("b"));
+        assert!(tabs.close(0).is_none());
+    }
+}
@@ -0,0 +1,170 @@
+use fvrs_core::thumbnail::{self, Thumbnail, ThumbnailSize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, mpsc};
+
+/// サムネイルを作るスレッド数
+const WORKERS: usize = 4;
+/// 保持するテクスチャの上限（超えたら長く表示されていないものから破棄）
+const MAX_TEXTURES: usize = 1000;
+
+/// ファイルの版ごとに区別する（更新されたファイルは作り直される）
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct ThumbnailKey {
+    path: PathBuf,
+    size: ThumbnailSize,
+    modified: i64,
+}
+
+enum ThumbnailState {
+    Pending,
+    Ready(egui::TextureHandle),
+    Failed,
+}
+
+/// 作成待ちの要求（新しいものから処理する）
+#[derive(Default)]
+struct RequestQueue {
+    requests: Mutex<VecDeque<ThumbnailKey>>,
+    available: Condvar,
+    shutdown: AtomicBool,
+}
+
+/// バックグラウンドで作ったサムネイルのテクスチャ
+pub struct ThumbnailCache {
+    entries: HashMap<ThumbnailKey, (ThumbnailState, u64)>,
+    queue: Arc<RequestQueue>,
+    sender: mpsc::Sender<(ThumbnailKey, Option<Thumbnail>)>,
+    receiver: mpsc::Receiver<(ThumbnailKey, Option<Thumbnail>)>,
+    workers_started: bool,
+    frame: u64,
+}
+
+impl Default for ThumbnailCache {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            entries: HashMap::new(),
+            queue: Arc::new(RequestQueue::default()),
+            sender,
+            receiver,
+            workers_started: false,
+            frame: 0,
+        }
+    }
+}
+
+impl Drop for ThumbnailCache {
+    fn drop(&mut self) {
+        // 待機に入る直前のスレッドにも確実に伝わるようロックしてから知らせる
+        let _requests = self.queue.requests.lock();
+        self.queue.shutdown.store(true, Ordering::Relaxed);
+        self.queue.available.notify_all();
+    }
+}
+
+impl ThumbnailCache {
+    /// フレームの始めに呼ぶ（完成したものをテクスチャにし、見えなくなった要求を取り消す）
+    pub fn begin_frame(&mut self, ctx: &egui::Context) {
+        for (key, thumbnail) in self.receiver.try_iter() {
+            let Some((state, _)) = self.entries.get_mut(&key) else {
+                continue;
+            };
+            *state = match thumbnail {
+                Some(thumbnail) => {
+                    let image = egui::ColorImage::from_rgba_unmultiplied(
+                        [thumbnail.width as usize, thumbnail.height as usize],
+                        &thumbnail.rgba,
+                    );
+                    let name = format!("thumbnail:{}", key.path.display());
+                    ThumbnailState::Ready(ctx.load_texture(name, image, egui::TextureOptions::LINEAR))
+                }
+                None => ThumbnailState::Failed,
+            };
+        }
+
+        // スクロールで見えなくなった項目は作らない
+        let previous = self.frame;
+        if let Ok(mut requests) = self.queue.requests.lock() {
+            requests.retain(|key| self.entries.get(key).is_some_and(|(_, used)| *used >= previous));
+        }
+        self.entries.retain(|_, (state, used)| !matches!(state, ThumbnailState::Pending) || *used >= previous);
+
+        if self.entries.len() > MAX_TEXTURES {
+            let mut used: Vec<u64> = self.entries.values().map(|(_, used)| *used).collect();
+            used.sort_unstable();
+            let threshold = used[self.entries.len() - MAX_TEXTURES];
+            self.entries.retain(|_, (_, used)| *used >= threshold);
+        }
+
+        self.frame += 1;
+    }
+
+    /// サムネイルを取得（まだなければ作成を依頼して None）
+    pub fn get(&mut self, ctx: &egui::Context, path: &Path, size: ThumbnailSize, modified: i64) -> Option<&egui::TextureHandle> {
+        let key = ThumbnailKey {
+            path: path.to_path_buf(),
+            size,
+            modified,
+        };
+        let frame = self.frame;
+        if !self.entries.contains_key(&key) {
+            self.start_workers(ctx);
+            if let Ok(mut requests) = self.queue.requests.lock() {
+                requests.push_front(key.clone());
+            }
+            self.queue.available.notify_one();
+            self.entries.insert(key.clone(), (ThumbnailState::Pending, frame));
+        }
+
+        let (state, used) = self.entries.get_mut(&key)?;
+        *used = frame;
+        match state {
+            ThumbnailState::Ready(texture) => Some(texture),
+            ThumbnailState::Pending | ThumbnailState::Failed => None,
+        }
+    }
+
+    fn start_workers(&mut self, ctx: &egui::Context) {
+        if self.workers_started {
+            return;
+        }
+        self.workers_started = true;
+
+        for _ in 0..WORKERS {
+            let queue = Arc::clone(&self.queue);
+            let sender = self.sender.clone();
+            let ctx = ctx.clone();
+            std::thread::spawn(move || {
+                while let Some(key) = next_request(&queue) {
+                    let result = match thumbnail::load_or_create(&key.path, key.size) {
+                        Ok(thumbnail) => Some(thumbnail),
+                        Err(e) => {
+                            tracing::debug!("サムネイルを作成できません: {:?} ({})", key.path, e);
+                            None
+                        }
+                    };
+                    if sender.send((key, result)).is_err() {
+                        return;
+                    }
+                    ctx.request_repaint();
+                }
+            });
+        }
+    }
+}
+
+/// 次の要求を待つ（終了時は None）
+fn next_request(queue: &RequestQueue) -> Option<ThumbnailKey> {
+    let mut requests = queue.requests.lock().ok()?;
+    loop {
+        if queue.shutdown.load(Ordering::Relaxed) {
+            return None;
+        }
+        if let Some(key) = requests.pop_front() {
+            return Some(key);
+        }
+        requests = queue.available.wait(requests).ok()?;
+    }
+}
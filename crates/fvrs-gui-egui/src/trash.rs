@@ -0,0 +1,153 @@
+use crate::journal::OperationJournal;
+use crate::state::FileOperation;
+use fvrs_core::trash::{self, TrashItem};
+use std::path::PathBuf;
+
+/// ゴミ箱ビューで確認待ちの操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrashConfirm {
+    /// 選択した項目を完全に削除
+    DeleteSelected,
+    /// ゴミ箱を空にする
+    Empty,
+    /// 元に戻す用の退避領域を空にする
+    EmptyTrashArea,
+}
+
+/// ゴミ箱の内容と選択状態
+#[derive(Default)]
+pub struct TrashBin {
+    pub items: Vec<TrashItem>,
+    /// 選択中の項目（`trashed_path` で識別）
+    pub selected: Vec<PathBuf>,
+    pub pending: Option<TrashConfirm>,
+    pub status: Option<String>,
+    pub error: Option<String>,
+    /// 元に戻す用の退避領域にある項目の数
+    pub trash_area_count: usize,
+    loaded: bool,
+}
+
+impl TrashBin {
+    /// ゴミ箱の内容を読み直す
+    pub fn refresh(&mut self) {
+        self.loaded = true;
+        self.trash_area_count = OperationJournal::trash_area_count();
+        if !trash::is_supported() {
+            return;
+        }
+        match trash::list() {
+            Ok(items) => {
+                self.selected
+                    .retain(|path| items.iter().any(|item| item.trashed_path == *path));
+                self.items = items;
+            }
+            Err(e) => {
+                self.items.clear();
+                self.selected.clear();
+                self.error = Some(format!("ゴミ箱を読み込めません: {}", e));
+            }
+        }
+    }
+
+    /// 未読み込みなら読み込む
+    pub fn ensure_loaded(&mut self) {
+        if !self.loaded {
+            self.refresh();
+        }
+    }
+
+    pub fn is_selected(&self, item: &TrashItem) -> bool {
+        self.selected.contains(&item.trashed_path)
+    }
+
+    pub fn toggle(&mut self, item: &TrashItem, additive: bool) {
+        let path = item.trashed_path.clone();
+        if !additive {
+            self.selected = vec![path];
+        } else if let Some(index) = self.selected.iter().position(|p| *p == path) {
+            self.selected.remove(index);
+        } else {
+            self.selected.push(path);
+        }
+    }
+
+    fn selected_items(&self) -> Vec<TrashItem> {
+        self.items
+            .iter()
+            .filter(|item| self.is_selected(item))
+            .cloned()
+            .collect()
+    }
+
+    /// 選択した項目を元の場所へ復元し、履歴に残す操作を返す
+    pub fn restore_selected(&mut self) -> Vec<FileOperation> {
+        let mut operations = Vec::new();
+        let mut failures = Vec::new();
+        for item in self.selected_items() {
+            match trash::restore(&item) {
+                Ok(path) => {
+                    tracing::info!("ゴミ箱から復元しました: {:?}", path);
+                    operations.push(FileOperation::Restore {
+                        trashed: item.trashed_path.clone(),
+                        path,
+                    });
+                }
+                Err(e) => failures.push(format!("{}: {}", item.name, e)),
+            }
+        }
+        self.finish("復元", operations.len(), failures);
+        operations
+    }
+
+    /// 選択した項目を完全に削除
+    pub fn delete_selected(&mut self) {
+        let mut deleted = 0;
+        let mut failures = Vec::new();
+        for item in self.selected_items() {
+            match trash::delete_permanently(&item) {
+                Ok(()) => deleted += 1,
+                Err(e) => failures.push(format!("{}: {}", item.name, e)),
+            }
+        }
+        self.finish("完全に削除", deleted, failures);
+    }
+
+    /// ゴミ箱を空にする
+    pub fn empty(&mut self) {
+        match trash::empty() {
+            Ok(count) => self.finish("ゴミ箱を空にする", count, Vec::new()),
+            Err(e) => {
+                self.error = Some(format!("ゴミ箱を空にできません: {}", e));
+                self.refresh();
+            }
+        }
+    }
+
+    /// 元に戻す用の退避領域を空にする
+    pub fn empty_trash_area(&mut self, journal: &mut OperationJournal) {
+        match journal.empty_trash_area() {
+            Ok(count) => self.finish("退避領域を空にする", count, Vec::new()),
+            Err(e) => {
+                self.error = Some(e);
+                self.refresh();
+            }
+        }
+    }
+
+    fn finish(&mut self, label: &str, count: usize, failures: Vec<String>) {
+        tracing::info!("ゴミ箱: {} {} 件", label, count);
+        self.status = Some(format!("{}: {} 件", label, count));
+        self.error = if failures.is_empty() {
+            None
+        } else {
+            tracing::warn!("ゴミ箱の操作に失敗: {:?}", failures);
+            Some(format!(
+                "{} 件を処理できませんでした: {}",
+                failures.len(),
+                failures.join(", ")
+            ))
+        };
+        self.refresh();
+    }
+}
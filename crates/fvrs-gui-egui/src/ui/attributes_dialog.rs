@@ -0,0 +1,279 @@
+use crate::app::FileVisorApp;
+use crate::attribute_editor::{self, AttributeEditor, ChangeForm};
+use egui::{Color32, RichText};
+use std::time::SystemTime;
+
+/// 属性変更ダイアログ
+pub struct AttributesDialog;
+
+impl AttributesDialog {
+    /// 選択中の項目で開く
+    pub fn open(app: &mut FileVisorApp) {
+        let targets = app.state.pane().selected_items.clone();
+        if targets.is_empty() {
+            tracing::warn!("属性を変更する項目が選択されていません");
+            return;
+        }
+        tracing::info!("属性変更ダイアログを表示: {} 項目", targets.len());
+        app.attribute_editor = Some(AttributeEditor::new(targets));
+    }
+
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        let Some(editor) = app.attribute_editor.as_mut() else {
+            return;
+        };
+        editor.poll(ctx);
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("属性の変更")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(460.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                Self::show_targets(ui, editor);
+                ui.separator();
+
+                ui.add_enabled_ui(!editor.is_running(), |ui| {
+                    let has_directories = editor.has_directories();
+                    ui.add_enabled(has_directories, egui::Checkbox::new(&mut editor.recursive, "フォルダーの中も変更する"));
+                    ui.add_enabled(
+                        has_directories,
+                        egui::Checkbox::new(&mut editor.separate_directories, "フォルダーには別の設定を使う"),
+                    );
+                    if editor.separate_directories {
+                        ui.horizontal(|ui| {
+                            if ui.selectable_label(!editor.editing_directories, "📄 ファイル").clicked() {
+                                editor.editing_directories = false;
+                            }
+                            if ui.selectable_label(editor.editing_directories, "📁 フォルダー").clicked() {
+                                editor.editing_directories = true;
+                            }
+                        });
+                    }
+                    ui.separator();
+                    Self::show_form(ui, editor);
+                });
+
+                ui.separator();
+                Self::show_result(ui, editor);
+                ui.horizontal(|ui| {
+                    if editor.is_running() {
+                        ui.spinner();
+                        ui.label(format!("変更中... {} 項目", editor.visited()));
+                        if ui.button("中止").clicked() {
+                            editor.cancel();
+                        }
+                    } else {
+                        if ui.button("適用").clicked() {
+                            editor.start(ui.ctx());
+                        }
+                        if ui.button("閉じる").clicked() {
+                            close = true;
+                        }
+                    }
+                });
+            });
+
+        if !open || close {
+            app.attribute_editor = None;
+        }
+    }
+
+    /// 対象と今の属性
+    fn show_targets(ui: &mut egui::Ui, editor: &AttributeEditor) {
+        match editor.targets.as_slice() {
+            [path] => {
+                ui.label(RichText::new(path.display().to_string()).strong());
+            }
+            targets => {
+                egui::CollapsingHeader::new(format!("{} 個の項目", targets.len())).show(ui, |ui| {
+                    egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                        for path in targets {
+                            ui.label(path.display().to_string());
+                        }
+                    });
+                });
+            }
+        }
+
+        egui::Grid::new("attributes_current").num_columns(2).spacing([12.0, 4.0]).show(ui, |ui| {
+            if let Some(mode) = editor.common(|a| a.mode) {
+                ui.label("権限:");
+                ui.monospace(format!("{} ({:04o})", fvrs_core::attributes::format_mode(mode), mode));
+                ui.end_row();
+            }
+            if let Some(owner) = &editor.owner
+                && (owner.uid.is_some() || owner.user.is_some())
+            {
+                ui.label("所有者:");
+                ui.label(format!("{} / {}", owner.display_user(), owner.display_group()));
+                ui.end_row();
+            }
+            if let Some(modified) = editor.common(|a| a.modified) {
+                ui.label("更新日時:");
+                ui.label(attribute_editor::format_time(modified));
+                ui.end_row();
+            }
+            if let Some(accessed) = editor.common(|a| a.accessed) {
+                ui.label("アクセス日時:");
+                ui.label(attribute_editor::format_time(accessed));
+                ui.end_row();
+            }
+        });
+    }
+
+    /// 変更内容の入力欄
+    fn show_form(ui: &mut egui::Ui, editor: &mut AttributeEditor) {
+        ui.label(RichText::new("クリックで「変更しない → オン → オフ」を切り替えます").weak().small());
+
+        #[cfg(windows)]
+        {
+            let current = [
+                editor.common(|a| Some(a.readonly)),
+                editor.common(|a| Some(a.hidden)),
+                editor.common(|a| Some(a.system)),
+                editor.common(|a| Some(a.archive)),
+            ];
+            let form = editor.form_mut();
+            ui.horizontal_wrapped(|ui| {
+                tri_state(ui, &mut form.readonly, current[0], "読み取り専用");
+                tri_state(ui, &mut form.hidden, current[1], "隠しファイル");
+                tri_state(ui, &mut form.system, current[2], "システム");
+                tri_state(ui, &mut form.archive, current[3], "アーカイブ");
+            });
+            ui.add_space(4.0);
+        }
+
+        #[cfg(unix)]
+        Self::show_unix_form(ui, editor);
+
+        let form = editor.form_mut();
+        egui::Grid::new("attributes_times").num_columns(3).spacing([8.0, 4.0]).show(ui, |ui| {
+            for (label, text) in [("更新日時:", &mut form.modified), ("アクセス日時:", &mut form.accessed)] {
+                ui.label(label);
+                ui.add(egui::TextEdit::singleline(text).desired_width(170.0).hint_text("変更しない"));
+                if ui.button("現在時刻").clicked() {
+                    *text = attribute_editor::format_time(SystemTime::now());
+                }
+                ui.end_row();
+            }
+        });
+
+        if ui.button("すべて変更しない").clicked() {
+            *editor.form_mut() = ChangeForm::default();
+        }
+    }
+
+    /// 権限ビットと所有者
+    #[cfg(unix)]
+    fn show_unix_form(ui: &mut egui::Ui, editor: &mut AttributeEditor) {
+        let current_mode = editor.common(|a| a.mode);
+        let current_bit = |bit: u32| current_mode.map(|mode| mode & bit != 0);
+
+        egui::Grid::new("attributes_mode").num_columns(4).spacing([8.0, 4.0]).show(ui, |ui| {
+            let form = editor.form_mut();
+            for (row, who) in ["所有者", "グループ", "その他"].iter().enumerate() {
+                ui.label(*who);
+                for &(bit, label) in &attribute_editor::MODE_BITS[row * 3..row * 3 + 3] {
+                    let mut value = form.mode_bit(bit);
+                    if tri_state(ui, &mut value, current_bit(bit), label) {
+                        form.set_mode_bit(bit, value);
+                    }
+                }
+                ui.end_row();
+            }
+            ui.label("特殊");
+            for &(bit, label) in &attribute_editor::SPECIAL_BITS {
+                let mut value = form.mode_bit(bit);
+                if tri_state(ui, &mut value, current_bit(bit), label) {
+                    form.set_mode_bit(bit, value);
+                }
+            }
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("8 進数:");
+            let response = ui.add(egui::TextEdit::singleline(&mut editor.octal_text).desired_width(60.0).hint_text("0755"));
+            let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("設定").clicked() || entered {
+                let text = editor.octal_text.clone();
+                if let Err(e) = editor.form_mut().set_octal(&text) {
+                    editor.error = Some(e);
+                }
+            }
+        });
+
+        let (user_hint, group_hint) = match &editor.owner {
+            Some(owner) => (owner.display_user(), owner.display_group()),
+            None => ("変更しない".to_string(), "変更しない".to_string()),
+        };
+        let form = editor.form_mut();
+        egui::Grid::new("attributes_owner").num_columns(2).spacing([8.0, 4.0]).show(ui, |ui| {
+            ui.label("所有者:");
+            ui.add(egui::TextEdit::singleline(&mut form.owner).desired_width(170.0).hint_text(user_hint))
+                .on_hover_text("ユーザー名か UID。空欄なら変更しません");
+            ui.end_row();
+            ui.label("グループ:");
+            ui.add(egui::TextEdit::singleline(&mut form.group).desired_width(170.0).hint_text(group_hint))
+                .on_hover_text("グループ名か GID。空欄なら変更しません");
+            ui.end_row();
+        });
+        ui.add_space(4.0);
+    }
+
+    /// 結果とエラー
+    fn show_result(ui: &mut egui::Ui, editor: &AttributeEditor) {
+        if let Some(error) = &editor.error {
+            ui.colored_label(Color32::RED, error);
+        }
+        let Some(report) = &editor.report else {
+            return;
+        };
+        ui.label(format!(
+            "変更 {} 項目 / 変更なし {} 項目 / 失敗 {} 項目",
+            report.changed,
+            report.unchanged,
+            report.failed.len()
+        ));
+        if !report.failed.is_empty() {
+            egui::CollapsingHeader::new("失敗した項目").default_open(true).show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                    for (path, reason) in &report.failed {
+                        ui.colored_label(Color32::RED, format!("{}: {}", path.display(), reason));
+                    }
+                });
+            });
+        }
+    }
+}
+
+/// 変更しない・オン・オフを切り替えるボタン（変わったら true）
+fn tri_state(ui: &mut egui::Ui, value: &mut Option<bool>, current: Option<bool>, label: &str) -> bool {
+    let text = match value {
+        None => RichText::new(format!("・{}", label)).weak(),
+        Some(true) => RichText::new(format!("✔ {}", label)).color(Color32::from_rgb(60, 170, 60)),
+        Some(false) => RichText::new(format!("✖ {}", label)).color(Color32::from_rgb(210, 70, 70)),
+    };
+    let now = match current {
+        Some(true) => "オン",
+        Some(false) => "オフ",
+        None => "混在",
+    };
+    let state = match value {
+        None => "変更しない",
+        Some(true) => "オンにする",
+        Some(false) => "オフにする",
+    };
+    let clicked = ui.button(text).on_hover_text(format!("{}（今: {}）", state, now)).clicked();
+    if clicked {
+        *value = match value {
+            None => Some(true),
+            Some(true) => Some(false),
+            Some(false) => None,
+        };
+    }
+    clicked
+}
@@ -0,0 +1,293 @@
+use crate::app::FileVisorApp;
+use crate::bookmarks::{BookmarkEditor, DraggedFolder, ICONS};
+use egui::{Color32, RichText, Stroke};
+use fvrs_core::bookmarks::{Bookmark, MAX_SHORTCUT};
+use std::path::{Path, PathBuf};
+
+/// サイドバーのブックマークでの操作（描画後にまとめて適用）
+enum BookmarkAction {
+    Open(PathBuf),
+    Add(PathBuf),
+    Edit(String),
+    Move(String, bool),
+    Remove(String),
+}
+
+/// ブックマーク（サイドバーの一覧と追加・編集ダイアログ）
+pub struct BookmarksUI;
+
+impl BookmarksUI {
+    /// `path` をブックマークに追加するダイアログを開く
+    pub fn open_new(app: &mut FileVisorApp, path: &Path) {
+        if !path.is_dir() {
+            tracing::warn!("ブックマークに追加できるのはフォルダーだけです: {:?}", path);
+            return;
+        }
+        app.bookmark_editor = Some(BookmarkEditor::new(&app.bookmarks.bookmarks, path));
+    }
+
+    /// 選択中のフォルダー（なければ表示中のフォルダー）を追加する（Ctrl+D）
+    pub fn add_current(app: &mut FileVisorApp) {
+        let pane = app.state.pane();
+        let path = match pane.selected_items.as_slice() {
+            [selected] if selected.is_dir() => selected.clone(),
+            _ => pane.current_path.clone(),
+        };
+        Self::open_new(app, &path);
+    }
+
+    /// Ctrl+数字のブックマークへ移動
+    pub fn open_shortcut(app: &mut FileVisorApp, number: u8) {
+        let Some(bookmark) = app.bookmarks.bookmarks.by_shortcut(number) else {
+            tracing::info!("Ctrl+{} にはブックマークが割り当てられていません", number);
+            return;
+        };
+        let path = bookmark.path.clone();
+        Self::open(app, path);
+    }
+
+    fn open(app: &mut FileVisorApp, path: PathBuf) {
+        if path.is_dir() {
+            app.state.sidebar_selected_item = Some(path.clone());
+            app.navigate_to(path);
+        } else {
+            tracing::warn!("フォルダーが見つかりません: {:?}", path);
+        }
+    }
+
+    /// フォルダーの項目をドラッグしてブックマークに追加できるようにする
+    pub fn drag_source(ui: &egui::Ui, response: &egui::Response, path: &Path) {
+        let drag = ui.interact(response.rect, response.id.with("bookmark_drag"), egui::Sense::drag());
+        drag.dnd_set_drag_payload(DraggedFolder(path.to_path_buf()));
+        if drag.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+                egui::Area::new(response.id.with("bookmark_drag_label"))
+                    .order(egui::Order::Tooltip)
+                    .fixed_pos(pointer + egui::vec2(12.0, 4.0))
+                    .interactable(false)
+                    .show(ui.ctx(), |ui| {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.label(format!("📁 {}", name));
+                        });
+                    });
+            }
+        }
+    }
+
+    /// サイドバーのブックマーク（グループごと、フォルダーをドロップすると追加）
+    pub fn show_section(ui: &mut egui::Ui, app: &mut FileVisorApp) {
+        let mut actions = Vec::new();
+        let current_path = app.state.pane().current_path.clone();
+        let bookmarks = &app.bookmarks.bookmarks;
+
+        let section = ui.scope(|ui| {
+            ui.label("🔖 ブックマーク");
+            if bookmarks.is_empty() {
+                ui.label(RichText::new("フォルダーをここへドラッグするか Ctrl+D で追加").weak().small());
+            }
+            for group in bookmarks.groups() {
+                let items = bookmarks.bookmarks().iter().filter(|bookmark| bookmark.group == group);
+                if group.is_empty() {
+                    for bookmark in items {
+                        Self::show_item(ui, bookmark, &current_path, &mut actions);
+                    }
+                } else {
+                    egui::CollapsingHeader::new(group)
+                        .id_salt(("bookmark_group", group))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for bookmark in items {
+                                Self::show_item(ui, bookmark, &current_path, &mut actions);
+                            }
+                        });
+                }
+            }
+        });
+
+        // ドロップ先を枠で示す
+        let response = section.response;
+        if response.dnd_hover_payload::<DraggedFolder>().is_some() {
+            ui.painter().rect_stroke(
+                response.rect.expand(2.0),
+                2.0,
+                Stroke::new(2.0, Color32::from_rgb(0, 120, 215)),
+                egui::StrokeKind::Outside,
+            );
+        }
+        if let Some(folder) = response.dnd_release_payload::<DraggedFolder>() {
+            actions.push(BookmarkAction::Add(folder.0.clone()));
+        }
+        // 他のアプリからドロップされたフォルダー
+        if response.contains_pointer() {
+            let dropped = ui.input(|i| i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect::<Vec<_>>());
+            if let Some(path) = dropped.into_iter().find(|path| path.is_dir()) {
+                actions.push(BookmarkAction::Add(path));
+            }
+        }
+
+        for action in actions {
+            match action {
+                BookmarkAction::Open(path) => Self::open(app, path),
+                BookmarkAction::Add(path) => Self::open_new(app, &path),
+                BookmarkAction::Edit(name) => {
+                    if let Some(bookmark) = app.bookmarks.bookmarks.get(&name) {
+                        app.bookmark_editor = Some(BookmarkEditor::edit(bookmark));
+                    }
+                }
+                BookmarkAction::Move(name, up) => app.bookmarks.move_within_group(&name, up),
+                BookmarkAction::Remove(name) => app.bookmarks.remove(&name),
+            }
+        }
+    }
+
+    fn show_item(ui: &mut egui::Ui, bookmark: &Bookmark, current_path: &Path, actions: &mut Vec<BookmarkAction>) {
+        let mut text = RichText::new(format!("{} {}", bookmark.icon, bookmark.name));
+        if !bookmark.path.is_dir() {
+            text = text.color(Color32::GRAY).strikethrough();
+        }
+        let hover = match bookmark.shortcut {
+            Some(number) => format!("{}\nCtrl+{}", bookmark.path.display(), number),
+            None => bookmark.path.display().to_string(),
+        };
+        let response = ui.selectable_label(bookmark.path == current_path, text).on_hover_text(hover);
+        if response.clicked() {
+            actions.push(BookmarkAction::Open(bookmark.path.clone()));
+        }
+        response.context_menu(|ui| {
+            if ui.button("開く").clicked() {
+                actions.push(BookmarkAction::Open(bookmark.path.clone()));
+                ui.close_menu();
+            }
+            if ui.button("編集...").clicked() {
+                actions.push(BookmarkAction::Edit(bookmark.name.clone()));
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("上へ移動").clicked() {
+                actions.push(BookmarkAction::Move(bookmark.name.clone(), true));
+                ui.close_menu();
+            }
+            if ui.button("下へ移動").clicked() {
+                actions.push(BookmarkAction::Move(bookmark.name.clone(), false));
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("ブックマークから削除").clicked() {
+                actions.push(BookmarkAction::Remove(bookmark.name.clone()));
+                ui.close_menu();
+            }
+        });
+    }
+
+    /// ブックマークの追加・編集ダイアログ
+    pub fn show_editor(ctx: &egui::Context, app: &mut FileVisorApp) {
+        let Some(editor) = app.bookmark_editor.as_mut() else {
+            return;
+        };
+        let bookmarks = &app.bookmarks.bookmarks;
+
+        let mut open = true;
+        let mut save = false;
+        let mut close = false;
+        let title = if editor.original.is_some() { "ブックマークの編集" } else { "ブックマークに追加" };
+        egui::Window::new(title)
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("bookmark_editor").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+                    ui.label("名前:");
+                    ui.text_edit_singleline(&mut editor.name)
+                        .on_hover_text(format!("コマンドラインでは @{} で指定できます", editor.name.trim()));
+                    ui.end_row();
+
+                    ui.label("フォルダー:");
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut editor.path);
+                        if ui.button("📁").on_hover_text("参照").clicked()
+                            && let Some(path) = rfd::FileDialog::new().set_directory(editor.path.trim()).pick_folder()
+                        {
+                            editor.path = path.to_string_lossy().into_owned();
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("アイコン:");
+                    ui.horizontal_wrapped(|ui| {
+                        for icon in ICONS {
+                            if ui.selectable_label(editor.icon == icon, icon).clicked() {
+                                editor.icon = icon.to_string();
+                            }
+                        }
+                        ui.add(egui::TextEdit::singleline(&mut editor.icon).desired_width(28.0));
+                    });
+                    ui.end_row();
+
+                    ui.label("グループ:");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut editor.group).hint_text("なし"));
+                        let groups: Vec<&str> = bookmarks.groups().into_iter().filter(|group| !group.is_empty()).collect();
+                        if !groups.is_empty() {
+                            ui.menu_button("▼", |ui| {
+                                for group in groups {
+                                    if ui.button(group).clicked() {
+                                        editor.group = group.to_string();
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("ショートカット:");
+                    let label = |number: Option<u8>| match number {
+                        Some(number) => format!("Ctrl+{}", number),
+                        None => "なし".to_string(),
+                    };
+                    egui::ComboBox::from_id_salt("bookmark_shortcut")
+                        .selected_text(label(editor.shortcut))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut editor.shortcut, None, label(None));
+                            for number in 1..=MAX_SHORTCUT {
+                                // 他のブックマークが使っている番号は付け替えになる
+                                let text = match bookmarks.by_shortcut(number) {
+                                    Some(other) if editor.original.as_deref() != Some(other.name.as_str()) => {
+                                        format!("{}（{} から付け替え）", label(Some(number)), other.name)
+                                    }
+                                    _ => label(Some(number)),
+                                };
+                                ui.selectable_value(&mut editor.shortcut, Some(number), text);
+                            }
+                        });
+                    ui.end_row();
+                });
+
+                if let Some(error) = &editor.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("保存").clicked() {
+                        save = true;
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if save {
+            match app.bookmarks.apply(editor) {
+                Ok(()) => close = true,
+                Err(e) => editor.error = Some(e),
+            }
+        }
+        if !open || close {
+            app.bookmark_editor = None;
+        }
+    }
+}
@@ -9,6 +9,7 @@ impl DialogsUI {
         ctx: &egui::Context,
         show_dialog: &mut bool,
         items_to_delete: &[std::path::PathBuf],
+        permanent: bool,
         _current_path: &Path,
         delete_callback: &mut dyn FnMut(),
         cancel_callback: &mut dyn FnMut(),
@@ -21,7 +22,11 @@ impl DialogsUI {
                 .show(ctx, |ui| {
                     ui.vertical_centered(|ui| {
                         ui.add_space(10.0);
-                        ui.colored_label(egui::Color32::RED, "⚠️ 削除確認");
+                        if permanent {
+                            ui.colored_label(egui::Color32::RED, "⚠️ 完全に削除");
+                        } else {
+                            ui.colored_label(egui::Color32::RED, "⚠️ 削除確認");
+                        }
                         ui.add_space(10.0);
                         
                         if items_to_delete.len() == 1 {
@@ -54,11 +59,15 @@ impl DialogsUI {
                         }
                         
                         ui.add_space(10.0);
-                        ui.colored_label(egui::Color32::GRAY, "この操作は元に戻せません");
+                        if permanent {
+                            ui.colored_label(egui::Color32::GRAY, "ゴミ箱を経由しないため、この操作は元に戻せません");
+                        } else {
+                            ui.colored_label(egui::Color32::GRAY, "ゴミ箱へ移動します（Ctrl+Z で元に戻せます）");
+                        }
                         ui.add_space(20.0);
                         
                         ui.horizontal(|ui| {
-                            if ui.button("🗑️ 削除").clicked() {
+                            if ui.button(if permanent { "🗑️ 完全に削除" } else { "🗑️ 削除" }).clicked() {
                                 delete_callback();
                                 *show_dialog = false;
                             }
@@ -102,16 +111,16 @@ impl DialogsUI {
                                     let shortcuts = [
                                         ("A", "属性変更", "ファイル・フォルダの属性を変更"),
                                         ("B", "バイナリ編集", "バイナリエディタで開く"),
-                                        ("C", "コピー", "選択アイテムをクリップボードにコピー"),
+                                        ("C", "コピー", "クリップボードにコピー（2画面表示では反対側へコピー）"),
                                         ("D", "削除", "選択アイテムを削除"),
                                         ("E", "エディタで編集", "テキストエディタで開く"),
-                                        ("F", "検索", "ファイル・フォルダを検索"),
-                                        ("G", "履歴", "ナビゲーション履歴を表示"),
-                                        ("H", "連結", "ファイルを連結"),
+                                        ("F", "検索", "名前・内容・サイズ・更新日時でファイルとフォルダーを検索"),
+                                        ("G", "履歴", "最近開いたフォルダー・よく使うフォルダーを表示"),
+                                        ("H", "連結と分割", "ファイルを連結・分割"),
                                         ("I", "ファイル情報", "選択アイテムの詳細情報を表示"),
                                         ("K", "フォルダの作成", "新しいフォルダを作成"),
                                         ("L", "フォルダを開く", "選択したフォルダに移動"),
-                                        ("M", "移動", "選択アイテムを切り取り"),
+                                        ("M", "移動", "選択アイテムを切り取り（2画面表示では反対側へ移動）"),
                                         ("N", "新規ファイル作成", "空ファイルを作成"),
                                         ("O", "開く", "選択アイテムを開く"),
                                         ("P", "圧縮書庫の作成", "選択アイテムを圧縮"),
@@ -149,9 +158,20 @@ impl DialogsUI {
 
                                 let other_shortcuts = [
                                     ("F5", "ディレクトリ更新"),
-                                    ("Delete", "削除"),
+                                    ("Delete", "ゴミ箱へ移動"),
+                                    ("Shift+Delete", "完全に削除"),
+                                    ("Ctrl+C", "クリップボードにコピー"),
+                                    ("Ctrl+X", "クリップボードに切り取り"),
+                                    ("Ctrl+V", "貼り付け（バックグラウンドで実行）"),
                                     ("Alt + ←", "戻る"),
                                     ("Alt + →", "進む"),
+                                    ("Tab", "ペイン切り替え（2画面表示では左右の一覧）"),
+                                    ("Ctrl+T", "新しいタブ"),
+                                    ("Ctrl+W", "タブを閉じる"),
+                                    ("Ctrl+Tab", "次のタブ（Shift で前のタブ）"),
+                                    ("Ctrl+P", "履歴やブックマークからフォルダーへすばやく移動"),
+                                    ("Ctrl+D", "ブックマークに追加"),
+                                    ("Ctrl+1～9", "ブックマークへ移動"),
                                 ];
 
                                 for (key, desc) in other_shortcuts {
@@ -443,7 +463,7 @@ impl DialogsUI {
             .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
-                    ui.label(format!("選択されたアイテム: {} 個", app.state.selected_items.len()));
+                    ui.label(format!("選択されたアイテム: {} 個", app.state.pane().selected_items.len()));
                     ui.add_space(10.0);
                     
                     ui.label("ファイル名:");
@@ -595,4 +615,62 @@ impl DialogsUI {
                 }
             });
     }
-} 
\ No newline at end of file
+
+    /// コピー・移動先の指定ダイアログ
+    pub fn show_transfer_dialog(ctx: &egui::Context, app: &mut crate::app::FileVisorApp) {
+        if !app.state.show_transfer_dialog {
+            return;
+        }
+
+        let title = if app.state.transfer_is_move { "移動" } else { "コピー" };
+        let mut close = false;
+        egui::Window::new(title)
+            .resizable(false)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("選択されたアイテム: {} 個", app.state.pane().selected_items.len()));
+                ui.add_space(10.0);
+
+                ui.label(format!("{}先:", title));
+                let text_edit = ui.add(
+                    egui::TextEdit::singleline(&mut app.state.transfer_destination)
+                        .desired_width(400.0)
+                );
+                if ui.memory(|mem| mem.everything_is_visible()) {
+                    text_edit.request_focus();
+                }
+
+                let destination = std::path::PathBuf::from(app.state.transfer_destination.trim());
+                let valid = destination.is_dir();
+                if !valid {
+                    ui.colored_label(egui::Color32::RED, "フォルダーが見つかりません");
+                }
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("キャンセル").clicked() {
+                            close = true;
+                        }
+                        let confirmed = ui.add_enabled(valid, egui::Button::new(title)).clicked()
+                            || (valid && ctx.input(|i| i.key_pressed(egui::Key::Enter)));
+                        if confirmed {
+                            app.transfer_selected(destination);
+                            close = true;
+                        }
+                    });
+                });
+
+                // Escapeキーでダイアログを閉じる
+                if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    close = true;
+                }
+            });
+
+        if close {
+            app.state.show_transfer_dialog = false;
+            app.state.transfer_destination.clear();
+        }
+    }
+}
@@ -0,0 +1,608 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use egui::{Align, Color32, Layout, Pos2, Rect, RichText, Sense, Stroke, Vec2};
+use egui_extras::{Column, TableBuilder};
+use fvrs_core::disk_usage::{DiskUsageNode, DiskUsageSort, ScanOptions};
+use crate::app::FileVisorApp;
+use crate::file_info::format_size;
+use crate::state::FileOperation;
+
+/// ツリーマップで描画する子要素の上限（小さな要素は省略）
+const TREEMAP_MAX_ITEMS: usize = 400;
+/// ツリーマップで入れ子表示する深さ
+const TREEMAP_MAX_DEPTH: usize = 2;
+
+/// ディスク使用量ビューでの操作（描画後にまとめて適用）
+enum DiskUsageAction {
+    Scan { full: bool },
+    Cancel,
+    Focus(PathBuf),
+    Toggle(PathBuf),
+    Select(PathBuf),
+    OpenInList(PathBuf),
+    RequestDelete(PathBuf),
+    ConfirmDelete,
+    CancelDelete,
+}
+
+/// ディスク使用量分析ビュー（ソート可能なツリーとツリーマップ）
+pub struct DiskUsageUI;
+
+impl DiskUsageUI {
+    /// 現在のディレクトリを対象に分析ビューを開く
+    pub fn open(app: &mut FileVisorApp) {
+        app.state.show_disk_usage_view = true;
+        let root = app.state.pane().current_path.clone();
+        let scanned_root = app.disk_usage.result.as_ref().map(|r| r.path.clone());
+        if scanned_root.as_ref() != Some(&root) && !app.disk_usage.is_running() {
+            Self::start_scan(app, root, false);
+        }
+    }
+
+    fn start_scan(app: &mut FileVisorApp, root: PathBuf, full: bool) {
+        let options = ScanOptions {
+            one_file_system: app.state.disk_usage_one_file_system,
+            full_rescan: full,
+        };
+        app.disk_usage.start(root, options);
+    }
+
+    /// ディスク使用量ビューを表示
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        if !app.state.show_disk_usage_view {
+            if app.disk_usage.is_running() {
+                app.disk_usage.cancel();
+            }
+            return;
+        }
+
+        app.disk_usage.poll();
+        if app.disk_usage.is_running() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        // ソート条件が変わったら結果ツリーを並べ替え
+        let sort = (app.state.disk_usage_sort, app.state.disk_usage_sort_ascending);
+        if app.disk_usage.sorted_by != Some(sort)
+            && let Some(tree) = &mut app.disk_usage.result
+        {
+            tree.sort_by(sort.0, sort.1);
+            app.disk_usage.sorted_by = Some(sort);
+        }
+
+        let mut actions = Vec::new();
+        let mut open = true;
+
+        egui::Window::new("ディスク使用量")
+            .default_width(800.0)
+            .default_height(600.0)
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                Self::show_toolbar(ui, app, &mut actions);
+                ui.separator();
+
+                let analyzer = &app.disk_usage;
+                if let Some(error) = &analyzer.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+
+                let Some(node) = analyzer.focused_node() else {
+                    if !analyzer.is_running() {
+                        ui.label("スキャン結果がありません");
+                    }
+                    return;
+                };
+
+                // パンくず（ドリルダウン位置）
+                ui.horizontal(|ui| {
+                    let can_go_up = analyzer.result.as_ref().is_some_and(|root| root.path != node.path);
+                    if ui.add_enabled(can_go_up, egui::Button::new("⬆ 上へ")).clicked()
+                        && let Some(parent) = node.path.parent()
+                    {
+                        actions.push(DiskUsageAction::Focus(parent.to_path_buf()));
+                    }
+                    ui.label(RichText::new(node.path.display().to_string()).strong());
+                    ui.label(format!(
+                        "{} / {} ファイル, {} フォルダー",
+                        format_size(node.size),
+                        node.file_count,
+                        node.dir_count
+                    ));
+                });
+                ui.add_space(4.0);
+
+                if app.state.disk_usage_show_treemap {
+                    let height = (ui.available_height() * 0.45).max(120.0);
+                    let (rect, response) = ui.allocate_exact_size(
+                        Vec2::new(ui.available_width(), height),
+                        Sense::click(),
+                    );
+                    Self::show_treemap(ui, rect, &response, node, analyzer.selected.as_ref(), &mut actions);
+                    ui.add_space(6.0);
+                }
+
+                Self::show_tree(
+                    ui,
+                    node,
+                    &analyzer.expanded,
+                    analyzer.selected.as_ref(),
+                    &mut app.state.disk_usage_sort,
+                    &mut app.state.disk_usage_sort_ascending,
+                    &mut actions,
+                );
+            });
+
+        Self::show_delete_confirmation(ctx, app, &mut actions);
+
+        if !open {
+            app.state.show_disk_usage_view = false;
+        }
+        for action in actions {
+            Self::apply(app, action);
+        }
+    }
+
+    /// ツールバー（スキャン操作とオプション）
+    fn show_toolbar(ui: &mut egui::Ui, app: &mut FileVisorApp, actions: &mut Vec<DiskUsageAction>) {
+        ui.horizontal(|ui| {
+            let running = app.disk_usage.is_running();
+            if ui.add_enabled(!running, egui::Button::new("🔄 スキャン"))
+                .on_hover_text("変更のあったフォルダーのみ再計算します")
+                .clicked()
+            {
+                actions.push(DiskUsageAction::Scan { full: false });
+            }
+            if ui.add_enabled(!running, egui::Button::new("完全再スキャン")).clicked() {
+                actions.push(DiskUsageAction::Scan { full: true });
+            }
+            if running && ui.button("⏹ 中止").clicked() {
+                actions.push(DiskUsageAction::Cancel);
+            }
+            ui.separator();
+            ui.checkbox(&mut app.state.disk_usage_one_file_system, "他のファイルシステムを除外");
+            ui.checkbox(&mut app.state.disk_usage_show_treemap, "ツリーマップ");
+        });
+
+        if let Some((root, progress)) = app.disk_usage.progress() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!(
+                    "{} をスキャン中: {} ファイル, {} フォルダー, {}（キャッシュ {} フォルダー）",
+                    root.display(),
+                    progress.files,
+                    progress.dirs,
+                    format_size(progress.bytes),
+                    progress.cached_dirs
+                ));
+            });
+            ui.label(RichText::new(progress.current.display().to_string()).small().weak());
+        }
+    }
+
+    /// ソート可能なツリー表示
+    fn show_tree(
+        ui: &mut egui::Ui,
+        node: &DiskUsageNode,
+        expanded: &HashSet<PathBuf>,
+        selected: Option<&PathBuf>,
+        sort: &mut DiskUsageSort,
+        ascending: &mut bool,
+        actions: &mut Vec<DiskUsageAction>,
+    ) {
+        let mut rows = Vec::new();
+        Self::collect_rows(node, 0, expanded, &mut rows);
+        let total = node.size;
+
+        let mut sort_button = |ui: &mut egui::Ui, key: DiskUsageSort, label: &str| {
+            let text = if *sort == key {
+                format!("{} {}", label, if *ascending { "▲" } else { "▼" })
+            } else {
+                label.to_string()
+            };
+            if ui.button(text).clicked() {
+                if *sort == key {
+                    *ascending = !*ascending;
+                } else {
+                    *sort = key;
+                    // 名前は昇順、サイズと件数は大きい順から
+                    *ascending = key == DiskUsageSort::Name;
+                }
+            }
+        };
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::remainder().at_least(250.0)) // 名前
+            .column(Column::auto().at_least(90.0)) // サイズ
+            .column(Column::auto().at_least(140.0)) // 割合
+            .column(Column::auto().at_least(80.0)) // ファイル数
+            .header(20.0, |mut header| {
+                header.col(|ui| sort_button(ui, DiskUsageSort::Name, "名前"));
+                header.col(|ui| sort_button(ui, DiskUsageSort::Size, "サイズ"));
+                header.col(|ui| {
+                    ui.strong("割合");
+                });
+                header.col(|ui| sort_button(ui, DiskUsageSort::Files, "ファイル数"));
+            })
+            .body(|body| {
+                body.rows(20.0, rows.len(), |mut row| {
+                    let (depth, entry) = rows[row.index()];
+                    let is_selected = selected == Some(&entry.path);
+
+                    row.col(|ui| {
+                        ui.add_space(depth as f32 * 16.0);
+                        if entry.is_dir && !entry.children.is_empty() {
+                            let icon = if expanded.contains(&entry.path) { "▼" } else { "▶" };
+                            if ui.small_button(icon).clicked() {
+                                actions.push(DiskUsageAction::Toggle(entry.path.clone()));
+                            }
+                        } else {
+                            ui.add_space(18.0);
+                        }
+                        let label = format!("{} {}", if entry.is_dir { "📁" } else { "📄" }, entry.name);
+                        let response = ui.selectable_label(is_selected, label);
+                        if response.clicked() {
+                            actions.push(DiskUsageAction::Select(entry.path.clone()));
+                        }
+                        if response.double_clicked() && entry.is_dir {
+                            actions.push(DiskUsageAction::Focus(entry.path.clone()));
+                        }
+                        Self::context_menu(&response, entry, actions);
+                    });
+                    row.col(|ui| {
+                        ui.label(format_size(entry.size));
+                    });
+                    row.col(|ui| {
+                        let ratio = entry.ratio_of(total);
+                        ui.add(
+                            egui::ProgressBar::new(ratio)
+                                .desired_width(130.0)
+                                .text(format!("{:.1}%", ratio * 100.0)),
+                        );
+                    });
+                    row.col(|ui| {
+                        if entry.is_dir {
+                            ui.label(entry.file_count.to_string());
+                        }
+                    });
+                });
+            });
+    }
+
+    /// 展開状態に従ってツリーを行リストに平坦化
+    fn collect_rows<'a>(
+        node: &'a DiskUsageNode,
+        depth: usize,
+        expanded: &HashSet<PathBuf>,
+        rows: &mut Vec<(usize, &'a DiskUsageNode)>,
+    ) {
+        for child in &node.children {
+            rows.push((depth, child));
+            if child.is_dir && expanded.contains(&child.path) {
+                Self::collect_rows(child, depth + 1, expanded, rows);
+            }
+        }
+    }
+
+    /// 右クリックメニュー
+    fn context_menu(response: &egui::Response, entry: &DiskUsageNode, actions: &mut Vec<DiskUsageAction>) {
+        response.context_menu(|ui| {
+            if entry.is_dir && ui.button("🔍 ドリルダウン").clicked() {
+                actions.push(DiskUsageAction::Focus(entry.path.clone()));
+                ui.close_menu();
+            }
+            if ui.button("📂 ファイル一覧で表示").clicked() {
+                actions.push(DiskUsageAction::OpenInList(entry.path.clone()));
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("🗑 ゴミ箱へ移動...").clicked() {
+                actions.push(DiskUsageAction::RequestDelete(entry.path.clone()));
+                ui.close_menu();
+            }
+        });
+    }
+
+    /// 入れ子のツリーマップを描画
+    fn show_treemap(
+        ui: &mut egui::Ui,
+        rect: Rect,
+        response: &egui::Response,
+        node: &DiskUsageNode,
+        selected: Option<&PathBuf>,
+        actions: &mut Vec<DiskUsageAction>,
+    ) {
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        let pointer = response.hover_pos();
+        let mut hovered: Option<&DiskUsageNode> = None;
+        Self::paint_treemap_level(&painter, rect, node, 0, pointer, selected, &mut hovered);
+
+        if let Some(item) = hovered {
+            let response = response.clone().on_hover_ui_at_pointer(|ui| {
+                ui.label(RichText::new(item.path.display().to_string()).strong());
+                ui.label(format!("{} ({:.1}%)", format_size(item.size), item.ratio_of(node.size) * 100.0));
+            });
+            if response.clicked() {
+                actions.push(DiskUsageAction::Select(item.path.clone()));
+            }
+            if response.double_clicked() {
+                // ディレクトリはドリルダウン、ファイルは親ディレクトリへ
+                let target = if item.is_dir {
+                    item.path.clone()
+                } else {
+                    item.path.parent().map(|p| p.to_path_buf()).unwrap_or_else(|| item.path.clone())
+                };
+                actions.push(DiskUsageAction::Focus(target));
+            }
+            Self::context_menu(&response, item, actions);
+        }
+    }
+
+    fn paint_treemap_level<'a>(
+        painter: &egui::Painter,
+        rect: Rect,
+        node: &'a DiskUsageNode,
+        depth: usize,
+        pointer: Option<Pos2>,
+        selected: Option<&PathBuf>,
+        hovered: &mut Option<&'a DiskUsageNode>,
+    ) {
+        let mut items: Vec<&DiskUsageNode> = node.children.iter().filter(|c| c.size > 0).collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items.truncate(TREEMAP_MAX_ITEMS);
+
+        let sizes: Vec<f64> = items.iter().map(|c| c.size as f64).collect();
+        let rects = squarify(&sizes, rect);
+        let text_color = Color32::from_gray(20);
+
+        for (item, item_rect) in items.into_iter().zip(rects) {
+            if item_rect.width() < 1.0 || item_rect.height() < 1.0 {
+                continue;
+            }
+            let is_hovered = pointer.is_some_and(|p| item_rect.contains(p));
+            if is_hovered {
+                *hovered = Some(item);
+            }
+
+            painter.rect_filled(item_rect.shrink(0.5), 1.0, treemap_color(item, depth));
+            let stroke = if selected == Some(&item.path) {
+                Stroke::new(2.0, Color32::WHITE)
+            } else {
+                Stroke::new(0.5, Color32::from_black_alpha(120))
+            };
+            painter.rect_stroke(item_rect.shrink(0.5), 1.0, stroke, egui::StrokeKind::Inside);
+
+            let header_height = 14.0;
+            let fits_label = item_rect.width() > 40.0 && item_rect.height() > header_height;
+            if fits_label {
+                painter.with_clip_rect(item_rect.shrink(1.0)).text(
+                    item_rect.left_top() + Vec2::new(3.0, 1.0),
+                    egui::Align2::LEFT_TOP,
+                    &item.name,
+                    egui::FontId::proportional(11.0),
+                    text_color,
+                );
+            }
+
+            // 十分な大きさのディレクトリは中身を入れ子で描画
+            if item.is_dir
+                && depth + 1 < TREEMAP_MAX_DEPTH
+                && item_rect.width() > 60.0
+                && item_rect.height() > 50.0
+            {
+                let inner = Rect::from_min_max(
+                    item_rect.min + Vec2::new(2.0, header_height + 2.0),
+                    item_rect.max - Vec2::splat(2.0),
+                );
+                Self::paint_treemap_level(painter, inner, item, depth + 1, pointer, selected, hovered);
+            }
+        }
+    }
+
+    /// 削除の確認ダイアログ
+    fn show_delete_confirmation(ctx: &egui::Context, app: &FileVisorApp, actions: &mut Vec<DiskUsageAction>) {
+        let Some(path) = &app.disk_usage.pending_delete else {
+            return;
+        };
+        let size = app.disk_usage.result.as_ref()
+            .and_then(|tree| tree.find(path))
+            .map(|node| node.size);
+
+        egui::Window::new("削除の確認")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("次の項目をゴミ箱へ移動しますか？");
+                ui.label(RichText::new(path.display().to_string()).strong());
+                if let Some(size) = size {
+                    ui.label(format!("サイズ: {}", format_size(size)));
+                }
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("ゴミ箱へ移動").clicked() {
+                        actions.push(DiskUsageAction::ConfirmDelete);
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        actions.push(DiskUsageAction::CancelDelete);
+                    }
+                });
+            });
+    }
+
+    fn apply(app: &mut FileVisorApp, action: DiskUsageAction) {
+        match action {
+            DiskUsageAction::Scan { full } => {
+                let root = app.disk_usage.result.as_ref()
+                    .map(|tree| tree.path.clone())
+                    .unwrap_or_else(|| app.state.pane().current_path.clone());
+                Self::start_scan(app, root, full);
+            }
+            DiskUsageAction::Cancel => app.disk_usage.cancel(),
+            DiskUsageAction::Focus(path) => {
+                app.disk_usage.focus = Some(path);
+            }
+            DiskUsageAction::Toggle(path) => {
+                if !app.disk_usage.expanded.remove(&path) {
+                    app.disk_usage.expanded.insert(path);
+                }
+            }
+            DiskUsageAction::Select(path) => {
+                app.disk_usage.selected = Some(path);
+            }
+            DiskUsageAction::OpenInList(path) => {
+                let (dir, select) = if path.is_dir() {
+                    (path, None)
+                } else {
+                    (path.parent().map(|p| p.to_path_buf()).unwrap_or_default(), Some(path))
+                };
+                app.navigate_to(dir);
+                if let Some(select) = select {
+                    app.state.pane_mut().selected_items = vec![select];
+                }
+            }
+            DiskUsageAction::RequestDelete(path) => {
+                app.disk_usage.pending_delete = Some(path);
+            }
+            DiskUsageAction::ConfirmDelete => {
+                if let Some(path) = app.disk_usage.pending_delete.take() {
+                    match app.disk_usage.delete(&path) {
+                        Ok(trashed) => {
+                            app.record_operation(FileOperation::Delete { path, trashed });
+                        }
+                        Err(e) => app.disk_usage.error = Some(e),
+                    }
+                }
+            }
+            DiskUsageAction::CancelDelete => {
+                app.disk_usage.pending_delete = None;
+            }
+        }
+    }
+}
+
+/// ファイルは拡張子ごと、ディレクトリは深さごとに色分け
+fn treemap_color(node: &DiskUsageNode, depth: usize) -> Color32 {
+    if node.is_dir {
+        let value = 0.55 + 0.1 * depth as f32;
+        return egui::ecolor::Hsva::new(0.6, 0.15, value, 1.0).into();
+    }
+    let extension = node.path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let hash = extension.bytes().fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32));
+    let hue = (hash % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.45, 0.85, 1.0).into()
+}
+
+/// Squarified treemap レイアウト（Bruls らの手法）
+///
+/// `sizes` は降順であること。戻り値は `sizes` と同じ順序の矩形。
+fn squarify(sizes: &[f64], rect: Rect) -> Vec<Rect> {
+    let total: f64 = sizes.iter().sum();
+    let mut rects = Vec::with_capacity(sizes.len());
+    if total <= 0.0 || rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return rects;
+    }
+
+    // 面積に換算
+    let scale = (rect.width() as f64 * rect.height() as f64) / total;
+    let areas: Vec<f64> = sizes.iter().map(|s| s * scale).collect();
+
+    let mut remaining = rect;
+    let mut start = 0;
+    while start < areas.len() {
+        let short_side = remaining.width().min(remaining.height()) as f64;
+        let mut end = start + 1;
+        let mut best = worst_ratio(&areas[start..end], short_side);
+        while end < areas.len() {
+            let next = worst_ratio(&areas[start..=end], short_side);
+            if next > best {
+                break;
+            }
+            best = next;
+            end += 1;
+        }
+
+        let row = &areas[start..end];
+        let row_area: f64 = row.iter().sum();
+        if remaining.width() >= remaining.height() {
+            // 左端に縦一列で配置
+            let width = (row_area / remaining.height() as f64) as f32;
+            let mut y = remaining.top();
+            for area in row {
+                let height = (area / width as f64) as f32;
+                rects.push(Rect::from_min_size(Pos2::new(remaining.left(), y), Vec2::new(width, height)));
+                y += height;
+            }
+            remaining.min.x += width;
+        } else {
+            // 上端に横一列で配置
+            let height = (row_area / remaining.width() as f64) as f32;
+            let mut x = remaining.left();
+            for area in row {
+                let width = (area / height as f64) as f32;
+                rects.push(Rect::from_min_size(Pos2::new(x, remaining.top()), Vec2::new(width, height)));
+                x += width;
+            }
+            remaining.min.y += height;
+        }
+        start = end;
+    }
+    rects
+}
+
+/// 行内で最も細長い矩形の縦横比
+fn worst_ratio(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    if sum <= 0.0 || side <= 0.0 {
+        return f64::INFINITY;
+    }
+    let max = row.iter().cloned().fold(f64::MIN, f64::max);
+    let min = row.iter().cloned().fold(f64::MAX, f64::min);
+    let side_sq = side * side;
+    let sum_sq = sum * sum;
+    (side_sq * max / sum_sq).max(sum_sq / (side_sq * min))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squarify_fills_rect_proportionally() {
+        let rect = Rect::from_min_size(Pos2::new(10.0, 20.0), Vec2::new(300.0, 200.0));
+        let sizes = [500.0, 250.0, 125.0, 75.0, 50.0];
+        let rects = squarify(&sizes, rect);
+        assert_eq!(rects.len(), sizes.len());
+
+        let total: f64 = sizes.iter().sum();
+        let rect_area = (rect.width() * rect.height()) as f64;
+        for (size, item) in sizes.iter().zip(&rects) {
+            let expected = size / total * rect_area;
+            let actual = (item.width() * item.height()) as f64;
+            assert!((expected - actual).abs() < 1.0, "{} != {}", expected, actual);
+            assert!(rect.expand(0.01).contains_rect(*item));
+        }
+        for (i, a) in rects.iter().enumerate() {
+            for b in &rects[i + 1..] {
+                let overlap = a.intersect(*b);
+                assert!(overlap.width() < 0.01 || overlap.height() < 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn squarify_handles_empty_input() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(100.0, 100.0));
+        assert!(squarify(&[], rect).is_empty());
+        assert!(squarify(&[0.0, 0.0], rect).is_empty());
+        assert!(squarify(&[1.0], Rect::from_min_size(Pos2::ZERO, Vec2::ZERO)).is_empty());
+    }
+}
@@ -0,0 +1,449 @@
+use crate::app::FileVisorApp;
+use crate::duplicates::DuplicateResolution;
+use crate::file_info::format_size;
+use egui::{Color32, RichText};
+use fvrs_core::duplicates::{DuplicateOptions, DuplicateStage};
+use std::path::PathBuf;
+
+/// 最初から展開しておくグループ数の上限
+const AUTO_EXPAND_GROUPS: usize = 20;
+
+/// 重複ファイルビューでの操作（描画後にまとめて適用）
+enum DuplicatesAction {
+    Search,
+    Cancel,
+    AddRoot(PathBuf),
+    RemoveRoot(usize),
+    KeepOldest,
+    KeepNewest,
+    OpenInList(PathBuf),
+    Request(DuplicateResolution),
+    Confirm,
+    CancelPending,
+}
+
+/// 重複ファイルの検索と整理
+pub struct DuplicatesUI;
+
+impl DuplicatesUI {
+    /// 現在のフォルダーを対象に重複ファイルビューを開く
+    pub fn open(app: &mut FileVisorApp) {
+        app.state.show_duplicates_view = true;
+        if app.duplicates.roots.is_empty() {
+            app.duplicates.roots.push(app.state.pane().current_path.clone());
+        }
+    }
+
+    /// 重複ファイルビューを表示
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        if !app.state.show_duplicates_view {
+            if app.duplicates.is_running() {
+                app.duplicates.cancel();
+            }
+            return;
+        }
+
+        app.duplicates.poll();
+        if app.duplicates.is_running() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        let mut actions = Vec::new();
+        let mut open = true;
+
+        egui::Window::new("重複ファイルの検索")
+            .default_width(800.0)
+            .default_height(600.0)
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                Self::show_search_options(ui, app, &mut actions);
+                ui.separator();
+
+                if let Some(error) = &app.duplicates.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                if let Some(status) = &app.duplicates.status {
+                    ui.label(status);
+                }
+                Self::show_results(ui, app, &mut actions);
+            });
+
+        Self::show_confirmation(ctx, app, &mut actions);
+
+        if !open {
+            app.state.show_duplicates_view = false;
+        }
+        for action in actions {
+            Self::apply(app, action);
+        }
+    }
+
+    /// 検索対象と条件
+    fn show_search_options(
+        ui: &mut egui::Ui,
+        app: &mut FileVisorApp,
+        actions: &mut Vec<DuplicatesAction>,
+    ) {
+        let running = app.duplicates.is_running();
+
+        ui.label("検索対象:");
+        for (index, root) in app.duplicates.roots.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!running, egui::Button::new("✖").small())
+                    .clicked()
+                {
+                    actions.push(DuplicatesAction::RemoveRoot(index));
+                }
+                ui.label(root.display().to_string());
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!running, |ui| {
+                if ui.button("＋ 現在のフォルダー").clicked() {
+                    actions.push(DuplicatesAction::AddRoot(app.state.pane().current_path.clone()));
+                }
+                if ui.button("📁 フォルダーを追加...").clicked()
+                    && let Some(path) = rfd::FileDialog::new()
+                        .set_directory(&app.state.pane().current_path)
+                        .pick_folder()
+                {
+                    actions.push(DuplicatesAction::AddRoot(path));
+                }
+            });
+        });
+
+        egui::Grid::new("duplicates_options")
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("最小サイズ:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut app.state.duplicates_min_size)
+                            .speed(1024.0)
+                            .suffix(" バイト"),
+                    );
+                    ui.label(RichText::new(format_size(app.state.duplicates_min_size)).weak());
+                });
+                ui.end_row();
+
+                ui.label("対象:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut app.state.duplicates_include)
+                        .hint_text("*.jpg, *.png（空欄ですべて）"),
+                );
+                ui.end_row();
+
+                ui.label("除外:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut app.state.duplicates_exclude)
+                        .hint_text("node_modules, *.tmp"),
+                );
+                ui.end_row();
+            });
+        ui.checkbox(
+            &mut app.state.duplicates_hardlinks,
+            "同じデータへのハードリンクも重複として扱う",
+        );
+
+        ui.horizontal(|ui| {
+            let can_search = !running && !app.duplicates.roots.is_empty();
+            if ui
+                .add_enabled(can_search, egui::Button::new("🔍 検索"))
+                .clicked()
+            {
+                actions.push(DuplicatesAction::Search);
+            }
+            if running && ui.button("⏹ 中止").clicked() {
+                actions.push(DuplicatesAction::Cancel);
+            }
+        });
+
+        if let Some(progress) = app.duplicates.progress() {
+            let stage = match progress.stage {
+                DuplicateStage::Scanning => "ファイルを収集中",
+                DuplicateStage::PartialHash => "先頭部分を比較中",
+                DuplicateStage::FullHash => "内容全体を比較中",
+            };
+            ui.horizontal(|ui| {
+                ui.spinner();
+                if progress.total > 0 {
+                    ui.add(
+                        egui::ProgressBar::new(progress.done as f32 / progress.total as f32)
+                            .desired_width(200.0)
+                            .text(format!("{} / {}", progress.done, progress.total)),
+                    );
+                    ui.label(format!(
+                        "{}（読み込み {}）",
+                        stage,
+                        format_size(progress.bytes_hashed)
+                    ));
+                } else {
+                    ui.label(format!("{}: {} ファイル", stage, progress.done));
+                }
+            });
+            ui.label(
+                RichText::new(progress.current.display().to_string())
+                    .small()
+                    .weak(),
+            );
+        }
+    }
+
+    /// 検索結果（グループごとに残すファイルを選択）
+    fn show_results(
+        ui: &mut egui::Ui,
+        app: &mut FileVisorApp,
+        actions: &mut Vec<DuplicatesAction>,
+    ) {
+        let finder = &mut app.duplicates;
+        let Some(report) = &finder.report else {
+            if !finder.is_running() {
+                ui.label("検索結果がありません");
+            }
+            return;
+        };
+        if report.groups.is_empty() {
+            ui.label(format!(
+                "重複ファイルは見つかりませんでした（{} ファイルを検査）",
+                report.files_scanned
+            ));
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!(
+                    "{} グループ, 削減可能な容量 {}",
+                    report.groups.len(),
+                    format_size(report.wasted_space)
+                ))
+                .strong(),
+            );
+            ui.label(format!(
+                "（{} ファイルを検査, {} 件のエラー）",
+                report.files_scanned, report.errors
+            ));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("残すファイル:");
+            if ui.button("最も古いもの").clicked() {
+                actions.push(DuplicatesAction::KeepOldest);
+            }
+            if ui.button("最も新しいもの").clicked() {
+                actions.push(DuplicatesAction::KeepNewest);
+            }
+            ui.separator();
+            ui.label("残り:");
+            if ui.button("🗑 削除...").clicked() {
+                actions.push(DuplicatesAction::Request(DuplicateResolution::Delete));
+            }
+            if ui.button("🔗 ハードリンクに置き換え...").clicked() {
+                actions.push(DuplicatesAction::Request(DuplicateResolution::HardLink));
+            }
+            if ui.button("📦 移動...").clicked()
+                && let Some(destination) = rfd::FileDialog::new().pick_folder()
+            {
+                actions.push(DuplicatesAction::Request(DuplicateResolution::MoveTo(
+                    destination,
+                )));
+            }
+        });
+        ui.separator();
+
+        let expand = report.groups.len() <= AUTO_EXPAND_GROUPS;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for (index, group) in report.groups.iter().enumerate() {
+                    let title = format!(
+                        "{} × {} 件（削減 {}）",
+                        format_size(group.size),
+                        group.files.len(),
+                        format_size(group.wasted_space())
+                    );
+                    egui::CollapsingHeader::new(title)
+                        .id_salt(("duplicate_group", &group.hash, index))
+                        .default_open(expand)
+                        .show(ui, |ui| {
+                            let Some(keep) = finder.keep.get_mut(index) else {
+                                return;
+                            };
+                            for (file_index, file) in group.files.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    ui.radio_value(keep, file_index, "")
+                                        .on_hover_text("このファイルを残す");
+                                    let text = RichText::new(file.path.display().to_string());
+                                    let text = if *keep == file_index {
+                                        text.strong()
+                                    } else {
+                                        text
+                                    };
+                                    ui.label(text).context_menu(|ui| {
+                                        if ui.button("ファイル一覧で表示").clicked() {
+                                            actions.push(DuplicatesAction::OpenInList(
+                                                file.path.clone(),
+                                            ));
+                                            ui.close_menu();
+                                        }
+                                    });
+                                    if let Some(modified) = file.modified {
+                                        ui.label(
+                                            RichText::new(
+                                                modified.format("%Y/%m/%d %H:%M").to_string(),
+                                            )
+                                            .weak(),
+                                        );
+                                    }
+                                    if !file.hard_links.is_empty() {
+                                        ui.label(
+                                            RichText::new(format!("🔗 {}", file.hard_links.len()))
+                                                .weak(),
+                                        )
+                                        .on_hover_text(
+                                            file.hard_links
+                                                .iter()
+                                                .map(|p| p.display().to_string())
+                                                .collect::<Vec<_>>()
+                                                .join("\n"),
+                                        );
+                                    }
+                                });
+                            }
+                        });
+                }
+            });
+    }
+
+    /// 処理の確認ダイアログ
+    fn show_confirmation(
+        ctx: &egui::Context,
+        app: &FileVisorApp,
+        actions: &mut Vec<DuplicatesAction>,
+    ) {
+        let Some(resolution) = &app.duplicates.pending else {
+            return;
+        };
+        let Some(report) = &app.duplicates.report else {
+            return;
+        };
+        let count: usize = report
+            .groups
+            .iter()
+            .map(|g| g.files.len().saturating_sub(1))
+            .sum();
+
+        egui::Window::new("重複ファイルの処理")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let message = match resolution {
+                    DuplicateResolution::Delete => format!(
+                        "各グループで選択したファイル以外の {} 件をゴミ箱へ移動しますか？",
+                        count
+                    ),
+                    DuplicateResolution::HardLink => format!(
+                        "各グループで選択したファイル以外の {} 件をハードリンクに置き換えますか？",
+                        count
+                    ),
+                    DuplicateResolution::MoveTo(destination) => format!(
+                        "各グループで選択したファイル以外の {} 件を {} へ移動しますか？",
+                        count,
+                        destination.display()
+                    ),
+                };
+                ui.label(message);
+                if *resolution != DuplicateResolution::HardLink {
+                    ui.label(format!(
+                        "解放される容量: {}",
+                        format_size(report.wasted_space)
+                    ));
+                }
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(resolution.label()).clicked() {
+                        actions.push(DuplicatesAction::Confirm);
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        actions.push(DuplicatesAction::CancelPending);
+                    }
+                });
+            });
+    }
+
+    fn apply(app: &mut FileVisorApp, action: DuplicatesAction) {
+        match action {
+            DuplicatesAction::Search => {
+                let options = DuplicateOptions {
+                    min_size: app.state.duplicates_min_size,
+                    include: split_globs(&app.state.duplicates_include),
+                    exclude: split_globs(&app.state.duplicates_exclude),
+                    hardlinks_are_duplicates: app.state.duplicates_hardlinks,
+                    ..Default::default()
+                };
+                app.duplicates.start(options);
+            }
+            DuplicatesAction::Cancel => app.duplicates.cancel(),
+            DuplicatesAction::AddRoot(path) => {
+                if !app.duplicates.roots.contains(&path) {
+                    app.duplicates.roots.push(path);
+                }
+            }
+            DuplicatesAction::RemoveRoot(index) => {
+                if index < app.duplicates.roots.len() {
+                    app.duplicates.roots.remove(index);
+                }
+            }
+            DuplicatesAction::KeepOldest | DuplicatesAction::KeepNewest => {
+                let newest = matches!(action, DuplicatesAction::KeepNewest);
+                if let Some(report) = &app.duplicates.report {
+                    // グループ内のファイルは古い順に並んでいる
+                    app.duplicates.keep = report
+                        .groups
+                        .iter()
+                        .map(|g| if newest { g.files.len() - 1 } else { 0 })
+                        .collect();
+                }
+            }
+            DuplicatesAction::OpenInList(path) => {
+                if let Some(parent) = path.parent() {
+                    app.navigate_to(parent.to_path_buf());
+                    app.state.pane_mut().selected_items = vec![path];
+                }
+            }
+            DuplicatesAction::Request(resolution) => {
+                app.duplicates.pending = Some(resolution);
+            }
+            DuplicatesAction::Confirm => {
+                if let Some(resolution) = app.duplicates.pending.take() {
+                    match app.duplicates.resolve(&resolution) {
+                        Ok(operations) => {
+                            for operation in operations {
+                                app.record_operation(operation);
+                            }
+                            app.volume_info_checked = None;
+                        }
+                        Err(e) => app.duplicates.error = Some(e),
+                    }
+                }
+            }
+            DuplicatesAction::CancelPending => {
+                app.duplicates.pending = None;
+            }
+        }
+    }
+}
+
+/// カンマ区切りのグロブパターンを分割
+fn split_globs(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 use egui::{Context, Color32, Stroke, Response};
 use crate::app::FileVisorApp;
 use crate::state::ActivePane;
+use crate::ui::{BookmarksUI, SmartFoldersUI, TrashUI};
 
 pub struct ExplorerTreeUI;
 
@@ -28,7 +29,11 @@ impl ExplorerTreeUI {
             egui::ScrollArea::vertical()
                 .id_salt("explorer_tree")
                 .show(ui, |ui| {
+                    BookmarksUI::show_section(ui, app);
+                    ui.separator();
+                    SmartFoldersUI::show_section(ui, app);
                     Self::show_drives(ui, app);
+                    Self::show_trash(ui, app);
                     ui.separator();
                     Self::show_directory_tree(ui, app);
                 });
@@ -47,7 +52,7 @@ impl ExplorerTreeUI {
                     .map(|p| p.starts_with(&drive_path))
                     .unwrap_or(false);
                 
-                let is_current = app.state.current_path.starts_with(&drive_path);
+                let is_current = app.state.pane().current_path.starts_with(&drive_path);
                 
                 let response = ui.selectable_label(
                     is_selected,
@@ -91,11 +96,19 @@ impl ExplorerTreeUI {
         }
     }
     
+    /// ゴミ箱（クリックで一覧を開く）
+    fn show_trash(ui: &mut egui::Ui, app: &mut FileVisorApp) {
+        let response = ui.selectable_label(app.state.show_trash_view, "🗑 ゴミ箱");
+        if response.clicked() {
+            TrashUI::open(app);
+        }
+    }
+    
     fn show_directory_tree(ui: &mut egui::Ui, app: &mut FileVisorApp) {
         ui.label("📂 フォルダーツリー");
         
         // 現在のパスをクローンして借用問題を回避
-        let current_path = app.state.current_path.clone();
+        let current_path = app.state.pane().current_path.clone();
         
         // 親ディレクトリがあれば表示
         if let Some(parent) = current_path.parent() {
@@ -147,9 +160,20 @@ impl ExplorerTreeUI {
                 .map(|p| *p == *folder_path)
                 .unwrap_or(false);
             
-            let is_current_path = app.state.current_path == *folder_path;
+            let is_current_path = app.state.pane().current_path == *folder_path;
             
             let response = ui.selectable_label(is_selected, format!("📁 {}", folder_name));
+            BookmarksUI::drag_source(ui, &response, folder_path);
+            let mut add_bookmark = false;
+            response.context_menu(|ui| {
+                if ui.button("🔖 ブックマークに追加").clicked() {
+                    add_bookmark = true;
+                    ui.close_menu();
+                }
+            });
+            if add_bookmark {
+                BookmarksUI::open_new(app, folder_path);
+            }
             
             if response.clicked() {
                 app.state.active_pane = ActivePane::LeftSidebar;
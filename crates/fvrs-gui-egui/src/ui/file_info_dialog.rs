@@ -1,6 +1,9 @@
 use egui::{Color32, RichText, ScrollArea};
 use crate::app::FileVisorApp;
+use crate::executable_info::ExecutableInfo;
 use crate::file_info::{DetailedFileInfo, FileInfoCollector, format_size};
+use crate::state::FileInfoTab;
+use super::VolumeBar;
 
 pub struct FileInfoDialog;
 
@@ -13,6 +16,25 @@ impl FileInfoDialog {
 
         let target_path = app.state.file_info_target.clone();
         let mut close_requested = false;
+        let mut refresh_requested = false;
+        
+        // 解析結果はパスが変わるまでキャッシュ（実行ファイルの解析を毎フレーム行わない）
+        let cached_path = app.file_info_cache.as_ref().map(|info| info.full_path.clone());
+        let load_error = match &target_path {
+            Some(path) if cached_path.as_ref() != Some(path) => {
+                match FileInfoCollector::collect_detailed_info(path) {
+                    Ok(info) => {
+                        app.file_info_cache = Some(info);
+                        None
+                    }
+                    Err(err) => {
+                        app.file_info_cache = None;
+                        Some(err)
+                    }
+                }
+            }
+            _ => None,
+        };
         
         egui::Window::new("ファイル情報")
             .default_width(600.0)
@@ -21,15 +43,10 @@ impl FileInfoDialog {
             .collapsible(false)
             .open(&mut app.state.show_file_info_dialog)
             .show(ctx, |ui| {
-                if let Some(target_path) = &target_path {
-                    match FileInfoCollector::collect_detailed_info(target_path) {
-                        Ok(info) => {
-                            Self::show_file_info_content(ui, &info);
-                        }
-                        Err(err) => {
-                            ui.colored_label(Color32::RED, format!("エラー: {}", err));
-                        }
-                    }
+                if let Some(err) = &load_error {
+                    ui.colored_label(Color32::RED, format!("エラー: {}", err));
+                } else if let Some(info) = &app.file_info_cache {
+                    Self::show_file_info_content(ui, info, &mut app.state.file_info_tab);
                 } else {
                     ui.label("ファイルが選択されていません");
                 }
@@ -44,57 +61,56 @@ impl FileInfoDialog {
                         }
                         
                         if ui.button("更新").clicked() {
-                            // 強制的に情報を再取得
+                            refresh_requested = true;
                         }
                     });
                 });
             });
         
+        if refresh_requested {
+            // 強制的に情報を再取得
+            app.file_info_cache = None;
+        }
         if close_requested {
             app.state.show_file_info_dialog = false;
         }
     }
     
     /// ファイル情報の詳細内容を表示
-    fn show_file_info_content(ui: &mut egui::Ui, info: &DetailedFileInfo) {
-        ScrollArea::vertical().show(ui, |ui| {
-            // ファイルアイコンと基本情報
-            ui.horizontal(|ui| {
-                // アイコン（簡略化）
-                let icon = if info.is_directory {
-                    "📁"
-                } else {
-                    Self::get_file_icon(&info.file_extension)
-                };
-                ui.label(RichText::new(icon).size(32.0));
-                
-                ui.vertical(|ui| {
-                    ui.label(RichText::new(&info.name).size(16.0).strong());
-                    ui.label(RichText::new(&info.file_type).color(Color32::GRAY));
-                });
-            });
-            
-            ui.add_space(10.0);
-            
-            // タブ形式で情報を整理（簡易実装）
-            ui.horizontal(|ui| {
-                let _ = ui.selectable_label(true, "全般");
-                let _ = ui.selectable_label(false, "詳細");
-                let _ = ui.selectable_label(false, "セキュリティ");
-            });
-            
-            ui.separator();
-            Self::show_general_info(ui, info);
-            
-            ui.separator();
-            ui.collapsing("詳細情報", |ui| {
-                Self::show_detailed_info(ui, info);
-            });
+    fn show_file_info_content(ui: &mut egui::Ui, info: &DetailedFileInfo, tab: &mut FileInfoTab) {
+        // ファイルアイコンと基本情報
+        ui.horizontal(|ui| {
+            // アイコン（簡略化）
+            let icon = if info.is_directory {
+                "📁"
+            } else {
+                Self::get_file_icon(&info.file_extension)
+            };
+            ui.label(RichText::new(icon).size(32.0));
             
-            ui.collapsing("セキュリティ情報", |ui| {
-                Self::show_security_info(ui, info);
+            ui.vertical(|ui| {
+                ui.label(RichText::new(&info.name).size(16.0).strong());
+                ui.label(RichText::new(&info.file_type).color(Color32::GRAY));
             });
         });
+        
+        ui.add_space(10.0);
+        
+        // タブ
+        ui.horizontal(|ui| {
+            ui.selectable_value(tab, FileInfoTab::General, "全般");
+            ui.selectable_value(tab, FileInfoTab::Details, "詳細");
+            ui.selectable_value(tab, FileInfoTab::Security, "セキュリティ");
+        });
+        
+        ui.separator();
+        ScrollArea::vertical().show(ui, |ui| {
+            match tab {
+                FileInfoTab::General => Self::show_general_info(ui, info),
+                FileInfoTab::Details => Self::show_detailed_info(ui, info),
+                FileInfoTab::Security => Self::show_security_info(ui, info),
+            }
+        });
     }
     
     /// 全般情報タブ
@@ -236,16 +252,46 @@ impl FileInfoDialog {
         
         ui.add_space(20.0);
         
+        // 実行可能ファイル情報
+        if let Some(executable) = &info.executable {
+            ui.separator();
+            Self::show_executable_info(ui, executable);
+            ui.add_space(20.0);
+        }
+        
         // ディスク容量情報
         if let Some(disk_info) = &info.disk_space {
             ui.separator();
             ui.label(RichText::new("ディスク容量情報").strong());
             ui.add_space(5.0);
             
+            VolumeBar::show(ui, disk_info, ui.available_width().min(400.0));
+            ui.add_space(5.0);
+            
             egui::Grid::new("disk_info_grid")
                 .num_columns(2)
                 .spacing([20.0, 8.0])
                 .show(ui, |ui| {
+                    ui.label("マウントポイント:");
+                    ui.label(disk_info.mount_point.display().to_string());
+                    ui.end_row();
+                    
+                    if let Some(label) = &disk_info.label {
+                        ui.label("ボリュームラベル:");
+                        ui.label(label);
+                        ui.end_row();
+                    }
+                    
+                    ui.label("ファイルシステム:");
+                    ui.label(if disk_info.filesystem.is_empty() { "不明" } else { &disk_info.filesystem });
+                    ui.end_row();
+                    
+                    if !disk_info.device.is_empty() {
+                        ui.label("デバイス:");
+                        ui.label(&disk_info.device);
+                        ui.end_row();
+                    }
+                    
                     ui.label("合計サイズ:");
                     ui.label(format_size(disk_info.total_space));
                     ui.end_row();
@@ -254,13 +300,93 @@ impl FileInfoDialog {
                     ui.label(format_size(disk_info.free_space));
                     ui.end_row();
                     
+                    if disk_info.available_space != disk_info.free_space {
+                        ui.label("利用可能:");
+                        ui.label(format_size(disk_info.available_space));
+                        ui.end_row();
+                    }
+                    
                     ui.label("使用済み:");
-                    ui.label(format_size(disk_info.used_space));
+                    ui.label(format!("{} ({:.1}%)", format_size(disk_info.used_space), disk_info.usage_ratio() * 100.0));
                     ui.end_row();
+                    
+                    ui.label("ブロックサイズ:");
+                    ui.label(format_size(disk_info.block_size));
+                    ui.end_row();
+                    
+                    if disk_info.read_only {
+                        ui.label("マウント:");
+                        ui.label("読み取り専用");
+                        ui.end_row();
+                    }
                 });
         }
     }
     
+    /// 実行可能ファイル（PE / ELF / Mach-O）の情報
+    fn show_executable_info(ui: &mut egui::Ui, executable: &ExecutableInfo) {
+        ui.label(RichText::new("実行可能ファイル情報").strong());
+        ui.add_space(5.0);
+        
+        egui::Grid::new("executable_info_grid")
+            .num_columns(2)
+            .spacing([20.0, 8.0])
+            .striped(true)
+            .show(ui, |ui| {
+                let mut row = |label: &str, value: &str| {
+                    ui.label(RichText::new(label).strong());
+                    ui.label(value);
+                    ui.end_row();
+                };
+                
+                if let Some(format) = &executable.format {
+                    row("形式:", format.display_name());
+                }
+                row("アーキテクチャ:", &executable.architecture);
+                row("ビット数:", &format!("{} ビット ({})", executable.bits, executable.endianness));
+                row("種類:", &executable.kind);
+                
+                if let Some(subsystem) = &executable.subsystem {
+                    row("サブシステム:", subsystem);
+                }
+                if executable.is_dotnet {
+                    row("ランタイム:", ".NET (マネージド)");
+                }
+                if let Some(product) = &executable.product_name {
+                    row("製品名:", product);
+                }
+                if let Some(version) = &executable.product_version {
+                    row("製品バージョン:", version);
+                }
+                if let Some(original) = &executable.original_filename {
+                    row("元のファイル名:", original);
+                }
+                row("デジタル署名:", if executable.signed { "あり" } else { "なし" });
+                
+                if let Some(interpreter) = &executable.interpreter {
+                    row("インタープリタ:", interpreter);
+                }
+                if let Some(soname) = &executable.soname {
+                    row("ライブラリ名:", soname);
+                }
+                if let Some(build_id) = &executable.build_id {
+                    row("ビルドID:", build_id);
+                }
+                if let Some(uuid) = &executable.uuid {
+                    row("UUID:", uuid);
+                }
+            });
+        
+        if !executable.dependencies.is_empty() {
+            ui.add_space(10.0);
+            ui.collapsing(format!("依存ライブラリ ({})", executable.dependencies.len()), |ui| {
+                for dependency in &executable.dependencies {
+                    ui.monospace(dependency);
+                }
+            });
+        }
+    }
+    
     /// セキュリティ情報タブ
     fn show_security_info(ui: &mut egui::Ui, info: &DetailedFileInfo) {
         ui.add_space(10.0);
@@ -274,6 +400,10 @@ impl FileInfoDialog {
                 ui.label(&info.owner);
                 ui.end_row();
                 
+                ui.label(RichText::new("グループ:").strong());
+                ui.label(&info.group);
+                ui.end_row();
+                
                 ui.label(RichText::new("アクセス許可:").strong());
                 ui.label(&info.permissions);
                 ui.end_row();
@@ -1,70 +1,264 @@
-use std::path::Path;
-use egui::{Color32, Layout, Align, Stroke};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use egui::{Color32, Layout, Align, RichText, Stroke};
 use egui_extras::{TableBuilder, Column};
 use fvrs_core::core::FileEntry;
-use crate::state::{ViewMode, SortColumn, ActivePane};
+use fvrs_core::thumbnail::{self, ThumbnailSize};
+use crate::app::FileVisorApp;
+use crate::state::{ViewMode, SortColumn, ActivePane, PaneSide, PaneState};
+use crate::thumbnails::ThumbnailCache;
+use crate::ui::{BookmarksUI, FileViewerUI, TabBarUI};
 use crate::utils::{format_file_size, format_time};
 
 pub struct FileListUI;
 
+/// ファイルリストの表示方法
+pub struct FileListView<'a> {
+    pub view_mode: ViewMode,
+    /// 操作対象のペインか（枠の色を変える）
+    pub is_active: bool,
+    pub thumbnails: &'a mut ThumbnailCache,
+    /// グリッド表示の項目の大きさ
+    pub grid_item_size: f32,
+}
+
+/// 表示する行（先頭に「..」、続いて並べ替え済みの項目）
+pub struct ListRows<'a> {
+    parent: Option<FileEntry>,
+    entries: &'a [Arc<FileEntry>],
+}
+
+impl ListRows<'_> {
+    fn len(&self) -> usize {
+        self.entries.len() + usize::from(self.parent.is_some())
+    }
+
+    fn get(&self, index: usize) -> &FileEntry {
+        match &self.parent {
+            Some(parent) if index == 0 => parent,
+            Some(_) => &self.entries[index - 1],
+            None => &self.entries[index],
+        }
+    }
+}
+
 impl FileListUI {
+    /// ファイル一覧のペインを表示（2画面表示では左右それぞれに呼ぶ）
+    pub fn show_pane(ui: &mut egui::Ui, app: &mut FileVisorApp, side: PaneSide) {
+        let is_target = app.state.active_side == side;
+        let is_active = is_target && app.state.active_pane == ActivePane::MainList;
+
+        // 表示するディレクトリを決定（操作対象のペインは左ペインの選択があればそれを使用）
+        let current_path = app.state.pane_at(side).current_path.clone();
+        let display_path = app.state.sidebar_selected_item
+            .clone()
+            .filter(|_| is_target)
+            .unwrap_or_else(|| current_path.clone());
+
+        let mut navigation_target: Option<PathBuf> = None;
+        let mut file_open_target: Option<PathBuf> = None;
+        let mut activate_pane = false;
+
+        ui.push_id(side, |ui| {
+            TabBarUI::show(ui, app, side);
+            if app.state.dual_pane {
+                let text = RichText::new(format!("📁 {}", display_path.display()));
+                let header = ui.add(
+                    egui::Label::new(if is_active { text.strong() } else { text.weak() })
+                        .truncate()
+                        .sense(egui::Sense::click()),
+                );
+                if header.clicked() {
+                    activate_pane = true;
+                }
+            }
+
+            // 読み込みは別スレッドで進むので、読み込まれた分から表示する
+            let loading = match app.load_directory(&display_path) {
+                Ok(listing) => listing.is_loading(),
+                Err(error_msg) => {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(50.0);
+                        ui.colored_label(Color32::RED, "❌ ディレクトリアクセスエラー");
+                        ui.label(&error_msg);
+                        ui.add_space(10.0);
+                        
+                        ui.horizontal(|ui| {
+                            if ui.button("再試行").clicked() {
+                                app.directory_cache.remove(&display_path);
+                            }
+                            if ui.button("ホームに戻る").clicked() {
+                                navigation_target = std::env::home_dir();
+                            }
+                            if ui.button("Cドライブに移動").clicked() {
+                                navigation_target = Some(PathBuf::from("C:\\"));
+                            }
+                        });
+                        
+                        ui.add_space(10.0);
+                        ui.colored_label(Color32::GRAY, "💡 ヒント: パスが存在するか、アクセス権限があるか確認してください");
+                    });
+                    return;
+                }
+            };
+
+            // 親ディレクトリエントリを作成（ルートでない場合、スマートフォルダーにはない）
+            let smart_folder = app.smart_folders.by_path(&display_path).cloned();
+            let parent = display_path.parent().filter(|_| smart_folder.is_none()).map(|parent| FileEntry {
+                name: "..".to_string(),
+                path: parent.to_path_buf(),
+                size: 0,
+                is_dir: true,
+                created: chrono::DateTime::from(std::time::SystemTime::UNIX_EPOCH),
+                modified: chrono::DateTime::from(std::time::SystemTime::UNIX_EPOCH),
+                extension: None,
+            });
+
+            // ペインごとのソート条件と検索語で並べ替え・絞り込み（件数が多ければ別スレッド）
+            let listing = &app.directory_cache[&display_path];
+            let pane = app.state.pane_at(side);
+            let view = app.list_views.entry(side).or_default();
+            view.update(
+                &display_path,
+                listing,
+                &pane.sort_column,
+                pane.sort_ascending,
+                &app.state.search_query,
+            );
+            if loading || view.is_pending() || listing.is_refresh_pending() {
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(50));
+            }
+            let Some(entries) = view.rows(&display_path) else {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(50.0);
+                    ui.spinner();
+                    ui.label(format!("読み込み中... ({} 件)", listing.entries().len()));
+                });
+                return;
+            };
+            let rows = ListRows { parent, entries };
+            if let Some(folder) = &smart_folder {
+                ui.label(
+                    RichText::new(format!("{} {} — {} の検索結果（変更に合わせて更新）", folder.icon, folder.name, folder.root.display()))
+                        .weak(),
+                );
+            }
+
+            let view_mode = app.state.view_mode.clone();
+            let grid_item_size = app.state.grid_item_size;
+            let pane = app.state.pane_at_mut(side);
+            let mut navigate_callback = |path: PathBuf| {
+                navigation_target = Some(path);
+            };
+            let mut file_open_callback = |path: PathBuf| {
+                file_open_target = Some(path);
+            };
+
+            let view = FileListView {
+                view_mode,
+                is_active,
+                thumbnails: &mut app.thumbnails,
+                grid_item_size,
+            };
+            if Self::show_file_list(
+                ui,
+                &rows,
+                &display_path,
+                view,
+                pane,
+                &mut navigate_callback,
+                &mut file_open_callback,
+            ) {
+                activate_pane = true;
+            }
+        });
+
+        // ペイン内のどこを押しても操作対象にする
+        if ui.ui_contains_pointer() && ui.input(|i| i.pointer.any_pressed()) {
+            activate_pane = true;
+        }
+
+        // ペインアクティブ化
+        if activate_pane || navigation_target.is_some() || file_open_target.is_some() {
+            app.activate_pane(side);
+        }
+
+        // ナビゲーションの実行（左ペインの選択も移動先に合わせる）
+        if let Some(target) = navigation_target {
+            app.state.sidebar_selected_item = Some(target.clone());
+            app.navigate_to(target);
+        }
+
+        // ファイル閲覧の実行
+        if let Some(target) = file_open_target {
+            // 圧縮ファイルかどうかをチェック
+            if crate::archive::ArchiveHandler::is_archive(&target) {
+                tracing::info!("圧縮ファイルビューアを表示: {:?}", target);
+                app.show_archive_viewer(target);
+            } else {
+                FileViewerUI::open_file_for_viewing(app, target);
+            }
+        }
+    }
+
+    /// 行の項目のパス（スマートフォルダーでは項目ごとに別のフォルダーにある）
+    fn entry_path(current_path: &Path, entry: &FileEntry) -> PathBuf {
+        if entry.name == ".." {
+            current_path.join(&entry.name)
+        } else {
+            entry.path.clone()
+        }
+    }
+
     /// ファイルリスト表示のメイン関数
+    ///
+    /// ペインの枠がクリックされたら `true` を返す
     pub fn show_file_list(
         ui: &mut egui::Ui,
-        entries: &[&FileEntry],
-        view_mode: ViewMode,
+        rows: &ListRows,
         current_path: &Path,
-        selected_items: &mut Vec<std::path::PathBuf>,
-        last_selected_index: &mut Option<usize>,
-        sort_column: &mut SortColumn,
-        sort_ascending: &mut bool,
-        directory_cache: &mut std::collections::HashMap<std::path::PathBuf, Vec<FileEntry>>,
+        view: FileListView,
+        pane: &mut PaneState,
         navigate_callback: &mut dyn FnMut(std::path::PathBuf),
         file_open_callback: &mut dyn FnMut(std::path::PathBuf),
-        active_pane: &ActivePane,
-        pane_activate_callback: &mut dyn FnMut(),
-    ) {
-        let is_active = *active_pane == ActivePane::MainList;
-        
+    ) -> bool {
         // ペイン全体にフレームを適用してアクティブ状態を視覚化
         let frame = egui::Frame::default()
-            .stroke(if is_active {
+            .stroke(if view.is_active {
                 Stroke::new(2.0, Color32::from_rgb(0, 120, 215)) // 青い枠
             } else {
                 Stroke::new(1.0, Color32::GRAY) // グレーの枠
             });
             
         let response = frame.show(ui, |ui| {
-            match view_mode {
+            match view.view_mode {
                 ViewMode::Details => Self::show_details_view(
-                    ui, entries, current_path, selected_items, last_selected_index,
-                    sort_column, sort_ascending, directory_cache, navigate_callback, file_open_callback
+                    ui, rows, current_path, &mut pane.selected_items, &mut pane.last_selected_index,
+                    &mut pane.sort_column, &mut pane.sort_ascending, navigate_callback, file_open_callback
                 ),
                 ViewMode::List => Self::show_list_view(
-                    ui, entries, current_path, selected_items, last_selected_index, navigate_callback, file_open_callback
+                    ui, rows, current_path, &mut pane.selected_items, &mut pane.last_selected_index,
+                    navigate_callback, file_open_callback
                 ),
                 ViewMode::Grid => Self::show_grid_view(
-                    ui, entries, current_path, selected_items, last_selected_index, navigate_callback, file_open_callback
+                    ui, rows, current_path, view, pane, navigate_callback, file_open_callback
                 ),
             }
         });
         
         // フレームがクリックされたらペインをアクティブ化
-        if response.response.clicked() {
-            pane_activate_callback();
-        }
+        response.response.clicked()
     }
 
     /// 詳細ビュー
     fn show_details_view(
         ui: &mut egui::Ui,
-        entries: &[&FileEntry],
+        rows: &ListRows,
         current_path: &Path,
         selected_items: &mut Vec<std::path::PathBuf>,
         last_selected_index: &mut Option<usize>,
         sort_column: &mut SortColumn,
         sort_ascending: &mut bool,
-        directory_cache: &mut std::collections::HashMap<std::path::PathBuf, Vec<FileEntry>>,
         navigate_callback: &mut dyn FnMut(std::path::PathBuf),
         file_open_callback: &mut dyn FnMut(std::path::PathBuf),
     ) {
@@ -93,7 +287,6 @@ impl FileListUI {
                             *sort_column = SortColumn::Name;
                             *sort_ascending = true;
                         }
-                        directory_cache.remove(&current_path.to_path_buf());
                     }
                 });
                 header.col(|ui| {
@@ -106,7 +299,6 @@ impl FileListUI {
                             *sort_column = SortColumn::Size;
                             *sort_ascending = true;
                         }
-                        directory_cache.remove(&current_path.to_path_buf());
                     }
                 });
                 header.col(|ui| {
@@ -119,7 +311,6 @@ impl FileListUI {
                             *sort_column = SortColumn::Modified;
                             *sort_ascending = true;
                         }
-                        directory_cache.remove(&current_path.to_path_buf());
                     }
                 });
                 header.col(|ui| {
@@ -132,15 +323,14 @@ impl FileListUI {
                             *sort_column = SortColumn::Type;
                             *sort_ascending = true;
                         }
-                        directory_cache.remove(&current_path.to_path_buf());
                     }
                 });
             })
             .body(|body| {
-                body.rows(20.0, entries.len(), |mut row| {
+                body.rows(20.0, rows.len(), |mut row| {
                     let row_index = row.index();
-                    let entry = entries[row_index];
-                    let entry_path = current_path.join(&entry.name);
+                    let entry = rows.get(row_index);
+                    let entry_path = Self::entry_path(current_path, entry);
                     let is_selected = selected_items.contains(&entry_path);
 
                     row.col(|ui| {
@@ -156,6 +346,9 @@ impl FileListUI {
                         };
                         
                         let name_response = ui.selectable_label(is_selected, display_name);
+                        if entry.is_dir && entry.name != ".." {
+                            BookmarksUI::drag_source(ui, &name_response, &entry_path);
+                        }
                         if name_response.double_clicked() {
                             if entry.name == ".." {
                                 // 親ディレクトリに移動
@@ -180,9 +373,9 @@ impl FileListUI {
                                     
                                     selected_items.clear();
                                     for idx in start_idx..=end_idx {
-                                        if idx < entries.len() {
-                                            let target_entry = entries[idx];
-                                            let target_path = current_path.join(&target_entry.name);
+                                        if idx < rows.len() {
+                                            let target_entry = rows.get(idx);
+                                            let target_path = Self::entry_path(current_path, target_entry);
                                             selected_items.push(target_path);
                                         }
                                     }
@@ -239,22 +432,28 @@ impl FileListUI {
     /// リストビュー
     fn show_list_view(
         ui: &mut egui::Ui,
-        entries: &[&FileEntry],
+        rows: &ListRows,
         current_path: &Path,
         selected_items: &mut Vec<std::path::PathBuf>,
         last_selected_index: &mut Option<usize>,
         navigate_callback: &mut dyn FnMut(std::path::PathBuf),
         file_open_callback: &mut dyn FnMut(std::path::PathBuf),
     ) {
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            for (row_index, entry) in entries.iter().enumerate() {
-                let entry_path = current_path.join(&entry.name);
+        // 見えている行だけを描画する
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical().show_rows(ui, row_height, rows.len(), |ui, row_range| {
+            for row_index in row_range {
+                let entry = rows.get(row_index);
+                let entry_path = Self::entry_path(current_path, entry);
                 let is_selected = selected_items.contains(&entry_path);
 
                 ui.horizontal(|ui| {
                     ui.label(if entry.is_dir { "📁" } else { "📄" });
                     
                     let response = ui.selectable_label(is_selected, &entry.name);
+                    if entry.is_dir && entry.name != ".." {
+                        BookmarksUI::drag_source(ui, &response, &entry_path);
+                    }
                     
                     if response.double_clicked() {
                         if entry.is_dir {
@@ -275,9 +474,9 @@ impl FileListUI {
                                 
                                 selected_items.clear();
                                 for idx in start_idx..=end_idx {
-                                    if idx < entries.len() {
-                                        let target_entry = entries[idx];
-                                        let target_path = current_path.join(&target_entry.name);
+                                    if idx < rows.len() {
+                                        let target_entry = rows.get(idx);
+                                        let target_path = Self::entry_path(current_path, target_entry);
                                         selected_items.push(target_path);
                                     }
                                 }
@@ -310,41 +509,69 @@ impl FileListUI {
     /// グリッドビュー
     fn show_grid_view(
         ui: &mut egui::Ui,
-        entries: &[&FileEntry],
+        rows: &ListRows,
         current_path: &Path,
-        selected_items: &mut Vec<std::path::PathBuf>,
-        last_selected_index: &mut Option<usize>,
+        view: FileListView,
+        pane: &mut PaneState,
         navigate_callback: &mut dyn FnMut(std::path::PathBuf),
         file_open_callback: &mut dyn FnMut(std::path::PathBuf),
     ) {
-        const ITEM_SIZE: f32 = 80.0;
+        let FileListView { thumbnails, grid_item_size: item_size, .. } = view;
+        let PaneState { selected_items, last_selected_index, .. } = pane;
         const SPACING: f32 = 10.0;
         
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            let available_width = ui.available_width();
-            let items_per_row = ((available_width + SPACING) / (ITEM_SIZE + SPACING)).max(1.0) as usize;
-            
-            let mut current_index = 0;
-            for chunk in entries.chunks(items_per_row) {
+        let available_width = ui.available_width();
+        let items_per_row = ((available_width + SPACING) / (item_size + SPACING)).max(1.0) as usize;
+        let grid_rows = rows.len().div_ceil(items_per_row);
+
+        // 名前の1行分を除いた正方形にサムネイルかアイコンを描く
+        let visual_size = (item_size - ui.text_style_height(&egui::TextStyle::Body) - ui.spacing().item_spacing.y).max(16.0);
+        let thumbnail_size = ThumbnailSize::for_pixels((visual_size * ui.ctx().pixels_per_point()) as u32);
+
+        // 見えている行だけを描画する（名前は1行に収めて行の高さをそろえる）
+        let row_height = item_size + SPACING + ui.spacing().item_spacing.y;
+        egui::ScrollArea::vertical().show_rows(ui, row_height, grid_rows, |ui, grid_range| {
+            for grid_row in grid_range {
+                let start = grid_row * items_per_row;
+                let end = (start + items_per_row).min(rows.len());
                 ui.horizontal(|ui| {
-                    for (chunk_idx, entry) in chunk.iter().enumerate() {
-                        let row_index = current_index + chunk_idx;
-                        let entry_path = current_path.join(&entry.name);
+                    for row_index in start..end {
+                        let entry = rows.get(row_index);
+                        let entry_path = Self::entry_path(current_path, entry);
                         let is_selected = selected_items.contains(&entry_path);
                         
                         ui.allocate_ui_with_layout(
-                            [ITEM_SIZE, ITEM_SIZE].into(),
+                            [item_size, item_size].into(),
                             Layout::top_down(Align::Center),
                             |ui| {
-                                let icon = if entry.is_dir { "📁" } else { "📄" };
-                                
-                                let icon_response = ui.button(icon);
+                                let texture = (!entry.is_dir && thumbnail::is_supported(&entry_path))
+                                    .then(|| thumbnails.get(ui.ctx(), &entry_path, thumbnail_size, entry.modified.timestamp()))
+                                    .flatten();
+                                let icon_response = match texture {
+                                    Some(texture) => ui.add_sized(
+                                        [visual_size, visual_size],
+                                        egui::Image::new(texture)
+                                            .max_size(egui::vec2(visual_size, visual_size))
+                                            .sense(egui::Sense::click()),
+                                    ),
+                                    None => {
+                                        let icon = if entry.is_dir { "📁" } else { "📄" };
+                                        ui.add_sized(
+                                            [visual_size, visual_size],
+                                            egui::Button::new(RichText::new(icon).size(visual_size * 0.5)).frame(false),
+                                        )
+                                    }
+                                };
                                 let name_response = ui.add(
                                     egui::Label::new(&entry.name)
                                         .selectable(false)
-                                        .wrap()
+                                        .truncate()
                                 );
                                 
+                                if entry.is_dir && entry.name != ".." {
+                                    BookmarksUI::drag_source(ui, &icon_response, &entry_path);
+                                }
+                                
                                 if is_selected {
                                     let rect = icon_response.rect.union(name_response.rect);
                                     ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(2.0, Color32::BLUE), egui::StrokeKind::Outside);
@@ -369,9 +596,9 @@ impl FileListUI {
                                             
                                             selected_items.clear();
                                             for idx in start_idx..=end_idx {
-                                                if idx < entries.len() {
-                                                    let target_entry = entries[idx];
-                                                    let target_path = current_path.join(&target_entry.name);
+                                                if idx < rows.len() {
+                                                    let target_entry = rows.get(idx);
+                                                    let target_path = Self::entry_path(current_path, target_entry);
                                                     selected_items.push(target_path);
                                                 }
                                             }
@@ -402,7 +629,6 @@ impl FileListUI {
                         ui.add_space(SPACING);
                     }
                 });
-                current_index += chunk.len();
                 ui.add_space(SPACING);
             }
         });
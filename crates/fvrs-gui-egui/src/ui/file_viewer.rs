@@ -1,5 +1,15 @@
 use std::path::{Path, PathBuf};
+use egui::text::LayoutJob;
+use egui_extras::syntax_highlighting::{self, CodeTheme};
+use fvrs_core::text::{self, LineEnding, TextEncoding};
 use crate::app::FileVisorApp;
+use crate::paged_viewer::{PagedMode, PagedViewer};
+use crate::ui::PagedViewerUI;
+
+/// これより大きいテキストは色分けしない（編集中の再計算が重くなるため）
+const HIGHLIGHT_LIMIT: usize = 512 * 1024;
+/// これより大きいテキストは全体を読み込まずにページ表示する
+const LARGE_TEXT_FILE: u64 = 16 * 1024 * 1024;
 
 pub struct FileViewerUI;
 
@@ -43,22 +53,28 @@ impl FileViewerUI {
                     }
                     
                     // 保存ボタン（編集モードの場合）
-                    if app.state.view_mode_text && app.state.is_file_modified {
-                        if ui.button("💾 保存").clicked() {
-                            Self::save_file(app);
-                        }
+                    if app.state.view_mode_text && app.state.is_file_modified && ui.button("💾 保存").clicked() {
+                        Self::save_file(app);
                     }
                     
                     // 行番号表示切替（編集モードのみ）
-                    if app.state.view_mode_text {
-                        if ui.button(if app.state.show_line_numbers { "🔢 行番号OFF" } else { "🔢 行番号ON" }).clicked() {
-                            app.state.show_line_numbers = !app.state.show_line_numbers;
-                        }
+                    if app.state.view_mode_text
+                        && ui.button(if app.state.show_line_numbers { "🔢 行番号OFF" } else { "🔢 行番号ON" }).clicked()
+                    {
+                        app.state.show_line_numbers = !app.state.show_line_numbers;
                     }
                     
-                    // モード切替（バイナリファイルは編集不可）
-                    if let Some(file_path) = &app.state.viewed_file_path {
-                        if Self::is_text_file(file_path) {
+                    // モード切替（バイナリファイル・ページ表示は編集不可）
+                    if let Some(file_path) = app.state.viewed_file_path.clone() {
+                        if app.paged_viewer.is_some() {
+                            if Self::is_text_file(&file_path)
+                                && !Self::is_large_file(&file_path)
+                                && ui.button("📄 通常表示").on_hover_text("全体を読み込んで編集できるようにします").clicked()
+                            {
+                                Self::load_text_file(app, file_path, None);
+                            }
+                            ui.label("👁 閲覧専用");
+                        } else if Self::is_text_file(&file_path) {
                             if app.state.view_mode_text {
                                 if ui.button("👁 閲覧モード").clicked() {
                                     app.state.view_mode_text = false;
@@ -68,6 +84,12 @@ impl FileViewerUI {
                                 if ui.button("✏️ 編集モード").clicked() {
                                     app.state.view_mode_text = true;
                                 }
+                                // 追記され続けるログなどを末尾から追えるようにする
+                                if !app.state.is_file_modified
+                                    && ui.button("📜 ページ表示").on_hover_text("見えている部分だけを読み込み、追記を追従できます").clicked()
+                                {
+                                    Self::open_paged(app, file_path, PagedMode::Text);
+                                }
                                 ui.label("👁 閲覧中");
                             }
                         } else {
@@ -76,9 +98,28 @@ impl FileViewerUI {
                     }
                 });
             });
+
+            // 文字コード・改行コード（テキストファイルのみ。ページ表示は専用のツールバー）
+            if let Some(file_path) = app.state.viewed_file_path.clone()
+                && Self::is_text_file(&file_path)
+                && app.paged_viewer.is_none()
+            {
+                ui.horizontal(|ui| {
+                    Self::show_format_selector(ui, app, file_path);
+                });
+            }
+            if let Some(message) = &app.state.viewed_file_message {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", message));
+            }
             
             ui.separator();
             
+            // 大きなファイル・バイナリは見えている部分だけを読む
+            if app.paged_viewer.is_some() {
+                PagedViewerUI::show(ui, app);
+                return;
+            }
+            
             // ファイル内容表示・編集エリア
             egui::ScrollArea::both()
                 .auto_shrink([false, false])
@@ -88,12 +129,19 @@ impl FileViewerUI {
                         if app.state.show_line_numbers {
                             Self::show_editor_with_line_numbers(ui, app);
                         } else {
+                            let language = Self::syntax_language(app);
+                            let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                                let mut job = Self::layout_text(ui, text, language.as_deref());
+                                job.wrap.max_width = wrap_width;
+                                ui.fonts(|f| f.layout_job(job))
+                            };
                             let response = ui.add(
                                 egui::TextEdit::multiline(&mut app.state.viewed_file_content)
                                     .font(egui::TextStyle::Monospace)
                                     .desired_width(f32::INFINITY)
                                     .desired_rows(30)
                                     .code_editor()
+                                    .layouter(&mut layouter)
                             );
                             
                             if response.changed() {
@@ -107,11 +155,13 @@ impl FileViewerUI {
                         }
                     } else {
                         // 閲覧モード
+                        let language = Self::syntax_language(app);
                         if app.state.show_line_numbers {
-                            Self::show_text_with_line_numbers(ui, &app.state.viewed_file_content);
+                            Self::show_text_with_line_numbers(ui, &app.state.viewed_file_content, language.as_deref());
                         } else {
+                            let job = Self::layout_text(ui, &app.state.viewed_file_content, language.as_deref());
                             ui.add(
-                                egui::Label::new(&app.state.viewed_file_content)
+                                egui::Label::new(job)
                                     .wrap()
                             );
                         }
@@ -153,131 +203,181 @@ impl FileViewerUI {
     
     /// ファイルを開く（閲覧モード）
     pub fn open_file_for_viewing(app: &mut FileVisorApp, file_path: PathBuf) {
-        if Self::is_text_file(&file_path) {
-            match std::fs::read_to_string(&file_path) {
-                Ok(content) => {
-                    app.state.viewed_file_path = Some(file_path);
-                    app.state.viewed_file_content = content;
-                    app.state.show_file_viewer = true;
-                    app.state.view_mode_text = false; // 閲覧モード
-                    app.state.is_file_modified = false;
-                    tracing::info!("ファイルを閲覧モードで開きました: {:?}", app.state.viewed_file_path);
-                }
-                Err(e) => {
-                    tracing::error!("ファイル読み込みエラー: {:?}", e);
-                    // TODO: エラーダイアログを表示
-                }
+        if Self::is_text_file(&file_path) && Self::is_large_file(&file_path) {
+            Self::open_paged(app, file_path, PagedMode::Text);
+        } else if Self::is_text_file(&file_path) {
+            if Self::load_text_file(app, file_path, None) {
+                app.state.view_mode_text = false; // 閲覧モード
+                tracing::info!("ファイルを閲覧モードで開きました: {:?}", app.state.viewed_file_path);
             }
         } else {
             // バイナリファイルの場合は16進表示で開く
-            Self::open_binary_file_for_viewing(app, file_path);
+            Self::open_paged(app, file_path, PagedMode::Hex);
         }
     }
     
     /// ファイルを開く（編集モード）
     pub fn open_file_for_editing(app: &mut FileVisorApp, file_path: PathBuf) {
-        if Self::is_text_file(&file_path) {
-            match std::fs::read_to_string(&file_path) {
-                Ok(content) => {
-                    app.state.viewed_file_path = Some(file_path);
-                    app.state.viewed_file_content = content;
-                    app.state.show_file_viewer = true;
-                    app.state.view_mode_text = true; // 編集モード
-                    app.state.is_file_modified = false;
-                    tracing::info!("ファイルを編集モードで開きました: {:?}", app.state.viewed_file_path);
-                }
-                Err(e) => {
-                    tracing::error!("ファイル読み込みエラー: {:?}", e);
-                    // TODO: エラーダイアログを表示
-                }
+        if Self::is_text_file(&file_path) && Self::is_large_file(&file_path) {
+            // 全体を読み込まないと編集できないので閲覧だけにする
+            tracing::warn!("大きなファイルは編集できません。閲覧モードで開きます: {:?}", file_path);
+            Self::open_paged(app, file_path, PagedMode::Text);
+            app.state.viewed_file_message = Some(format!(
+                "{} を超えるファイルは編集できません。閲覧専用で開きました",
+                crate::file_info::format_size(LARGE_TEXT_FILE)
+            ));
+        } else if Self::is_text_file(&file_path) {
+            if Self::load_text_file(app, file_path, None) {
+                app.state.view_mode_text = true; // 編集モード
+                tracing::info!("ファイルを編集モードで開きました: {:?}", app.state.viewed_file_path);
             }
         } else {
             // バイナリファイルは編集不可として閲覧モードで開く
             tracing::warn!("バイナリファイルは編集できません。閲覧モードで開きます: {:?}", file_path);
-            Self::open_binary_file_for_viewing(app, file_path);
+            Self::open_paged(app, file_path, PagedMode::Hex);
         }
     }
     
-    /// バイナリファイルを16進表示で開く
-    fn open_binary_file_for_viewing(app: &mut FileVisorApp, file_path: PathBuf) {
+    /// テキストファイルを読み込む（encoding が None なら文字コードを判定）
+    fn load_text_file(app: &mut FileVisorApp, file_path: PathBuf, encoding: Option<TextEncoding>) -> bool {
         match std::fs::read(&file_path) {
-            Ok(data) => {
-                let hex_content = Self::format_as_hex(&data);
+            Ok(bytes) => {
+                let decoded = text::decode(&bytes, encoding);
+                let mut warnings = Vec::new();
+                if decoded.had_errors {
+                    warnings.push(format!(
+                        "{} として読めない文字がありました。文字コードを選び直してください（このまま保存すると失われます）",
+                        decoded.format.encoding.label()
+                    ));
+                }
+                if decoded.mixed_line_endings {
+                    warnings.push(format!(
+                        "改行コードが混在しています。保存するとすべて {} になります",
+                        decoded.format.line_ending.label()
+                    ));
+                }
+                app.state.viewed_file_message = (!warnings.is_empty()).then(|| warnings.join("\n"));
+                app.paged_viewer = None;
                 app.state.viewed_file_path = Some(file_path);
-                app.state.viewed_file_content = hex_content;
+                app.state.viewed_file_content = decoded.text;
+                app.state.viewed_file_format = decoded.format;
                 app.state.show_file_viewer = true;
-                app.state.view_mode_text = false; // 閲覧モード（編集不可）
                 app.state.is_file_modified = false;
-                tracing::info!("バイナリファイルを16進表示で開きました: {:?}", app.state.viewed_file_path);
+                true
             }
             Err(e) => {
-                tracing::error!("バイナリファイル読み込みエラー: {:?}", e);
-                // TODO: エラーダイアログを表示
+                tracing::error!("ファイル読み込みエラー: {:?}", e);
+                app.state.viewed_file_message = Some(format!("読み込めません: {}", e));
+                false
             }
         }
     }
-    
-    /// バイナリデータを16進文字列に変換
-    fn format_as_hex(data: &[u8]) -> String {
-        let mut result = String::new();
-        
-        for (i, chunk) in data.chunks(16).enumerate() {
-            // オフセット表示
-            result.push_str(&format!("{:08X}  ", i * 16));
-            
-            // 16進数表示
-            for (j, byte) in chunk.iter().enumerate() {
-                if j == 8 {
-                    result.push(' '); // 8バイト目で区切り
+
+    /// 文字コード・改行コードの選択と色分けの切り替え
+    fn show_format_selector(ui: &mut egui::Ui, app: &mut FileVisorApp, file_path: PathBuf) {
+        let current = app.state.viewed_file_format.encoding;
+        let mut selected = current;
+        ui.label("文字コード:");
+        egui::ComboBox::from_id_salt("viewer_encoding")
+            .selected_text(current.label())
+            .show_ui(ui, |ui| {
+                for encoding in TextEncoding::ALL {
+                    ui.selectable_value(&mut selected, encoding, encoding.label());
                 }
-                result.push_str(&format!("{:02X} ", byte));
+            })
+            .response
+            .on_hover_text("変更がなければ選んだ文字コードで読み直します。編集中は保存時の文字コードを変えます");
+        if selected != current {
+            if app.state.is_file_modified {
+                app.state.viewed_file_format.encoding = selected;
+            } else {
+                Self::load_text_file(app, file_path, Some(selected));
             }
-            
-            // 不足分を空白で埋める
-            let remaining = 16 - chunk.len();
-            for j in 0..remaining {
-                if chunk.len() + j == 8 {
-                    result.push(' ');
+        }
+
+        let current = app.state.viewed_file_format.line_ending;
+        let mut selected = current;
+        ui.label("改行:");
+        egui::ComboBox::from_id_salt("viewer_line_ending")
+            .selected_text(current.label())
+            .show_ui(ui, |ui| {
+                for line_ending in LineEnding::ALL {
+                    ui.selectable_value(&mut selected, line_ending, line_ending.label());
                 }
-                result.push_str("   ");
+            });
+        if selected != current {
+            // 改行コードだけの変更も保存できるようにする
+            app.state.viewed_file_format.line_ending = selected;
+            app.state.is_file_modified = true;
+        }
+
+        ui.checkbox(&mut app.state.syntax_highlighting, "色分け");
+    }
+
+    /// 色分けに使う言語名（拡張子、なければファイル名。無効時は None）
+    fn syntax_language(app: &FileVisorApp) -> Option<String> {
+        if !app.state.syntax_highlighting {
+            return None;
+        }
+        let path = app.state.viewed_file_path.as_ref()?;
+        path.extension()
+            .or_else(|| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+
+    /// テキストを言語に応じて色分けする（言語なし・大きすぎる場合は単色）
+    fn layout_text(ui: &egui::Ui, content: &str, language: Option<&str>) -> LayoutJob {
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        match language {
+            Some(language) if content.len() <= HIGHLIGHT_LIMIT => {
+                let theme = CodeTheme::from_memory(ui.ctx(), ui.style());
+                syntax_highlighting::highlight(ui.ctx(), ui.style(), &theme, content, language)
             }
-            
-            result.push(' ');
-            
-            // ASCII表示
-            for byte in chunk {
-                if byte.is_ascii_graphic() || *byte == b' ' {
-                    result.push(*byte as char);
-                } else {
-                    result.push('.');
-                }
+            _ => LayoutJob::simple(content.to_owned(), font_id, ui.visuals().text_color(), f32::INFINITY),
+        }
+    }
+
+    /// 全体を読み込まずにページ表示で開く（大きなテキスト・バイナリ）
+    fn open_paged(app: &mut FileVisorApp, file_path: PathBuf, mode: PagedMode) {
+        match PagedViewer::open(&file_path, mode) {
+            Ok(viewer) => {
+                app.paged_viewer = Some(viewer);
+                app.state.viewed_file_path = Some(file_path);
+                app.state.viewed_file_content.clear();
+                app.state.viewed_file_message = None;
+                app.state.show_file_viewer = true;
+                app.state.view_mode_text = false; // 閲覧モード（編集不可）
+                app.state.is_file_modified = false;
+                tracing::info!("ページ表示で開きました: {:?} ({:?})", app.state.viewed_file_path, mode);
+            }
+            Err(e) => {
+                tracing::error!("{}", e);
+                app.state.viewed_file_message = Some(e);
             }
-            
-            result.push('\n');
         }
-        
-        // ファイルサイズ情報を先頭に追加
-        let header = format!(
-            "バイナリファイル - サイズ: {} バイト ({} KB)\n\n",
-            data.len(),
-            (data.len() + 1023) / 1024
-        );
-        
-        header + &result
+    }
+
+    /// ページ表示にする大きさのファイルか
+    fn is_large_file(path: &Path) -> bool {
+        std::fs::metadata(path).is_ok_and(|metadata| metadata.len() > LARGE_TEXT_FILE)
     }
     
-    /// ファイルを保存
-    fn save_file(app: &mut FileVisorApp) {
-        if let Some(file_path) = &app.state.viewed_file_path {
-            match std::fs::write(file_path, &app.state.viewed_file_content) {
-                Ok(_) => {
-                    app.state.is_file_modified = false;
-                    tracing::info!("ファイルを保存しました: {:?}", file_path);
-                }
-                Err(e) => {
-                    tracing::error!("ファイル保存エラー: {:?}", e);
-                    // TODO: エラーダイアログを表示
-                }
+    /// ファイルを開いたときの文字コード・改行コードで保存（成功したら true）
+    fn save_file(app: &mut FileVisorApp) -> bool {
+        let Some(file_path) = &app.state.viewed_file_path else {
+            return false;
+        };
+        match text::write_text_file(file_path, &app.state.viewed_file_content, app.state.viewed_file_format) {
+            Ok(()) => {
+                app.state.is_file_modified = false;
+                app.state.viewed_file_message = None;
+                tracing::info!("ファイルを保存しました: {:?} ({})", file_path, app.state.viewed_file_format.encoding.label());
+                true
+            }
+            Err(e) => {
+                tracing::error!("ファイル保存エラー: {:?}", e);
+                app.state.viewed_file_message = Some(format!("保存できません: {}", e));
+                false
             }
         }
     }
@@ -296,9 +396,11 @@ impl FileViewerUI {
     
     /// ファイル閲覧パネルを強制的に閉じる（未保存変更があっても）
     pub fn force_close_file_viewer(app: &mut FileVisorApp) {
+        app.paged_viewer = None;
         app.state.show_file_viewer = false;
         app.state.viewed_file_path = None;
         app.state.viewed_file_content.clear();
+        app.state.viewed_file_message = None;
         app.state.is_file_modified = false;
         app.state.show_unsaved_dialog = false;
         app.state.pending_close_action = false;
@@ -306,41 +408,38 @@ impl FileViewerUI {
     
     /// ファイルを保存して閉じる
     pub fn save_and_close_file_viewer(app: &mut FileVisorApp) {
-        Self::save_file(app);
-        Self::force_close_file_viewer(app);
+        // 保存できなければ編集内容を残したまま開いておく
+        if Self::save_file(app) {
+            Self::force_close_file_viewer(app);
+        } else {
+            app.state.show_unsaved_dialog = false;
+            app.state.pending_close_action = false;
+        }
     }
     
     /// 行番号付きエディタを表示
     fn show_editor_with_line_numbers(ui: &mut egui::Ui, app: &mut FileVisorApp) {
-        ui.horizontal(|ui| {
-            // 行番号エリア
-            let line_count = app.state.viewed_file_content.lines().count();
-            let line_number_width = (line_count.to_string().len() as f32 * 10.0).max(40.0);
-            
-            ui.allocate_ui_with_layout(
-                [line_number_width, ui.available_height()].into(),
-                egui::Layout::top_down(egui::Align::RIGHT),
-                |ui| {
-                    ui.add_space(5.0);
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .show(ui, |ui| {
-                            for line_num in 1..=line_count.max(1) {
-                                ui.monospace(format!("{:4}", line_num));
-                            }
-                        });
-                }
-            );
+        let language = Self::syntax_language(app);
+        ui.horizontal_top(|ui| {
+            // 行番号エリア（外側のスクロールで本文と一緒に動く）
+            let line_count = app.state.viewed_file_content.split('\n').count();
+            Self::show_line_number_column(ui, line_count);
             
             ui.separator();
             
-            // エディタエリア
+            // エディタエリア（行番号とずれないよう折り返さない）
+            let mut layouter = |ui: &egui::Ui, text: &str, _wrap_width: f32| {
+                let job = Self::layout_text(ui, text, language.as_deref());
+                ui.fonts(|f| f.layout_job(job))
+            };
             let response = ui.add(
                 egui::TextEdit::multiline(&mut app.state.viewed_file_content)
                     .font(egui::TextStyle::Monospace)
                     .desired_width(f32::INFINITY)
                     .desired_rows(30)
                     .code_editor()
+                    .margin(egui::Margin::ZERO)
+                    .layouter(&mut layouter)
             );
             
             if response.changed() {
@@ -350,67 +449,52 @@ impl FileViewerUI {
     }
     
     /// 行番号付きテキストを表示（閲覧モード）
-    fn show_text_with_line_numbers(ui: &mut egui::Ui, content: &str) {
-        ui.horizontal(|ui| {
+    fn show_text_with_line_numbers(ui: &mut egui::Ui, content: &str, language: Option<&str>) {
+        ui.horizontal_top(|ui| {
             // 行番号エリア
-            let lines: Vec<&str> = content.lines().collect();
-            let line_count = lines.len();
-            let line_number_width = (line_count.to_string().len() as f32 * 10.0).max(40.0);
-            
-            ui.allocate_ui_with_layout(
-                [line_number_width, ui.available_height()].into(),
-                egui::Layout::top_down(egui::Align::RIGHT),
-                |ui| {
-                    ui.add_space(5.0);
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .show(ui, |ui| {
-                            for line_num in 1..=line_count.max(1) {
-                                ui.monospace(format!("{:4}", line_num));
-                            }
-                        });
-                }
-            );
+            Self::show_line_number_column(ui, content.lines().count());
             
             ui.separator();
             
             // テキストエリア
-            egui::ScrollArea::vertical()
-                .auto_shrink([false, false])
-                .show(ui, |ui| {
-                    for line in lines {
-                        ui.monospace(line);
-                    }
-                });
+            let mut job = Self::layout_text(ui, content, language);
+            job.wrap.max_width = f32::INFINITY;
+            ui.add(egui::Label::new(job).extend());
         });
     }
 
+    /// 行番号の列（本文と同じ等幅フォントで 1 つのラベルにまとめる）
+    fn show_line_number_column(ui: &mut egui::Ui, line_count: usize) {
+        let width = line_count.max(1).to_string().len().max(4);
+        let numbers = (1..=line_count.max(1))
+            .map(|line_num| format!("{:>width$}", line_num))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.label(egui::RichText::new(numbers).monospace().weak());
+    }
+
     /// テキストファイルかどうかを判定
     fn is_text_file(file_path: &Path) -> bool {
-        if let Some(extension) = file_path.extension() {
-            if let Some(ext_str) = extension.to_str() {
-                let text_extensions = [
-                    "txt", "md", "rs", "py", "js", "html", "css", "json", "xml", "yaml", "yml",
-                    "toml", "ini", "cfg", "conf", "log", "csv", "sql", "sh", "bat", "cmd",
-                    "c", "cpp", "h", "hpp", "java", "kt", "swift", "go", "php", "rb", "pl",
-                    "ts", "jsx", "tsx", "vue", "svelte", "scss", "less", "sass", "dockerfile",
-                    "gitignore", "gitattributes", "license", "readme", "changelog", "makefile"
-                ];
-                
-                return text_extensions.contains(&ext_str.to_lowercase().as_str());
-            }
+        if let Some(ext_str) = file_path.extension().and_then(|extension| extension.to_str()) {
+            let text_extensions = [
+                "txt", "md", "rs", "py", "js", "html", "css", "json", "xml", "yaml", "yml",
+                "toml", "ini", "cfg", "conf", "log", "csv", "sql", "sh", "bat", "cmd",
+                "c", "cpp", "h", "hpp", "java", "kt", "swift", "go", "php", "rb", "pl",
+                "ts", "jsx", "tsx", "vue", "svelte", "scss", "less", "sass", "dockerfile",
+                "gitignore", "gitattributes", "license", "readme", "changelog", "makefile"
+            ];
+            
+            return text_extensions.contains(&ext_str.to_lowercase().as_str());
         }
         
         // 拡張子がない場合は、ファイル名で判定
-        if let Some(file_name) = file_path.file_name() {
-            if let Some(name_str) = file_name.to_str() {
-                let text_files = [
-                    "readme", "license", "changelog", "makefile", "dockerfile",
-                    "gitignore", "gitattributes", "cargo.toml", "package.json"
-                ];
-                
-                return text_files.contains(&name_str.to_lowercase().as_str());
-            }
+        if let Some(name_str) = file_path.file_name().and_then(|file_name| file_name.to_str()) {
+            let text_files = [
+                "readme", "license", "changelog", "makefile", "dockerfile",
+                "gitignore", "gitattributes", "cargo.toml", "package.json"
+            ];
+            
+            return text_files.contains(&name_str.to_lowercase().as_str());
         }
         
         false
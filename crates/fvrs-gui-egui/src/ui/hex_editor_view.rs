@@ -0,0 +1,483 @@
+use crate::app::FileVisorApp;
+use crate::file_info::format_size;
+use crate::hex_editor::{HexEditor, ROW_BYTES};
+use egui::{Color32, Event, Key, Modifiers};
+use fvrs_core::hexedit;
+use std::path::PathBuf;
+
+/// 16 進欄の幅（文字数。8 バイト目の区切りを含む）
+const HEX_COLUMN_CHARS: f32 = 50.0;
+/// オフセット欄の幅（文字数）
+const OFFSET_COLUMN_CHARS: f32 = 12.0;
+/// データインスペクタの幅
+const INSPECTOR_WIDTH: f32 = 320.0;
+
+/// 行の表示位置（文字幅から計算）
+struct RowLayout {
+    rect: egui::Rect,
+    char_width: f32,
+    row_height: f32,
+}
+
+impl RowLayout {
+    fn hex_x(&self, column: u64) -> f32 {
+        let chars = column * 3 + u64::from(column >= 8);
+        self.rect.left() + (OFFSET_COLUMN_CHARS + chars as f32) * self.char_width
+    }
+
+    fn text_x(&self, column: u64) -> f32 {
+        self.rect.left() + (OFFSET_COLUMN_CHARS + HEX_COLUMN_CHARS + column as f32) * self.char_width
+    }
+
+    /// 画面上の位置にあるバイト（ASCII 欄なら true）
+    fn hit(&self, pos: egui::Pos2, top: u64) -> Option<(u64, bool)> {
+        let row = ((pos.y - self.rect.top()) / self.row_height).floor().max(0.0) as u64;
+        let chars = (pos.x - self.rect.left()) / self.char_width - OFFSET_COLUMN_CHARS;
+        let (column, text_column) = if chars >= HEX_COLUMN_CHARS - 0.5 {
+            ((chars - HEX_COLUMN_CHARS).floor().max(0.0) as u64, true)
+        } else if chars >= 0.0 {
+            let chars = if chars >= 24.5 { chars - 1.0 } else { chars };
+            ((chars / 3.0).floor() as u64, false)
+        } else {
+            return None;
+        };
+        Some(((top + row) * ROW_BYTES + column.min(ROW_BYTES - 1), text_column))
+    }
+}
+
+/// バイナリエディタ
+pub struct HexEditorUI;
+
+impl HexEditorUI {
+    /// バイナリエディタで開く（未保存の変更があれば開き直さない）
+    pub fn open(app: &mut FileVisorApp, path: PathBuf) {
+        if let Some(editor) = app.hex_editor.as_mut()
+            && (editor.is_modified() || editor.is_saving())
+        {
+            editor.error = Some("未保存の変更があります。保存するか閉じてから開き直してください".to_string());
+            return;
+        }
+        match HexEditor::open(&path) {
+            Ok(editor) => {
+                tracing::info!("バイナリエディタで開きました: {:?}", path);
+                app.hex_editor = Some(editor);
+            }
+            Err(e) => tracing::error!("{}", e),
+        }
+    }
+
+    /// バイナリエディタのウィンドウを表示
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        let Some(editor) = app.hex_editor.as_mut() else {
+            return;
+        };
+        if editor.poll(ctx) {
+            app.hex_editor = None;
+            return;
+        }
+
+        let name = editor
+            .path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let title = format!("バイナリ編集 - {}{}", name, if editor.is_modified() { " ●" } else { "" });
+        let mut open = true;
+        let mut close = false;
+
+        egui::Window::new(title)
+            .id(egui::Id::new("hex_editor_window"))
+            .default_width(1000.0)
+            .default_height(600.0)
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                Self::show_toolbar(ui, editor);
+                Self::show_find_bar(ui, editor);
+
+                if editor.confirm_close {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(Color32::YELLOW, "⚠ 未保存の変更があります");
+                        if ui.button("💾 保存して閉じる").clicked() {
+                            editor.save_and_close(ui.ctx());
+                        }
+                        if ui.button("保存せずに閉じる").clicked() {
+                            close = true;
+                        }
+                        if ui.button("キャンセル").clicked() {
+                            editor.confirm_close = false;
+                        }
+                    });
+                }
+                if let Some(error) = &editor.error {
+                    ui.colored_label(Color32::RED, error);
+                } else if let Some(status) = &editor.status {
+                    ui.label(status);
+                }
+                ui.separator();
+
+                if editor.is_saving() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("保存中...");
+                    });
+                    return;
+                }
+
+                // 下のステータスバーの分を残す
+                let height = (ui.available_height() - 28.0).max(100.0);
+                ui.horizontal_top(|ui| {
+                    Self::show_rows(ui, editor, height);
+                    ui.separator();
+                    Self::show_inspector(ui, editor, height);
+                });
+                ui.separator();
+                Self::show_status(ui, editor);
+            });
+
+        if !open {
+            if editor.is_modified() || editor.is_saving() {
+                editor.confirm_close = true;
+            } else {
+                close = true;
+            }
+        }
+        if close {
+            app.hex_editor = None;
+        }
+    }
+
+    /// 保存・元に戻す・入力モード
+    fn show_toolbar(ui: &mut egui::Ui, editor: &mut HexEditor) {
+        ui.horizontal(|ui| {
+            let idle = !editor.is_busy();
+            let can_undo = editor.document().is_some_and(|d| d.can_undo());
+            let can_redo = editor.document().is_some_and(|d| d.can_redo());
+
+            if ui
+                .add_enabled(idle && editor.is_modified(), egui::Button::new("💾 保存"))
+                .on_hover_text("一時ファイルに書き出してから置き換えます (Ctrl+S)")
+                .clicked()
+            {
+                editor.save(ui.ctx());
+            }
+            if ui.add_enabled(can_undo, egui::Button::new("↶ 元に戻す")).on_hover_text("Ctrl+Z").clicked() {
+                editor.undo();
+            }
+            if ui.add_enabled(can_redo, egui::Button::new("↷ やり直し")).on_hover_text("Ctrl+Y").clicked() {
+                editor.redo();
+            }
+
+            ui.separator();
+            if ui.selectable_label(!editor.insert_mode, "上書き").on_hover_text("Insert キーで切り替え").clicked() {
+                editor.insert_mode = false;
+            }
+            if ui.selectable_label(editor.insert_mode, "挿入").on_hover_text("Insert キーで切り替え").clicked() {
+                editor.insert_mode = true;
+            }
+            ui.separator();
+            if ui.selectable_label(!editor.text_column, "16進で入力").on_hover_text("Tab キーで切り替え").clicked() {
+                editor.text_column = false;
+            }
+            if ui.selectable_label(editor.text_column, "文字で入力").on_hover_text("Tab キーで切り替え").clicked() {
+                editor.text_column = true;
+            }
+        });
+    }
+
+    /// バイト列・文字列の検索
+    fn show_find_bar(ui: &mut egui::Ui, editor: &mut HexEditor) {
+        ui.horizontal(|ui| {
+            ui.label("検索:");
+            let hint = if editor.find_hex { "DE AD BE EF" } else { "文字列 (UTF-8)" };
+            let response =
+                ui.add(egui::TextEdit::singleline(&mut editor.find_text).desired_width(220.0).hint_text(hint));
+            if editor.focus_find {
+                response.request_focus();
+                editor.focus_find = false;
+            }
+            let entered = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+            ui.checkbox(&mut editor.find_hex, "16進");
+
+            let idle = !editor.is_busy();
+            let backward = ui.add_enabled(idle, egui::Button::new("◀ 前")).on_hover_text("Shift+F3").clicked();
+            let forward = ui.add_enabled(idle, egui::Button::new("次 ▶")).on_hover_text("F3").clicked();
+            if backward || forward || (entered && idle) {
+                editor.find(ui.ctx(), backward);
+            }
+            if editor.is_searching() {
+                ui.spinner();
+                ui.label("検索中...");
+            }
+        });
+    }
+
+    /// 見えている行だけを読み込んで描画し、マウス・キー操作を処理する
+    fn show_rows(ui: &mut egui::Ui, editor: &mut HexEditor, height: f32) {
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let char_width = ui.fonts(|f| f.glyph_width(&font, '0'));
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let width = (OFFSET_COLUMN_CHARS + HEX_COLUMN_CHARS + ROW_BYTES as f32 + 1.0) * char_width;
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::click_and_drag());
+        let layout = RowLayout { rect, char_width, row_height };
+
+        let visible = ((rect.height() / row_height).floor() as u64).max(1);
+        // 末尾に追記できるよう、最後のバイトの次の位置も 1 行として数える
+        let row_count = editor.len() / ROW_BYTES + 1;
+        let max_top = row_count.saturating_sub(visible);
+
+        Self::handle_keys(ui, editor, visible);
+        Self::handle_pointer(ui, &response, &layout, editor);
+        if response.hovered() {
+            let delta = ui.input(|i| i.smooth_scroll_delta.y);
+            editor.scroll_remainder -= delta / row_height;
+            let rows = editor.scroll_remainder.trunc();
+            editor.scroll_remainder -= rows;
+            editor.top = (editor.top as i128 + rows as i128).max(0) as u64;
+        }
+        if editor.scroll_to_cursor {
+            let cursor_row = editor.cursor / ROW_BYTES;
+            if cursor_row < editor.top {
+                editor.top = cursor_row;
+            } else if cursor_row >= editor.top + visible {
+                editor.top = cursor_row + 1 - visible;
+            }
+            editor.scroll_to_cursor = false;
+        }
+        editor.top = editor.top.min(max_top);
+
+        // スクロールバー（上端が先頭になるよう反転）
+        let mut position = max_top - editor.top;
+        ui.scope(|ui| {
+            ui.spacing_mut().slider_width = height;
+            let slider = egui::Slider::new(&mut position, 0..=max_top.max(1)).vertical().show_value(false);
+            if ui.add_enabled(max_top > 0, slider).changed() {
+                editor.top = max_top.saturating_sub(position);
+            }
+        });
+
+        let Some(document) = editor.document() else {
+            return;
+        };
+        let len = document.len();
+        let first = editor.top * ROW_BYTES;
+        let bytes = match document.content().read(first, (visible * ROW_BYTES) as usize) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                editor.error = Some(format!("読み込みエラー: {}", e));
+                return;
+            }
+        };
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        let text_color = ui.visuals().text_color();
+        let weak_color = ui.visuals().weak_text_color();
+        let selection_color = ui.visuals().selection.bg_fill;
+        let cursor_stroke = egui::Stroke::new(1.5, ui.visuals().selection.stroke.color);
+        let selection = editor.selection();
+
+        for row in 0..visible {
+            let row_offset = first + row * ROW_BYTES;
+            if row_offset > len {
+                break;
+            }
+            let y = rect.top() + row as f32 * row_height;
+            painter.text(
+                egui::pos2(rect.left(), y),
+                egui::Align2::LEFT_TOP,
+                format!("{:010X}", row_offset),
+                font.clone(),
+                weak_color,
+            );
+
+            for column in 0..ROW_BYTES {
+                let offset = row_offset + column;
+                let hex_rect = egui::Rect::from_min_size(
+                    egui::pos2(layout.hex_x(column), y),
+                    egui::vec2(2.0 * char_width, row_height),
+                );
+                let text_rect = egui::Rect::from_min_size(
+                    egui::pos2(layout.text_x(column), y),
+                    egui::vec2(char_width, row_height),
+                );
+
+                if offset == editor.cursor {
+                    // 入力する側の欄は太枠、もう一方は塗りつぶしで示す
+                    let (active, other) = if editor.text_column {
+                        (text_rect, hex_rect)
+                    } else if editor.low_nibble {
+                        (hex_rect.with_min_x(hex_rect.left() + char_width), text_rect)
+                    } else {
+                        (hex_rect.with_max_x(hex_rect.left() + char_width), text_rect)
+                    };
+                    painter.rect_filled(other, 0.0, selection_color.gamma_multiply(0.5));
+                    painter.rect_stroke(active, 0.0, cursor_stroke, egui::StrokeKind::Inside);
+                }
+
+                let Some(&byte) = bytes.get((offset - first) as usize) else {
+                    continue;
+                };
+                if selection.as_ref().is_some_and(|range| range.contains(&offset)) {
+                    painter.rect_filled(hex_rect.expand2(egui::vec2(char_width * 0.5, 0.0)), 0.0, selection_color);
+                    painter.rect_filled(text_rect, 0.0, selection_color);
+                }
+                let color = if byte == 0 { weak_color } else { text_color };
+                painter.text(hex_rect.min, egui::Align2::LEFT_TOP, format!("{:02X}", byte), font.clone(), color);
+                let c = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+                painter.text(text_rect.min, egui::Align2::LEFT_TOP, c, font.clone(), color);
+            }
+        }
+    }
+
+    /// クリックでカーソルを置き、ドラッグ・Shift+クリックで選択する
+    fn handle_pointer(ui: &egui::Ui, response: &egui::Response, layout: &RowLayout, editor: &mut HexEditor) {
+        if !response.is_pointer_button_down_on() {
+            return;
+        }
+        let Some(pos) = response.interact_pointer_pos() else {
+            return;
+        };
+        let Some((offset, text_column)) = layout.hit(pos, editor.top) else {
+            return;
+        };
+        let (pressed, shift) = ui.input(|i| (i.pointer.primary_pressed(), i.modifiers.shift));
+        if pressed {
+            editor.text_column = text_column;
+            editor.move_cursor(offset, shift);
+        } else if offset != editor.cursor {
+            editor.move_cursor(offset, true);
+        }
+    }
+
+    /// 入力・移動・編集のキー（テキスト欄の入力中は何もしない）
+    fn handle_keys(ui: &egui::Ui, editor: &mut HexEditor, visible: u64) {
+        if ui.ctx().wants_keyboard_input() || editor.document().is_none() {
+            return;
+        }
+        let events = ui.input(|i| i.events.clone());
+        for event in events {
+            match event {
+                Event::Text(text) => {
+                    if editor.text_column {
+                        editor.type_bytes(text.as_bytes());
+                    } else {
+                        for digit in text.chars().filter_map(|c| c.to_digit(16)) {
+                            editor.type_hex_digit(digit as u8);
+                        }
+                    }
+                }
+                Event::Copy => {
+                    if let Some(text) = editor.copy_selection() {
+                        ui.ctx().copy_text(text);
+                    }
+                }
+                Event::Cut => {
+                    if let Some(text) = editor.copy_selection() {
+                        ui.ctx().copy_text(text);
+                        editor.delete(false);
+                    }
+                }
+                Event::Paste(text) => editor.paste(&text),
+                Event::Key { key, pressed: true, modifiers, .. } => {
+                    Self::handle_key(ui.ctx(), editor, key, modifiers, visible);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_key(ctx: &egui::Context, editor: &mut HexEditor, key: Key, modifiers: Modifiers, visible: u64) {
+        let cursor = editor.cursor;
+        let row_start = cursor - cursor % ROW_BYTES;
+        let page = visible * ROW_BYTES;
+        let target = match key {
+            Key::ArrowLeft => Some(cursor.saturating_sub(1)),
+            Key::ArrowRight => Some(cursor + 1),
+            Key::ArrowUp => Some(cursor.checked_sub(ROW_BYTES).unwrap_or(cursor)),
+            Key::ArrowDown => Some(cursor + ROW_BYTES),
+            Key::PageUp => Some(cursor.saturating_sub(page)),
+            Key::PageDown => Some(cursor + page),
+            Key::Home if modifiers.command => Some(0),
+            Key::Home => Some(row_start),
+            Key::End if modifiers.command => Some(editor.len()),
+            Key::End => Some(row_start + ROW_BYTES - 1),
+            _ => None,
+        };
+        if let Some(target) = target {
+            editor.move_cursor(target, modifiers.shift);
+            return;
+        }
+
+        match key {
+            Key::Tab => editor.text_column = !editor.text_column,
+            Key::Insert => editor.insert_mode = !editor.insert_mode,
+            Key::Backspace => editor.delete(true),
+            Key::Delete => editor.delete(false),
+            Key::Escape => editor.anchor = None,
+            Key::F3 if !editor.is_busy() => editor.find(ctx, modifiers.shift),
+            Key::Z if modifiers.command && modifiers.shift => editor.redo(),
+            Key::Z if modifiers.command => editor.undo(),
+            Key::Y if modifiers.command => editor.redo(),
+            Key::A if modifiers.command => editor.select_all(),
+            Key::F if modifiers.command => editor.focus_find = true,
+            Key::S if modifiers.command && editor.is_modified() && !editor.is_busy() => editor.save(ctx),
+            _ => {}
+        }
+    }
+
+    /// カーソル位置のバイトを各形式で読んだ値
+    fn show_inspector(ui: &mut egui::Ui, editor: &mut HexEditor, height: f32) {
+        let Some(document) = editor.document() else {
+            return;
+        };
+        let bytes = document.content().read(editor.cursor, 8).unwrap_or_default();
+
+        ui.vertical(|ui| {
+            ui.set_width(INSPECTOR_WIDTH);
+            ui.strong("データインスペクタ");
+            egui::ScrollArea::both()
+                .id_salt("hex_inspector")
+                .max_height(height - 24.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("hex_inspector_grid").num_columns(3).striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.label("リトルエンディアン");
+                        ui.label("ビッグエンディアン");
+                        ui.end_row();
+                        for value in hexedit::inspect(&bytes) {
+                            ui.label(value.label);
+                            ui.monospace(value.little_endian.as_deref().unwrap_or("-"));
+                            ui.monospace(value.big_endian.as_deref().unwrap_or("-"));
+                            ui.end_row();
+                        }
+                    });
+                });
+        });
+    }
+
+    /// カーソル位置・大きさ・選択範囲
+    fn show_status(ui: &mut egui::Ui, editor: &HexEditor) {
+        ui.horizontal(|ui| {
+            ui.label(format!("オフセット: 0x{:X} ({})", editor.cursor, editor.cursor));
+            ui.separator();
+            ui.label(format!("サイズ: {} ({} バイト)", format_size(editor.len()), editor.len()));
+            if let Some(range) = editor.selection() {
+                ui.separator();
+                ui.label(format!(
+                    "選択: {} バイト (0x{:X}-0x{:X})",
+                    range.end - range.start,
+                    range.start,
+                    range.end - 1
+                ));
+            }
+            ui.separator();
+            ui.label(if editor.insert_mode { "挿入" } else { "上書き" });
+            if editor.is_modified() {
+                ui.separator();
+                ui.colored_label(Color32::YELLOW, "● 変更あり");
+            }
+        });
+    }
+}
@@ -0,0 +1,218 @@
+use crate::app::FileVisorApp;
+use crate::history::HistoryTab;
+use crate::utils::format_time;
+use chrono::Local;
+use egui::{Align, Color32, Layout, RichText};
+use egui_extras::{Column, TableBuilder};
+use fvrs_core::history;
+use std::path::PathBuf;
+
+/// 履歴ダイアログでの操作（描画後にまとめて適用）
+enum HistoryAction {
+    Open(PathBuf),
+    /// 表示中のタブの履歴の位置へ移動
+    GoTo(usize),
+    Remove(PathBuf),
+    RemoveMissing,
+    Clear,
+}
+
+/// 表示する 1 行
+struct HistoryRow {
+    path: PathBuf,
+    /// 日時や頻度などの補足
+    detail: String,
+    current: bool,
+    /// 表示中のタブの履歴での位置
+    position: Option<usize>,
+}
+
+/// フォルダーの履歴（G キー）
+pub struct HistoryUI;
+
+impl HistoryUI {
+    pub fn open(app: &mut FileVisorApp) {
+        app.state.show_history_view = true;
+        app.history.filter.clear();
+    }
+
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        if !app.state.show_history_view {
+            return;
+        }
+
+        let mut actions = Vec::new();
+        let mut open = true;
+        egui::Window::new("🕘 履歴")
+            .default_width(640.0)
+            .default_height(440.0)
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let tab = &mut app.history.tab;
+                    ui.selectable_value(tab, HistoryTab::Recent, "最近開いたフォルダー");
+                    ui.selectable_value(tab, HistoryTab::Frequent, "よく使うフォルダー");
+                    ui.selectable_value(tab, HistoryTab::Pane, "このタブの履歴");
+                    ui.separator();
+                    ui.add(egui::TextEdit::singleline(&mut app.history.filter).desired_width(160.0).hint_text("絞り込み"));
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("存在しないフォルダーを削除").clicked() {
+                        actions.push(HistoryAction::RemoveMissing);
+                    }
+                    ui.menu_button("履歴を消去", |ui| {
+                        ui.label("最近開いたフォルダーとよく使うフォルダーをすべて消去します");
+                        if ui.button("消去する").clicked() {
+                            actions.push(HistoryAction::Clear);
+                            ui.close_menu();
+                        }
+                    });
+                    ui.label(RichText::new("Ctrl+P でフォルダーへすばやく移動できます").weak());
+                });
+                ui.separator();
+
+                let rows = Self::rows(app);
+                if rows.is_empty() {
+                    ui.label("履歴はありません");
+                } else {
+                    Self::show_rows(ui, app.history.tab, &rows, &mut actions);
+                }
+            });
+
+        if !open {
+            app.state.show_history_view = false;
+        }
+        for action in actions {
+            match action {
+                HistoryAction::Open(path) => {
+                    if path.is_dir() {
+                        app.navigate_to(path);
+                        app.state.show_history_view = false;
+                    } else {
+                        tracing::warn!("フォルダーが見つかりません: {:?}", path);
+                    }
+                }
+                HistoryAction::GoTo(position) => app.go_to_history(position),
+                HistoryAction::Remove(path) => app.history.remove(&path),
+                HistoryAction::RemoveMissing => {
+                    let removed = app.history.remove_missing();
+                    tracing::info!("存在しないフォルダーを履歴から削除: {} 件", removed);
+                }
+                HistoryAction::Clear => {
+                    app.history.clear();
+                    tracing::info!("履歴を消去しました");
+                }
+            }
+        }
+    }
+
+    /// 表示中のタブの行（絞り込み済み）
+    fn rows(app: &FileVisorApp) -> Vec<HistoryRow> {
+        let current_path = &app.state.pane().current_path;
+        let rows: Vec<HistoryRow> = match app.history.tab {
+            HistoryTab::Recent => app
+                .history
+                .history
+                .recent()
+                .iter()
+                .map(|visit| HistoryRow {
+                    path: visit.path.clone(),
+                    detail: format_time(visit.time),
+                    current: visit.path == *current_path,
+                    position: None,
+                })
+                .collect(),
+            HistoryTab::Frequent => app
+                .history
+                .history
+                .ranked(Local::now())
+                .into_iter()
+                .map(|(entry, frecency)| HistoryRow {
+                    path: entry.path.clone(),
+                    detail: format!("{:.1}（最終 {}）", frecency, format_time(entry.last_visit)),
+                    current: entry.path == *current_path,
+                    position: None,
+                })
+                .collect(),
+            HistoryTab::Pane => {
+                let pane = app.state.pane();
+                pane.navigation_history
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(position, path)| HistoryRow {
+                        path: path.clone(),
+                        detail: match position.cmp(&pane.history_position) {
+                            std::cmp::Ordering::Less => "戻る".to_string(),
+                            std::cmp::Ordering::Equal => "現在".to_string(),
+                            std::cmp::Ordering::Greater => "進む".to_string(),
+                        },
+                        current: position == pane.history_position,
+                        position: Some(position),
+                    })
+                    .collect()
+            }
+        };
+
+        let filter = app.history.filter.trim();
+        if filter.is_empty() {
+            return rows;
+        }
+        rows.into_iter()
+            .filter(|row| history::fuzzy_match(filter, &row.path.to_string_lossy()).is_some())
+            .collect()
+    }
+
+    fn show_rows(ui: &mut egui::Ui, tab: HistoryTab, rows: &[HistoryRow], actions: &mut Vec<HistoryAction>) {
+        let detail_header = match tab {
+            HistoryTab::Recent => "開いた日時",
+            HistoryTab::Frequent => "スコア",
+            HistoryTab::Pane => "",
+        };
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::remainder().at_least(300.0)) // フォルダー
+            .column(Column::auto().at_least(130.0)) // 日時・スコア
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong("フォルダー");
+                });
+                header.col(|ui| {
+                    ui.strong(detail_header);
+                });
+            })
+            .body(|body| {
+                body.rows(20.0, rows.len(), |mut row| {
+                    let item = &rows[row.index()];
+                    row.col(|ui| {
+                        let mut text = RichText::new(format!("📁 {}", item.path.display()));
+                        if !item.path.is_dir() {
+                            text = text.color(Color32::GRAY).strikethrough();
+                        }
+                        let response = ui.selectable_label(item.current, text).on_hover_text("クリックで移動");
+                        if response.clicked() {
+                            actions.push(match item.position {
+                                Some(position) => HistoryAction::GoTo(position),
+                                None => HistoryAction::Open(item.path.clone()),
+                            });
+                        }
+                        if item.position.is_none() {
+                            response.context_menu(|ui| {
+                                if ui.button("履歴から削除").clicked() {
+                                    actions.push(HistoryAction::Remove(item.path.clone()));
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(RichText::new(&item.detail).weak());
+                    });
+                });
+            });
+    }
+}
@@ -0,0 +1,286 @@
+use crate::app::FileVisorApp;
+use crate::file_info::format_size;
+use egui::{Color32, RichText};
+use fvrs_core::imaging::{self, ExifSummary, ImageTransform};
+
+/// 拡大縮小の範囲
+const MIN_ZOOM: f32 = 0.02;
+const MAX_ZOOM: f32 = 32.0;
+/// ボタン 1 回分の拡大率
+const ZOOM_STEP: f32 = 1.25;
+
+/// 画像プレビューパネル（選択中の画像を表示）
+pub struct ImagePreviewUI;
+
+impl ImagePreviewUI {
+    /// 画像プレビューパネルを表示（CentralPanel より前に呼ぶ）
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        if !app.state.show_image_preview {
+            if app.image_preview.path().is_some() {
+                app.image_preview.clear();
+            }
+            return;
+        }
+
+        let target = app
+            .state
+            .pane()
+            .selected_items
+            .last()
+            .filter(|path| imaging::is_supported(path))
+            .cloned();
+        app.image_preview.set_target(ctx, target.as_deref());
+        app.image_preview.poll(ctx);
+
+        egui::SidePanel::right("image_preview_panel")
+            .resizable(true)
+            .default_width(360.0)
+            .width_range(200.0..=1200.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("画像プレビュー");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("❌").clicked() {
+                            app.state.show_image_preview = false;
+                        }
+                    });
+                });
+                ui.separator();
+
+                let Some(path) = app.image_preview.path().map(|path| path.to_path_buf()) else {
+                    ui.label("画像ファイルを選択するとここに表示されます");
+                    return;
+                };
+
+                let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                ui.label(RichText::new(format!("🖼 {}", name)).strong());
+                if let Some(size) = app.image_preview.image_size() {
+                    let file_size = std::fs::metadata(&path).map(|m| format_size(m.len())).unwrap_or_default();
+                    ui.label(format!("{:.0} × {:.0} px  {}", size.x, size.y, file_size));
+                }
+
+                Self::show_toolbar(ui, app);
+                if let Some(error) = &app.image_preview.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                if let Some(status) = &app.image_preview.status {
+                    ui.colored_label(Color32::GREEN, status);
+                }
+                ui.separator();
+
+                let height = (ui.available_height() * 0.6).max(150.0);
+                Self::show_image(ui, app, height);
+                ui.separator();
+
+                egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| match &app.image_preview.exif {
+                    Some(exif) => Self::show_exif(ui, exif),
+                    None if app.image_preview.is_loading() => {}
+                    None => {
+                        ui.label(RichText::new("EXIF 情報はありません").weak());
+                    }
+                });
+            });
+    }
+
+    /// 表示倍率と回転・反転の操作
+    fn show_toolbar(ui: &mut egui::Ui, app: &mut FileVisorApp) {
+        let ctx = ui.ctx().clone();
+        let preview = &mut app.image_preview;
+        let loaded = preview.texture().is_some() && !preview.is_saving();
+
+        ui.horizontal(|ui| {
+            if ui.selectable_label(preview.zoom.is_none(), "フィット").clicked() {
+                preview.zoom = None;
+                preview.pan = egui::Vec2::ZERO;
+            }
+            if ui.button("100%").clicked() {
+                preview.zoom = Some(1.0);
+                preview.pan = egui::Vec2::ZERO;
+            }
+            let current = preview.zoom.unwrap_or(ui.data(|d| d.get_temp(fit_zoom_id()).unwrap_or(1.0)));
+            if ui.button("－").on_hover_text("縮小").clicked() {
+                preview.zoom = Some((current / ZOOM_STEP).clamp(MIN_ZOOM, MAX_ZOOM));
+            }
+            if ui.button("＋").on_hover_text("拡大").clicked() {
+                preview.zoom = Some((current * ZOOM_STEP).clamp(MIN_ZOOM, MAX_ZOOM));
+            }
+            ui.label(format!("{:.0}%", current * 100.0));
+        });
+
+        ui.horizontal(|ui| {
+            for (label, hint, transform) in [
+                ("⟲", "左に 90° 回転", ImageTransform::RotateLeft),
+                ("⟳", "右に 90° 回転", ImageTransform::RotateRight),
+                ("⇆", "左右反転", ImageTransform::FlipHorizontal),
+                ("⇅", "上下反転", ImageTransform::FlipVertical),
+            ] {
+                if ui.add_enabled(loaded, egui::Button::new(label)).on_hover_text(hint).clicked() {
+                    preview.apply(&ctx, transform);
+                }
+            }
+
+            if preview.is_saving() {
+                ui.spinner();
+                ui.label("保存中...");
+            } else if !preview.transforms.is_empty() && !preview.confirm_save {
+                if ui.button("💾 保存").on_hover_text("元のファイルを上書きします").clicked() {
+                    preview.confirm_save = true;
+                }
+                if ui.button("元に戻す").clicked() {
+                    preview.revert(&ctx);
+                }
+            }
+        });
+
+        if preview.confirm_save && !preview.transforms.is_empty() {
+            ui.colored_label(Color32::YELLOW, "元のファイルを上書きします。よろしいですか？");
+            let is_jpeg = preview
+                .path()
+                .and_then(|path| path.extension())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("jpeg"));
+            if is_jpeg {
+                ui.label(RichText::new("JPEG は再圧縮されるため画質がわずかに劣化します").weak());
+            }
+            ui.horizontal(|ui| {
+                if ui.button("上書き保存").clicked() {
+                    preview.save();
+                }
+                if ui.button("キャンセル").clicked() {
+                    preview.confirm_save = false;
+                }
+            });
+        }
+    }
+
+    /// 画像の表示（ホイールで拡大縮小、ドラッグで移動、ダブルクリックでフィット切替）
+    fn show_image(ui: &mut egui::Ui, app: &mut FileVisorApp, height: f32) {
+        let size = egui::vec2(ui.available_width(), height);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+
+        let preview = &mut app.image_preview;
+        let (Some(texture), Some(image_size)) = (preview.texture(), preview.image_size()) else {
+            if preview.is_loading() {
+                ui.put(rect, egui::Spinner::new());
+            }
+            return;
+        };
+        let texture_id = texture.id();
+
+        let fit = (rect.width() / image_size.x).min(rect.height() / image_size.y).min(1.0);
+        ui.data_mut(|d| d.insert_temp(fit_zoom_id(), fit));
+        let mut zoom = preview.zoom.unwrap_or(fit);
+
+        if response.hovered() {
+            let (scroll, pinch, pointer) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta(), i.pointer.hover_pos()));
+            let factor = (scroll / 200.0).exp() * pinch;
+            if factor != 1.0 {
+                let new_zoom = (zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+                // カーソル位置の点が動かないように中心をずらす
+                if let Some(pointer) = pointer {
+                    let center = rect.center() + preview.pan;
+                    let new_center = pointer - (pointer - center) * (new_zoom / zoom);
+                    preview.pan = new_center - rect.center();
+                }
+                zoom = new_zoom;
+                preview.zoom = Some(zoom);
+            }
+        }
+        if response.dragged() {
+            preview.pan += response.drag_delta();
+            preview.zoom = Some(zoom);
+        }
+        if response.double_clicked() {
+            preview.zoom = if preview.zoom.is_none() { Some(1.0) } else { None };
+            preview.pan = egui::Vec2::ZERO;
+            zoom = preview.zoom.unwrap_or(fit);
+        }
+        if preview.zoom.is_none() {
+            preview.pan = egui::Vec2::ZERO;
+        }
+
+        let image_rect = egui::Rect::from_center_size(rect.center() + preview.pan, image_size * zoom);
+        painter.image(
+            texture_id,
+            image_rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            Color32::WHITE,
+        );
+        response.on_hover_text("ホイール: 拡大縮小 / ドラッグ: 移動 / ダブルクリック: フィット切替");
+    }
+
+    /// EXIF の主な項目とすべての項目
+    fn show_exif(ui: &mut egui::Ui, exif: &ExifSummary) {
+        egui::CollapsingHeader::new("EXIF").default_open(true).show(ui, |ui| {
+            egui::Grid::new("exif_summary").num_columns(2).striped(true).show(ui, |ui| {
+                for (label, value) in [
+                    ("カメラ", &exif.camera),
+                    ("レンズ", &exif.lens),
+                    ("撮影日時", &exif.date_taken),
+                    ("露出", &exif.exposure),
+                ] {
+                    if let Some(value) = value {
+                        ui.label(label);
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                }
+                if let Some(orientation) = exif.orientation {
+                    ui.label("向き");
+                    ui.label(orientation_label(orientation));
+                    ui.end_row();
+                }
+                if let Some(gps) = exif.gps {
+                    ui.label("位置");
+                    ui.vertical(|ui| {
+                        ui.label(format!("{:.6}, {:.6}", gps.latitude, gps.longitude));
+                        if let Some(altitude) = gps.altitude {
+                            ui.label(format!("高度 {:.1} m", altitude));
+                        }
+                        ui.hyperlink_to(
+                            "地図で開く",
+                            format!(
+                                "https://www.openstreetmap.org/?mlat={0}&mlon={1}#map=15/{0}/{1}",
+                                gps.latitude, gps.longitude
+                            ),
+                        );
+                    });
+                    ui.end_row();
+                }
+            });
+
+            egui::CollapsingHeader::new(format!("すべての項目 ({})", exif.fields.len()))
+                .id_salt("exif_fields")
+                .show(ui, |ui| {
+                    egui::Grid::new("exif_fields_grid").num_columns(2).striped(true).show(ui, |ui| {
+                        for (tag, value) in &exif.fields {
+                            ui.label(tag);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+                });
+        });
+    }
+}
+
+/// 枠に合わせたときの倍率（ツールバーの表示用に前のフレームの値を覚えておく）
+fn fit_zoom_id() -> egui::Id {
+    egui::Id::new("image_preview_fit_zoom")
+}
+
+/// EXIF の向きの説明（表示は補正済み）
+fn orientation_label(orientation: u16) -> &'static str {
+    match orientation {
+        1 => "標準",
+        2 => "左右反転（補正して表示）",
+        3 => "180° 回転（補正して表示）",
+        4 => "上下反転（補正して表示）",
+        5 => "転置（補正して表示）",
+        6 => "右に 90° 回転（補正して表示）",
+        7 => "逆転置（補正して表示）",
+        8 => "左に 90° 回転（補正して表示）",
+        _ => "不明",
+    }
+}
@@ -0,0 +1,135 @@
+use crate::app::FileVisorApp;
+use crate::utils::format_time;
+use egui::{Color32, RichText};
+use std::path::PathBuf;
+
+/// インデックスのダイアログでの操作（描画後にまとめて適用）
+enum IndexAction {
+    AddRoot(PathBuf),
+    RemoveRoot(PathBuf),
+    ApplyExclude,
+    Rebuild,
+    Cancel,
+}
+
+/// ファイル名のインデックスの設定と状態
+pub struct IndexUI;
+
+impl IndexUI {
+    pub fn open(app: &mut FileVisorApp) {
+        app.state.show_index_view = true;
+    }
+
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        if !app.state.show_index_view {
+            return;
+        }
+
+        let mut actions = Vec::new();
+        let mut open = true;
+        let current_path = app.state.pane().current_path.clone();
+        egui::Window::new("🗂 ファイルインデックス")
+            .default_width(560.0)
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let service = &mut app.file_index;
+                ui.label(
+                    RichText::new("登録したフォルダーの中のファイル名を記録しておき、検索（F）や CLI の locate ですぐに探せるようにします。")
+                        .weak(),
+                );
+                ui.separator();
+
+                ui.label("対象のフォルダー:");
+                if service.settings.roots.is_empty() {
+                    ui.label(RichText::new("まだありません").weak());
+                }
+                for root in &service.settings.roots {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("✖").on_hover_text("インデックスから外す").clicked() {
+                            actions.push(IndexAction::RemoveRoot(root.clone()));
+                        }
+                        ui.label(root.display().to_string());
+                    });
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(service.can_add(&current_path), egui::Button::new("➕ 表示中のフォルダーを追加"))
+                        .on_hover_text(current_path.display().to_string())
+                        .clicked()
+                    {
+                        actions.push(IndexAction::AddRoot(current_path.clone()));
+                    }
+                    if ui.button("📁 フォルダーを選んで追加...").clicked()
+                        && let Some(path) = rfd::FileDialog::new().set_directory(&current_path).pick_folder()
+                    {
+                        actions.push(IndexAction::AddRoot(path));
+                    }
+                });
+                ui.add_space(4.0);
+
+                ui.horizontal(|ui| {
+                    ui.label("除外する名前:");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut service.exclude_input)
+                            .desired_width(300.0)
+                            .hint_text("例: .git; node_modules; *.tmp"),
+                    );
+                    let changed = service.exclude_input != service.settings.exclude.join("; ");
+                    let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.add_enabled(changed, egui::Button::new("適用")).clicked() || (changed && submitted) {
+                        actions.push(IndexAction::ApplyExclude);
+                    }
+                });
+                ui.separator();
+
+                let index = service.index();
+                ui.horizontal(|ui| {
+                    if let Some((count, elapsed)) = service.build_progress() {
+                        ui.spinner();
+                        ui.label(format!("作成中... {} 項目（{:.0} 秒）", count, elapsed.as_secs_f32()));
+                        if ui.button("⏹ 中止").clicked() {
+                            actions.push(IndexAction::Cancel);
+                        }
+                        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+                    } else {
+                        match index.built() {
+                            Some(built) => ui.label(format!("{} 項目（{} に作成）", index.len(), format_time(built))),
+                            None => ui.label("インデックスはまだありません"),
+                        };
+                        if ui.add_enabled(!service.settings.roots.is_empty(), egui::Button::new("🔄 作り直す")).clicked() {
+                            actions.push(IndexAction::Rebuild);
+                        }
+                    }
+                });
+                if index.errors() > 0 {
+                    ui.label(RichText::new(format!("読めない項目: {}", index.errors())).color(Color32::YELLOW));
+                }
+                if service.is_watching() {
+                    ui.label(RichText::new("変更を監視して自動的に更新しています").weak());
+                }
+                if let Some(error) = &service.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+            });
+
+        if !open {
+            app.state.show_index_view = false;
+        }
+        for action in actions {
+            let service = &mut app.file_index;
+            match action {
+                IndexAction::AddRoot(path) => {
+                    if let Err(e) = service.add_root(&path) {
+                        service.error = Some(e);
+                    }
+                }
+                IndexAction::RemoveRoot(path) => service.remove_root(&path),
+                IndexAction::ApplyExclude => service.apply_exclude(),
+                IndexAction::Rebuild => service.start_rebuild(),
+                IndexAction::Cancel => service.cancel_rebuild(),
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use crate::app::FileVisorApp;
+use crate::jobs::{Conflict, ConflictChoice, ConflictResolution, JobState};
+use crate::utils::format_file_size;
+use egui::{Color32, RichText};
+use std::time::{Duration, SystemTime};
+
+/// ジョブ一覧での操作（描画後にまとめて適用）
+enum JobsAction {
+    Pause(u64),
+    Resume(u64),
+    Cancel(u64),
+    ClearFinished,
+    Resolve(u64, ConflictChoice),
+}
+
+/// ファイル操作キューの一覧と衝突ダイアログ
+pub struct JobsUI;
+
+impl JobsUI {
+    /// ジョブの進捗を取り込み、一覧と衝突ダイアログを表示
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        app.poll_jobs();
+        if app.jobs.has_active() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+
+        let mut actions = Vec::new();
+        if app.state.show_jobs_panel {
+            let mut open = true;
+            egui::Window::new("ファイル操作")
+                .default_width(520.0)
+                .resizable(true)
+                .collapsible(true)
+                .open(&mut open)
+                .show(ctx, |ui| Self::show_jobs(ui, app, &mut actions));
+            if !open {
+                app.state.show_jobs_panel = false;
+            }
+        }
+        Self::show_conflict(ctx, app, &mut actions);
+
+        for action in actions {
+            match action {
+                JobsAction::Pause(id) => app.jobs.pause(id),
+                JobsAction::Resume(id) => app.jobs.resume(id),
+                JobsAction::Cancel(id) => app.jobs.cancel(id),
+                JobsAction::ClearFinished => app.jobs.clear_finished(),
+                JobsAction::Resolve(id, choice) => app.jobs.resolve_conflict(id, choice),
+            }
+        }
+    }
+
+    fn show_jobs(ui: &mut egui::Ui, app: &mut FileVisorApp, actions: &mut Vec<JobsAction>) {
+        ui.checkbox(
+            &mut app.state.copy_verify,
+            "コピー後に内容を照合する（BLAKE3）",
+        );
+        ui.separator();
+
+        let jobs = app.jobs.jobs();
+        if jobs.is_empty() {
+            ui.label("ファイル操作はありません");
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
+                for job in jobs.iter().rev() {
+                    let progress = job.progress();
+                    ui.horizontal(|ui| {
+                        let state = match progress.state {
+                            JobState::Completed if !progress.errors.is_empty() => {
+                                RichText::new("完了（エラーあり）")
+                                    .color(Color32::from_rgb(200, 120, 0))
+                            }
+                            JobState::Completed => {
+                                RichText::new("完了").color(Color32::from_rgb(0, 150, 0))
+                            }
+                            JobState::Cancelled => RichText::new("中止").weak(),
+                            state => RichText::new(state.label()),
+                        };
+                        ui.label(state);
+                        ui.label(RichText::new(job.kind.description()).strong());
+                    });
+
+                    if !progress.state.is_finished() {
+                        ui.add(
+                            egui::ProgressBar::new(progress.fraction())
+                                .show_percentage()
+                                .animate(progress.state == JobState::Running),
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{} / {} ファイル, {} / {}",
+                                progress.files_done,
+                                progress.files_total,
+                                format_file_size(progress.bytes_done),
+                                format_file_size(progress.bytes_total)
+                            ));
+                            if progress.throughput > 0.0 {
+                                ui.label(format!(
+                                    "{}/秒",
+                                    format_file_size(progress.throughput as u64)
+                                ));
+                            }
+                            if let Some(eta) = progress.eta() {
+                                ui.label(format!("残り {}", format_duration(eta)));
+                            }
+                        });
+                        if let Some(current) = &progress.current {
+                            let mut text = current.display().to_string();
+                            if progress.current_size > 0 {
+                                text = format!(
+                                    "{} ({} / {})",
+                                    text,
+                                    format_file_size(progress.current_done),
+                                    format_file_size(progress.current_size)
+                                );
+                            }
+                            ui.label(RichText::new(text).small().weak());
+                        }
+                        ui.horizontal(|ui| {
+                            if app.jobs.is_paused(job.id) {
+                                if ui.button("▶ 再開").clicked() {
+                                    actions.push(JobsAction::Resume(job.id));
+                                }
+                            } else if ui.button("⏸ 一時停止").clicked() {
+                                actions.push(JobsAction::Pause(job.id));
+                            }
+                            if ui.button("⏹ 中止").clicked() {
+                                actions.push(JobsAction::Cancel(job.id));
+                            }
+                        });
+                    }
+
+                    if !progress.errors.is_empty() {
+                        ui.collapsing(format!("{} 件のエラー", progress.errors.len()), |ui| {
+                            for error in &progress.errors {
+                                ui.colored_label(Color32::RED, error);
+                            }
+                        });
+                    }
+                    ui.separator();
+                }
+            });
+
+        if ui.button("完了したものを消去").clicked() {
+            actions.push(JobsAction::ClearFinished);
+        }
+    }
+
+    /// 同名の項目があったときの確認ダイアログ
+    fn show_conflict(ctx: &egui::Context, app: &FileVisorApp, actions: &mut Vec<JobsAction>) {
+        let Some((id, conflict)) = app.jobs.pending_conflict() else {
+            return;
+        };
+        let apply_id = egui::Id::new("job_conflict_apply_to_all");
+        let mut apply_to_all = ctx.data(|d| d.get_temp::<bool>(apply_id).unwrap_or(false));
+
+        egui::Window::new("同名の項目があります")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} には既に同じ名前の項目があります。",
+                    conflict
+                        .destination
+                        .parent()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default()
+                ));
+                ui.add_space(6.0);
+                Self::conflict_details(ui, &conflict);
+                ui.add_space(6.0);
+                ui.checkbox(&mut apply_to_all, "以降の項目にも同じ処理を適用する");
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    for resolution in [
+                        ConflictResolution::Overwrite,
+                        ConflictResolution::Skip,
+                        ConflictResolution::Rename,
+                        ConflictResolution::NewerOnly,
+                    ] {
+                        if ui.button(resolution.label()).clicked() {
+                            actions.push(JobsAction::Resolve(
+                                id,
+                                ConflictChoice {
+                                    resolution,
+                                    apply_to_all,
+                                },
+                            ));
+                        }
+                    }
+                    if ui.button("中止").clicked() {
+                        actions.push(JobsAction::Cancel(id));
+                    }
+                });
+                ui.label(
+                    RichText::new("上書きされる項目はゴミ箱へ移動されます")
+                        .small()
+                        .weak(),
+                );
+            });
+
+        // 次の衝突では毎回チェックを外した状態から始める
+        let answered = !actions.is_empty();
+        ctx.data_mut(|d| d.insert_temp(apply_id, apply_to_all && !answered));
+    }
+
+    fn conflict_details(ui: &mut egui::Ui, conflict: &Conflict) {
+        egui::Grid::new("job_conflict_grid")
+            .num_columns(3)
+            .spacing([12.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("");
+                ui.strong("サイズ");
+                ui.strong("更新日時");
+                ui.end_row();
+
+                let newer = match (conflict.source_modified, conflict.destination_modified) {
+                    (Some(source), Some(destination)) => Some(source > destination),
+                    _ => None,
+                };
+                ui.label(format!(
+                    "コピー元: {}",
+                    crate::utils::get_display_name(&conflict.source)
+                ));
+                ui.label(format_file_size(conflict.source_size));
+                ui.label(modified_text(conflict.source_modified, newer == Some(true)));
+                ui.end_row();
+
+                ui.label(format!(
+                    "既存: {}",
+                    crate::utils::get_display_name(&conflict.destination)
+                ));
+                ui.label(format_file_size(conflict.destination_size));
+                ui.label(modified_text(
+                    conflict.destination_modified,
+                    newer == Some(false),
+                ));
+                ui.end_row();
+            });
+    }
+}
+
+/// 更新日時（新しいほうを強調）
+fn modified_text(time: Option<SystemTime>, newer: bool) -> RichText {
+    let text = time
+        .map(|t| crate::utils::format_time(chrono::DateTime::<chrono::Local>::from(t)))
+        .unwrap_or_else(|| "-".to_string());
+    if newer {
+        RichText::new(format!("{}（新しい）", text)).strong()
+    } else {
+        RichText::new(text)
+    }
+}
+
+/// 残り時間の表示
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{} 時間 {} 分", seconds / 3600, seconds % 3600 / 60)
+    } else if seconds >= 60 {
+        format!("{} 分 {} 秒", seconds / 60, seconds % 60)
+    } else {
+        format!("{} 秒", seconds)
+    }
+}
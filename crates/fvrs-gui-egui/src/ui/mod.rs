@@ -4,10 +4,44 @@ pub mod shortcuts;
 pub mod file_viewer;
 pub mod file_info_dialog;
 pub mod explorer_tree;
+pub mod volume_bar;
+pub mod disk_usage_view;
+pub mod duplicates_view;
+pub mod trash_view;
+pub mod jobs_view;
+pub mod tab_bar;
+pub mod image_preview_view;
+pub mod paged_viewer_view;
+pub mod hex_editor_view;
+pub mod attributes_dialog;
+pub mod split_join_dialog;
+pub mod history_view;
+pub mod quick_jump_view;
+pub mod bookmarks_view;
+pub mod search_view;
+pub mod index_view;
+pub mod smart_folders_view;
 
 pub use file_list::*;
 pub use dialogs::*;
 pub use shortcuts::*;
 pub use file_viewer::*;
 pub use file_info_dialog::*;
-pub use explorer_tree::*; 
\ No newline at end of file
+pub use explorer_tree::*;
+pub use volume_bar::*;
+pub use disk_usage_view::*;
+pub use duplicates_view::*;
+pub use trash_view::*;
+pub use jobs_view::*;
+pub use tab_bar::*;
+pub use image_preview_view::*;
+pub use paged_viewer_view::*;
+pub use hex_editor_view::*;
+pub use attributes_dialog::*;
+pub use split_join_dialog::*;
+pub use history_view::*;
+pub use quick_jump_view::*;
+pub use bookmarks_view::*;
+pub use search_view::*;
+pub use index_view::*;
+pub use smart_folders_view::*;
\ No newline at end of file
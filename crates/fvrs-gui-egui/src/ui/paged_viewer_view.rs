@@ -0,0 +1,214 @@
+use crate::app::FileVisorApp;
+use crate::file_info::format_size;
+use crate::paged_viewer::{HEX_ROW_BYTES, PagedMode, PagedViewer};
+use egui::Color32;
+use fvrs_core::text::TextEncoding;
+
+/// 横スクロールの上限（ピクセル）
+const MAX_SCROLL_X: f32 = 100_000.0;
+
+/// 大きなファイルのページ表示（見えている行だけを読み込む）
+pub struct PagedViewerUI;
+
+impl PagedViewerUI {
+    /// ツールバーと本文を表示
+    pub fn show(ui: &mut egui::Ui, app: &mut FileVisorApp) {
+        let ctx = ui.ctx().clone();
+        let Some(viewer) = app.paged_viewer.as_mut() else {
+            return;
+        };
+        viewer.poll(&ctx);
+
+        Self::show_toolbar(ui, viewer);
+        if let Some(error) = &viewer.error {
+            ui.colored_label(Color32::YELLOW, format!("⚠ {}", error));
+        }
+        ui.separator();
+
+        // 下のステータスバーの分を残す
+        let height = (ui.available_height() - 28.0).max(100.0);
+        let visible = Self::show_rows(ui, viewer, height);
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui.button("📋").on_hover_text("表示中の行をコピー").clicked() {
+                let rows = viewer.rows(viewer.top, visible);
+                ui.ctx().copy_text(rows.join("\n"));
+            }
+            ui.label(format!("サイズ: {}", format_size(viewer.file_size())));
+            ui.separator();
+            match viewer.mode {
+                PagedMode::Text => ui.label(format!("行数: {}", viewer.row_count())),
+                PagedMode::Hex => ui.label(format!("オフセット: 0x{:X}", viewer.top * HEX_ROW_BYTES)),
+            };
+            if viewer.is_indexing() {
+                ui.separator();
+                ui.spinner();
+                ui.label(format!("行の索引を作成中... {:.0}%", viewer.indexed_fraction() * 100.0));
+            }
+        });
+    }
+
+    /// 表示方法・文字コード・移動・追従
+    fn show_toolbar(ui: &mut egui::Ui, viewer: &mut PagedViewer) {
+        ui.horizontal(|ui| {
+            let text_enabled = viewer.supports_text();
+            if ui
+                .add_enabled(text_enabled, egui::SelectableLabel::new(viewer.mode == PagedMode::Text, "テキスト"))
+                .clicked()
+            {
+                viewer.set_mode(PagedMode::Text);
+            }
+            if ui.selectable_label(viewer.mode == PagedMode::Hex, "16進").clicked() {
+                viewer.set_mode(PagedMode::Hex);
+            }
+
+            if viewer.mode == PagedMode::Text {
+                ui.separator();
+                ui.label("文字コード:");
+                egui::ComboBox::from_id_salt("paged_encoding")
+                    .selected_text(viewer.encoding.label())
+                    .show_ui(ui, |ui| {
+                        // UTF-16 は行に分けられないので選ばせない
+                        for encoding in TextEncoding::ALL {
+                            if !matches!(encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be) {
+                                ui.selectable_value(&mut viewer.encoding, encoding, encoding.label());
+                            }
+                        }
+                    });
+            }
+
+            ui.separator();
+            let hint = match viewer.mode {
+                PagedMode::Text => "行番号 / 0x オフセット",
+                PagedMode::Hex => "オフセット (0x で 16 進)",
+            };
+            let response = ui.add(egui::TextEdit::singleline(&mut viewer.goto_text).desired_width(140.0).hint_text(hint));
+            let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button("移動").clicked() || entered) && !viewer.goto_text.trim().is_empty() {
+                let target = viewer.goto_text.clone();
+                viewer.error = viewer.goto(&target).err();
+            }
+
+            ui.separator();
+            ui.checkbox(&mut viewer.follow, "追従").on_hover_text("追記されたら末尾を表示し続けます (tail -f)");
+        });
+    }
+
+    /// 見えている行だけを読み込んで表示する（スクロール位置は行単位で持つ）
+    fn show_rows(ui: &mut egui::Ui, viewer: &mut PagedViewer, height: f32) -> usize {
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let scrollbar_width = ui.spacing().scroll.bar_width + 6.0;
+        let size = egui::vec2((ui.available_width() - scrollbar_width).max(50.0), height);
+
+        ui.horizontal_top(|ui| {
+            let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+            let visible = ((rect.height() / row_height).floor() as u64).max(1);
+            let row_count = viewer.row_count();
+            let max_top = row_count.saturating_sub(visible);
+
+            if ui.rect_contains_pointer(rect) {
+                Self::handle_scroll(ui, viewer, row_height, visible);
+            }
+            if viewer.follow {
+                viewer.top = max_top;
+            }
+            viewer.top = viewer.top.min(max_top);
+
+            // スクロールバー（上端が先頭になるよう反転）
+            let mut position = max_top - viewer.top;
+            ui.scope(|ui| {
+                ui.spacing_mut().slider_width = height;
+                let slider = egui::Slider::new(&mut position, 0..=max_top.max(1)).vertical().show_value(false);
+                if ui.add_enabled(max_top > 0, slider).changed() {
+                    viewer.top = max_top.saturating_sub(position);
+                    viewer.follow = false;
+                }
+            });
+
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+            let font = egui::TextStyle::Monospace.resolve(ui.style());
+            let text_color = ui.visuals().text_color();
+            let weak_color = ui.visuals().weak_text_color();
+
+            let first = viewer.top;
+            let rows = viewer.rows(first, visible as usize + 1);
+            let gutter = match viewer.mode {
+                PagedMode::Text => {
+                    let digits = row_count.max(1).to_string().len().max(4);
+                    ui.fonts(|f| f.glyph_width(&font, '0')) * (digits as f32 + 1.0)
+                }
+                PagedMode::Hex => 0.0,
+            };
+
+            for (i, row) in rows.iter().enumerate() {
+                let y = rect.top() + i as f32 * row_height;
+                if viewer.mode == PagedMode::Text {
+                    painter.text(
+                        egui::pos2(rect.left() + gutter - 8.0, y),
+                        egui::Align2::RIGHT_TOP,
+                        (first + i as u64 + 1).to_string(),
+                        font.clone(),
+                        weak_color,
+                    );
+                }
+                let text_painter = painter.with_clip_rect(rect.with_min_x(rect.left() + gutter));
+                text_painter.text(
+                    egui::pos2(rect.left() + gutter - viewer.scroll_x, y),
+                    egui::Align2::LEFT_TOP,
+                    row,
+                    font.clone(),
+                    text_color,
+                );
+            }
+
+            if rows.is_empty() && viewer.is_indexing() {
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "読み込み中...",
+                    font,
+                    weak_color,
+                );
+            }
+            visible as usize
+        })
+        .inner
+    }
+
+    /// ホイール・キー操作で先頭の行を動かす
+    fn handle_scroll(ui: &egui::Ui, viewer: &mut PagedViewer, row_height: f32, visible: u64) {
+        let (delta, page_up, page_down, home, end) = ui.input(|i| {
+            (
+                i.smooth_scroll_delta,
+                i.key_pressed(egui::Key::PageUp),
+                i.key_pressed(egui::Key::PageDown),
+                i.modifiers.ctrl && i.key_pressed(egui::Key::Home),
+                i.modifiers.ctrl && i.key_pressed(egui::Key::End),
+            )
+        });
+
+        viewer.scroll_x = (viewer.scroll_x - delta.x).clamp(0.0, MAX_SCROLL_X);
+        viewer.scroll_remainder -= delta.y / row_height;
+        let lines = viewer.scroll_remainder.trunc();
+        viewer.scroll_remainder -= lines;
+
+        let mut top = viewer.top as i128 + lines as i128;
+        if page_up {
+            top -= visible as i128;
+        }
+        if page_down {
+            top += visible as i128;
+        }
+        if home {
+            top = 0;
+        }
+        let top = if end { u64::MAX } else { top.max(0) as u64 };
+        if top < viewer.top {
+            viewer.follow = false;
+        }
+        // 末尾を越えた分は呼び出し側で切り詰める
+        viewer.top = top;
+    }
+}
@@ -0,0 +1,123 @@
+use crate::app::FileVisorApp;
+use crate::history::{JumpCandidate, JumpSource, QuickJump};
+use egui::text::LayoutJob;
+use egui::{Color32, FontId, Key, RichText, TextFormat};
+
+/// 表示する候補の行数
+const VISIBLE_ROWS: usize = 12;
+
+/// 履歴やブックマークからフォルダーへすばやく移動する（Ctrl+P）
+pub struct QuickJumpUI;
+
+impl QuickJumpUI {
+    pub fn open(app: &mut FileVisorApp) {
+        app.quick_jump = Some(QuickJump::default());
+    }
+
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        let Some(jump) = app.quick_jump.as_mut() else {
+            return;
+        };
+        jump.update(&app.history.history, &app.bookmarks.bookmarks);
+
+        // 入力欄にフォーカスがあっても候補を選べるよう、先にキーを取り出す
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, Key::Escape),
+            )
+        });
+        if up {
+            jump.move_selection(false);
+        }
+        if down {
+            jump.move_selection(true);
+        }
+
+        let mut chosen = enter.then(|| jump.selected_path().map(|path| path.to_path_buf())).flatten();
+        egui::Window::new("quick_jump")
+            .title_bar(false)
+            .resizable(false)
+            .collapsible(false)
+            .fixed_size([560.0, 0.0])
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut jump.query)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("フォルダー名の一部を入力（スペースで区切って複数指定）"),
+                );
+                response.request_focus();
+                ui.separator();
+
+                if jump.candidates.is_empty() {
+                    ui.label(RichText::new("一致するフォルダーはありません").weak());
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(VISIBLE_ROWS as f32 * 22.0).show(ui, |ui| {
+                    for (index, candidate) in jump.candidates.iter().enumerate() {
+                        let selected = index == jump.selected;
+                        let response = ui.horizontal(|ui| {
+                            let icon = match candidate.source {
+                                JumpSource::Bookmark => "🔖",
+                                JumpSource::Frequent => "⭐",
+                                JumpSource::Recent => "🕘",
+                            };
+                            ui.label(icon);
+                            if let Some(name) = &candidate.bookmark {
+                                ui.label(RichText::new(name).strong());
+                            }
+                            ui.selectable_label(selected, highlighted(ui, candidate))
+                        });
+                        let response = response.inner;
+                        if selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            chosen = Some(candidate.path.clone());
+                        }
+                    }
+                });
+                ui.label(RichText::new("↑↓: 選択  Enter: 移動  Esc: 閉じる").weak().small());
+            });
+
+        if escape {
+            app.quick_jump = None;
+        }
+        if let Some(path) = chosen {
+            app.quick_jump = None;
+            if path.is_dir() {
+                app.navigate_to(path);
+            } else {
+                tracing::warn!("フォルダーが見つかりません: {:?}", path);
+            }
+        }
+    }
+}
+
+/// 一致した文字を強調したパス
+fn highlighted(ui: &egui::Ui, candidate: &JumpCandidate) -> LayoutJob {
+    let font = FontId::proportional(14.0);
+    let normal = if candidate.exists { ui.visuals().text_color() } else { Color32::GRAY };
+    let matched = ui.visuals().warn_fg_color;
+
+    // 一致する・しない文字の連続ごとにまとめて追加する
+    let mut job = LayoutJob::default();
+    let mut positions = candidate.positions.iter().peekable();
+    let mut run = String::new();
+    let mut run_matches = false;
+    for (index, c) in candidate.label.chars().enumerate() {
+        let is_match = positions.next_if(|&&position| position == index).is_some();
+        if is_match != run_matches && !run.is_empty() {
+            let color = if run_matches { matched } else { normal };
+            job.append(&std::mem::take(&mut run), 0.0, TextFormat::simple(font.clone(), color));
+        }
+        run_matches = is_match;
+        run.push(c);
+    }
+    let color = if run_matches { matched } else { normal };
+    job.append(&run, 0.0, TextFormat::simple(font, color));
+    job
+}
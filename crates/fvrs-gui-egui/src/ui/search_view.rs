@@ -0,0 +1,391 @@
+use crate::app::FileVisorApp;
+use crate::file_info::format_size;
+use crate::search::{FileSearch, MAX_CONTEXT_LINES, ModifiedWithin, NameSyntax, category_label};
+use crate::split_join::SizeUnit;
+use crate::ui::{FileViewerUI, SmartFoldersUI};
+use crate::utils::format_time;
+use egui::text::LayoutJob;
+use egui::{Color32, FontId, Key, RichText, TextFormat};
+use fvrs_core::core::{EntryKind, FileCategory};
+use fvrs_core::search::LineMatch;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// ファイルごとに表示する一致行の数（前後の行は数えない）
+const PREVIEW_LINES: usize = 5;
+
+/// 検索ダイアログでの操作（描画後にまとめて適用）
+enum SearchAction {
+    Start,
+    Cancel,
+    /// 今の条件をスマートフォルダーとして保存する
+    SaveAsSmartFolder,
+    /// 結果のあるフォルダーを開いて選択する
+    Reveal(PathBuf),
+    /// ファイルを閲覧する・フォルダーに入る
+    Open(PathBuf),
+}
+
+/// 結果一覧の 1 行
+enum ResultRow {
+    Entry(usize),
+    Line(usize, usize),
+    /// 離れた一致行のあいだの区切り
+    Gap,
+    /// 表示しきれなかった一致行の数
+    More(u64),
+}
+
+/// ファイルの検索（F キー）
+pub struct SearchUI;
+
+impl SearchUI {
+    /// 表示中のフォルダーを検索する場所にして開く
+    pub fn open(app: &mut FileVisorApp) {
+        app.state.show_search_view = true;
+        if !app.file_search.is_running() {
+            app.file_search.root = app.state.pane().current_path.clone();
+        }
+    }
+
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        if !app.state.show_search_view {
+            if app.file_search.is_running() {
+                app.file_search.cancel();
+            }
+            return;
+        }
+        app.file_search.poll(ctx);
+
+        let mut actions = Vec::new();
+        let mut open = true;
+        egui::Window::new("🔍 検索")
+            .default_width(720.0)
+            .default_height(520.0)
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let search = &mut app.file_search;
+                let indexed = search.can_use_index(&app.file_index);
+                Self::show_form(ui, search, indexed, &mut actions);
+                ui.separator();
+                Self::show_status(ui, search);
+                ui.separator();
+                Self::show_results(ui, search, &mut actions);
+            });
+
+        if !open {
+            app.state.show_search_view = false;
+        }
+        for action in actions {
+            match action {
+                SearchAction::Start => app.file_search.start(&app.file_index),
+                SearchAction::Cancel => app.file_search.cancel(),
+                SearchAction::SaveAsSmartFolder => SmartFoldersUI::open_from_search(app),
+                SearchAction::Reveal(path) => {
+                    let Some(parent) = path.parent().map(PathBuf::from) else {
+                        continue;
+                    };
+                    app.state.sidebar_selected_item = Some(parent.clone());
+                    app.navigate_to(parent);
+                    app.state.pane_mut().selected_items = vec![path];
+                }
+                SearchAction::Open(path) => {
+                    if path.is_dir() {
+                        app.state.sidebar_selected_item = Some(path.clone());
+                        app.navigate_to(path);
+                    } else {
+                        FileViewerUI::open_file_for_viewing(app, path);
+                    }
+                }
+            }
+        }
+    }
+
+    fn show_form(ui: &mut egui::Ui, search: &mut FileSearch, indexed: bool, actions: &mut Vec<SearchAction>) {
+        let mut submitted = false;
+        let running = search.is_running();
+        ui.add_enabled_ui(!running, |ui| {
+            egui::Grid::new("search_form").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+                ui.label("場所:");
+                ui.horizontal(|ui| {
+                    let mut root = search.root.to_string_lossy().into_owned();
+                    if ui.add(egui::TextEdit::singleline(&mut root).desired_width(420.0)).changed() {
+                        search.root = PathBuf::from(root);
+                    }
+                    if ui.button("📁").on_hover_text("参照").clicked()
+                        && let Some(path) = rfd::FileDialog::new().set_directory(&search.root).pick_folder()
+                    {
+                        search.root = path;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("名前:");
+                ui.horizontal(|ui| {
+                    let hint = match search.syntax {
+                        NameSyntax::Glob => "名前の一部、または *.rs;*.toml",
+                        NameSyntax::Regex => r"例: ^main\.(rs|c)$",
+                    };
+                    let response = ui.add(egui::TextEdit::singleline(&mut search.name).desired_width(260.0).hint_text(hint));
+                    submitted |= response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                    ui.radio_value(&mut search.syntax, NameSyntax::Glob, "ワイルドカード");
+                    ui.radio_value(&mut search.syntax, NameSyntax::Regex, "正規表現");
+                });
+                ui.end_row();
+
+                ui.label("内容:");
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut search.content)
+                            .desired_width(260.0)
+                            .hint_text("ファイルの中から探す文字列"),
+                    );
+                    submitted |= response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                    ui.checkbox(&mut search.content_regex, "正規表現");
+                    ui.checkbox(&mut search.case_sensitive, "大文字と小文字を区別");
+                });
+                ui.end_row();
+
+                ui.label("種類:");
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut search.kind, EntryKind::Any, "すべて");
+                    ui.selectable_value(&mut search.kind, EntryKind::Files, "ファイル");
+                    ui.selectable_value(&mut search.kind, EntryKind::Directories, "フォルダー");
+                    ui.separator();
+                    let label = |category: Option<FileCategory>| category.map_or("ファイルの種類: 指定なし", category_label);
+                    egui::ComboBox::from_id_salt("search_category")
+                        .selected_text(label(search.category))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut search.category, None, label(None));
+                            for category in FileCategory::ALL {
+                                ui.selectable_value(&mut search.category, Some(category), label(Some(category)));
+                            }
+                        });
+                });
+                ui.end_row();
+
+                ui.label("サイズ:");
+                ui.horizontal(|ui| {
+                    Self::size_limit(ui, &mut search.min_size, "以上");
+                    Self::size_limit(ui, &mut search.max_size, "以下");
+                    egui::ComboBox::from_id_salt("search_size_unit")
+                        .selected_text(search.size_unit.label())
+                        .width(60.0)
+                        .show_ui(ui, |ui| {
+                            for unit in SizeUnit::ALL {
+                                ui.selectable_value(&mut search.size_unit, unit, unit.label());
+                            }
+                        });
+                });
+                ui.end_row();
+
+                ui.label("更新日時:");
+                egui::ComboBox::from_id_salt("search_modified")
+                    .selected_text(search.modified.label())
+                    .show_ui(ui, |ui| {
+                        for modified in ModifiedWithin::ALL {
+                            ui.selectable_value(&mut search.modified, modified, modified.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("オプション:");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut search.respect_ignore, ".gitignore に従う")
+                        .on_hover_text(".gitignore などで除外された項目と .git フォルダーを飛ばす");
+                    ui.checkbox(&mut search.include_hidden, "隠しファイル");
+                    ui.checkbox(&mut search.skip_binary, "バイナリを除外");
+                    ui.label("前後の行:");
+                    ui.add(egui::DragValue::new(&mut search.context_lines).range(0..=MAX_CONTEXT_LINES));
+                    ui.add_enabled(indexed, egui::Checkbox::new(&mut search.use_index, "インデックスを使う"))
+                        .on_hover_text("名前だけで探すとき、ファイルインデックスからすぐに結果を出す（.gitignore は考慮しない）")
+                        .on_disabled_hover_text("この場所はインデックスにないか、内容を探す条件があります");
+                });
+                ui.end_row();
+            });
+        });
+
+        ui.horizontal(|ui| {
+            if running {
+                if ui.button("⏹ 中止").clicked() {
+                    actions.push(SearchAction::Cancel);
+                }
+            } else if ui.button("🔍 検索").clicked() || submitted {
+                actions.push(SearchAction::Start);
+            }
+            if ui
+                .add_enabled(!running, egui::Button::new("💾 スマートフォルダーとして保存..."))
+                .on_hover_text("この条件で探した結果を、サイドバーからいつでも開けるフォルダーにする")
+                .clicked()
+            {
+                actions.push(SearchAction::SaveAsSmartFolder);
+            }
+        });
+    }
+
+    /// サイズの下限・上限（チェックを外すと条件なし）
+    pub fn size_limit(ui: &mut egui::Ui, limit: &mut Option<u64>, label: &str) {
+        let mut enabled = limit.is_some();
+        if ui.checkbox(&mut enabled, "").changed() {
+            *limit = enabled.then_some(1);
+        }
+        let mut value = limit.unwrap_or(1);
+        if ui.add_enabled(enabled, egui::DragValue::new(&mut value).range(0..=u64::MAX)).changed() {
+            *limit = Some(value);
+        }
+        ui.label(label);
+    }
+
+    fn show_status(ui: &mut egui::Ui, search: &FileSearch) {
+        ui.horizontal(|ui| {
+            if let Some(elapsed) = search.running_time() {
+                ui.spinner();
+                ui.label(format!(
+                    "検索中... {} 件（{} 項目を調査, {:.1} 秒）",
+                    search.hits.len(),
+                    search.scanned(),
+                    elapsed.as_secs_f32()
+                ));
+            } else if let Some(summary) = &search.summary {
+                let seconds = search.elapsed.map_or(0.0, |elapsed| elapsed.as_secs_f32());
+                let source = if search.from_index { "インデックス, " } else { "" };
+                ui.label(format!(
+                    "{} 件見つかりました（{}{} 項目を調査, {:.1} 秒）",
+                    search.hits.len(),
+                    source,
+                    summary.scanned,
+                    seconds
+                ));
+                if summary.errors > 0 {
+                    ui.label(RichText::new(format!("読めない項目: {}", summary.errors)).color(Color32::YELLOW));
+                }
+                if summary.binary > 0 {
+                    ui.label(RichText::new(format!("バイナリとして除外: {}", summary.binary)).weak());
+                }
+                if search.truncated {
+                    ui.label(RichText::new("件数が多いため途中で打ち切りました").color(Color32::YELLOW));
+                }
+            } else if search.elapsed.is_some() {
+                ui.label(format!("中止しました（{} 件）", search.hits.len()));
+            }
+            if let Some(error) = &search.error {
+                ui.colored_label(Color32::RED, error);
+            }
+        });
+    }
+
+    fn show_results(ui: &mut egui::Ui, search: &FileSearch, actions: &mut Vec<SearchAction>) {
+        if search.hits.is_empty() {
+            if search.summary.is_some() {
+                ui.label("一致する項目はありません");
+            }
+            return;
+        }
+
+        let mut rows = Vec::new();
+        for (index, hit) in search.hits.iter().enumerate() {
+            rows.push(ResultRow::Entry(index));
+            let mut shown = 0u64;
+            let mut previous = None;
+            for (line_index, line) in hit.lines.iter().enumerate() {
+                if !line.is_context() {
+                    if shown == PREVIEW_LINES as u64 {
+                        break;
+                    }
+                    shown += 1;
+                }
+                if previous.is_some_and(|previous| line.line_number > previous + 1) {
+                    rows.push(ResultRow::Gap);
+                }
+                previous = Some(line.line_number);
+                rows.push(ResultRow::Line(index, line_index));
+            }
+            if hit.line_count > shown {
+                rows.push(ResultRow::More(hit.line_count - shown));
+            }
+        }
+
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show_rows(ui, row_height, rows.len(), |ui, range| {
+            for row in &rows[range] {
+                match *row {
+                    ResultRow::Entry(index) => {
+                        let entry = &search.hits[index].entry;
+                        ui.horizontal(|ui| {
+                            ui.label(if entry.is_dir { "📁" } else { "📄" });
+                            let response = ui
+                                .selectable_label(false, search.relative(&entry.path).display().to_string())
+                                .on_hover_text("クリックで場所を開く・ダブルクリックで開く");
+                            if response.double_clicked() {
+                                actions.push(SearchAction::Open(entry.path.clone()));
+                            } else if response.clicked() {
+                                actions.push(SearchAction::Reveal(entry.path.clone()));
+                            }
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.label(RichText::new(format_time(entry.modified)).weak());
+                                if !entry.is_dir {
+                                    ui.label(RichText::new(format_size(entry.size)).weak());
+                                }
+                            });
+                        });
+                    }
+                    ResultRow::Line(index, line) => {
+                        let hit = &search.hits[index];
+                        let line = &hit.lines[line];
+                        ui.horizontal(|ui| {
+                            ui.add_space(24.0);
+                            ui.label(RichText::new(line_label(line)).monospace().weak());
+                            let job = highlighted(ui, &line.text, &line.matches, line.is_context());
+                            if ui.add(egui::Label::new(job).truncate().sense(egui::Sense::click())).clicked() {
+                                actions.push(SearchAction::Reveal(hit.entry.path.clone()));
+                            }
+                        });
+                    }
+                    ResultRow::Gap => {
+                        ui.horizontal(|ui| {
+                            ui.add_space(24.0);
+                            ui.label(RichText::new("    ⋮").monospace().weak());
+                        });
+                    }
+                    ResultRow::More(count) => {
+                        ui.horizontal(|ui| {
+                            ui.add_space(24.0);
+                            ui.label(RichText::new(format!("…ほか {} 行", count)).weak().small());
+                        });
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// 行番号と桁（grep と同じく、一致行は「:」、前後の行は「-」で区切る）
+fn line_label(line: &LineMatch) -> String {
+    if line.is_context() {
+        format!("{:>6}-    ", line.line_number)
+    } else {
+        format!("{:>6}:{:<4}", line.line_number, line.column)
+    }
+}
+
+/// 一致した部分を強調した行（前後の行は薄く表示する）
+fn highlighted(ui: &egui::Ui, text: &str, matches: &[Range<usize>], context: bool) -> LayoutJob {
+    let font = FontId::monospace(12.0);
+    let normal = if context { ui.visuals().weak_text_color() } else { ui.visuals().text_color() };
+    let matched = ui.visuals().warn_fg_color;
+
+    let mut job = LayoutJob::default();
+    let mut end = 0;
+    for found in matches {
+        if found.start < end || found.is_empty() {
+            continue;
+        }
+        job.append(&text[end..found.start], 0.0, TextFormat::simple(font.clone(), normal));
+        job.append(&text[found.clone()], 0.0, TextFormat::simple(font.clone(), matched));
+        end = found.end;
+    }
+    job.append(&text[end..], 0.0, TextFormat::simple(font, normal));
+    job
+}
@@ -16,17 +16,86 @@ impl ShortcutHandler {
             || app.state.show_unpack_dialog
             || app.state.show_pack_dialog
             || app.state.show_rename_dialog 
+            || app.state.show_transfer_dialog
+            || app.jobs.pending_conflict().is_some()
+            || app.hex_editor.is_some()
+            || app.attribute_editor.is_some()
+            || app.split_join.is_some()
+            || app.state.show_history_view
+            || app.quick_jump.is_some()
+            || app.bookmark_editor.is_some()
+            || app.smart_folder_editor.is_some()
+            || app.state.show_search_view
+            || app.state.show_index_view
+            || app.state.show_file_viewer
+            || app.paged_viewer.is_some()
+            || app.state.show_duplicates_view
                  {
              return;
          }
 
+        // テキスト入力中のクリップボード操作や修飾キーなしのキーはテキストに任せる
+        let text_focused = ctx.wants_keyboard_input();
         ctx.input(|i| {
             // 基本的なショートカット（ペインに関係なく動作）
             if i.key_pressed(Key::F5) {
                 Self::refresh_directory(app);
             }
-            if i.key_pressed(Key::Delete) && !app.state.selected_items.is_empty() {
-                Self::delete_files(app);
+            if i.key_pressed(Key::Delete) && !text_focused && !app.state.pane().selected_items.is_empty() {
+                // Shift+Delete のみゴミ箱を経由せずに削除
+                if i.modifiers.shift {
+                    app.show_permanent_delete_confirmation();
+                } else {
+                    Self::delete_files(app);
+                }
+            }
+            // Ctrl+Z: 元に戻す, Ctrl+Y / Ctrl+Shift+Z: やり直し
+            if i.modifiers.command && i.key_pressed(Key::Z) {
+                if i.modifiers.shift {
+                    app.redo();
+                } else {
+                    app.undo();
+                }
+            }
+            if i.modifiers.command && i.key_pressed(Key::Y) {
+                app.redo();
+            }
+            // Ctrl+C / Ctrl+X / Ctrl+V: クリップボード（貼り付けはバックグラウンドで実行）
+            if i.modifiers.command && !text_focused {
+                if i.key_pressed(Key::C) {
+                    app.set_clipboard(false);
+                }
+                if i.key_pressed(Key::X) {
+                    app.set_clipboard(true);
+                }
+                if i.key_pressed(Key::V) {
+                    app.paste_clipboard();
+                }
+            }
+            // Ctrl+T / Ctrl+W: タブを開く・閉じる, Ctrl+Tab / Ctrl+Shift+Tab: タブ切り替え
+            if i.modifiers.command && i.key_pressed(Key::T) {
+                app.new_tab();
+            }
+            if i.modifiers.command && i.key_pressed(Key::W) {
+                app.close_current_tab();
+            }
+            if i.modifiers.ctrl && i.key_pressed(Key::Tab) {
+                app.cycle_tab(!i.modifiers.shift);
+            }
+            // Ctrl+P: 履歴からフォルダーへすばやく移動
+            if i.modifiers.command && i.key_pressed(Key::P) {
+                crate::ui::QuickJumpUI::open(app);
+            }
+            // Ctrl+D: ブックマークに追加, Ctrl+1～9: ブックマークへ移動
+            if i.modifiers.command && i.key_pressed(Key::D) {
+                crate::ui::BookmarksUI::add_current(app);
+            }
+            if i.modifiers.command {
+                const NUMBER_KEYS: [Key; 9] =
+                    [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9];
+                if let Some(index) = NUMBER_KEYS.iter().position(|key| i.key_pressed(*key)) {
+                    crate::ui::BookmarksUI::open_shortcut(app, index as u8 + 1);
+                }
             }
             if i.modifiers.alt && i.key_pressed(Key::ArrowLeft) {
                 Self::go_back(app);
@@ -36,12 +105,12 @@ impl ShortcutHandler {
             }
             
             // Tab キーでペイン切り替え
-            if i.key_pressed(Key::Tab) && !i.modifiers.any() {
+            if i.key_pressed(Key::Tab) && !i.modifiers.any() && !text_focused {
                 Self::switch_pane(app);
             }
             
             // 矢印キーによるナビゲーション（アクティブペインでのみ）
-            if app.state.active_pane == ActivePane::MainList {
+            if app.state.active_pane == ActivePane::MainList && !text_focused {
                 if i.key_pressed(Key::ArrowUp) {
                     Self::navigate_list_up(app);
                 }
@@ -50,8 +119,8 @@ impl ShortcutHandler {
                 }
             }
 
-            // A～Zのワンタッチキー（修飾キーなしの場合のみ）
-            if !i.modifiers.any() {
+            // A～Zのワンタッチキー（修飾キーなし、かつテキスト入力中でない場合のみ）
+            if !i.modifiers.any() && !text_focused {
                 // A: 属性変更
                 if i.key_pressed(Key::A) {
                     Self::change_attributes(app);
@@ -155,25 +224,29 @@ impl ShortcutHandler {
             }
         });
 
+        if text_focused {
+            return;
+        }
+
         if ctx.input(|i| i.key_pressed(Key::U)) {
             tracing::info!("解凍ダイアログを表示");
             app.show_unpack_dialog();
         }
 
-        if ctx.input(|i| i.key_pressed(Key::P)) {
+        if ctx.input(|i| i.key_pressed(Key::P) && !i.modifiers.command) {
             tracing::info!("圧縮ダイアログを表示");
             app.show_pack_dialog();
         }
 
-                    if ctx.input(|i| i.key_pressed(Key::V)) {
-            if let Some(selected_path) = app.state.selected_items.first() {
-                let full_path = selected_path.clone();
-                if crate::archive::ArchiveHandler::is_archive(&full_path) {
-                    tracing::info!("圧縮ファイルビューアを表示: {:?}", full_path);
-                    app.show_archive_viewer(full_path);
-                } else {
-                    // app.open_file(full_path);
-                }
+        if ctx.input(|i| i.key_pressed(Key::V) && !i.modifiers.command)
+            && let Some(selected_path) = app.state.pane().selected_items.first()
+        {
+            let full_path = selected_path.clone();
+            if crate::archive::ArchiveHandler::is_archive(&full_path) {
+                tracing::info!("圧縮ファイルビューアを表示: {:?}", full_path);
+                app.show_archive_viewer(full_path);
+            } else {
+                // app.open_file(full_path);
             }
         }
 
@@ -186,7 +259,7 @@ impl ShortcutHandler {
     // ===== 基本操作 =====
     
     fn refresh_directory(app: &mut FileVisorApp) {
-        app.directory_cache.remove(&app.state.current_path);
+        app.directory_cache.remove(&app.state.pane().current_path);
         tracing::info!("ディレクトリを更新しました");
     }
 
@@ -199,6 +272,12 @@ impl ShortcutHandler {
     }
     
     fn switch_pane(app: &mut FileVisorApp) {
+        // 2画面表示では左右のファイル一覧を切り替える
+        if app.state.dual_pane && app.state.active_pane == ActivePane::MainList {
+            app.activate_pane(app.state.active_side.other());
+            tracing::info!("ペインを切り替えました: {:?}", app.state.active_side);
+            return;
+        }
         app.state.active_pane = match app.state.active_pane {
             ActivePane::LeftSidebar => ActivePane::MainList,
             ActivePane::MainList => ActivePane::LeftSidebar,
@@ -207,62 +286,76 @@ impl ShortcutHandler {
     }
     
     fn navigate_list_up(app: &mut FileVisorApp) {
-        if let Some(current_index) = app.state.last_selected_index {
+        if let Some(current_index) = app.state.pane().last_selected_index {
             if current_index > 0 {
-                app.state.last_selected_index = Some(current_index - 1);
+                app.state.pane_mut().last_selected_index = Some(current_index - 1);
                 // 実際の選択を更新する処理が必要
                 tracing::info!("リスト上へ移動: {}", current_index - 1);
             }
-        } else if !app.state.selected_items.is_empty() {
-            app.state.last_selected_index = Some(0);
+        } else if !app.state.pane().selected_items.is_empty() {
+            app.state.pane_mut().last_selected_index = Some(0);
         }
     }
     
     fn navigate_list_down(app: &mut FileVisorApp) {
-        if let Some(current_index) = app.state.last_selected_index {
-            app.state.last_selected_index = Some(current_index + 1);
+        if let Some(current_index) = app.state.pane().last_selected_index {
+            app.state.pane_mut().last_selected_index = Some(current_index + 1);
             // 実際の選択を更新する処理が必要
             tracing::info!("リスト下へ移動: {}", current_index + 1);
-        } else if app.state.selected_items.is_empty() {
-            app.state.last_selected_index = Some(0);
+        } else if app.state.pane().selected_items.is_empty() {
+            app.state.pane_mut().last_selected_index = Some(0);
         }
     }
 
     // ===== ファイル操作 =====
 
     fn copy_files(app: &mut FileVisorApp) {
-        if !app.state.selected_items.is_empty() {
+        if app.state.dual_pane {
+            // 反対側のペインをコピー先にして確認
+            app.show_transfer_dialog(false);
+        } else {
             // クリップボードに選択アイテムをコピー
-            use crate::state::ClipboardOperation;
-            app.state.clipboard = Some(ClipboardOperation::Copy(app.state.selected_items.clone()));
-            tracing::info!("{}個のアイテムをコピーしました", app.state.selected_items.len());
+            app.set_clipboard(false);
         }
     }
 
     fn move_files(app: &mut FileVisorApp) {
-        if !app.state.selected_items.is_empty() {
+        if app.state.dual_pane {
+            // 反対側のペインを移動先にして確認
+            app.show_transfer_dialog(true);
+        } else {
             // クリップボードに選択アイテムを切り取り
-            use crate::state::ClipboardOperation;
-            app.state.clipboard = Some(ClipboardOperation::Cut(app.state.selected_items.clone()));
-            tracing::info!("{}個のアイテムを切り取りました", app.state.selected_items.len());
+            app.set_clipboard(true);
         }
     }
 
+    fn change_attributes(app: &mut FileVisorApp) {
+        crate::ui::AttributesDialog::open(app);
+    }
+
+    fn show_history(app: &mut FileVisorApp) {
+        crate::ui::HistoryUI::open(app);
+    }
+
+    fn concatenate_files(app: &mut FileVisorApp) {
+        crate::ui::SplitJoinDialog::open(app);
+    }
+
     fn delete_files(app: &mut FileVisorApp) {
-        if !app.state.selected_items.is_empty() {
+        if !app.state.pane().selected_items.is_empty() {
             app.show_delete_confirmation();
         }
     }
 
     fn rename_files(app: &mut FileVisorApp) {
-        if app.state.selected_items.len() == 1 {
-            tracing::info!("名前変更: {:?}", app.state.selected_items[0]);
+        if app.state.pane().selected_items.len() == 1 {
+            tracing::info!("名前変更: {:?}", app.state.pane().selected_items[0]);
             // TODO: 名前変更ダイアログを実装
         }
     }
 
     fn open_files(app: &mut FileVisorApp) {
-        for path in &app.state.selected_items {
+        for path in &app.state.pane().selected_items {
             if path.is_file() {
                 if let Err(e) = open::that(path) {
                     tracing::error!("ファイルオープンエラー: {:?}", e);
@@ -275,11 +368,11 @@ impl ShortcutHandler {
     }
 
     fn execute_files(app: &mut FileVisorApp) {
-        for path in &app.state.selected_items {
-            if path.is_file() {
-                if let Err(e) = open::that(path) {
-                    tracing::error!("実行エラー: {:?}", e);
-                }
+        for path in &app.state.pane().selected_items {
+            if path.is_file()
+                && let Err(e) = open::that(path)
+            {
+                tracing::error!("実行エラー: {:?}", e);
             }
         }
     }
@@ -287,14 +380,12 @@ impl ShortcutHandler {
     // ===== 選択操作 =====
 
     fn select_all(app: &mut FileVisorApp) {
-        let current_path = app.state.current_path.clone();
-        if let Ok(entries) = app.load_directory(&current_path) {
-            let entries = entries.clone(); // 借用の競合を避けるためにクローン
-            app.state.selected_items.clear();
-            for entry in entries {
-                app.state.selected_items.push(entry.path.clone());
-            }
-            app.state.last_selected_index = None;
+        let current_path = app.state.pane().current_path.clone();
+        if let Ok(listing) = app.load_directory(&current_path) {
+            let paths: Vec<_> = listing.entries().iter().map(|entry| entry.path.clone()).collect();
+            let pane = app.state.pane_mut();
+            pane.selected_items = paths;
+            pane.last_selected_index = None;
             tracing::info!("すべてのアイテムを選択しました");
         }
     }
@@ -308,10 +399,10 @@ impl ShortcutHandler {
     }
 
     fn open_folder(app: &mut FileVisorApp) {
-        if let Some(selected) = app.state.selected_items.first() {
-            if selected.is_dir() {
-                app.navigate_to(selected.clone());
-            }
+        if let Some(selected) = app.state.pane().selected_items.first()
+            && selected.is_dir()
+        {
+            app.navigate_to(selected.clone());
         }
     }
 
@@ -319,21 +410,21 @@ impl ShortcutHandler {
 
     fn change_sort(app: &mut FileVisorApp) {
         use crate::state::SortColumn;
-        app.state.sort_column = match app.state.sort_column {
+        app.state.pane_mut().sort_column = match app.state.pane_mut().sort_column {
             SortColumn::Name => SortColumn::Size,
             SortColumn::Size => SortColumn::Modified,
             SortColumn::Modified => SortColumn::Type,
             SortColumn::Type => SortColumn::Name,
         };
-        app.directory_cache.remove(&app.state.current_path);
-        tracing::info!("ソート条件を変更しました: {:?}", app.state.sort_column);
+        app.directory_cache.remove(&app.state.pane().current_path);
+        tracing::info!("ソート条件を変更しました: {:?}", app.state.pane().sort_column);
     }
 
     // ===== パス・情報 =====
 
     fn copy_full_path(app: &mut FileVisorApp) {
-        if !app.state.selected_items.is_empty() {
-            let paths: Vec<String> = app.state.selected_items
+        if !app.state.pane().selected_items.is_empty() {
+            let paths: Vec<String> = app.state.pane().selected_items
                 .iter()
                 .map(|p| p.to_string_lossy().to_string())
                 .collect();
@@ -353,7 +444,7 @@ impl ShortcutHandler {
     }
 
     fn show_file_info(app: &mut FileVisorApp) {
-        if let Some(selected) = app.state.selected_items.first() {
+        if let Some(selected) = app.state.pane().selected_items.first().cloned() {
             app.state.file_info_target = Some(selected.clone());
             app.state.show_file_info_dialog = true;
             tracing::info!("ファイル情報ダイアログを表示: {:?}", selected);
@@ -362,18 +453,23 @@ impl ShortcutHandler {
         }
     }
 
-    // ===== まだ実装されていない機能（ログのみ） =====
-
-    fn change_attributes(_app: &mut FileVisorApp) {
-        tracing::info!("属性変更機能（未実装）");
-    }
+    // ===== 編集・閲覧・その他 =====
 
-    fn binary_edit(_app: &mut FileVisorApp) {
-        tracing::info!("バイナリ編集機能（未実装）");
+    fn binary_edit(app: &mut FileVisorApp) {
+        if let Some(selected_file) = app.state.pane().selected_items.first().cloned() {
+            if selected_file.is_file() {
+                use crate::ui::HexEditorUI;
+                HexEditorUI::open(app, selected_file);
+            } else {
+                tracing::warn!("選択されたアイテムはファイルではありません");
+            }
+        } else {
+            tracing::warn!("バイナリ編集するファイルが選択されていません");
+        }
     }
 
     fn edit_with_editor(app: &mut FileVisorApp) {
-        if let Some(selected_file) = app.state.selected_items.first().cloned() {
+        if let Some(selected_file) = app.state.pane().selected_items.first().cloned() {
             if selected_file.is_file() {
                 use crate::ui::FileViewerUI;
                 FileViewerUI::open_file_for_editing(app, selected_file.clone());
@@ -386,16 +482,8 @@ impl ShortcutHandler {
         }
     }
 
-    fn find_files(_app: &mut FileVisorApp) {
-        tracing::info!("検索機能（未実装）");
-    }
-
-    fn show_history(_app: &mut FileVisorApp) {
-        tracing::info!("履歴機能（未実装）");
-    }
-
-    fn concatenate_files(_app: &mut FileVisorApp) {
-        tracing::info!("連結機能（未実装）");  
+    fn find_files(app: &mut FileVisorApp) {
+        crate::ui::SearchUI::open(app);
     }
 
     fn create_file(app: &mut FileVisorApp) {
@@ -413,7 +501,7 @@ impl ShortcutHandler {
     }
 
     fn view_files(app: &mut FileVisorApp) {
-        if let Some(selected_file) = app.state.selected_items.first().cloned() {
+        if let Some(selected_file) = app.state.pane().selected_items.first().cloned() {
             if selected_file.is_file() {
                 use crate::ui::FileViewerUI;
                 FileViewerUI::open_file_for_viewing(app, selected_file.clone());
@@ -0,0 +1,264 @@
+use crate::app::FileVisorApp;
+use crate::search::{ModifiedWithin, NameSyntax, category_label};
+use crate::smart_folders::{ICONS, SmartFolderEditor, smart_path};
+use crate::split_join::SizeUnit;
+use crate::ui::SearchUI;
+use egui::{Color32, RichText};
+use fvrs_core::core::{EntryKind, FileCategory};
+use fvrs_core::smart_folders::SmartFolder;
+use std::path::{Path, PathBuf};
+
+/// サイドバーのスマートフォルダーでの操作（描画後にまとめて適用）
+enum SmartFolderAction {
+    Open(PathBuf),
+    Edit(String),
+    Move(String, bool),
+    Remove(String),
+}
+
+/// スマートフォルダー（保存した検索をフォルダーとして表示）
+pub struct SmartFoldersUI;
+
+impl SmartFoldersUI {
+    /// 検索ダイアログの条件をスマートフォルダーとして保存するダイアログを開く
+    pub fn open_from_search(app: &mut FileVisorApp) {
+        app.smart_folder_editor = Some(SmartFolderEditor::from_search(&app.smart_folders.folders, &app.file_search));
+    }
+
+    /// サイドバーのスマートフォルダー
+    pub fn show_section(ui: &mut egui::Ui, app: &mut FileVisorApp) {
+        let folders = &app.smart_folders.folders;
+        if folders.is_empty() {
+            return;
+        }
+        let mut actions = Vec::new();
+        let current_path = app.state.pane().current_path.clone();
+
+        ui.label("🔎 スマートフォルダー");
+        for folder in folders.folders() {
+            Self::show_item(ui, folder, &current_path, &mut actions);
+        }
+        ui.separator();
+
+        for action in actions {
+            match action {
+                SmartFolderAction::Open(path) => {
+                    app.state.sidebar_selected_item = Some(path.clone());
+                    app.navigate_to(path);
+                }
+                SmartFolderAction::Edit(name) => {
+                    if let Some(folder) = app.smart_folders.folders.get(&name) {
+                        app.smart_folder_editor = Some(SmartFolderEditor::edit(folder));
+                    }
+                }
+                SmartFolderAction::Move(name, up) => app.smart_folders.move_by_one(&name, up),
+                SmartFolderAction::Remove(name) => {
+                    app.smart_folders.remove(&name);
+                    app.directory_cache.remove(&smart_path(&name));
+                }
+            }
+        }
+    }
+
+    fn show_item(ui: &mut egui::Ui, folder: &SmartFolder, current_path: &Path, actions: &mut Vec<SmartFolderAction>) {
+        let path = smart_path(&folder.name);
+        let mut text = RichText::new(format!("{} {}", folder.icon, folder.name));
+        if !folder.root.is_dir() {
+            text = text.color(Color32::GRAY).strikethrough();
+        }
+        let response = ui
+            .selectable_label(path == current_path, text)
+            .on_hover_text(format!("{} の検索結果", folder.root.display()));
+        if response.clicked() {
+            actions.push(SmartFolderAction::Open(path.clone()));
+        }
+        response.context_menu(|ui| {
+            if ui.button("開く").clicked() {
+                actions.push(SmartFolderAction::Open(path.clone()));
+                ui.close_menu();
+            }
+            if ui.button("条件を編集...").clicked() {
+                actions.push(SmartFolderAction::Edit(folder.name.clone()));
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("上へ移動").clicked() {
+                actions.push(SmartFolderAction::Move(folder.name.clone(), true));
+                ui.close_menu();
+            }
+            if ui.button("下へ移動").clicked() {
+                actions.push(SmartFolderAction::Move(folder.name.clone(), false));
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("スマートフォルダーを削除").on_hover_text("検索条件だけを削除し、ファイルは残す").clicked() {
+                actions.push(SmartFolderAction::Remove(folder.name.clone()));
+                ui.close_menu();
+            }
+        });
+    }
+
+    /// スマートフォルダーの追加・編集ダイアログ
+    pub fn show_editor(ctx: &egui::Context, app: &mut FileVisorApp) {
+        let Some(editor) = app.smart_folder_editor.as_mut() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut save = false;
+        let mut close = false;
+        let title = if editor.original.is_some() { "スマートフォルダーの編集" } else { "スマートフォルダーとして保存" };
+        egui::Window::new(title)
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("smart_folder_editor").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+                    ui.label("名前:");
+                    ui.text_edit_singleline(&mut editor.name);
+                    ui.end_row();
+
+                    ui.label("アイコン:");
+                    ui.horizontal_wrapped(|ui| {
+                        for icon in ICONS {
+                            if ui.selectable_label(editor.icon == icon, icon).clicked() {
+                                editor.icon = icon.to_string();
+                            }
+                        }
+                        ui.add(egui::TextEdit::singleline(&mut editor.icon).desired_width(28.0));
+                    });
+                    ui.end_row();
+
+                    ui.label("場所:");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut editor.root).desired_width(340.0));
+                        if ui.button("📁").on_hover_text("参照").clicked()
+                            && let Some(path) = rfd::FileDialog::new().set_directory(editor.root.trim()).pick_folder()
+                        {
+                            editor.root = path.to_string_lossy().into_owned();
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("名前の条件:");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut editor.pattern).desired_width(220.0).hint_text("すべて"));
+                        ui.radio_value(&mut editor.syntax, NameSyntax::Glob, "ワイルドカード");
+                        ui.radio_value(&mut editor.syntax, NameSyntax::Regex, "正規表現");
+                    });
+                    ui.end_row();
+
+                    ui.label("拡張子:");
+                    ui.add(egui::TextEdit::singleline(&mut editor.extensions).desired_width(220.0).hint_text("例: jpg; png"));
+                    ui.end_row();
+
+                    ui.label("内容:");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut editor.content)
+                                .desired_width(220.0)
+                                .hint_text("ファイルの中から探す文字列"),
+                        );
+                        ui.checkbox(&mut editor.content_regex, "正規表現");
+                        ui.checkbox(&mut editor.case_sensitive, "大文字と小文字を区別");
+                    });
+                    ui.end_row();
+
+                    ui.label("種類:");
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut editor.kind, EntryKind::Any, "すべて");
+                        ui.selectable_value(&mut editor.kind, EntryKind::Files, "ファイル");
+                        ui.selectable_value(&mut editor.kind, EntryKind::Directories, "フォルダー");
+                    });
+                    ui.end_row();
+
+                    ui.label("ファイルの種類:");
+                    ui.horizontal_wrapped(|ui| {
+                        for category in FileCategory::ALL {
+                            let mut checked = editor.categories.contains(&category);
+                            if ui.checkbox(&mut checked, category_label(category)).changed() {
+                                if checked {
+                                    editor.categories.push(category);
+                                } else {
+                                    editor.categories.retain(|other| *other != category);
+                                }
+                            }
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("サイズ:");
+                    ui.horizontal(|ui| {
+                        SearchUI::size_limit(ui, &mut editor.min_size, "以上");
+                        SearchUI::size_limit(ui, &mut editor.max_size, "以下");
+                        egui::ComboBox::from_id_salt("smart_folder_size_unit")
+                            .selected_text(editor.size_unit.label())
+                            .width(60.0)
+                            .show_ui(ui, |ui| {
+                                for unit in SizeUnit::ALL {
+                                    ui.selectable_value(&mut editor.size_unit, unit, unit.label());
+                                }
+                            });
+                    });
+                    ui.end_row();
+
+                    ui.label("更新日時:");
+                    egui::ComboBox::from_id_salt("smart_folder_modified")
+                        .selected_text(editor.modified.label())
+                        .show_ui(ui, |ui| {
+                            for modified in ModifiedWithin::ALL {
+                                ui.selectable_value(&mut editor.modified, modified, modified.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text("開くたびに、その時点から数える");
+                    ui.end_row();
+
+                    ui.label("オプション:");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut editor.respect_ignore, ".gitignore に従う");
+                        ui.checkbox(&mut editor.include_hidden, "隠しファイル");
+                    });
+                    ui.end_row();
+                });
+
+                if let Some(error) = &editor.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("保存").clicked() {
+                        save = true;
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if save {
+            match app.smart_folders.apply(editor) {
+                Ok(()) => {
+                    // 名前を変えた場合も含め、開いている結果は新しい条件で検索し直す
+                    let path = smart_path(editor.name.trim());
+                    if let Some(original) = &editor.original {
+                        let original = smart_path(original);
+                        app.directory_cache.remove(&original);
+                        for pane in app.state.all_panes_mut() {
+                            if pane.current_path == original {
+                                pane.current_path = path.clone();
+                            }
+                        }
+                    }
+                    app.directory_cache.remove(&path);
+                    close = true;
+                }
+                Err(e) => editor.error = Some(e),
+            }
+        }
+        if !open || close {
+            app.smart_folder_editor = None;
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::app::FileVisorApp;
+use crate::file_info::format_size;
+use crate::split_join::{ALGORITHMS, SizeUnit, SplitBy, SplitJoin, SplitJoinTab};
+use egui::{Color32, RichText};
+use fvrs_core::split::PartStatus;
+
+/// 連結と分割ダイアログ
+pub struct SplitJoinDialog;
+
+impl SplitJoinDialog {
+    /// 選択中の項目で開く
+    pub fn open(app: &mut FileVisorApp) {
+        let pane = app.state.pane();
+        if !pane.selected_items.iter().any(|path| path.is_file()) {
+            tracing::warn!("連結・分割するファイルが選択されていません");
+            return;
+        }
+        app.split_join = Some(SplitJoin::new(pane.current_path.clone(), &pane.selected_items));
+    }
+
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        let Some(dialog) = app.split_join.as_mut() else {
+            return;
+        };
+        dialog.poll(ctx);
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("連結と分割")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(480.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.add_enabled_ui(!dialog.is_running(), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut dialog.tab, SplitJoinTab::Join, "連結");
+                        ui.selectable_value(&mut dialog.tab, SplitJoinTab::Split, "分割");
+                        if dialog.manifest_path.is_some() {
+                            ui.selectable_value(&mut dialog.tab, SplitJoinTab::Rejoin, "復元");
+                        }
+                    });
+                    ui.separator();
+                    match dialog.tab {
+                        SplitJoinTab::Join => Self::show_join(ui, dialog),
+                        SplitJoinTab::Split => Self::show_split(ui, dialog),
+                        SplitJoinTab::Rejoin => Self::show_rejoin(ui, dialog),
+                    }
+                });
+
+                ui.separator();
+                if let Some(error) = &dialog.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                if let Some(status) = &dialog.status {
+                    ui.label(status);
+                }
+                ui.horizontal(|ui| {
+                    if let Some((label, fraction)) = dialog.progress() {
+                        ui.add(egui::ProgressBar::new(fraction).desired_width(240.0).show_percentage().text(label));
+                        if ui.button("中止").clicked() {
+                            dialog.cancel();
+                        }
+                    } else if ui.button("閉じる").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if !open || close {
+            app.split_join = None;
+        }
+    }
+
+    /// 出力先フォルダー
+    fn show_directory(ui: &mut egui::Ui, dialog: &mut SplitJoin) {
+        ui.horizontal(|ui| {
+            ui.label("出力先:");
+            ui.label(RichText::new(dialog.directory.display().to_string()).monospace());
+            if ui.button("📁 変更...").clicked()
+                && let Some(path) = rfd::FileDialog::new().set_directory(&dialog.directory).pick_folder()
+            {
+                dialog.directory = path;
+            }
+        });
+    }
+
+    fn show_join(ui: &mut egui::Ui, dialog: &mut SplitJoin) {
+        ui.label("上から順に連結します");
+        let mut move_request = None;
+        let mut remove = None;
+        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+            let count = dialog.join_sources.len();
+            for (index, path) in dialog.join_sources.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(index > 0, egui::Button::new("⬆").small()).clicked() {
+                        move_request = Some((index, true));
+                    }
+                    if ui.add_enabled(index + 1 < count, egui::Button::new("⬇").small()).clicked() {
+                        move_request = Some((index, false));
+                    }
+                    if ui.small_button("✖").on_hover_text("一覧から外す").clicked() {
+                        remove = Some(index);
+                    }
+                    let size = std::fs::metadata(path).map(|m| format_size(m.len())).unwrap_or_default();
+                    ui.label(path.file_name().unwrap_or_default().to_string_lossy());
+                    ui.label(RichText::new(size).weak());
+                });
+            }
+        });
+        if let Some((index, up)) = move_request {
+            dialog.move_source(index, up);
+        }
+        if let Some(index) = remove {
+            dialog.join_sources.remove(index);
+        }
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label("ファイル名:");
+            ui.text_edit_singleline(&mut dialog.join_output);
+        });
+        Self::show_directory(ui, dialog);
+        if ui.add_enabled(!dialog.join_sources.is_empty(), egui::Button::new("連結")).clicked() {
+            dialog.start_join(ui.ctx());
+        }
+    }
+
+    fn show_split(ui: &mut egui::Ui, dialog: &mut SplitJoin) {
+        let Some(source) = dialog.split_source.clone() else {
+            ui.label("分割するファイルを 1 つ選んでから開いてください");
+            return;
+        };
+        let size = std::fs::metadata(&source).map(|m| m.len()).unwrap_or(0);
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(source.file_name().unwrap_or_default().to_string_lossy()).strong());
+            ui.label(RichText::new(format_size(size)).weak());
+        });
+
+        egui::Grid::new("split_options").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+            ui.radio_value(&mut dialog.split_by, SplitBy::Size, "サイズで分割:");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut dialog.part_size).range(1..=u64::MAX));
+                egui::ComboBox::from_id_salt("split_size_unit")
+                    .selected_text(dialog.size_unit.label())
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for unit in SizeUnit::ALL {
+                            ui.selectable_value(&mut dialog.size_unit, unit, unit.label());
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.radio_value(&mut dialog.split_by, SplitBy::Lines, "行数で分割:");
+            ui.add(egui::DragValue::new(&mut dialog.part_lines).range(1..=u64::MAX).suffix(" 行"));
+            ui.end_row();
+
+            ui.radio_value(&mut dialog.split_by, SplitBy::Count, "個数で分割:");
+            ui.add(egui::DragValue::new(&mut dialog.part_count).range(1..=9999).suffix(" 個"));
+            ui.end_row();
+
+            ui.label("ハッシュ:");
+            egui::ComboBox::from_id_salt("split_algorithm")
+                .selected_text(format!("{:?}", dialog.algorithm))
+                .show_ui(ui, |ui| {
+                    for algorithm in ALGORITHMS {
+                        ui.selectable_value(&mut dialog.algorithm, algorithm, format!("{:?}", algorithm));
+                    }
+                });
+            ui.end_row();
+        });
+
+        let name = source.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        ui.label(
+            RichText::new(format!(
+                "{}.001, {}.002, ... と、照合用の {}{} を作成します",
+                name,
+                name,
+                name,
+                fvrs_core::split::MANIFEST_SUFFIX
+            ))
+            .weak()
+            .small(),
+        );
+        Self::show_directory(ui, dialog);
+        if ui.button("分割").clicked() {
+            dialog.start_split(ui.ctx());
+        }
+    }
+
+    fn show_rejoin(ui: &mut egui::Ui, dialog: &mut SplitJoin) {
+        let Some(manifest) = &dialog.manifest else {
+            ui.label("マニフェストを読み込めませんでした");
+            return;
+        };
+        ui.label(format!(
+            "{} ({}, {} 個の部品, {:?})",
+            manifest.file_name,
+            format_size(manifest.size),
+            manifest.parts.len(),
+            manifest.algorithm
+        ));
+
+        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+            egui::Grid::new("rejoin_parts").num_columns(3).striped(true).show(ui, |ui| {
+                for (index, part) in manifest.parts.iter().enumerate() {
+                    ui.label(&part.file_name);
+                    ui.label(RichText::new(format_size(part.size)).weak());
+                    let status = dialog.part_statuses.as_ref().and_then(|statuses| statuses.get(index));
+                    match status.map(|(_, status)| status) {
+                        Some(PartStatus::Ok) => ui.colored_label(Color32::from_rgb(60, 170, 60), "✔ 正常"),
+                        Some(PartStatus::Missing) => ui.colored_label(Color32::RED, "✖ 見つかりません"),
+                        Some(PartStatus::WrongSize(size)) => {
+                            ui.colored_label(Color32::RED, format!("✖ サイズが違います ({})", format_size(*size)))
+                        }
+                        Some(PartStatus::WrongHash) => ui.colored_label(Color32::RED, "✖ ハッシュが違います"),
+                        None => ui.label(""),
+                    };
+                    ui.end_row();
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("ファイル名:");
+            ui.text_edit_singleline(&mut dialog.rejoin_output);
+        });
+        Self::show_directory(ui, dialog);
+        ui.horizontal(|ui| {
+            if ui.button("検証").on_hover_text("部品のサイズとハッシュを確かめます").clicked() {
+                dialog.start_verify(ui.ctx());
+            }
+            if ui.button("復元").on_hover_text("部品を確かめてから連結し、全体のハッシュを照合します").clicked() {
+                dialog.start_rejoin(ui.ctx());
+            }
+        });
+    }
+}
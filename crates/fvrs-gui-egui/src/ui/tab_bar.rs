@@ -0,0 +1,117 @@
+use crate::app::FileVisorApp;
+use crate::state::{PaneSide, PaneState};
+use egui::{Color32, Stroke};
+
+/// タブの見出しに表示する最大文字数
+const MAX_TITLE_CHARS: usize = 24;
+
+/// ドラッグ中のタブ（同じペイン内でのみ並べ替えられる）
+#[derive(Clone, Copy)]
+struct DraggedTab {
+    side: PaneSide,
+    index: usize,
+}
+
+/// タブバーでの操作（描画後にまとめて適用）
+enum TabAction {
+    Select(usize),
+    Close(usize),
+    New,
+    Move { from: usize, to: usize },
+}
+
+/// ペインごとのタブバー
+pub struct TabBarUI;
+
+impl TabBarUI {
+    /// タブバーを表示（クリックで切り替え、中クリックか × で閉じる、ドラッグで並べ替え）
+    pub fn show(ui: &mut egui::Ui, app: &mut FileVisorApp, side: PaneSide) {
+        let tabs = app.state.tabs_at(side);
+        let can_close = tabs.tabs.len() > 1;
+        let mut actions = Vec::new();
+
+        egui::ScrollArea::horizontal()
+            .id_salt("tab_bar")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for (index, tab) in tabs.tabs.iter().enumerate() {
+                        let selected = index == tabs.active;
+                        let id = ui.id().with(("tab", index));
+                        let response = ui
+                            .dnd_drag_source(id, DraggedTab { side, index }, |ui| {
+                                ui.horizontal(|ui| {
+                                    let label = ui
+                                        .selectable_label(selected, tab_title(tab))
+                                        .on_hover_text(tab.current_path.display().to_string());
+                                    if label.clicked() {
+                                        actions.push(TabAction::Select(index));
+                                    }
+                                    if label.middle_clicked() && can_close {
+                                        actions.push(TabAction::Close(index));
+                                    }
+                                    if can_close && ui.small_button("×").clicked() {
+                                        actions.push(TabAction::Close(index));
+                                    }
+                                });
+                            })
+                            .response;
+
+                        // ドロップ先の位置を線で示す
+                        let hovered = response
+                            .dnd_hover_payload::<DraggedTab>()
+                            .is_some_and(|dragged| dragged.side == side && dragged.index != index);
+                        if hovered {
+                            ui.painter().vline(
+                                response.rect.left() - 2.0,
+                                response.rect.y_range(),
+                                Stroke::new(2.0, Color32::from_rgb(0, 120, 215)),
+                            );
+                        }
+                        if let Some(dragged) = response.dnd_release_payload::<DraggedTab>()
+                            && dragged.side == side
+                        {
+                            actions.push(TabAction::Move {
+                                from: dragged.index,
+                                to: index,
+                            });
+                        }
+                    }
+
+                    if ui
+                        .small_button("+")
+                        .on_hover_text("新しいタブ (Ctrl+T)")
+                        .clicked()
+                    {
+                        actions.push(TabAction::New);
+                    }
+                });
+            });
+
+        for action in actions {
+            match action {
+                TabAction::Select(index) => app.select_tab(side, index),
+                TabAction::Close(index) => app.close_tab(side, index),
+                TabAction::New => {
+                    app.activate_pane(side);
+                    app.new_tab();
+                }
+                TabAction::Move { from, to } => app.state.tabs_at_mut(side).move_tab(from, to),
+            }
+        }
+    }
+}
+
+/// タブの見出し（フォルダー名、長い名前は省略）
+fn tab_title(tab: &PaneState) -> String {
+    let name = tab
+        .current_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| tab.current_path.display().to_string());
+    if name.chars().count() > MAX_TITLE_CHARS {
+        let head: String = name.chars().take(MAX_TITLE_CHARS - 1).collect();
+        format!("{}…", head)
+    } else {
+        name
+    }
+}
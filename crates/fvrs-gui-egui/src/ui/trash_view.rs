@@ -0,0 +1,311 @@
+use crate::app::FileVisorApp;
+use crate::file_info::format_size;
+use crate::journal::OperationJournal;
+use crate::trash::TrashConfirm;
+use egui::{Align, Color32, Layout, RichText};
+use egui_extras::{Column, TableBuilder};
+use fvrs_core::trash;
+use std::path::PathBuf;
+
+/// ゴミ箱ビューでの操作（描画後にまとめて適用）
+enum TrashAction {
+    Refresh,
+    Select { path: PathBuf, additive: bool },
+    SelectAll,
+    Restore,
+    OpenLocation(PathBuf),
+    Request(TrashConfirm),
+    Confirm,
+    CancelPending,
+}
+
+/// ゴミ箱の一覧と復元・完全削除
+pub struct TrashUI;
+
+impl TrashUI {
+    /// ゴミ箱ビューを開く（開くたびに内容を読み直す）
+    pub fn open(app: &mut FileVisorApp) {
+        app.state.show_trash_view = true;
+        app.trash.refresh();
+    }
+
+    /// ゴミ箱ビューを表示
+    pub fn show(ctx: &egui::Context, app: &mut FileVisorApp) {
+        if !app.state.show_trash_view {
+            return;
+        }
+        app.trash.ensure_loaded();
+
+        let mut actions = Vec::new();
+        let mut open = true;
+
+        egui::Window::new("🗑 ゴミ箱")
+            .default_width(760.0)
+            .default_height(480.0)
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if !trash::is_supported() {
+                    ui.label("このプラットフォームではゴミ箱を利用できません");
+                    ui.label(RichText::new("削除したファイルは退避領域へ移動され、Ctrl+Z で元に戻せます").weak());
+                    ui.separator();
+                    Self::show_messages(ui, app);
+                    Self::show_trash_area(ui, app, &mut actions);
+                    return;
+                }
+                Self::show_toolbar(ui, app, &mut actions);
+                ui.separator();
+
+                Self::show_messages(ui, app);
+                Self::show_items(ui, app, &mut actions);
+                if app.trash.trash_area_count > 0 {
+                    ui.separator();
+                    Self::show_trash_area(ui, app, &mut actions);
+                }
+            });
+
+        Self::show_confirmation(ctx, app, &mut actions);
+
+        if !open {
+            app.state.show_trash_view = false;
+        }
+        for action in actions {
+            Self::apply(app, action);
+        }
+    }
+
+    fn show_toolbar(ui: &mut egui::Ui, app: &FileVisorApp, actions: &mut Vec<TrashAction>) {
+        let has_selection = !app.trash.selected.is_empty();
+        let has_items = !app.trash.items.is_empty();
+        ui.horizontal(|ui| {
+            if ui.button("🔄 更新").clicked() {
+                actions.push(TrashAction::Refresh);
+            }
+            if ui
+                .add_enabled(has_items, egui::Button::new("すべて選択"))
+                .clicked()
+            {
+                actions.push(TrashAction::SelectAll);
+            }
+            if ui
+                .add_enabled(has_selection, egui::Button::new("↩ 元に戻す"))
+                .clicked()
+            {
+                actions.push(TrashAction::Restore);
+            }
+            if ui
+                .add_enabled(has_selection, egui::Button::new("✖ 完全に削除..."))
+                .clicked()
+            {
+                actions.push(TrashAction::Request(TrashConfirm::DeleteSelected));
+            }
+            if ui
+                .add_enabled(has_items, egui::Button::new("🗑 ゴミ箱を空にする..."))
+                .clicked()
+            {
+                actions.push(TrashAction::Request(TrashConfirm::Empty));
+            }
+        });
+
+        let total: u64 = app.trash.items.iter().map(|item| item.size).sum();
+        ui.label(format!(
+            "{} 項目, {}",
+            app.trash.items.len(),
+            format_size(total)
+        ));
+    }
+
+    fn show_messages(ui: &mut egui::Ui, app: &FileVisorApp) {
+        if let Some(error) = &app.trash.error {
+            ui.colored_label(Color32::RED, error);
+        }
+        if let Some(status) = &app.trash.status {
+            ui.label(status);
+        }
+    }
+
+    /// 元に戻す用の退避領域（自動では削除しないので、ここから空にする）
+    fn show_trash_area(ui: &mut egui::Ui, app: &FileVisorApp, actions: &mut Vec<TrashAction>) {
+        let count = app.trash.trash_area_count;
+        ui.horizontal(|ui| {
+            ui.label(format!("退避領域: {} 項目", count));
+            if ui.button("🔄 更新").clicked() {
+                actions.push(TrashAction::Refresh);
+            }
+            if let Some(dir) = OperationJournal::trash_area_dir()
+                && ui.add_enabled(count > 0, egui::Button::new("📂 開く")).clicked()
+            {
+                actions.push(TrashAction::OpenLocation(dir));
+            }
+            if ui
+                .add_enabled(count > 0, egui::Button::new("🗑 退避領域を空にする..."))
+                .clicked()
+            {
+                actions.push(TrashAction::Request(TrashConfirm::EmptyTrashArea));
+            }
+        });
+    }
+
+    fn show_items(ui: &mut egui::Ui, app: &FileVisorApp, actions: &mut Vec<TrashAction>) {
+        if app.trash.items.is_empty() {
+            ui.label("ゴミ箱は空です");
+            return;
+        }
+
+        let additive = ui.input(|i| i.modifiers.command);
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::auto().at_least(200.0)) // 名前
+            .column(Column::remainder().at_least(200.0)) // 元の場所
+            .column(Column::auto().at_least(130.0)) // 削除日時
+            .column(Column::auto().at_least(80.0)) // サイズ
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong("名前");
+                });
+                header.col(|ui| {
+                    ui.strong("元の場所");
+                });
+                header.col(|ui| {
+                    ui.strong("削除日時");
+                });
+                header.col(|ui| {
+                    ui.strong("サイズ");
+                });
+            })
+            .body(|body| {
+                body.rows(20.0, app.trash.items.len(), |mut row| {
+                    let item = &app.trash.items[row.index()];
+                    let location = item
+                        .original_path
+                        .parent()
+                        .map(|p| p.to_path_buf())
+                        .unwrap_or_default();
+
+                    row.col(|ui| {
+                        let label =
+                            format!("{} {}", if item.is_dir { "📁" } else { "📄" }, item.name);
+                        let response = ui.selectable_label(app.trash.is_selected(item), label);
+                        if response.clicked() {
+                            actions.push(TrashAction::Select {
+                                path: item.trashed_path.clone(),
+                                additive,
+                            });
+                        }
+                        response.context_menu(|ui| {
+                            if ui.button("↩ 元に戻す").clicked() {
+                                actions.push(TrashAction::Select {
+                                    path: item.trashed_path.clone(),
+                                    additive: false,
+                                });
+                                actions.push(TrashAction::Restore);
+                                ui.close_menu();
+                            }
+                            if ui.button("📂 元の場所を開く").clicked() {
+                                actions.push(TrashAction::OpenLocation(location.clone()));
+                                ui.close_menu();
+                            }
+                            ui.separator();
+                            if ui.button("✖ 完全に削除...").clicked() {
+                                actions.push(TrashAction::Select {
+                                    path: item.trashed_path.clone(),
+                                    additive: false,
+                                });
+                                actions.push(TrashAction::Request(TrashConfirm::DeleteSelected));
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                    row.col(|ui| {
+                        ui.label(location.display().to_string());
+                    });
+                    row.col(|ui| {
+                        let deleted_at = item
+                            .deleted_at
+                            .map(|t| t.format("%Y/%m/%d %H:%M").to_string())
+                            .unwrap_or_else(|| "-".to_string());
+                        ui.label(deleted_at);
+                    });
+                    row.col(|ui| {
+                        ui.label(format_size(item.size));
+                    });
+                });
+            });
+    }
+
+    /// 完全削除の確認ダイアログ
+    fn show_confirmation(ctx: &egui::Context, app: &FileVisorApp, actions: &mut Vec<TrashAction>) {
+        let Some(pending) = &app.trash.pending else {
+            return;
+        };
+        let message = match pending {
+            TrashConfirm::DeleteSelected => format!(
+                "選択した {} 項目を完全に削除しますか？",
+                app.trash.selected.len()
+            ),
+            TrashConfirm::Empty => format!(
+                "ゴミ箱内の {} 項目をすべて完全に削除しますか？",
+                app.trash.items.len()
+            ),
+            TrashConfirm::EmptyTrashArea => format!(
+                "退避領域の {} 項目をすべて完全に削除しますか？\n退避ファイルを使う操作は元に戻せなくなります",
+                app.trash.trash_area_count
+            ),
+        };
+
+        egui::Window::new("完全に削除")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.colored_label(Color32::RED, "⚠️ この操作は元に戻せません");
+                ui.label(message);
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("完全に削除").clicked() {
+                        actions.push(TrashAction::Confirm);
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        actions.push(TrashAction::CancelPending);
+                    }
+                });
+            });
+    }
+
+    fn apply(app: &mut FileVisorApp, action: TrashAction) {
+        match action {
+            TrashAction::Refresh => app.trash.refresh(),
+            TrashAction::Select { path, additive } => {
+                if let Some(item) = app.trash.items.iter().find(|i| i.trashed_path == path) {
+                    let item = item.clone();
+                    app.trash.toggle(&item, additive);
+                }
+            }
+            TrashAction::SelectAll => {
+                app.trash.selected = app
+                    .trash
+                    .items
+                    .iter()
+                    .map(|item| item.trashed_path.clone())
+                    .collect();
+            }
+            TrashAction::Restore => {
+                for operation in app.trash.restore_selected() {
+                    app.record_operation(operation);
+                }
+            }
+            TrashAction::OpenLocation(path) => app.navigate_to(path),
+            TrashAction::Request(confirm) => app.trash.pending = Some(confirm),
+            TrashAction::Confirm => match app.trash.pending.take() {
+                Some(TrashConfirm::DeleteSelected) => app.trash.delete_selected(),
+                Some(TrashConfirm::Empty) => app.trash.empty(),
+                Some(TrashConfirm::EmptyTrashArea) => app.trash.empty_trash_area(&mut app.journal),
+                None => {}
+            },
+            TrashAction::CancelPending => app.trash.pending = None,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use egui::{Color32, ProgressBar};
+use fvrs_core::volume::VolumeInfo;
+use crate::file_info::format_size;
+
+/// ディスク使用率バー
+pub struct VolumeBar;
+
+impl VolumeBar {
+    /// 使用率が高いとき（90% 以上）に警告色で表示
+    const WARNING_RATIO: f32 = 0.9;
+
+    /// 使用率バーを表示（ホバーで詳細を表示）
+    pub fn show(ui: &mut egui::Ui, info: &VolumeInfo, width: f32) -> egui::Response {
+        let ratio = info.usage_ratio();
+        let fill = if ratio >= Self::WARNING_RATIO {
+            Color32::from_rgb(200, 60, 60)
+        } else {
+            ui.visuals().selection.bg_fill
+        };
+
+        ui.add(
+            ProgressBar::new(ratio)
+                .desired_width(width)
+                .fill(fill)
+                .text(format!(
+                    "{} 空き / {}",
+                    format_size(info.available_space),
+                    format_size(info.total_space)
+                )),
+        )
+        .on_hover_ui(|ui| Self::show_details(ui, info))
+    }
+
+    /// ボリュームの詳細（ツールチップ用）
+    fn show_details(ui: &mut egui::Ui, info: &VolumeInfo) {
+        egui::Grid::new("volume_bar_details")
+            .num_columns(2)
+            .spacing([12.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("マウントポイント:");
+                ui.label(info.mount_point.display().to_string());
+                ui.end_row();
+
+                if let Some(label) = &info.label {
+                    ui.label("ボリュームラベル:");
+                    ui.label(label);
+                    ui.end_row();
+                }
+
+                ui.label("ファイルシステム:");
+                ui.label(&info.filesystem);
+                ui.end_row();
+
+                ui.label("使用済み:");
+                ui.label(format!("{} ({:.1}%)", format_size(info.used_space), info.usage_ratio() * 100.0));
+                ui.end_row();
+
+                ui.label("空き領域:");
+                ui.label(format_size(info.available_space));
+                ui.end_row();
+            });
+    }
+}